@@ -3,7 +3,8 @@ use clap::{Parser, Subcommand};
 use sp1_cli::{
     commands::{
         build::BuildCmd, build_toolchain::BuildToolchainCmd,
-        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd,
+        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd, test::TestCmd,
+        worker::WorkerCmd,
     },
     SP1_VERSION_MESSAGE,
 };
@@ -29,8 +30,10 @@ pub enum ProveCliCommands {
     New(NewCmd),
     Build(BuildCmd),
     Prove(ProveCmd),
+    Test(TestCmd),
     BuildToolchain(BuildToolchainCmd),
     InstallToolchain(InstallToolchainCmd),
+    Worker(WorkerCmd),
 }
 
 fn main() -> Result<()> {
@@ -40,7 +43,9 @@ fn main() -> Result<()> {
         ProveCliCommands::New(cmd) => cmd.run(),
         ProveCliCommands::Build(cmd) => cmd.run(),
         ProveCliCommands::Prove(cmd) => cmd.run(),
+        ProveCliCommands::Test(cmd) => cmd.run(),
         ProveCliCommands::BuildToolchain(cmd) => cmd.run(),
         ProveCliCommands::InstallToolchain(cmd) => cmd.run(),
+        ProveCliCommands::Worker(cmd) => cmd.run(),
     }
 }
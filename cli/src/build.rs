@@ -20,6 +20,12 @@ pub(crate) struct BuildArgs {
     pub(crate) docker: bool,
     #[clap(long, action, help = "Ignore Rust version check.")]
     pub(crate) ignore_rust_version: bool,
+    #[clap(
+        long,
+        action,
+        help = "Emit compressed (RV32IMC) instructions to reduce the program size."
+    )]
+    pub(crate) compressed: bool,
 }
 
 pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
@@ -57,6 +63,9 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
         if args.ignore_rust_version {
             child_args.push("--ignore-rust-version");
         }
+        if args.compressed {
+            child_args.push("--compressed");
+        }
 
         let mut child = Command::new("docker")
             .args(&child_args)
@@ -86,7 +95,7 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
             exit(result.code().unwrap_or(1))
         }
     } else {
-        let rust_flags = [
+        let mut rust_flags = vec![
             "-C",
             "passes=loweratomic",
             "-C",
@@ -94,6 +103,9 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
             "-C",
             "panic=abort",
         ];
+        if args.compressed {
+            rust_flags.extend(["-C", "target-feature=+c"]);
+        }
 
         let mut cargo_args = vec!["build", "--release", "--target", build_target, "--locked"];
         if args.ignore_rust_version {
@@ -3,3 +3,5 @@ pub mod build_toolchain;
 pub mod install_toolchain;
 pub mod new;
 pub mod prove;
+pub mod test;
+pub mod worker;
@@ -0,0 +1,219 @@
+use anstyle::*;
+use anyhow::{bail, Result};
+use clap::Parser;
+use sp1_core::runtime::{ExecutionError, Program, Runtime};
+use sp1_core::utils::{num_to_comma_separated, SP1CoreOpts};
+use std::fs;
+
+use crate::build::{build_program, BuildArgs};
+
+/// A test of a program, as listed by the `run` function of a `#[sp1_derive::tests]` module.
+#[derive(Debug, Clone)]
+pub struct GuestTest {
+    pub name: String,
+    pub should_panic: bool,
+    /// A string the panic message must contain, if the test should panic.
+    pub expected: Option<String>,
+}
+
+/// The result of running a test in the zkVM.
+#[derive(Debug, Clone)]
+pub struct TestOutcome {
+    pub passed: bool,
+    pub cycles: u64,
+    /// Why the test failed, or the panic message of a test that should panic.
+    pub message: Option<String>,
+}
+
+/// Lists the tests of a program built from a `#[sp1_derive::tests]` module.
+pub fn list_tests(elf: &[u8]) -> Result<Vec<GuestTest>> {
+    let mut runtime = Runtime::new(Program::from(elf), SP1CoreOpts::default());
+    runtime.write_stdin(&None::<String>);
+    runtime.run_untraced()?;
+    let tests = runtime.read_public_values::<Vec<(String, bool, Option<String>)>>();
+    Ok(tests
+        .into_iter()
+        .map(|(name, should_panic, expected)| GuestTest {
+            name,
+            should_panic,
+            expected,
+        })
+        .collect())
+}
+
+/// Runs a test of a program built from a `#[sp1_derive::tests]` module.
+pub fn run_test(elf: &[u8], test: &GuestTest) -> TestOutcome {
+    run_program_test(Program::from(elf), test)
+}
+
+fn run_program_test(program: Program, test: &GuestTest) -> TestOutcome {
+    let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+    runtime.write_stdin(&Some(test.name.clone()));
+    runtime.captured_stderr = Some(String::new());
+    let result = runtime.run_untraced();
+    let panic_message = runtime.captured_stderr.take().unwrap_or_default();
+
+    let (passed, message) = match result {
+        Ok(()) if test.should_panic => (false, Some("test did not panic as expected".to_string())),
+        Ok(()) => (true, None),
+        // A panic halts the program with exit code 1, after writing its message to stderr.
+        Err(ExecutionError::HaltWithNonZeroExitCode(1)) => {
+            let matches = match &test.expected {
+                Some(expected) => panic_message.contains(expected.as_str()),
+                None => true,
+            };
+            match (test.should_panic, matches) {
+                (true, true) => (true, Some(panic_message)),
+                (true, false) => (
+                    false,
+                    Some(format!(
+                        "panic did not contain expected string {:?}\n{}",
+                        test.expected.as_deref().unwrap_or_default(),
+                        panic_message
+                    )),
+                ),
+                (false, _) => (false, Some(panic_message)),
+            }
+        }
+        Err(error) => (false, Some(format!("{}\n{}", error, panic_message))),
+    };
+
+    TestOutcome {
+        passed,
+        cycles: runtime.state.global_clk,
+        message,
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "test", about = "Build a program and run its tests in the zkVM")]
+pub struct TestCmd {
+    /// Only run the tests whose names contain this string.
+    filter: Option<String>,
+
+    #[clap(flatten)]
+    build_args: BuildArgs,
+}
+
+impl TestCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf_path = build_program(&self.build_args)?;
+        let elf = fs::read(elf_path)?;
+
+        let tests = list_tests(&elf)?
+            .into_iter()
+            .filter(|test| match &self.filter {
+                Some(filter) => test.name.contains(filter.as_str()),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        let green = AnsiColor::Green.on_default();
+        let red = AnsiColor::Red.on_default();
+        println!("\nrunning {} tests", tests.len());
+        let mut failures = Vec::new();
+        for test in tests.iter() {
+            let outcome = run_test(&elf, test);
+            let status = if outcome.passed {
+                format!("{green}ok{green:#}")
+            } else {
+                format!("{red}FAILED{red:#}")
+            };
+            println!(
+                "test {} ... {} ({} cycles)",
+                test.name,
+                status,
+                num_to_comma_separated(outcome.cycles)
+            );
+            if !outcome.passed {
+                failures.push((test, outcome));
+            }
+        }
+
+        if !failures.is_empty() {
+            println!("\nfailures:\n");
+            for (test, outcome) in failures.iter() {
+                println!("---- {} ----", test.name);
+                println!("{}\n", outcome.message.as_deref().unwrap_or_default());
+            }
+        }
+
+        let status = if failures.is_empty() {
+            format!("{green}ok{green:#}")
+        } else {
+            format!("{red}FAILED{red:#}")
+        };
+        println!(
+            "\ntest result: {}. {} passed; {} failed\n",
+            status,
+            tests.len() - failures.len(),
+            failures.len()
+        );
+
+        if !failures.is_empty() {
+            bail!("{} of {} tests failed", failures.len(), tests.len());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_core::runtime::{Instruction, Opcode, SyscallCode};
+
+    use super::*;
+
+    /// A program that writes `stderr` to stderr, and halts with the exit code.
+    fn program(stderr: &[u8; 4], exit_code: u32) -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, u32::from_le_bytes(*stderr), false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 2, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HALT as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, exit_code, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    fn test(should_panic: bool, expected: Option<&str>) -> GuestTest {
+        GuestTest {
+            name: "test".to_string(),
+            should_panic,
+            expected: expected.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_run_program_test() {
+        let outcome = run_program_test(program(b"done", 0), &test(false, None));
+        assert!(outcome.passed);
+        assert!(outcome.cycles > 0);
+        assert_eq!(outcome.message, None);
+
+        let outcome = run_program_test(program(b"oops", 1), &test(false, None));
+        assert!(!outcome.passed);
+        assert_eq!(outcome.message.as_deref(), Some("oops"));
+
+        let outcome = run_program_test(program(b"done", 0), &test(true, None));
+        assert!(!outcome.passed);
+
+        let outcome = run_program_test(program(b"oops", 1), &test(true, None));
+        assert!(outcome.passed);
+
+        let outcome = run_program_test(program(b"oops", 1), &test(true, Some("oop")));
+        assert!(outcome.passed);
+        assert_eq!(outcome.message.as_deref(), Some("oops"));
+
+        let outcome = run_program_test(program(b"oops", 1), &test(true, Some("ouch")));
+        assert!(!outcome.passed);
+
+        // Any other exit code is a failure, even if the test should panic.
+        let outcome = run_program_test(program(b"oops", 2), &test(true, None));
+        assert!(!outcome.passed);
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Parser;
+use sp1_core::utils::setup_logger;
+use sp1_sdk::network::worker::{NetworkWorker, WorkerOpts};
+
+#[derive(Parser)]
+#[command(
+    name = "worker",
+    about = "Claim and fulfill proof requests from a prover network"
+)]
+pub struct WorkerCmd {
+    /// The private key used to authenticate with the network.
+    #[clap(long, env = "SP1_PRIVATE_KEY", hide_env_values = true)]
+    private_key: String,
+
+    /// The maximum number of requests proven at the same time.
+    #[clap(long, default_value = "1")]
+    max_concurrency: usize,
+
+    /// How often to poll the network for new requests, in seconds.
+    #[clap(long, default_value = "5")]
+    poll_interval: u64,
+}
+
+impl WorkerCmd {
+    pub fn run(&self) -> Result<()> {
+        setup_logger();
+
+        let opts = WorkerOpts {
+            max_concurrency: self.max_concurrency,
+            poll_interval: Duration::from_secs(self.poll_interval),
+            ..Default::default()
+        };
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(async {
+            let worker = NetworkWorker::new(&self.private_key, opts);
+            worker
+                .run(async {
+                    tokio::signal::ctrl_c()
+                        .await
+                        .expect("failed to listen for ctrl-c");
+                })
+                .await
+        })
+    }
+}
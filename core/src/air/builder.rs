@@ -277,7 +277,7 @@ pub trait WordAirBuilder: ByteAirBuilder {
     /// Check that each limb of the given slice is a u16.
     fn slice_range_check_u16(
         &mut self,
-        input: &[impl Into<Self::Expr> + Copy],
+        input: &[impl Into<Self::Expr> + Clone],
         shard: impl Into<Self::Expr> + Clone,
         channel: impl Into<Self::Expr> + Clone,
         mult: impl Into<Self::Expr> + Clone,
@@ -285,7 +285,7 @@ pub trait WordAirBuilder: ByteAirBuilder {
         input.iter().for_each(|limb| {
             self.send_byte(
                 Self::Expr::from_canonical_u8(ByteOpcode::U16Range as u8),
-                *limb,
+                limb.clone(),
                 Self::Expr::zero(),
                 Self::Expr::zero(),
                 shard.clone(),
@@ -23,6 +23,22 @@ pub trait MachineAir<F: Field>: BaseAir<F> {
     ///    the record such as byte lookup requests.
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F>;
 
+    /// Generate the trace for a given execution record, reusing the allocation of `buffer` for the
+    /// trace values.
+    ///
+    /// `buffer` is a trace of this air from a previous shard, see
+    /// [TraceArena](crate::stark::TraceArena). AIRs with large traces should override this method,
+    /// the default implementation drops the buffer and calls [MachineAir::generate_trace].
+    fn generate_trace_into(
+        &self,
+        input: &Self::Record,
+        output: &mut Self::Record,
+        buffer: Vec<F>,
+    ) -> RowMajorMatrix<F> {
+        drop(buffer);
+        self.generate_trace(input, output)
+    }
+
     /// Generate the dependencies for a given execution record.
     fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
         self.generate_trace(input, output);
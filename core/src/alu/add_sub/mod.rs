@@ -5,8 +5,7 @@ use p3_air::{Air, BaseAir};
 use p3_field::PrimeField;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
-use p3_maybe_rayon::prelude::ParallelIterator;
-use p3_maybe_rayon::prelude::ParallelSlice;
+use p3_maybe_rayon::prelude::*;
 use sp1_derive::AlignedBorrow;
 
 use crate::air::MachineAir;
@@ -14,7 +13,7 @@ use crate::air::{SP1AirBuilder, Word};
 use crate::operations::AddOperation;
 use crate::runtime::{ExecutionRecord, Opcode, Program};
 use crate::stark::MachineRecord;
-use crate::utils::pad_to_power_of_two;
+use crate::utils::padded_trace_buffer;
 
 /// The number of main trace columns for `AddSubChip`.
 pub const NUM_ADD_SUB_COLS: usize = size_of::<AddSubCols<u8>>();
@@ -68,6 +67,15 @@ impl<F: PrimeField> MachineAir<F> for AddSubChip {
         &self,
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
     ) -> RowMajorMatrix<F> {
         // Generate the rows for the trace.
         let chunk_size = std::cmp::max(
@@ -80,15 +88,17 @@ impl<F: PrimeField> MachineAir<F> for AddSubChip {
             .chain(input.sub_events.iter())
             .collect::<Vec<_>>();
 
-        let rows_and_records = merged_events
-            .par_chunks(chunk_size)
-            .map(|events| {
+        // Write the rows directly into the padded trace.
+        let mut values = padded_trace_buffer(buffer, merged_events.len(), NUM_ADD_SUB_COLS);
+        let records = values
+            .par_chunks_mut(chunk_size * NUM_ADD_SUB_COLS)
+            .zip(merged_events.par_chunks(chunk_size))
+            .map(|(rows, events)| {
                 let mut record = ExecutionRecord::default();
-                let rows = events
-                    .iter()
-                    .map(|event| {
-                        let mut row = [F::zero(); NUM_ADD_SUB_COLS];
-                        let cols: &mut AddSubCols<F> = row.as_mut_slice().borrow_mut();
+                rows.chunks_mut(NUM_ADD_SUB_COLS)
+                    .zip(events)
+                    .for_each(|(row, event)| {
+                        let cols: &mut AddSubCols<F> = row.borrow_mut();
                         let is_add = event.opcode == Opcode::ADD;
                         cols.shard = F::from_canonical_u32(event.shard);
                         cols.channel = F::from_canonical_u32(event.channel);
@@ -107,29 +117,17 @@ impl<F: PrimeField> MachineAir<F> for AddSubChip {
                         );
                         cols.operand_1 = Word::from(operand_1);
                         cols.operand_2 = Word::from(operand_2);
-                        row
-                    })
-                    .collect::<Vec<_>>();
-                (rows, record)
+                    });
+                record
             })
             .collect::<Vec<_>>();
 
-        let mut rows: Vec<[F; NUM_ADD_SUB_COLS]> = vec![];
-        for mut row_and_record in rows_and_records {
-            rows.extend(row_and_record.0);
-            output.append(&mut row_and_record.1);
+        for mut record in records {
+            output.append(&mut record);
         }
 
         // Convert the trace to a row major matrix.
-        let mut trace = RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_ADD_SUB_COLS,
-        );
-
-        // Pad the trace to a power of two.
-        pad_to_power_of_two::<NUM_ADD_SUB_COLS, F>(&mut trace.values);
-
-        trace
+        RowMajorMatrix::new(values, NUM_ADD_SUB_COLS)
     }
 
     fn included(&self, shard: &Self::Record) -> bool {
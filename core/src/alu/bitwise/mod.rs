@@ -12,7 +12,7 @@ use crate::air::{SP1AirBuilder, Word};
 use crate::bytes::event::ByteRecord;
 use crate::bytes::{ByteLookupEvent, ByteOpcode};
 use crate::runtime::{ExecutionRecord, Opcode, Program};
-use crate::utils::pad_to_power_of_two;
+use crate::utils::padded_trace_buffer;
 
 /// The number of main trace columns for `BitwiseChip`.
 pub const NUM_BITWISE_COLS: usize = size_of::<BitwiseCols<u8>>();
@@ -64,13 +64,22 @@ impl<F: PrimeField> MachineAir<F> for BitwiseChip {
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
     ) -> RowMajorMatrix<F> {
-        // Generate the trace rows for each event.
-        let rows = input
-            .bitwise_events
-            .iter()
-            .map(|event| {
-                let mut row = [F::zero(); NUM_BITWISE_COLS];
-                let cols: &mut BitwiseCols<F> = row.as_mut_slice().borrow_mut();
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
+    ) -> RowMajorMatrix<F> {
+        // Generate the trace rows for each event, directly into the padded trace.
+        let mut values = padded_trace_buffer(buffer, input.bitwise_events.len(), NUM_BITWISE_COLS);
+        values
+            .chunks_mut(NUM_BITWISE_COLS)
+            .zip(input.bitwise_events.iter())
+            .for_each(|(row, event)| {
+                let cols: &mut BitwiseCols<F> = row.borrow_mut();
                 let a = event.a.to_le_bytes();
                 let b = event.b.to_le_bytes();
                 let c = event.c.to_le_bytes();
@@ -97,21 +106,10 @@ impl<F: PrimeField> MachineAir<F> for BitwiseChip {
                     };
                     output.add_byte_lookup_event(byte_event);
                 }
-
-                row
-            })
-            .collect::<Vec<_>>();
+            });
 
         // Convert the trace to a row major matrix.
-        let mut trace = RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_BITWISE_COLS,
-        );
-
-        // Pad the trace to a power of two.
-        pad_to_power_of_two::<NUM_BITWISE_COLS, F>(&mut trace.values);
-
-        trace
+        RowMajorMatrix::new(values, NUM_BITWISE_COLS)
     }
 
     fn included(&self, shard: &Self::Record) -> bool {
@@ -82,7 +82,7 @@ use crate::bytes::{ByteLookupEvent, ByteOpcode};
 use crate::disassembler::WORD_SIZE;
 use crate::operations::{IsEqualWordOperation, IsZeroWordOperation};
 use crate::runtime::{ExecutionRecord, Opcode, Program};
-use crate::utils::pad_to_power_of_two;
+use crate::utils::padded_trace_buffer;
 
 /// The number of main trace columns for `DivRemChip`.
 pub const NUM_DIVREM_COLS: usize = size_of::<DivRemCols<u8>>();
@@ -204,19 +204,29 @@ impl<F: PrimeField> MachineAir<F> for DivRemChip {
         &self,
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
     ) -> RowMajorMatrix<F> {
         // Generate the trace rows for each event.
-        let mut rows: Vec<[F; NUM_DIVREM_COLS]> = vec![];
-        let divrem_events = input.divrem_events.clone();
-        for event in divrem_events.iter() {
+        let mut values = padded_trace_buffer(buffer, input.divrem_events.len(), NUM_DIVREM_COLS);
+        for (row, event) in values
+            .chunks_mut(NUM_DIVREM_COLS)
+            .zip(input.divrem_events.iter())
+        {
             assert!(
                 event.opcode == Opcode::DIVU
                     || event.opcode == Opcode::REMU
                     || event.opcode == Opcode::REM
                     || event.opcode == Opcode::DIV
             );
-            let mut row = [F::zero(); NUM_DIVREM_COLS];
-            let cols: &mut DivRemCols<F> = row.as_mut_slice().borrow_mut();
+            let cols: &mut DivRemCols<F> = row.borrow_mut();
 
             // Initialize cols with basic operands and flags derived from the current event.
             {
@@ -397,18 +407,10 @@ impl<F: PrimeField> MachineAir<F> for DivRemChip {
                     output.add_u8_range_checks(event.shard, event.channel, &c_times_quotient);
                 }
             }
-
-            rows.push(row);
         }
 
         // Convert the trace to a row major matrix.
-        let mut trace = RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_DIVREM_COLS,
-        );
-
-        // Pad the trace to a power of two.
-        pad_to_power_of_two::<NUM_DIVREM_COLS, F>(&mut trace.values);
+        let mut trace = RowMajorMatrix::new(values, NUM_DIVREM_COLS);
 
         // Create the template for the padded rows. These are fake rows that don't fail on some
         // sanity checks.
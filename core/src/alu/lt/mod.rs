@@ -15,7 +15,7 @@ use crate::air::{SP1AirBuilder, Word};
 use crate::bytes::event::ByteRecord;
 use crate::bytes::{ByteLookupEvent, ByteOpcode};
 use crate::runtime::{ExecutionRecord, Opcode, Program};
-use crate::utils::pad_to_power_of_two;
+use crate::utils::padded_trace_buffer;
 
 /// The number of main trace columns for `LtChip`.
 pub const NUM_LT_COLS: usize = size_of::<LtCols<u8>>();
@@ -106,14 +106,23 @@ impl<F: PrimeField32> MachineAir<F> for LtChip {
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
     ) -> RowMajorMatrix<F> {
-        // Generate the trace rows for each event.
-        let (rows, new_byte_lookup_events): (Vec<_>, Vec<_>) = input
-            .lt_events
-            .par_iter()
-            .map(|event| {
-                let mut row = [F::zero(); NUM_LT_COLS];
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
+    ) -> RowMajorMatrix<F> {
+        // Generate the trace rows for each event, directly into the padded trace.
+        let mut values = padded_trace_buffer(buffer, input.lt_events.len(), NUM_LT_COLS);
+        let new_byte_lookup_events = values
+            .par_chunks_mut(NUM_LT_COLS)
+            .zip(input.lt_events.par_iter())
+            .map(|(row, event)| {
                 let mut new_byte_lookup_events: Vec<ByteLookupEvent> = Vec::new();
-                let cols: &mut LtCols<F> = row.as_mut_slice().borrow_mut();
+                let cols: &mut LtCols<F> = row.borrow_mut();
                 let a = event.a.to_le_bytes();
                 let b = event.b.to_le_bytes();
                 let c = event.c.to_le_bytes();
@@ -205,22 +214,16 @@ impl<F: PrimeField32> MachineAir<F> for LtChip {
                     c: cols.comparison_bytes[1].as_canonical_u32(),
                 });
 
-                (row, new_byte_lookup_events)
+                new_byte_lookup_events
             })
-            .unzip();
+            .collect::<Vec<_>>();
 
         for byte_lookup_events in new_byte_lookup_events {
             output.add_byte_lookup_events(byte_lookup_events);
         }
 
         // Convert the trace to a row major matrix.
-        let mut trace =
-            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_LT_COLS);
-
-        // Pad the trace to a power of two.
-        pad_to_power_of_two::<NUM_LT_COLS, F>(&mut trace.values);
-
-        trace
+        RowMajorMatrix::new(values, NUM_LT_COLS)
     }
 
     fn included(&self, shard: &Self::Record) -> bool {
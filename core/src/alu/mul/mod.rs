@@ -38,8 +38,7 @@ use p3_field::AbstractField;
 use p3_field::PrimeField;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
-use p3_maybe_rayon::prelude::ParallelIterator;
-use p3_maybe_rayon::prelude::ParallelSlice;
+use p3_maybe_rayon::prelude::*;
 use sp1_derive::AlignedBorrow;
 
 use crate::air::MachineAir;
@@ -50,7 +49,7 @@ use crate::bytes::{ByteLookupEvent, ByteOpcode};
 use crate::disassembler::WORD_SIZE;
 use crate::runtime::{ExecutionRecord, Opcode, Program};
 use crate::stark::MachineRecord;
-use crate::utils::pad_to_power_of_two;
+use crate::utils::padded_trace_buffer;
 
 /// The number of main trace columns for `MulChip`.
 pub const NUM_MUL_COLS: usize = size_of::<MulCols<u8>>();
@@ -135,19 +134,31 @@ impl<F: PrimeField> MachineAir<F> for MulChip {
         &self,
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
     ) -> RowMajorMatrix<F> {
         let mul_events = input.mul_events.clone();
         // Compute the chunk size based on the number of events and the number of CPUs.
         let chunk_size = std::cmp::max(mul_events.len() / num_cpus::get(), 1);
 
-        // Generate the trace rows & corresponding records for each chunk of events in parallel.
-        let rows_and_records = mul_events
-            .par_chunks(chunk_size)
-            .map(|events| {
+        // Generate the trace rows & corresponding records for each chunk of events in parallel,
+        // writing the rows directly into the padded trace.
+        let mut values = padded_trace_buffer(buffer, mul_events.len(), NUM_MUL_COLS);
+        let records = values
+            .par_chunks_mut(chunk_size * NUM_MUL_COLS)
+            .zip(mul_events.par_chunks(chunk_size))
+            .map(|(rows, events)| {
                 let mut record = ExecutionRecord::default();
-                let rows = events
-                    .iter()
-                    .map(|event| {
+                rows.chunks_mut(NUM_MUL_COLS)
+                    .zip(events)
+                    .for_each(|(row, event)| {
                         // Ensure that the opcode is MUL, MULHU, MULH, or MULHSU.
                         assert!(
                             event.opcode == Opcode::MUL
@@ -155,8 +166,7 @@ impl<F: PrimeField> MachineAir<F> for MulChip {
                                 || event.opcode == Opcode::MULH
                                 || event.opcode == Opcode::MULHSU
                         );
-                        let mut row = [F::zero(); NUM_MUL_COLS];
-                        let cols: &mut MulCols<F> = row.as_mut_slice().borrow_mut();
+                        let cols: &mut MulCols<F> = row.borrow_mut();
 
                         let a_word = event.a.to_le_bytes();
                         let b_word = event.b.to_le_bytes();
@@ -249,28 +259,17 @@ impl<F: PrimeField> MachineAir<F> for MulChip {
                                 &product.map(|x| x as u8),
                             );
                         }
-                        row
-                    })
-                    .collect::<Vec<_>>();
-                (rows, record)
+                    });
+                record
             })
             .collect::<Vec<_>>();
 
-        // Generate the trace rows for each event.
-        let mut rows: Vec<[F; NUM_MUL_COLS]> = vec![];
-        for mut row_and_record in rows_and_records {
-            rows.extend(row_and_record.0);
-            output.append(&mut row_and_record.1);
+        for mut record in records {
+            output.append(&mut record);
         }
 
         // Convert the trace to a row major matrix.
-        let mut trace =
-            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_MUL_COLS);
-
-        // Pad the trace to a power of two.
-        pad_to_power_of_two::<NUM_MUL_COLS, F>(&mut trace.values);
-
-        trace
+        RowMajorMatrix::new(values, NUM_MUL_COLS)
     }
 
     fn included(&self, shard: &Self::Record) -> bool {
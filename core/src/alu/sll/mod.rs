@@ -45,7 +45,7 @@ use crate::air::{SP1AirBuilder, Word};
 use crate::bytes::event::ByteRecord;
 use crate::disassembler::WORD_SIZE;
 use crate::runtime::{ExecutionRecord, Opcode, Program};
-use crate::utils::pad_to_power_of_two;
+use crate::utils::padded_trace_buffer;
 
 /// The number of main trace columns for `ShiftLeft`.
 pub const NUM_SHIFT_LEFT_COLS: usize = size_of::<ShiftLeftCols<u8>>();
@@ -110,13 +110,24 @@ impl<F: PrimeField> MachineAir<F> for ShiftLeft {
         &self,
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
     ) -> RowMajorMatrix<F> {
         // Generate the trace rows for each event.
-        let mut rows: Vec<[F; NUM_SHIFT_LEFT_COLS]> = vec![];
-        let shift_left_events = input.shift_left_events.clone();
-        for event in shift_left_events.iter() {
-            let mut row = [F::zero(); NUM_SHIFT_LEFT_COLS];
-            let cols: &mut ShiftLeftCols<F> = row.as_mut_slice().borrow_mut();
+        let mut values =
+            padded_trace_buffer(buffer, input.shift_left_events.len(), NUM_SHIFT_LEFT_COLS);
+        for (row, event) in values
+            .chunks_mut(NUM_SHIFT_LEFT_COLS)
+            .zip(input.shift_left_events.iter())
+        {
+            let cols: &mut ShiftLeftCols<F> = row.borrow_mut();
             let a = event.a.to_le_bytes();
             let b = event.b.to_le_bytes();
             let c = event.c.to_le_bytes();
@@ -171,18 +182,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftLeft {
                     F::from_canonical_u8(a[i])
                 );
             }
-
-            rows.push(row);
         }
 
         // Convert the trace to a row major matrix.
-        let mut trace = RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_SHIFT_LEFT_COLS,
-        );
-
-        // Pad the trace to a power of two.
-        pad_to_power_of_two::<NUM_SHIFT_LEFT_COLS, F>(&mut trace.values);
+        let mut trace = RowMajorMatrix::new(values, NUM_SHIFT_LEFT_COLS);
 
         // Create the template for the padded rows. These are fake rows that don't fail on some
         // sanity checks.
@@ -60,7 +60,7 @@ use crate::bytes::utils::shr_carry;
 use crate::bytes::{ByteLookupEvent, ByteOpcode};
 use crate::disassembler::WORD_SIZE;
 use crate::runtime::{ExecutionRecord, Opcode, Program};
-use crate::utils::pad_to_power_of_two;
+use crate::utils::padded_trace_buffer;
 
 /// The number of main trace columns for `ShiftRightChip`.
 pub const NUM_SHIFT_RIGHT_COLS: usize = size_of::<ShiftRightCols<u8>>();
@@ -141,14 +141,25 @@ impl<F: PrimeField> MachineAir<F> for ShiftRightChip {
         &self,
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
     ) -> RowMajorMatrix<F> {
         // Generate the trace rows for each event.
-        let mut rows: Vec<[F; NUM_SHIFT_RIGHT_COLS]> = Vec::new();
-        let sr_events = input.shift_right_events.clone();
-        for event in sr_events.iter() {
+        let mut values =
+            padded_trace_buffer(buffer, input.shift_right_events.len(), NUM_SHIFT_RIGHT_COLS);
+        for (row, event) in values
+            .chunks_mut(NUM_SHIFT_RIGHT_COLS)
+            .zip(input.shift_right_events.iter())
+        {
             assert!(event.opcode == Opcode::SRL || event.opcode == Opcode::SRA);
-            let mut row = [F::zero(); NUM_SHIFT_RIGHT_COLS];
-            let cols: &mut ShiftRightCols<F> = row.as_mut_slice().borrow_mut();
+            let cols: &mut ShiftRightCols<F> = row.borrow_mut();
             // Initialize cols with basic operands and flags derived from the current event.
             {
                 cols.shard = F::from_canonical_u32(event.shard);
@@ -254,18 +265,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftRightChip {
                     &shr_carry_output_shifted_byte,
                 );
             }
-
-            rows.push(row);
         }
 
         // Convert the trace to a row major matrix.
-        let mut trace = RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_SHIFT_RIGHT_COLS,
-        );
-
-        // Pad the trace to a power of two.
-        pad_to_power_of_two::<NUM_SHIFT_RIGHT_COLS, F>(&mut trace.values);
+        let mut trace = RowMajorMatrix::new(values, NUM_SHIFT_RIGHT_COLS);
 
         // Create the template for the padded rows. These are fake rows that don't fail on some
         // sanity checks.
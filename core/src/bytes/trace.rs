@@ -52,7 +52,10 @@ impl<F: Field> MachineAir<F> for ByteChip<F> {
             NUM_BYTE_MULT_COLS,
         );
 
-        for (lookup, mult) in input.byte_lookups[&shard].iter() {
+        // Precompile shards have no byte lookups of their own, since the lookups of precompile
+        // events are counted in the shard the events were executed in.
+        let byte_lookups = input.byte_lookups.get(&shard).into_iter().flatten();
+        for (lookup, mult) in byte_lookups {
             let (row, index) = event_map[lookup];
             let channel = lookup.channel as usize;
             let cols: &mut ByteMultCols<F> = trace.row_mut(row).borrow_mut();
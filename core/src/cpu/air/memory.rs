@@ -21,6 +21,25 @@ impl CpuChip {
             + opcode_selectors.is_sb
             + opcode_selectors.is_sh
             + opcode_selectors.is_sw
+            + self.is_atomic_instruction::<AB>(opcode_selectors)
+    }
+
+    /// Computes whether the opcode is an atomic memory instruction.
+    pub(crate) fn is_atomic_instruction<AB: SP1AirBuilder>(
+        &self,
+        opcode_selectors: &OpcodeSelectorCols<AB::Var>,
+    ) -> AB::Expr {
+        opcode_selectors.is_lr_w
+            + opcode_selectors.is_sc_w
+            + opcode_selectors.is_amoswap_w
+            + opcode_selectors.is_amoadd_w
+            + opcode_selectors.is_amoxor_w
+            + opcode_selectors.is_amoand_w
+            + opcode_selectors.is_amoor_w
+            + opcode_selectors.is_amomin_w
+            + opcode_selectors.is_amomax_w
+            + opcode_selectors.is_amominu_w
+            + opcode_selectors.is_amomaxu_w
     }
 
     /// Computes whether the opcode is a load instruction.
@@ -46,7 +65,8 @@ impl CpuChip {
     /// Constrains the addr_aligned, addr_offset, and addr_word memory columns.
     ///
     /// This method will do the following:
-    /// 1. Calculate that the unaligned address is correctly computed to be op_b.value + op_c.value.
+    /// 1. Calculate that the unaligned address is correctly computed to be op_b.value + op_c.value,
+    ///    or op_b.value for atomic instructions.
     /// 2. Calculate that the address offset is address % 4.
     /// 3. Assert the validity of the aligned address given the address offset and the unaligned address.
     pub(crate) fn eval_memory_address_and_access<AB: SP1AirBuilder>(
@@ -57,6 +77,7 @@ impl CpuChip {
     ) {
         // Get the memory specific columns.
         let memory_columns = local.opcode_specific_columns.memory();
+        let is_atomic = self.is_atomic_instruction::<AB>(&local.selectors);
 
         // Send to the ALU table to verify correct calculation of addr_word.
         builder.send_alu(
@@ -66,9 +87,14 @@ impl CpuChip {
             local.op_c_val(),
             local.shard,
             local.channel,
-            is_memory_instruction.clone(),
+            is_memory_instruction.clone() - is_atomic.clone(),
         );
 
+        // Atomic instructions have no offset, and op_c holds the source register instead.
+        builder
+            .when(is_atomic)
+            .assert_word_eq(memory_columns.addr_word, local.op_b_val());
+
         // Check that each addr_word element is a byte.
         builder.slice_range_check_u8(
             &memory_columns.addr_word.0,
@@ -215,6 +241,95 @@ impl CpuChip {
             .assert_word_eq(mem_val.map(|x| x.into()), a_val.map(|x| x.into()));
     }
 
+    /// Evaluates constraints related to the atomic memory instructions.
+    ///
+    /// Each atomic instruction is a read-modify-write of the word at op_b.value: op_a receives the
+    /// previous memory value and the new memory value is computed from it and op_c.value.
+    pub(crate) fn eval_memory_atomic<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &CpuCols<AB::Var>,
+    ) {
+        let memory_columns = local.opcode_specific_columns.memory();
+        let selectors = &local.selectors;
+        let is_atomic = self.is_atomic_instruction::<AB>(selectors);
+
+        let one = AB::Expr::one();
+        let c_val = local.op_c_val();
+        let mem_val = *memory_columns.memory_access.value();
+        let prev_mem_val = *memory_columns.memory_access.prev_value();
+
+        // Atomic instructions must access a word aligned address.
+        builder
+            .when(is_atomic.clone())
+            .assert_zero(memory_columns.addr_offset);
+
+        // Write the previous memory value to op_a.  SC.W always succeeds, since a reservation can
+        // only be invalidated by another hart, so it writes zero instead.
+        builder
+            .when(is_atomic - selectors.is_sc_w)
+            .when_not(local.instruction.op_a_0)
+            .assert_word_eq(local.op_a_val(), prev_mem_val);
+        builder
+            .when(selectors.is_sc_w)
+            .assert_word_zero(local.op_a_val());
+
+        // LR.W leaves the memory value unchanged, while SC.W and AMOSWAP.W store op_c.
+        builder
+            .when(selectors.is_lr_w)
+            .assert_word_eq(mem_val, prev_mem_val);
+        builder
+            .when(selectors.is_sc_w + selectors.is_amoswap_w)
+            .assert_word_eq(mem_val, c_val);
+
+        // Use the ALU table to verify the new memory value of AMOADD.W, AMOXOR.W, AMOAND.W, and
+        // AMOOR.W.
+        let alu_opcode = selectors.is_amoadd_w * AB::Expr::from_canonical_u32(Opcode::ADD as u32)
+            + selectors.is_amoxor_w * AB::Expr::from_canonical_u32(Opcode::XOR as u32)
+            + selectors.is_amoand_w * AB::Expr::from_canonical_u32(Opcode::AND as u32)
+            + selectors.is_amoor_w * AB::Expr::from_canonical_u32(Opcode::OR as u32);
+        builder.send_alu(
+            alu_opcode,
+            mem_val,
+            prev_mem_val,
+            c_val,
+            local.shard,
+            local.channel,
+            selectors.is_amoadd_w
+                + selectors.is_amoxor_w
+                + selectors.is_amoand_w
+                + selectors.is_amoor_w,
+        );
+
+        // For AMOMIN.W, AMOMAX.W, AMOMINU.W, and AMOMAXU.W, use the ALU table to compare the
+        // previous memory value with op_c, and select the new memory value with the result.
+        let is_signed = selectors.is_amomin_w + selectors.is_amomax_w;
+        let is_unsigned = selectors.is_amominu_w + selectors.is_amomaxu_w;
+        builder.send_alu(
+            is_signed.clone() * AB::Expr::from_canonical_u32(Opcode::SLT as u32)
+                + is_unsigned.clone() * AB::Expr::from_canonical_u32(Opcode::SLTU as u32),
+            Word::extend_var::<AB>(memory_columns.amo_lt),
+            prev_mem_val,
+            c_val,
+            local.shard,
+            local.channel,
+            is_signed + is_unsigned,
+        );
+        let lt = memory_columns.amo_lt;
+        let min_value = Word(core::array::from_fn(|i| {
+            lt * prev_mem_val[i] + (one.clone() - lt) * c_val[i]
+        }));
+        let max_value = Word(core::array::from_fn(|i| {
+            lt * c_val[i] + (one.clone() - lt) * prev_mem_val[i]
+        }));
+        builder
+            .when(selectors.is_amomin_w + selectors.is_amominu_w)
+            .assert_word_eq(mem_val.map(|x| x.into()), min_value);
+        builder
+            .when(selectors.is_amomax_w + selectors.is_amomaxu_w)
+            .assert_word_eq(mem_val.map(|x| x.into()), max_value);
+    }
+
     /// This function is used to evaluate the unsigned memory value for the load memory instructions.
     pub(crate) fn eval_unsigned_mem_value<AB: SP1AirBuilder>(
         &self,
@@ -65,6 +65,7 @@ where
         self.eval_memory_address_and_access::<AB>(builder, local, is_memory_instruction.clone());
         self.eval_memory_load::<AB>(builder, local);
         self.eval_memory_store::<AB>(builder, local);
+        self.eval_memory_atomic::<AB>(builder, local);
 
         // Channel constraints.
         eval_channel_selectors(
@@ -133,6 +134,14 @@ impl CpuChip {
         opcode_selectors.is_alu.into()
     }
 
+    /// The size of the instruction in bytes, which is 2 for compressed instructions and 4 otherwise.
+    pub(crate) fn instruction_size<AB: SP1AirBuilder>(
+        &self,
+        opcode_selectors: &OpcodeSelectorCols<AB::Var>,
+    ) -> AB::Expr {
+        AB::Expr::from_canonical_u8(4) - AB::Expr::two() * opcode_selectors.is_compressed
+    }
+
     /// Constraints related to jump operations.
     pub(crate) fn eval_jump_ops<AB: SP1AirBuilder>(
         &self,
@@ -145,7 +154,8 @@ impl CpuChip {
 
         let is_jump_instruction = local.selectors.is_jal + local.selectors.is_jalr;
 
-        // Verify that the local.pc + 4 is saved in op_a for both jump instructions.
+        // Verify that the address of the next instruction, local.pc + 4 (or local.pc + 2 for
+        // compressed instructions), is saved in op_a for both jump instructions.
         // When op_a is set to register X0, the RISC-V spec states that the jump instruction will
         // not have a return destination address (it is effectively a GOTO command).  In this case,
         // we shouldn't verify the return address.
@@ -154,7 +164,7 @@ impl CpuChip {
             .when_not(local.instruction.op_a_0)
             .assert_eq(
                 local.op_a_val().reduce::<AB>(),
-                local.pc + AB::F::from_canonical_u8(4),
+                local.pc + self.instruction_size::<AB>(&local.selectors),
             );
 
         // Verify that the word form of local.pc is correct for JAL instructions.
@@ -301,19 +311,21 @@ impl CpuChip {
             is_branch_instruction + local.selectors.is_jal + local.selectors.is_jalr + is_halt,
         );
 
-        // Verify that the pc increments by 4 for all instructions except branch, jump and halt instructions.
+        // Verify that the pc increments by the instruction size (4, or 2 for compressed instructions)
+        // for all instructions except branch, jump and halt instructions.
         // The other case is handled by eval_jump, eval_branch and eval_ecall (for halt).
+        let instruction_size = self.instruction_size::<AB>(&local.selectors);
         builder
             .when_transition()
             .when(next.is_real)
             .when(local.is_sequential_instr)
-            .assert_eq(local.pc + AB::Expr::from_canonical_u8(4), next.pc);
+            .assert_eq(local.pc + instruction_size.clone(), next.pc);
 
-        // When the last row is real and it's a sequential instruction, assert that local.next_pc <==> local.pc + 4
+        // When the last row is real and it's a sequential instruction, assert that local.next_pc <==> local.pc + instruction_size
         builder
             .when(local.is_real)
             .when(local.is_sequential_instr)
-            .assert_eq(local.pc + AB::Expr::from_canonical_u8(4), local.next_pc);
+            .assert_eq(local.pc + instruction_size, local.next_pc);
     }
 
     /// Constraints related to the public values.
@@ -17,6 +17,9 @@ pub struct OpcodeSelectorCols<T> {
     /// Whether op_c is an immediate value.
     pub imm_c: T,
 
+    /// Whether the instruction is a 16-bit compressed instruction.
+    pub is_compressed: T,
+
     /// Table selectors for opcodes.
     pub is_alu: T,
 
@@ -33,6 +36,19 @@ pub struct OpcodeSelectorCols<T> {
     pub is_sh: T,
     pub is_sw: T,
 
+    /// Atomic Instructions.
+    pub is_lr_w: T,
+    pub is_sc_w: T,
+    pub is_amoswap_w: T,
+    pub is_amoadd_w: T,
+    pub is_amoxor_w: T,
+    pub is_amoand_w: T,
+    pub is_amoor_w: T,
+    pub is_amomin_w: T,
+    pub is_amomax_w: T,
+    pub is_amominu_w: T,
+    pub is_amomaxu_w: T,
+
     /// Branch Instructions.
     pub is_beq: T,
     pub is_bne: T,
@@ -54,6 +70,7 @@ impl<F: PrimeField> OpcodeSelectorCols<F> {
     pub fn populate(&mut self, instruction: Instruction) {
         self.imm_b = F::from_bool(instruction.imm_b);
         self.imm_c = F::from_bool(instruction.imm_c);
+        self.is_compressed = F::from_bool(instruction.compressed);
 
         if instruction.is_alu_instruction() {
             self.is_alu = F::one();
@@ -69,6 +86,17 @@ impl<F: PrimeField> OpcodeSelectorCols<F> {
                 Opcode::SB => self.is_sb = F::one(),
                 Opcode::SH => self.is_sh = F::one(),
                 Opcode::SW => self.is_sw = F::one(),
+                Opcode::LR_W => self.is_lr_w = F::one(),
+                Opcode::SC_W => self.is_sc_w = F::one(),
+                Opcode::AMOSWAP_W => self.is_amoswap_w = F::one(),
+                Opcode::AMOADD_W => self.is_amoadd_w = F::one(),
+                Opcode::AMOXOR_W => self.is_amoxor_w = F::one(),
+                Opcode::AMOAND_W => self.is_amoand_w = F::one(),
+                Opcode::AMOOR_W => self.is_amoor_w = F::one(),
+                Opcode::AMOMIN_W => self.is_amomin_w = F::one(),
+                Opcode::AMOMAX_W => self.is_amomax_w = F::one(),
+                Opcode::AMOMINU_W => self.is_amominu_w = F::one(),
+                Opcode::AMOMAXU_W => self.is_amomaxu_w = F::one(),
                 _ => unreachable!(),
             }
         } else if instruction.is_branch_instruction() {
@@ -101,6 +129,7 @@ impl<T> IntoIterator for OpcodeSelectorCols<T> {
         vec![
             self.imm_b,
             self.imm_c,
+            self.is_compressed,
             self.is_alu,
             self.is_ecall,
             self.is_lb,
@@ -111,6 +140,17 @@ impl<T> IntoIterator for OpcodeSelectorCols<T> {
             self.is_sb,
             self.is_sh,
             self.is_sw,
+            self.is_lr_w,
+            self.is_sc_w,
+            self.is_amoswap_w,
+            self.is_amoadd_w,
+            self.is_amoxor_w,
+            self.is_amoand_w,
+            self.is_amoor_w,
+            self.is_amomin_w,
+            self.is_amomax_w,
+            self.is_amominu_w,
+            self.is_amomaxu_w,
             self.is_beq,
             self.is_bne,
             self.is_blt,
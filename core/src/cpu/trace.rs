@@ -34,6 +34,15 @@ impl<F: PrimeField32> MachineAir<F> for CpuChip {
         &self,
         input: &ExecutionRecord,
         output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        self.generate_trace_into(input, output, Vec::new())
+    }
+
+    fn generate_trace_into(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        buffer: Vec<F>,
     ) -> RowMajorMatrix<F> {
         let mut new_alu_events = HashMap::new();
         let mut new_blu_events = Vec::new();
@@ -48,7 +57,9 @@ impl<F: PrimeField32> MachineAir<F> for CpuChip {
         // No need to sort by the shard, since the cpu events are already partitioned by that.
         rows_with_events.sort_unstable_by_key(|(event, _, _)| event[CPU_COL_MAP.clk]);
 
-        let mut rows = Vec::<F>::new();
+        let mut rows = buffer;
+        rows.clear();
+        rows.reserve(rows_with_events.len() * NUM_CPU_COLS);
         rows_with_events.into_iter().for_each(|row_with_events| {
             let (row, alu_events, blu_events) = row_with_events;
             rows.extend(row);
@@ -114,8 +125,8 @@ impl<F: PrimeField32> MachineAir<F> for CpuChip {
             });
     }
 
-    fn included(&self, _: &Self::Record) -> bool {
-        true
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.is_precompile_shard()
     }
 }
 
@@ -244,41 +255,38 @@ impl CpuChip {
         new_alu_events: &mut HashMap<Opcode, Vec<alu::AluEvent>>,
         new_blu_events: &mut Vec<ByteLookupEvent>,
     ) {
-        if !matches!(
-            event.instruction.opcode,
-            Opcode::LB
-                | Opcode::LH
-                | Opcode::LW
-                | Opcode::LBU
-                | Opcode::LHU
-                | Opcode::SB
-                | Opcode::SH
-                | Opcode::SW
-        ) {
+        if !event.instruction.is_memory_instruction() {
             return;
         }
 
         // Populate addr_word and addr_aligned columns.
         let memory_columns = cols.opcode_specific_columns.memory_mut();
-        let memory_addr = event.b.wrapping_add(event.c);
+        let is_atomic = event.instruction.is_atomic_instruction();
+        let memory_addr = if is_atomic {
+            event.b
+        } else {
+            event.b.wrapping_add(event.c)
+        };
         memory_columns.addr_word = memory_addr.into();
         memory_columns.addr_aligned =
             F::from_canonical_u32(memory_addr - memory_addr % WORD_SIZE as u32);
 
         // Add event to ALU check to check that addr == b + c
-        let add_event = AluEvent {
-            shard: event.shard,
-            channel: event.channel,
-            clk: event.clk,
-            opcode: Opcode::ADD,
-            a: memory_addr,
-            b: event.b,
-            c: event.c,
-        };
-        new_alu_events
-            .entry(Opcode::ADD)
-            .and_modify(|op_new_events| op_new_events.push(add_event))
-            .or_insert(vec![add_event]);
+        if !is_atomic {
+            let add_event = AluEvent {
+                shard: event.shard,
+                channel: event.channel,
+                clk: event.clk,
+                opcode: Opcode::ADD,
+                a: memory_addr,
+                b: event.b,
+                c: event.c,
+            };
+            new_alu_events
+                .entry(Opcode::ADD)
+                .and_modify(|op_new_events| op_new_events.push(add_event))
+                .or_insert(vec![add_event]);
+        }
 
         // Populate memory offsets.
         let addr_offset = (memory_addr % WORD_SIZE as u32) as u8;
@@ -349,6 +357,42 @@ impl CpuChip {
             }
         }
 
+        // For the atomic instructions, add the ALU events used to compute the new memory value.
+        if let Some(MemoryRecordEnum::Write(record)) = event.memory_record {
+            let alu_event = match event.instruction.opcode {
+                Opcode::AMOADD_W => Some((Opcode::ADD, record.value)),
+                Opcode::AMOXOR_W => Some((Opcode::XOR, record.value)),
+                Opcode::AMOAND_W => Some((Opcode::AND, record.value)),
+                Opcode::AMOOR_W => Some((Opcode::OR, record.value)),
+                Opcode::AMOMIN_W | Opcode::AMOMAX_W => Some((
+                    Opcode::SLT,
+                    ((record.prev_value as i32) < (event.c as i32)) as u32,
+                )),
+                Opcode::AMOMINU_W | Opcode::AMOMAXU_W => {
+                    Some((Opcode::SLTU, (record.prev_value < event.c) as u32))
+                }
+                _ => None,
+            };
+            if let Some((opcode, a)) = alu_event {
+                if matches!(opcode, Opcode::SLT | Opcode::SLTU) {
+                    memory_columns.amo_lt = F::from_canonical_u32(a);
+                }
+                let alu_event = AluEvent {
+                    shard: event.shard,
+                    channel: event.channel,
+                    clk: event.clk,
+                    opcode,
+                    a,
+                    b: record.prev_value,
+                    c: event.c,
+                };
+                new_alu_events
+                    .entry(opcode)
+                    .and_modify(|op_new_events| op_new_events.push(alu_event))
+                    .or_insert(vec![alu_event]);
+            }
+        }
+
         // Add event to byte lookup for byte range checking each byte in the memory addr
         let addr_bytes = memory_addr.to_le_bytes();
         for byte_pair in addr_bytes.chunks_exact(2) {
@@ -659,6 +703,7 @@ mod tests {
                 op_c: 2,
                 imm_b: false,
                 imm_c: false,
+                compressed: false,
             },
             a: 1,
             a_record: None,
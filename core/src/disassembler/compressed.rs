@@ -0,0 +1,294 @@
+//! Expansion of the 16-bit instructions in the RV32C extension.
+//!
+//! Every compressed instruction has an equivalent 32-bit instruction in RV32I, so the compressed
+//! instructions are expanded into their 32-bit encoding and then transpiled like any other
+//! instruction. The floating point instructions are not supported.
+//!
+//! Reference: The RISC-V Instruction Set Manual, Volume I, Chapter 16 ("C" Standard Extension).
+
+const OPCODE_LOAD: u32 = 0b0000011;
+const OPCODE_OP_IMM: u32 = 0b0010011;
+const OPCODE_STORE: u32 = 0b0100011;
+const OPCODE_OP: u32 = 0b0110011;
+const OPCODE_LUI: u32 = 0b0110111;
+const OPCODE_BRANCH: u32 = 0b1100011;
+const OPCODE_JALR: u32 = 0b1100111;
+const OPCODE_JAL: u32 = 0b1101111;
+
+/// The encoding of `ebreak`.
+const EBREAK: u32 = 0x00100073;
+
+/// Returns whether the low bits of an instruction indicate a 16-bit compressed instruction.
+pub const fn is_compressed(instruction: u16) -> bool {
+    instruction & 0b11 != 0b11
+}
+
+/// Expand a 16-bit compressed instruction into its equivalent 32-bit encoding.
+///
+/// Returns `None` if the instruction is illegal, reserved, or not supported.
+pub fn expand_compressed(instruction: u16) -> Option<u32> {
+    let inst = instruction as u32;
+    let funct3 = bits(inst, 15, 13);
+
+    // The full and the compact (x8-x15) register fields.
+    let rd = bits(inst, 11, 7);
+    let rs2 = bits(inst, 6, 2);
+    let rs1_prime = bits(inst, 9, 7) + 8;
+    let rs2_prime = bits(inst, 4, 2) + 8;
+
+    // The 6-bit immediate used by C.ADDI, C.LI, C.ANDI, and the shifts.
+    let imm6 = (bits(inst, 12, 12) << 5) | bits(inst, 6, 2);
+
+    match (inst & 0b11, funct3) {
+        // C.ADDI4SPN
+        (0b00, 0b000) => {
+            let imm = (bits(inst, 12, 11) << 4)
+                | (bits(inst, 10, 7) << 6)
+                | (bits(inst, 6, 6) << 2)
+                | (bits(inst, 5, 5) << 3);
+            (imm != 0).then(|| i_type(imm, 2, 0b000, rs2_prime, OPCODE_OP_IMM))
+        }
+        // C.LW
+        (0b00, 0b010) => Some(i_type(
+            lw_sw_offset(inst),
+            rs1_prime,
+            0b010,
+            rs2_prime,
+            OPCODE_LOAD,
+        )),
+        // C.SW
+        (0b00, 0b110) => Some(s_type(
+            lw_sw_offset(inst),
+            rs2_prime,
+            rs1_prime,
+            0b010,
+            OPCODE_STORE,
+        )),
+        // C.ADDI (and C.NOP)
+        (0b01, 0b000) => Some(i_type(sign_extend(imm6, 6), rd, 0b000, rd, OPCODE_OP_IMM)),
+        // C.JAL
+        (0b01, 0b001) => Some(j_type(cj_offset(inst), 1)),
+        // C.LI
+        (0b01, 0b010) => Some(i_type(sign_extend(imm6, 6), 0, 0b000, rd, OPCODE_OP_IMM)),
+        // C.ADDI16SP
+        (0b01, 0b011) if rd == 2 => {
+            let imm = (bits(inst, 12, 12) << 9)
+                | (bits(inst, 6, 6) << 4)
+                | (bits(inst, 5, 5) << 6)
+                | (bits(inst, 4, 3) << 7)
+                | (bits(inst, 2, 2) << 5);
+            (imm != 0).then(|| i_type(sign_extend(imm, 10), 2, 0b000, 2, OPCODE_OP_IMM))
+        }
+        // C.LUI
+        (0b01, 0b011) => (imm6 != 0).then(|| (sign_extend(imm6, 6) << 12) | (rd << 7) | OPCODE_LUI),
+        // C.SRLI, C.SRAI, C.ANDI, C.SUB, C.XOR, C.OR, and C.AND
+        (0b01, 0b100) => match bits(inst, 11, 10) {
+            // The shift amount must be less than 32 in RV32C.
+            0b00 if imm6 < 32 => Some(i_type(imm6, rs1_prime, 0b101, rs1_prime, OPCODE_OP_IMM)),
+            0b01 if imm6 < 32 => Some(i_type(
+                imm6 | 0x400,
+                rs1_prime,
+                0b101,
+                rs1_prime,
+                OPCODE_OP_IMM,
+            )),
+            0b10 => Some(i_type(
+                sign_extend(imm6, 6),
+                rs1_prime,
+                0b111,
+                rs1_prime,
+                OPCODE_OP_IMM,
+            )),
+            0b11 if bits(inst, 12, 12) == 0 => {
+                let (funct7, funct3) = match bits(inst, 6, 5) {
+                    0b00 => (0b0100000, 0b000),
+                    0b01 => (0, 0b100),
+                    0b10 => (0, 0b110),
+                    _ => (0, 0b111),
+                };
+                Some(r_type(funct7, rs2_prime, rs1_prime, funct3, rs1_prime))
+            }
+            _ => None,
+        },
+        // C.J
+        (0b01, 0b101) => Some(j_type(cj_offset(inst), 0)),
+        // C.BEQZ and C.BNEZ
+        (0b01, 0b110) | (0b01, 0b111) => {
+            let offset = (bits(inst, 12, 12) << 8)
+                | (bits(inst, 11, 10) << 3)
+                | (bits(inst, 6, 5) << 6)
+                | (bits(inst, 4, 3) << 1)
+                | (bits(inst, 2, 2) << 5);
+            Some(b_type(sign_extend(offset, 9), 0, rs1_prime, funct3 & 0b001))
+        }
+        // C.SLLI
+        (0b10, 0b000) if imm6 < 32 => Some(i_type(imm6, rd, 0b001, rd, OPCODE_OP_IMM)),
+        // C.LWSP
+        (0b10, 0b010) if rd != 0 => {
+            let offset =
+                (bits(inst, 12, 12) << 5) | (bits(inst, 6, 4) << 2) | (bits(inst, 3, 2) << 6);
+            Some(i_type(offset, 2, 0b010, rd, OPCODE_LOAD))
+        }
+        // C.JR, C.MV, C.EBREAK, C.JALR, and C.ADD
+        (0b10, 0b100) => match (bits(inst, 12, 12), rd, rs2) {
+            (0, 0, 0) => None,
+            (0, rs1, 0) => Some(i_type(0, rs1, 0b000, 0, OPCODE_JALR)),
+            (0, rd, rs2) => Some(r_type(0, rs2, 0, 0b000, rd)),
+            (1, 0, 0) => Some(EBREAK),
+            (1, rs1, 0) => Some(i_type(0, rs1, 0b000, 1, OPCODE_JALR)),
+            (_, rd, rs2) => Some(r_type(0, rs2, rd, 0b000, rd)),
+        },
+        // C.SWSP
+        (0b10, 0b110) => {
+            let offset = (bits(inst, 12, 9) << 2) | (bits(inst, 8, 7) << 6);
+            Some(s_type(offset, rs2, 2, 0b010, OPCODE_STORE))
+        }
+        _ => None,
+    }
+}
+
+/// Extract the bits `hi..=lo` of a value.
+const fn bits(value: u32, hi: u32, lo: u32) -> u32 {
+    (value >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+/// Sign extend the lowest `width` bits of a value.
+const fn sign_extend(value: u32, width: u32) -> u32 {
+    let shift = 32 - width;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+/// The offset of C.LW and C.SW.
+const fn lw_sw_offset(inst: u32) -> u32 {
+    (bits(inst, 12, 10) << 3) | (bits(inst, 6, 6) << 2) | (bits(inst, 5, 5) << 6)
+}
+
+/// The offset of C.J and C.JAL.
+const fn cj_offset(inst: u32) -> u32 {
+    let offset = (bits(inst, 12, 12) << 11)
+        | (bits(inst, 11, 11) << 4)
+        | (bits(inst, 10, 9) << 8)
+        | (bits(inst, 8, 8) << 10)
+        | (bits(inst, 7, 7) << 6)
+        | (bits(inst, 6, 6) << 7)
+        | (bits(inst, 5, 3) << 1)
+        | (bits(inst, 2, 2) << 5);
+    sign_extend(offset, 12)
+}
+
+const fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | OPCODE_OP
+}
+
+const fn i_type(imm: u32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    ((imm & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+const fn s_type(imm: u32, rs2: u32, rs1: u32, funct3: u32, opcode: u32) -> u32 {
+    (bits(imm, 11, 5) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 0) << 7)
+        | opcode
+}
+
+const fn b_type(imm: u32, rs2: u32, rs1: u32, funct3: u32) -> u32 {
+    (bits(imm, 12, 12) << 31)
+        | (bits(imm, 10, 5) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 1) << 8)
+        | (bits(imm, 11, 11) << 7)
+        | OPCODE_BRANCH
+}
+
+const fn j_type(imm: u32, rd: u32) -> u32 {
+    (bits(imm, 20, 20) << 31)
+        | (bits(imm, 10, 1) << 21)
+        | (bits(imm, 11, 11) << 20)
+        | (bits(imm, 19, 12) << 12)
+        | (rd << 7)
+        | OPCODE_JAL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_compressed() {
+        // The expected encodings are the output of the GNU assembler for the uncompressed form.
+        let cases: &[(u16, u32)] = &[
+            // c.addi4spn a0, sp, 16 => addi a0, sp, 16
+            (0x0808, 0x01010513),
+            // c.lw a0, 4(a1) => lw a0, 4(a1)
+            (0x41c8, 0x0045a503),
+            // c.sw a0, 4(a1) => sw a0, 4(a1)
+            (0xc1c8, 0x00a5a223),
+            // c.addi a0, -1 => addi a0, a0, -1
+            (0x157d, 0xfff50513),
+            // c.nop => addi zero, zero, 0
+            (0x0001, 0x00000013),
+            // c.li a0, 5 => addi a0, zero, 5
+            (0x4515, 0x00500513),
+            // c.addi16sp sp, -64 => addi sp, sp, -64
+            (0x7139, 0xfc010113),
+            // c.lui a0, 1 => lui a0, 0x1
+            (0x6505, 0x00001537),
+            // c.srli a0, 1 => srli a0, a0, 1
+            (0x8105, 0x00155513),
+            // c.srai a0, 1 => srai a0, a0, 1
+            (0x8505, 0x40155513),
+            // c.andi a0, -1 => andi a0, a0, -1
+            (0x997d, 0xfff57513),
+            // c.sub a0, a1 => sub a0, a0, a1
+            (0x8d0d, 0x40b50533),
+            // c.xor a0, a1 => xor a0, a0, a1
+            (0x8d2d, 0x00b54533),
+            // c.or a0, a1 => or a0, a0, a1
+            (0x8d4d, 0x00b56533),
+            // c.and a0, a1 => and a0, a0, a1
+            (0x8d6d, 0x00b57533),
+            // c.j -2 => jal zero, -2
+            (0xbffd, 0xfffff06f),
+            // c.jal 8 => jal ra, 8
+            (0x2021, 0x008000ef),
+            // c.beqz a0, 8 => beq a0, zero, 8
+            (0xc501, 0x00050463),
+            // c.bnez a0, -8 => bne a0, zero, -8
+            (0xfd65, 0xfe051ce3),
+            // c.slli a0, 2 => slli a0, a0, 2
+            (0x050a, 0x00251513),
+            // c.lwsp a0, 12(sp) => lw a0, 12(sp)
+            (0x4532, 0x00c12503),
+            // c.swsp a0, 12(sp) => sw a0, 12(sp)
+            (0xc62a, 0x00a12623),
+            // c.jr ra => jalr zero, 0(ra)
+            (0x8082, 0x00008067),
+            // c.mv a0, a1 => add a0, zero, a1
+            (0x852e, 0x00b00533),
+            // c.ebreak => ebreak
+            (0x9002, 0x00100073),
+            // c.jalr a0 => jalr ra, 0(a0)
+            (0x9502, 0x000500e7),
+            // c.add a0, a1 => add a0, a0, a1
+            (0x952e, 0x00b50533),
+        ];
+        for (compressed, expanded) in cases {
+            assert!(is_compressed(*compressed));
+            assert_eq!(
+                expand_compressed(*compressed),
+                Some(*expanded),
+                "failed to expand {:#06x}",
+                compressed
+            );
+        }
+
+        // The all-zero instruction is defined to be illegal.
+        assert_eq!(expand_compressed(0x0000), None);
+        // c.flw is not supported.
+        assert_eq!(expand_compressed(0x6188), None);
+    }
+}
@@ -12,7 +12,10 @@ pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 /// The size of a word in bytes.
 pub const WORD_SIZE: usize = 4;
 
-/// A RV32IM ELF file.
+/// The ELF header flag indicating that the file contains compressed instructions.
+const EF_RISCV_RVC: u32 = 0x0001;
+
+/// A RV32IM ELF file, optionally with compressed (RV32IMC) instructions.
 #[derive(Debug, Clone)]
 pub struct Elf {
     /// The instructions of the program encoded as 32-bits.
@@ -26,6 +29,9 @@ pub struct Elf {
 
     /// The initial memory image, useful for global constants.
     pub memory_image: BTreeMap<u32, u32>,
+
+    /// Whether the instructions may contain 16-bit compressed instructions.
+    pub compressed: bool,
 }
 
 impl Elf {
@@ -35,12 +41,14 @@ impl Elf {
         pc_start: u32,
         pc_base: u32,
         memory_image: BTreeMap<u32, u32>,
+        compressed: bool,
     ) -> Self {
         Self {
             instructions,
             pc_start,
             pc_base,
             memory_image,
+            compressed,
         }
     }
 
@@ -68,8 +76,12 @@ impl Elf {
             .try_into()
             .expect("e_entry was larger than 32 bits");
 
+        // Compressed instructions are only aligned to two bytes.
+        let compressed = elf.ehdr.e_flags & EF_RISCV_RVC != 0;
+        let instruction_alignment = if compressed { 2 } else { WORD_SIZE as u32 };
+
         // Make sure the entrypoint is valid.
-        if entry == MAXIMUM_MEMORY_SIZE || entry % WORD_SIZE as u32 != 0 {
+        if entry == MAXIMUM_MEMORY_SIZE || entry % instruction_alignment != 0 {
             panic!("invalid entrypoint");
         }
 
@@ -151,6 +163,6 @@ impl Elf {
             }
         }
 
-        Elf::new(instructions, entry, base_address, image)
+        Elf::new(instructions, entry, base_address, image, compressed)
     }
 }
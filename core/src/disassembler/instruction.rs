@@ -3,6 +3,7 @@ use rrs_lib::instruction_formats::{
 };
 use rrs_lib::{process_instruction, InstructionProcessor};
 
+use super::{expand_compressed, is_compressed};
 use crate::runtime::{Instruction, Opcode, Register};
 
 impl Instruction {
@@ -392,8 +393,10 @@ impl InstructionProcessor for InstructionTranspiler {
         Instruction::unimp()
     }
 
+    /// FENCE instructions are no-ops, since the VM executes a single hart in program order. They
+    /// are converted to `add %x0, %x0, %x0`.
     fn process_fence(&mut self, _: IType) -> Self::InstructionResult {
-        Instruction::unimp()
+        Instruction::new(Opcode::ADD, 0, 0, 0, false, false)
     }
 
     fn process_mret(&mut self) -> Self::InstructionResult {
@@ -405,13 +408,153 @@ impl InstructionProcessor for InstructionTranspiler {
     }
 }
 
+/// The major opcode of the instructions in the RV32A extension.
+const OPCODE_AMO: u32 = 0b0101111;
+
+/// Decode an instruction from the RV32A extension, which is not supported by `rrs_lib`.
+///
+/// The `aq` and `rl` ordering bits are ignored, since the VM executes a single hart in program
+/// order. The instructions are decoded in the R-type format, with `rs1` holding the address.
+pub fn decode_atomic(instruction_u32: u32) -> Option<Instruction> {
+    let opcode = instruction_u32 & 0x7f;
+    let funct3 = (instruction_u32 >> 12) & 0x7;
+    if opcode != OPCODE_AMO || funct3 != 0b010 {
+        return None;
+    }
+
+    let rd = (instruction_u32 >> 7) & 0x1f;
+    let rs1 = (instruction_u32 >> 15) & 0x1f;
+    let rs2 = (instruction_u32 >> 20) & 0x1f;
+    let funct5 = instruction_u32 >> 27;
+    let opcode = match funct5 {
+        0b00010 if rs2 == 0 => Opcode::LR_W,
+        0b00011 => Opcode::SC_W,
+        0b00001 => Opcode::AMOSWAP_W,
+        0b00000 => Opcode::AMOADD_W,
+        0b00100 => Opcode::AMOXOR_W,
+        0b01100 => Opcode::AMOAND_W,
+        0b01000 => Opcode::AMOOR_W,
+        0b10000 => Opcode::AMOMIN_W,
+        0b10100 => Opcode::AMOMAX_W,
+        0b11000 => Opcode::AMOMINU_W,
+        0b11100 => Opcode::AMOMAXU_W,
+        _ => return None,
+    };
+    Some(Instruction::new(opcode, rd, rs1, rs2, false, false))
+}
+
+/// Transpile a single 32-bit encoded instruction.
+fn transpile_instruction(
+    transpiler: &mut InstructionTranspiler,
+    instruction_u32: u32,
+) -> Instruction {
+    match decode_atomic(instruction_u32) {
+        Some(instruction) => instruction,
+        None => process_instruction(transpiler, instruction_u32).unwrap(),
+    }
+}
+
 /// Transpile the instructions from the 32-bit encoded instructions.
 pub fn transpile(instructions_u32: &[u32]) -> Vec<Instruction> {
     let mut instructions = Vec::new();
     let mut transpiler = InstructionTranspiler;
     for instruction_u32 in instructions_u32 {
-        let instruction = process_instruction(&mut transpiler, *instruction_u32).unwrap();
+        let instruction = transpile_instruction(&mut transpiler, *instruction_u32);
         instructions.push(instruction);
     }
     instructions
 }
+
+/// Transpile the instructions from a mix of 16-bit compressed and 32-bit encoded instructions.
+///
+/// The input is the little-endian words of the program, which are read as a stream of 16-bit
+/// parcels.  Compressed instructions are expanded and marked as compressed, and illegal compressed
+/// instructions (such as zero padding) are transpiled to UNIMP.
+pub fn transpile_compressed(instructions_u32: &[u32]) -> Vec<Instruction> {
+    let parcels = instructions_u32
+        .iter()
+        .flat_map(|word| [*word as u16, (*word >> 16) as u16])
+        .collect::<Vec<_>>();
+
+    let mut instructions = Vec::new();
+    let mut transpiler = InstructionTranspiler;
+    let mut i = 0;
+    while i < parcels.len() {
+        if is_compressed(parcels[i]) {
+            let instruction = match expand_compressed(parcels[i]) {
+                Some(instruction_u32) => transpile_instruction(&mut transpiler, instruction_u32),
+                None => Instruction::unimp(),
+            };
+            instructions.push(instruction.with_compressed(true));
+            i += 1;
+        } else if i + 1 < parcels.len() {
+            let instruction_u32 = parcels[i] as u32 | (parcels[i + 1] as u32) << 16;
+            instructions.push(transpile_instruction(&mut transpiler, instruction_u32));
+            i += 2;
+        } else {
+            // A 32-bit instruction can't start in the last parcel of the program.
+            instructions.push(Instruction::unimp().with_compressed(true));
+            i += 1;
+        }
+    }
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_atomic() {
+        // amoadd.w a0, a2, (a1)
+        let instruction = decode_atomic(0x00c5a52f).unwrap();
+        assert_eq!(instruction.opcode, Opcode::AMOADD_W);
+        assert_eq!(
+            (instruction.op_a, instruction.op_b, instruction.op_c),
+            (10, 11, 12)
+        );
+
+        // lr.w.aq a0, (a1)
+        let instruction = decode_atomic(0x1405a52f).unwrap();
+        assert_eq!(instruction.opcode, Opcode::LR_W);
+        assert_eq!(
+            (instruction.op_a, instruction.op_b, instruction.op_c),
+            (10, 11, 0)
+        );
+
+        // sc.w.rl a0, a2, (a1)
+        let instruction = decode_atomic(0x1ac5a52f).unwrap();
+        assert_eq!(instruction.opcode, Opcode::SC_W);
+
+        // amomaxu.w a0, a2, (a1)
+        let instruction = decode_atomic(0xe0c5a52f).unwrap();
+        assert_eq!(instruction.opcode, Opcode::AMOMAXU_W);
+
+        // addi a0, a1, 1 is not an atomic instruction.
+        assert!(decode_atomic(0x00158513).is_none());
+    }
+
+    #[test]
+    fn test_transpile_compressed() {
+        // c.li a0, 5; addi a0, a0, 1 (straddling a word boundary); c.mv a1, a0; c.nop
+        let instructions = transpile_compressed(&[0x05134515, 0x85aa0015, 0x00000001]);
+        let expected = [
+            (Opcode::ADD, 10, 0, 5, true),
+            (Opcode::ADD, 10, 10, 1, false),
+            (Opcode::ADD, 11, 0, 10, true),
+            (Opcode::ADD, 0, 0, 0, true),
+            (Opcode::UNIMP, 0, 0, 0, true),
+        ];
+        assert_eq!(instructions.len(), expected.len());
+        for (instruction, (opcode, op_a, op_b, op_c, compressed)) in
+            instructions.iter().zip(expected)
+        {
+            assert_eq!(instruction.opcode, opcode);
+            assert_eq!(
+                (instruction.op_a, instruction.op_b, instruction.op_c),
+                (op_a, op_b, op_c)
+            );
+            assert_eq!(instruction.compressed, compressed);
+        }
+    }
+}
@@ -1,6 +1,8 @@
+mod compressed;
 mod elf;
 mod instruction;
 
+pub use compressed::*;
 pub use elf::*;
 pub use instruction::*;
 
@@ -16,16 +18,38 @@ impl Program {
             pc_start,
             pc_base,
             memory_image: BTreeMap::new(),
+            instruction_pcs: Vec::new(),
         }
     }
 
-    /// Disassemble a RV32IM ELF to a program that be executed by the VM.
+    /// Disassemble a RV32IM or RV32IMC ELF to a program that be executed by the VM.
     pub fn from(input: &[u8]) -> Self {
         // Decode the bytes as an ELF.
         let elf = Elf::decode(input);
 
         // Transpile the RV32IM instructions.
-        let instructions = transpile(&elf.instructions);
+        if !elf.compressed {
+            let instructions = transpile(&elf.instructions);
+            return Program {
+                instructions,
+                pc_start: elf.pc_start,
+                pc_base: elf.pc_base,
+                memory_image: elf.memory_image,
+                instruction_pcs: Vec::new(),
+            };
+        }
+
+        // Transpile the RV32IMC instructions and compute the address of each instruction, since
+        // instructions are no longer laid out every four bytes.
+        let instructions = transpile_compressed(&elf.instructions);
+        let instruction_pcs = instructions
+            .iter()
+            .scan(elf.pc_base, |pc, instruction| {
+                let instruction_pc = *pc;
+                *pc += instruction.size();
+                Some(instruction_pc)
+            })
+            .collect();
 
         // Return the program.
         Program {
@@ -33,6 +57,7 @@ impl Program {
             pc_start: elf.pc_start,
             pc_base: elf.pc_base,
             memory_image: elf.memory_image,
+            instruction_pcs,
         }
     }
 
@@ -4,7 +4,11 @@ use crate::{
 };
 use k256::sha2::{Digest, Sha256};
 use num_bigint::BigUint;
+use rkyv::ser::serializers::AllocSerializer;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_primitives::merkle::{self, MerkleHash, MerkleProof};
+use std::collections::BTreeMap;
+use std::path::Path;
 
 /// Standard input for the prover.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +21,12 @@ pub struct SP1Stdin {
         ShardProof<BabyBearPoseidon2>,
         StarkVerifyingKey<BabyBearPoseidon2>,
     )>,
+    /// Environment variables, read by the program with `std::env::var`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Read-only files by path, read by the program with `sp1_zkvm::fs`.
+    #[serde(default)]
+    pub files: BTreeMap<String, Vec<u8>>,
 }
 
 /// Public values for the prover.
@@ -32,6 +42,8 @@ impl SP1Stdin {
             buffer: Vec::new(),
             ptr: 0,
             proofs: Vec::new(),
+            env: BTreeMap::new(),
+            files: BTreeMap::new(),
         }
     }
 
@@ -41,6 +53,8 @@ impl SP1Stdin {
             buffer: vec![data.to_vec()],
             ptr: 0,
             proofs: Vec::new(),
+            env: BTreeMap::new(),
+            files: BTreeMap::new(),
         }
     }
 
@@ -70,10 +84,60 @@ impl SP1Stdin {
         self.buffer.push(slice.to_vec());
     }
 
+    /// Write a value archived with rkyv, which the program can access in place with
+    /// `sp1_zkvm::io::read_archived` instead of deserializing it.
+    pub fn write_archived<T: rkyv::Serialize<AllocSerializer<256>>>(&mut self, data: &T) {
+        let bytes = rkyv::to_bytes::<_, 256>(data).expect("serialization failed");
+        self.buffer.push(bytes.into_vec());
+    }
+
     pub fn write_vec(&mut self, vec: Vec<u8>) {
         self.buffer.push(vec);
     }
 
+    /// Write a seed for the randomness of the program, read with `sp1_zkvm::entropy::read_seed`
+    /// or `sp1_zkvm::entropy::read_committed_seed` at this position of the input.
+    pub fn write_entropy(&mut self, seed: [u8; 32]) {
+        self.buffer.push(seed.to_vec());
+    }
+
+    /// Set an environment variable of the program.
+    pub fn set_env(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.env.insert(key.into(), value.into());
+    }
+
+    /// Add a file to the program's filesystem, at the given path.
+    pub fn write_file(&mut self, path: impl Into<String>, contents: impl Into<Vec<u8>>) {
+        self.files.insert(path.into(), contents.into());
+    }
+
+    /// Add the files of a host directory and its subdirectories to the program's filesystem, with
+    /// their paths relative to the directory joined to `prefix` with `/`.
+    pub fn write_dir(&mut self, dir: impl AsRef<Path>, prefix: &str) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = match prefix.trim_end_matches('/') {
+                "" => name,
+                prefix => format!("{}/{}", prefix, name),
+            };
+            if entry.file_type()?.is_dir() {
+                self.write_dir(entry.path(), &path)?;
+            } else {
+                self.write_file(path, std::fs::read(entry.path())?);
+            }
+        }
+        Ok(())
+    }
+
+    /// The SHA-256 hash of a file of the program's filesystem, as computed by
+    /// `sp1_zkvm::fs::hash`.
+    pub fn file_hash(&self, path: &str) -> Option<[u8; 32]> {
+        self.files
+            .get(path)
+            .map(|contents| Sha256::digest(contents).into())
+    }
+
     pub fn write_proof(
         &mut self,
         proof: ShardProof<BabyBearPoseidon2>,
@@ -141,16 +205,47 @@ impl SP1PublicValues {
         let mut hasher = Sha256::new();
         hasher.update(self.buffer.data.as_slice());
         let hash_result = hasher.finalize();
-        let mut hash = hash_result.to_vec();
+        mask_digest(hash_result.into())
+    }
+
+    /// Splits public values committed with `sp1_zkvm::merkle` into the values of the leaves, or
+    /// returns `None` if they are not a list of length-prefixed leaves.
+    pub fn merkle_leaves(&self) -> Option<Vec<&[u8]>> {
+        merkle::split_leaves(self.buffer.data.as_slice())
+    }
+
+    /// The root of the Merkle tree of public values committed with `sp1_zkvm::merkle`, which is
+    /// the digest the proof commits to.
+    pub fn merkle_root(&self, hash: MerkleHash) -> Option<[u8; 32]> {
+        merkle::public_values_digest(self.buffer.data.as_slice(), Some(hash))
+    }
 
-        // Mask the top 3 bits.
-        hash[0] &= 0b00011111;
+    /// The Merkle root like [Self::merkle_root], with the top 3 bits masked like [Self::hash].
+    pub fn merkle_root_hash(&self, hash: MerkleHash) -> Option<BigUint> {
+        self.merkle_root(hash).map(mask_digest)
+    }
 
-        // Return the masked hash as a BigUint.
-        BigUint::from_bytes_be(&hash)
+    /// Proves that the leaf at `index` is part of the Merkle tree of public values committed with
+    /// `sp1_zkvm::merkle`, so that it can be checked against the root without the other leaves.
+    pub fn merkle_proof(&self, hash: MerkleHash, index: usize) -> Option<MerkleProof> {
+        let leaves = self.merkle_leaves()?;
+        if index >= leaves.len() {
+            return None;
+        }
+        let leaves = leaves
+            .iter()
+            .map(|leaf| hash.leaf(leaf))
+            .collect::<Vec<_>>();
+        Some(hash.prove(&leaves, index))
     }
 }
 
+/// Masks the top 3 bits of a digest so that it fits in a BN254 element.
+fn mask_digest(mut digest: [u8; 32]) -> BigUint {
+    digest[0] &= 0b00011111;
+    BigUint::from_bytes_be(&digest)
+}
+
 impl AsRef<[u8]> for SP1PublicValues {
     fn as_ref(&self) -> &[u8] {
         &self.buffer.data
@@ -213,4 +308,40 @@ mod tests {
 
         assert_eq!(hash, expected_hash_biguint);
     }
+
+    #[test]
+    fn test_write_archived() {
+        let value = (7u64, vec![1u32, 2, 3]);
+        let mut stdin = SP1Stdin::new();
+        stdin.write_archived(&value);
+
+        // The program reads the input into an aligned buffer, and accesses it in place.
+        let mut bytes = rkyv::AlignedVec::new();
+        bytes.extend_from_slice(&stdin.buffer[0]);
+        let archived = unsafe { rkyv::archived_root::<(u64, Vec<u32>)>(&bytes) };
+        assert_eq!(archived.0, 7);
+        assert_eq!(archived.1.as_slice(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_merkle_public_values() {
+        let values: [&[u8]; 3] = [b"first", b"", b"third"];
+        let mut public_values = SP1PublicValues::new();
+        for value in values {
+            public_values.write_slice(&(value.len() as u32).to_le_bytes());
+            public_values.write_slice(value);
+        }
+        assert_eq!(public_values.merkle_leaves().unwrap(), values);
+
+        let hash = MerkleHash::Keccak256;
+        let root = public_values.merkle_root(hash).unwrap();
+        let proof = public_values.merkle_proof(hash, 2).unwrap();
+        assert!(hash.verify(&root, &hash.leaf(b"third"), &proof));
+        assert!(!hash.verify(&root, &hash.leaf(b"first"), &proof));
+        assert!(public_values.merkle_proof(hash, 3).is_none());
+
+        // A truncated leaf is not a list of leaves.
+        public_values.write_slice(&[1, 0, 0, 0]);
+        assert!(public_values.merkle_leaves().is_none());
+    }
 }
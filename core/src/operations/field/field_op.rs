@@ -2,11 +2,11 @@ use std::fmt::Debug;
 
 use num::{BigUint, Zero};
 use p3_air::AirBuilder;
-use p3_field::PrimeField32;
+use p3_field::{AbstractField, PrimeField32};
 use sp1_derive::AlignedBorrow;
 
 use super::params::{FieldParameters, Limbs};
-use super::util::{compute_root_quotient_and_shift, split_u16_limbs_to_u8_limbs};
+use super::util::{compute_root_quotient_and_shift, split_witness_limbs};
 use super::util_air::eval_field_operation;
 use crate::air::Polynomial;
 use crate::air::SP1AirBuilder;
@@ -93,7 +93,7 @@ impl<F: PrimeField32, P: FieldParameters> FieldOpCols<F, P> {
             P::NB_BITS_PER_LIMB as u32,
             P::NB_WITNESS_LIMBS,
         );
-        let (mut p_witness_low, mut p_witness_high) = split_u16_limbs_to_u8_limbs(&p_witness);
+        let (mut p_witness_low, mut p_witness_high) = split_witness_limbs(&p_witness);
 
         self.result = p_result.into();
         self.carry = p_carry.into();
@@ -164,7 +164,16 @@ impl<F: PrimeField32, P: FieldParameters> FieldOpCols<F, P> {
         record.add_u8_range_checks_field(shard, channel, &self.result.0);
         record.add_u8_range_checks_field(shard, channel, &self.carry.0);
         record.add_u8_range_checks_field(shard, channel, &self.witness_low.0);
-        record.add_u8_range_checks_field(shard, channel, &self.witness_high.0);
+        if P::NB_WITNESS_HIGH_BITS <= P::NB_BITS_PER_LIMB {
+            record.add_u8_range_checks_field(shard, channel, &self.witness_high.0);
+        } else {
+            let shift = 16 - P::NB_WITNESS_HIGH_BITS;
+            for limb in self.witness_high.0.iter() {
+                let limb = limb.as_canonical_u32();
+                record.add_u16_range_check(shard, channel, limb);
+                record.add_u16_range_check(shard, channel, limb << shift);
+            }
+        }
 
         result
     }
@@ -237,12 +246,32 @@ impl<V: Copy, P: FieldParameters> FieldOpCols<V, P> {
             channel.clone(),
             is_real.clone(),
         );
-        builder.slice_range_check_u8(
-            p_witness_high.coefficients(),
-            shard.clone(),
-            channel.clone(),
-            is_real,
-        );
+        if P::NB_WITNESS_HIGH_BITS <= P::NB_BITS_PER_LIMB {
+            builder.slice_range_check_u8(
+                p_witness_high.coefficients(),
+                shard.clone(),
+                channel.clone(),
+                is_real,
+            );
+        } else {
+            // A high limb has at most `NB_WITNESS_HIGH_BITS` bits if both it and its shift to
+            // 16 bits are u16, as the shift can't overflow the field.
+            let shift: AB::Expr =
+                AB::F::from_canonical_u32(1 << (16 - P::NB_WITNESS_HIGH_BITS)).into();
+            let p_witness_high_shifted = &p_witness_high * shift;
+            builder.slice_range_check_u16(
+                p_witness_high.coefficients(),
+                shard.clone(),
+                channel.clone(),
+                is_real.clone(),
+            );
+            builder.slice_range_check_u16(
+                p_witness_high_shifted.coefficients(),
+                shard.clone(),
+                channel.clone(),
+                is_real,
+            );
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
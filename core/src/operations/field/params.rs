@@ -35,6 +35,14 @@ pub trait FieldParameters:
     const NB_WITNESS_LIMBS: usize = Self::Witness::USIZE;
     const WITNESS_OFFSET: usize;
 
+    /// The number of bits of the high limbs of the witness, at most 16.
+    ///
+    /// Each witness limb is split into a low byte and a high part, which only needs more than a
+    /// byte when there are hundreds of limbs. Only [FieldOpCols] supports more than a byte.
+    ///
+    /// [FieldOpCols]: super::field_op::FieldOpCols
+    const NB_WITNESS_HIGH_BITS: usize = NB_BITS_PER_LIMB;
+
     /// The bytes of the modulus in little-endian order.
     const MODULUS: &'static [u8];
 
@@ -145,3 +145,134 @@ impl<V: Copy, P: FieldParameters> FieldRangeCols<V, P> {
         )
     }
 }
+
+/// Operation columns for verifying that `lhs < rhs`, for a right-hand side given by columns, like a
+/// modulus read from memory.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct FieldLtCols<T, P: FieldParameters> {
+    /// Boolean flags to indicate the first byte in which `lhs` is smaller than `rhs`.
+    pub(crate) byte_flags: Limbs<T, P::Limbs>,
+
+    pub(crate) lhs_comparison_byte: T,
+
+    pub(crate) rhs_comparison_byte: T,
+}
+
+impl<F: PrimeField32, P: FieldParameters> FieldLtCols<F, P> {
+    pub fn populate(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        lhs: &BigUint,
+        rhs: &BigUint,
+    ) {
+        assert!(lhs < rhs);
+
+        let value_limbs = P::to_limbs(lhs);
+        let rhs_limbs = P::to_limbs(rhs);
+
+        let mut byte_flags = vec![0u8; P::NB_LIMBS];
+
+        for (byte, rhs_byte, flag) in izip!(
+            value_limbs.iter().rev(),
+            rhs_limbs.iter().rev(),
+            byte_flags.iter_mut().rev()
+        ) {
+            assert!(byte <= rhs_byte);
+            if byte < rhs_byte {
+                *flag = 1;
+                self.lhs_comparison_byte = F::from_canonical_u8(*byte);
+                self.rhs_comparison_byte = F::from_canonical_u8(*rhs_byte);
+                record.add_byte_lookup_event(ByteLookupEvent {
+                    opcode: ByteOpcode::LTU,
+                    shard,
+                    channel,
+                    a1: 1,
+                    a2: 0,
+                    b: *byte as u32,
+                    c: *rhs_byte as u32,
+                });
+                break;
+            }
+        }
+
+        for (byte, flag) in izip!(byte_flags.iter(), self.byte_flags.0.iter_mut()) {
+            *flag = F::from_canonical_u8(*byte);
+        }
+    }
+}
+
+impl<V: Copy, P: FieldParameters> FieldLtCols<V, P> {
+    /// Constrains `lhs < rhs` when `is_real` is one. When it's zero, the columns must be zero.
+    pub fn eval<
+        AB: SP1AirBuilder<Var = V>,
+        E1: Into<Polynomial<AB::Expr>> + Clone,
+        E2: Into<Polynomial<AB::Expr>> + Clone,
+    >(
+        &self,
+        builder: &mut AB,
+        lhs: &E1,
+        rhs: &E2,
+        shard: impl Into<AB::Expr> + Clone,
+        channel: impl Into<AB::Expr> + Clone,
+        is_real: impl Into<AB::Expr> + Clone,
+    ) where
+        V: Into<AB::Expr>,
+        Limbs<V, P::Limbs>: Copy,
+    {
+        // The byte flags give the first most significant byte in which `lhs` is smaller than
+        // `rhs`, as in [FieldRangeCols::eval], except that the bytes of `rhs` are expressions.
+
+        // Verify that exactly one flag is set to one on real rows, and none on the others.
+        let mut sum_flags: AB::Expr = AB::Expr::zero();
+        for &flag in self.byte_flags.0.iter() {
+            // Assert that the flag is boolean.
+            builder.assert_bool(flag);
+            // Add the flag to the sum.
+            sum_flags += flag.into();
+        }
+        builder.assert_eq(sum_flags, is_real.clone());
+
+        // A flag to indicate whether an equality check is necessary (this is for all bytes from
+        // most significant until the first inequality.
+        let mut is_inequality_visited = AB::Expr::zero();
+
+        let lhs: Polynomial<_> = lhs.clone().into();
+        let rhs: Polynomial<_> = rhs.clone().into();
+
+        let mut lhs_comparison_byte = AB::Expr::zero();
+        let mut rhs_comparison_byte = AB::Expr::zero();
+        for (lhs_byte, rhs_byte, &flag) in izip!(
+            lhs.coefficients().iter().rev(),
+            rhs.coefficients().iter().rev(),
+            self.byte_flags.0.iter().rev()
+        ) {
+            // Once the byte flag was set to one, we turn off the quality check flag.
+            is_inequality_visited += flag.into();
+
+            lhs_comparison_byte += lhs_byte.clone() * flag;
+            rhs_comparison_byte += rhs_byte.clone() * flag;
+
+            builder
+                .when(is_real.clone())
+                .when_not(is_inequality_visited.clone())
+                .assert_eq(lhs_byte.clone(), rhs_byte.clone());
+        }
+
+        builder.assert_eq(self.lhs_comparison_byte, lhs_comparison_byte);
+        builder.assert_eq(self.rhs_comparison_byte, rhs_comparison_byte);
+
+        // Send the comparison interaction.
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            self.lhs_comparison_byte,
+            self.rhs_comparison_byte,
+            shard,
+            channel,
+            is_real,
+        )
+    }
+}
@@ -58,6 +58,21 @@ pub fn compute_root_quotient_and_shift<F: PrimeField32>(
         .collect::<Vec<F>>()
 }
 
+/// Splits each witness limb into its low byte and its remaining high bits.
+#[inline]
+pub fn split_witness_limbs<F: PrimeField32>(slice: &[F]) -> (Vec<F>, Vec<F>) {
+    (
+        slice
+            .iter()
+            .map(|x| F::from_canonical_u64(x.as_canonical_u64() & 0xff))
+            .collect(),
+        slice
+            .iter()
+            .map(|x| F::from_canonical_u64(x.as_canonical_u64() >> 8))
+            .collect(),
+    )
+}
+
 #[inline]
 pub fn split_u16_limbs_to_u8_limbs<F: PrimeField32>(slice: &[F]) -> (Vec<F>, Vec<F>) {
     (
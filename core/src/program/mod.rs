@@ -68,7 +68,7 @@ impl<F: PrimeField> MachineAir<F> for ProgramChip {
             .into_iter()
             .enumerate()
             .map(|(i, instruction)| {
-                let pc = program.pc_base + (i as u32 * 4);
+                let pc = program.instruction_pc(i);
                 let mut row = [F::zero(); NUM_PROGRAM_PREPROCESSED_COLS];
                 let cols: &mut ProgramPreprocessedCols<F> = row.as_mut_slice().borrow_mut();
                 cols.pc = F::from_canonical_u32(pc);
@@ -120,7 +120,7 @@ impl<F: PrimeField> MachineAir<F> for ProgramChip {
             .into_iter()
             .enumerate()
             .map(|(i, _)| {
-                let pc = input.program.pc_base + (i as u32 * 4);
+                let pc = input.program.instruction_pc(i);
                 let mut row = [F::zero(); NUM_PROGRAM_MULT_COLS];
                 let cols: &mut ProgramMultiplicityCols<F> = row.as_mut_slice().borrow_mut();
                 cols.shard = F::from_canonical_u32(input.index);
@@ -209,6 +209,7 @@ mod tests {
                 pc_start: 0,
                 pc_base: 0,
                 memory_image: BTreeMap::new(),
+                instruction_pcs: Vec::new(),
             }),
             ..Default::default()
         };
@@ -12,6 +12,9 @@ pub struct Instruction {
     pub op_c: u32,
     pub imm_b: bool,
     pub imm_c: bool,
+    /// Whether the instruction was expanded from a 16-bit compressed instruction.
+    #[serde(default)]
+    pub compressed: bool,
 }
 
 impl Instruction {
@@ -31,6 +34,22 @@ impl Instruction {
             op_c,
             imm_b,
             imm_c,
+            compressed: false,
+        }
+    }
+
+    /// Mark the instruction as expanded from a 16-bit compressed instruction.
+    pub const fn with_compressed(mut self, compressed: bool) -> Self {
+        self.compressed = compressed;
+        self
+    }
+
+    /// Returns the size of the encoded instruction in bytes.
+    pub const fn size(&self) -> u32 {
+        if self.compressed {
+            2
+        } else {
+            4
         }
     }
 
@@ -76,6 +95,24 @@ impl Instruction {
                 | Opcode::SB
                 | Opcode::SH
                 | Opcode::SW
+        ) || self.is_atomic_instruction()
+    }
+
+    /// Returns if the instruction is an atomic memory instruction.
+    pub const fn is_atomic_instruction(&self) -> bool {
+        matches!(
+            self.opcode,
+            Opcode::LR_W
+                | Opcode::SC_W
+                | Opcode::AMOSWAP_W
+                | Opcode::AMOADD_W
+                | Opcode::AMOXOR_W
+                | Opcode::AMOAND_W
+                | Opcode::AMOOR_W
+                | Opcode::AMOMIN_W
+                | Opcode::AMOMAX_W
+                | Opcode::AMOMINU_W
+                | Opcode::AMOMAXU_W
         )
     }
 
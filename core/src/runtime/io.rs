@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
 use std::io::Read;
 
+use crate::io::SP1Stdin;
 use crate::stark::{ShardProof, StarkVerifyingKey};
 use crate::utils::BabyBearPoseidon2;
 
@@ -26,12 +28,31 @@ impl Runtime {
         self.state.input_stream.push(input.to_vec());
     }
 
+    /// Writes all the inputs of the program: the input buffer, environment variables, files and
+    /// the proofs to verify.
+    pub fn write_inputs(&mut self, stdin: &SP1Stdin) {
+        self.write_vecs(&stdin.buffer);
+        self.write_env(&stdin.env);
+        self.write_files(&stdin.files);
+        for (proof, vk) in stdin.proofs.iter() {
+            self.write_proof(proof.clone(), vk.clone());
+        }
+    }
+
     pub fn write_vecs(&mut self, inputs: &[Vec<u8>]) {
         for input in inputs {
             self.state.input_stream.push(input.clone());
         }
     }
 
+    pub fn write_env(&mut self, env: &BTreeMap<String, String>) {
+        self.state.env.extend(env.clone());
+    }
+
+    pub fn write_files(&mut self, files: &BTreeMap<String, Vec<u8>>) {
+        self.files.extend(files.clone());
+    }
+
     pub fn write_proof(
         &mut self,
         proof: ShardProof<BabyBearPoseidon2>,
@@ -2,6 +2,7 @@ mod instruction;
 mod io;
 mod memory;
 mod opcode;
+mod paged_memory;
 mod program;
 mod record;
 mod register;
@@ -13,6 +14,7 @@ mod utils;
 pub use instruction::*;
 pub use memory::*;
 pub use opcode::*;
+pub use paged_memory::*;
 pub use program::*;
 pub use record::*;
 pub use register::*;
@@ -20,19 +22,20 @@ pub use state::*;
 pub use syscall::*;
 pub use utils::*;
 
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::fs::File;
 use std::io::BufWriter;
 use std::io::Write;
 use std::sync::Arc;
 
+use sp1_primitives::OUT_OF_MEMORY_EXIT_CODE;
 use thiserror::Error;
 
 use crate::bytes::NUM_BYTE_LOOKUP_CHANNELS;
 use crate::memory::MemoryInitializeFinalizeEvent;
-use crate::utils::SP1CoreOpts;
+use crate::syscall::{DeferredProofVerification, DeferredProofVerificationError};
+use crate::utils::{SP1CoreOpts, ShardSizing};
 use crate::{alu::AluEvent, cpu::CpuEvent};
 
 /// An implementation of a runtime for the SP1 RISC-V zkVM.
@@ -88,12 +91,26 @@ pub struct Runtime {
 
     /// Whether we should write to the report.
     pub should_report: bool,
+
+    /// Verifies the proofs read by the `VERIFY_SP1_PROOF` syscall. If unset, deferred proofs are
+    /// only verified in the recursion layer.
+    pub deferred_proof_verifier: Option<Arc<dyn DeferredProofVerification>>,
+
+    /// The output the program wrote to stderr, if it's captured instead of printed.
+    pub captured_stderr: Option<String>,
+
+    /// The files of the program by path, served to it through the input stream. They're kept out
+    /// of the execution state so that they aren't copied into every checkpoint.
+    pub files: BTreeMap<String, Vec<u8>>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct ExecutionReport {
     pub instruction_counts: HashMap<Opcode, u64>,
     pub syscall_counts: HashMap<SyscallCode, u64>,
+    /// The shard sizes chosen for proving the program, if they were chosen from the resources of
+    /// the prover.
+    pub shard_sizing: Option<ShardSizing>,
 }
 
 impl ExecutionReport {
@@ -128,6 +145,10 @@ impl Display for ExecutionReport {
         }
         writeln!(f, "Total Syscall Count: {}", self.total_syscall_count())?;
 
+        if let Some(shard_sizing) = &self.shard_sizing {
+            writeln!(f, "Shard Sizing: {}", shard_sizing)?;
+        }
+
         Ok(())
     }
 }
@@ -136,6 +157,8 @@ impl Display for ExecutionReport {
 pub enum ExecutionError {
     #[error("execution failed with exit code {0}")]
     HaltWithNonZeroExitCode(u32),
+    #[error("the program ran out of heap memory")]
+    OutOfMemory(),
     #[error("invalid memory access for opcode {0} and address {1}")]
     InvalidMemoryAccess(Opcode, u32),
     #[error("unimplemented syscall {0}")]
@@ -144,6 +167,8 @@ pub enum ExecutionError {
     Breakpoint(),
     #[error("got unimplemented as opcode")]
     Unimplemented(),
+    #[error("deferred proof {0} failed verification: {1}")]
+    InvalidDeferredProof(usize, DeferredProofVerificationError),
 }
 
 impl Runtime {
@@ -191,6 +216,9 @@ impl Runtime {
             max_syscall_cycles,
             report: Default::default(),
             should_report: false,
+            deferred_proof_verifier: None,
+            captured_stderr: None,
+            files: BTreeMap::new(),
         }
     }
 
@@ -210,7 +238,7 @@ impl Runtime {
         let mut registers = [0; 32];
         for i in 0..32 {
             let addr = Register::from_u32(i as u32) as u32;
-            registers[i] = match self.state.memory.get(&addr) {
+            registers[i] = match self.state.memory.get(addr) {
                 Some(record) => record.value,
                 None => 0,
             };
@@ -221,7 +249,7 @@ impl Runtime {
     /// Get the current value of a register.
     pub fn register(&self, register: Register) -> u32 {
         let addr = register as u32;
-        match self.state.memory.get(&addr) {
+        match self.state.memory.get(addr) {
             Some(record) => record.value,
             None => 0,
         }
@@ -229,7 +257,7 @@ impl Runtime {
 
     /// Get the current value of a word.
     pub fn word(&self, addr: u32) -> u32 {
-        match self.state.memory.get(&addr) {
+        match self.state.memory.get(addr) {
             Some(record) => record.value,
             None => 0,
         }
@@ -262,23 +290,10 @@ impl Runtime {
         // Get the memory record entry.
         let entry = self.state.memory.entry(addr);
 
-        // If we're in unconstrained mode, we don't want to modify state, so we'll save the
-        // original state if it's the first time modifying it.
-        if self.unconstrained {
-            let record = match entry {
-                Entry::Occupied(ref entry) => Some(entry.get()),
-                Entry::Vacant(_) => None,
-            };
-            self.unconstrained_state
-                .memory_diff
-                .entry(addr)
-                .or_insert(record.copied());
-        }
-
         // If it's the first time accessing this address, initialize previous values.
         let record: &mut MemoryRecord = match entry {
-            Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => {
+            MemoryEntry::Occupied(entry) => entry.into_mut(),
+            MemoryEntry::Vacant(entry) => {
                 // If addr has a specific value to be initialized with, use that, otherwise 0.
                 let value = self.state.uninitialized_memory.remove(&addr).unwrap_or(0);
 
@@ -310,23 +325,10 @@ impl Runtime {
         // Get the memory record entry.
         let entry = self.state.memory.entry(addr);
 
-        // If we're in unconstrained mode, we don't want to modify state, so we'll save the
-        // original state if it's the first time modifying it.
-        if self.unconstrained {
-            let record = match entry {
-                Entry::Occupied(ref entry) => Some(entry.get()),
-                Entry::Vacant(_) => None,
-            };
-            self.unconstrained_state
-                .memory_diff
-                .entry(addr)
-                .or_insert(record.copied());
-        }
-
         // If it's the first time accessing this address, initialize previous values.
         let record: &mut MemoryRecord = match entry {
-            Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => {
+            MemoryEntry::Occupied(entry) => entry.into_mut(),
+            MemoryEntry::Vacant(entry) => {
                 // If addr has a specific value to be initialized with, use that, otherwise 0.
                 let value = self.state.uninitialized_memory.remove(&addr).unwrap_or(0);
 
@@ -557,6 +559,16 @@ impl Runtime {
         (a, b, c, addr, memory_value)
     }
 
+    /// Fetch the input operand values for an atomic instruction.
+    fn atomic_rr(&mut self, instruction: Instruction) -> (Register, u32, u32, u32, u32) {
+        let (rd, rs1, rs2) = instruction.r_type();
+        let c = self.rr(rs2, MemoryAccessPosition::C);
+        let b = self.rr(rs1, MemoryAccessPosition::B);
+        let addr = b;
+        let memory_value = self.word(align(addr));
+        (rd, b, c, addr, memory_value)
+    }
+
     /// Fetch the input operand values for a branch instruction.
     fn branch_rr(&mut self, instruction: Instruction) -> (u32, u32, u32) {
         let (rs1, rs2, imm) = instruction.b_type();
@@ -568,7 +580,7 @@ impl Runtime {
 
     /// Fetch the instruction at the current program counter.
     fn fetch(&self) -> Instruction {
-        let idx = ((self.state.pc - self.program.pc_base) / 4) as usize;
+        let idx = self.program.instruction_index(self.state.pc);
         self.program.instructions[idx]
     }
 
@@ -578,7 +590,7 @@ impl Runtime {
         let mut clk = self.state.clk;
         let mut exit_code = 0u32;
 
-        let mut next_pc = self.state.pc.wrapping_add(4);
+        let mut next_pc = self.state.pc.wrapping_add(instruction.size());
 
         let rd: Register;
         let (a, b, c): (u32, u32, u32);
@@ -778,14 +790,14 @@ impl Runtime {
             Opcode::JAL => {
                 let (rd, imm) = instruction.j_type();
                 (b, c) = (imm, 0);
-                a = self.state.pc + 4;
+                a = self.state.pc + instruction.size();
                 self.rw(rd, a);
                 next_pc = self.state.pc.wrapping_add(imm);
             }
             Opcode::JALR => {
                 let (rd, rs1, imm) = instruction.i_type();
                 (b, c) = (self.rr(rs1, MemoryAccessPosition::B), imm);
-                a = self.state.pc + 4;
+                a = self.state.pc + instruction.size();
                 self.rw(rd, a);
                 next_pc = b.wrapping_add(c);
             }
@@ -831,9 +843,15 @@ impl Runtime {
 
                         // If the syscall is `HALT` and the exit code is non-zero, return an error.
                         if syscall == SyscallCode::HALT && precompile_rt.exit_code != 0 {
-                            return Err(ExecutionError::HaltWithNonZeroExitCode(
-                                precompile_rt.exit_code,
-                            ));
+                            return Err(match precompile_rt.exit_code {
+                                OUT_OF_MEMORY_EXIT_CODE => ExecutionError::OutOfMemory(),
+                                exit_code => ExecutionError::HaltWithNonZeroExitCode(exit_code),
+                            });
+                        }
+
+                        // If the syscall raised an error, return it.
+                        if let Some(error) = precompile_rt.error.take() {
+                            return Err(error);
                         }
 
                         (
@@ -916,6 +934,52 @@ impl Runtime {
                 self.alu_rw(instruction, rd, a, b, c);
             }
 
+            // Atomic instructions.
+            //
+            // Since the VM executes a single hart, every atomic instruction is a read-modify-write
+            // of an aligned word and a reservation made by LR.W can never be invalidated, so SC.W
+            // always succeeds.
+            Opcode::LR_W
+            | Opcode::SC_W
+            | Opcode::AMOSWAP_W
+            | Opcode::AMOADD_W
+            | Opcode::AMOXOR_W
+            | Opcode::AMOAND_W
+            | Opcode::AMOOR_W
+            | Opcode::AMOMIN_W
+            | Opcode::AMOMAX_W
+            | Opcode::AMOMINU_W
+            | Opcode::AMOMAXU_W => {
+                (rd, b, c, addr, memory_read_value) = self.atomic_rr(instruction);
+                if addr % 4 != 0 {
+                    return Err(ExecutionError::InvalidMemoryAccess(
+                        instruction.opcode,
+                        addr,
+                    ));
+                }
+                let value = match instruction.opcode {
+                    Opcode::LR_W => memory_read_value,
+                    Opcode::SC_W | Opcode::AMOSWAP_W => c,
+                    Opcode::AMOADD_W => memory_read_value.wrapping_add(c),
+                    Opcode::AMOXOR_W => memory_read_value ^ c,
+                    Opcode::AMOAND_W => memory_read_value & c,
+                    Opcode::AMOOR_W => memory_read_value | c,
+                    Opcode::AMOMIN_W => (memory_read_value as i32).min(c as i32) as u32,
+                    Opcode::AMOMAX_W => (memory_read_value as i32).max(c as i32) as u32,
+                    Opcode::AMOMINU_W => memory_read_value.min(c),
+                    Opcode::AMOMAXU_W => memory_read_value.max(c),
+                    _ => unreachable!(),
+                };
+                a = if instruction.opcode == Opcode::SC_W {
+                    0
+                } else {
+                    memory_read_value
+                };
+                memory_store_value = Some(value);
+                self.mw_cpu(addr, value, MemoryAccessPosition::Memory);
+                self.rw(rd, a);
+            }
+
             // See https://github.com/riscv-non-isa/riscv-asm-manual/blob/master/riscv-asm.md#instruction-aliases
             Opcode::UNIMP => {
                 return Err(ExecutionError::Unimplemented());
@@ -978,8 +1042,7 @@ impl Runtime {
             self.state.channel = 0;
         }
 
-        Ok(self.state.pc.wrapping_sub(self.program.pc_base)
-            >= (self.program.instructions.len() * 4) as u32)
+        Ok(self.state.pc.wrapping_sub(self.program.pc_base) >= self.program.code_size())
     }
 
     /// Execute up to `self.shard_batch_size` cycles, returning the events emitted and whether the program ended.
@@ -1104,7 +1167,7 @@ impl Runtime {
 
         // We handle the addr = 0 case separately, as we constrain it to be 0 in the first row
         // of the memory finalize table so it must be first in the array of events.
-        let addr_0_record = self.state.memory.get(&0u32);
+        let addr_0_record = self.state.memory.get(0);
 
         let addr_0_final_record = match addr_0_record {
             Some(record) => record,
@@ -1119,15 +1182,13 @@ impl Runtime {
             addr_0_final_record,
         ));
 
-        for addr in self.state.memory.keys() {
-            if addr == &0 {
+        for (addr, record) in self.state.memory.iter() {
+            if addr == 0 {
                 continue; // We handle addr = 0 separately above.
             }
 
-            let record = *self.state.memory.get(addr).unwrap();
-
             memory_finalize_events.push(MemoryInitializeFinalizeEvent::finalize_from_record(
-                *addr, &record,
+                addr, record,
             ));
         }
     }
@@ -1140,15 +1201,19 @@ impl Runtime {
 #[cfg(test)]
 pub mod tests {
 
+    use sp1_primitives::OUT_OF_MEMORY_EXIT_CODE;
+
     use crate::{
+        io::SP1Stdin,
         runtime::Register,
+        syscall::{DeferredProofVerificationError, FD_ENV, FD_FILE},
         utils::{
-            tests::{FIBONACCI_ELF, PANIC_ELF, SSZ_WITHDRAWALS_ELF},
+            tests::{FIBONACCI_ELF, PANIC_ELF, SSZ_WITHDRAWALS_ELF, VERIFY_PROOF_ELF},
             SP1CoreOpts,
         },
     };
 
-    use super::{Instruction, Opcode, Program, Runtime};
+    use super::{ExecutionError, Instruction, Opcode, Program, Runtime, SyscallCode};
 
     pub fn simple_program() -> Program {
         let instructions = vec![
@@ -1226,11 +1291,28 @@ pub mod tests {
                     (HALT, 1),
                 ]
                 .into(),
+                shard_sizing: None,
             }
         });
         assert_eq!(runtime.report.total_instruction_count(), 2757356);
     }
 
+    #[test]
+    fn test_verify_proof_missing_from_proof_stream() {
+        let program = Program::from(VERIFY_PROOF_ELF);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.write_stdin(&[0u32; 8]);
+        runtime.write_stdin(&vec![vec![1u8, 2, 3]]);
+        let result = runtime.run();
+        assert!(matches!(
+            result,
+            Err(ExecutionError::InvalidDeferredProof(
+                0,
+                DeferredProofVerificationError::ProofStreamExhausted
+            ))
+        ));
+    }
+
     #[test]
     #[should_panic]
     fn test_panic() {
@@ -1239,6 +1321,70 @@ pub mod tests {
         runtime.run().unwrap();
     }
 
+    #[test]
+    fn test_out_of_memory() {
+        // main:
+        //     addi x5, x0, 0
+        //     addi x10, x0, exit_code
+        //     ecall
+        let halt = |exit_code: u32| {
+            let instructions = vec![
+                Instruction::new(Opcode::ADD, 5, 0, 0, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, exit_code, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ];
+            let program = Program::new(instructions, 0, 0);
+            Runtime::new(program, SP1CoreOpts::default()).run()
+        };
+        assert!(matches!(
+            halt(OUT_OF_MEMORY_EXIT_CODE),
+            Err(ExecutionError::OutOfMemory())
+        ));
+
+        // No byte exit code is reported as out of memory.
+        for exit_code in [2, u8::MAX as u32] {
+            assert!(matches!(
+                halt(exit_code),
+                Err(ExecutionError::HaltWithNonZeroExitCode(code)) if code == exit_code
+            ));
+        }
+    }
+
+    #[test]
+    fn test_env_and_files() {
+        let name = |name: &[u8; 4]| u32::from_le_bytes(*name);
+        let request = |fd: u32, name: u32| {
+            vec![
+                Instruction::new(Opcode::ADD, 29, 0, name, false, true),
+                Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, fd, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+                Instruction::new(Opcode::ADD, 12, 0, 4, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HINT_LEN as u32, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]
+        };
+        let mut instructions = request(FD_ENV, name(b"HOME"));
+        instructions.push(Instruction::new(Opcode::ADD, 30, 5, 0, false, true));
+        instructions.extend(request(FD_FILE, name(b"DATA")));
+        instructions.push(Instruction::new(Opcode::ADD, 31, 5, 0, false, true));
+        let program = Program::new(instructions, 0, 0);
+
+        let mut stdin = SP1Stdin::new();
+        stdin.set_env("HOME", "/root");
+        stdin.write_file("CONFIG", vec![1, 2, 3]);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.write_inputs(&stdin);
+        runtime.run().unwrap();
+
+        // Each value is inserted as the next input, followed by whether it exists.
+        assert_eq!(runtime.register(Register::X30), 6);
+        assert_eq!(runtime.register(Register::X31), 1);
+        assert_eq!(runtime.state.input_stream, [vec![0], b"/root\x01".to_vec()]);
+    }
+
     #[test]
     fn test_add() {
         // main:
@@ -1841,4 +1987,106 @@ pub mod tests {
         assert_eq!(runtime.register(Register::X12), 0x12346525);
         assert_eq!(runtime.register(Register::X11), 0x65256525);
     }
+
+    pub fn simple_atomic_program() -> Program {
+        let instructions = vec![
+            // Store 5 at the address 0x27654320 held in x31.
+            Instruction::new(Opcode::ADD, 31, 0, 0x27654320, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 5, false, true),
+            Instruction::new(Opcode::SW, 30, 31, 0, false, true),
+            // LR and SC
+            Instruction::new(Opcode::ADD, 29, 0, 7, false, true),
+            Instruction::new(Opcode::LR_W, 28, 31, 0, false, false),
+            Instruction::new(Opcode::SC_W, 27, 31, 29, false, false),
+            Instruction::new(Opcode::LW, 26, 31, 0, false, true),
+            // AMOADD, AMOSWAP
+            Instruction::new(Opcode::AMOADD_W, 25, 31, 29, false, false),
+            Instruction::new(Opcode::AMOSWAP_W, 24, 31, 30, false, false),
+            // AMOXOR, AMOAND, AMOOR
+            Instruction::new(Opcode::AMOXOR_W, 0, 31, 29, false, false),
+            Instruction::new(Opcode::AMOAND_W, 23, 31, 30, false, false),
+            Instruction::new(Opcode::AMOOR_W, 22, 31, 29, false, false),
+            // AMOMIN, AMOMAX, AMOMINU, AMOMAXU with a negative operand.
+            Instruction::new(Opcode::ADD, 21, 0, 0xfffffffe, false, true),
+            Instruction::new(Opcode::AMOMAXU_W, 0, 31, 21, false, false),
+            Instruction::new(Opcode::AMOMIN_W, 20, 31, 29, false, false),
+            Instruction::new(Opcode::AMOMAX_W, 19, 31, 29, false, false),
+            Instruction::new(Opcode::AMOMINU_W, 18, 31, 21, false, false),
+            Instruction::new(Opcode::LW, 17, 31, 0, false, true),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_simple_atomic_program_run() {
+        let program = simple_atomic_program();
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // Assert LR & SC case
+        assert_eq!(runtime.register(Register::X28), 5);
+        assert_eq!(runtime.register(Register::X27), 0);
+        assert_eq!(runtime.register(Register::X26), 7);
+
+        // Assert AMOADD & AMOSWAP cases
+        assert_eq!(runtime.register(Register::X25), 7);
+        assert_eq!(runtime.register(Register::X24), 14);
+
+        // Assert AMOXOR, AMOAND & AMOOR cases
+        assert_eq!(runtime.register(Register::X0), 0);
+        assert_eq!(runtime.register(Register::X23), 5 ^ 7);
+        assert_eq!(runtime.register(Register::X22), (5 ^ 7) & 5);
+
+        // Assert AMOMIN, AMOMAX, AMOMINU & AMOMAXU cases
+        assert_eq!(runtime.register(Register::X20), 0xfffffffe);
+        assert_eq!(runtime.register(Register::X19), 0xfffffffe);
+        assert_eq!(runtime.register(Register::X18), 7);
+        assert_eq!(runtime.register(Register::X17), 7);
+    }
+
+    pub fn simple_compressed_program() -> Program {
+        let instructions = vec![
+            // c.li x29, 5
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true).with_compressed(true),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            // c.jal 6
+            Instruction::new(Opcode::JAL, 1, 6, 0, true, true).with_compressed(true),
+            Instruction::new(Opcode::ADD, 31, 0, 1, false, true).with_compressed(true),
+            Instruction::new(Opcode::ADD, 31, 0, 2, false, true).with_compressed(true),
+            // c.beqz x29, 100
+            Instruction::new(Opcode::BEQ, 29, 0, 100, false, true).with_compressed(true),
+            Instruction::new(Opcode::ADD, 28, 29, 30, false, false),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        program.instruction_pcs = vec![0, 2, 6, 8, 10, 12, 14];
+        program
+    }
+
+    #[test]
+    fn test_simple_compressed_program_run() {
+        let program = simple_compressed_program();
+        assert_eq!(program.code_size(), 18);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X1), 8);
+        assert_eq!(runtime.register(Register::X31), 0);
+        assert_eq!(runtime.register(Register::X28), 42);
+    }
+
+    #[test]
+    fn test_misaligned_atomic() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 31, 0, 0x27654322, false, true),
+            Instruction::new(Opcode::AMOADD_W, 30, 31, 0, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        assert!(matches!(
+            runtime.run(),
+            Err(ExecutionError::InvalidMemoryAccess(
+                Opcode::AMOADD_W,
+                0x27654322
+            ))
+        ));
+    }
 }
@@ -60,6 +60,19 @@ pub enum Opcode {
 
     // Miscellaneaous instructions.
     UNIMP = 39,
+
+    // Atomic instructions.
+    LR_W = 40,
+    SC_W = 41,
+    AMOSWAP_W = 42,
+    AMOADD_W = 43,
+    AMOXOR_W = 44,
+    AMOAND_W = 45,
+    AMOOR_W = 46,
+    AMOMIN_W = 47,
+    AMOMAX_W = 48,
+    AMOMINU_W = 49,
+    AMOMAXU_W = 50,
 }
 
 impl Display for Opcode {
@@ -109,6 +122,17 @@ impl Opcode {
             Opcode::REM => "rem",
             Opcode::REMU => "remu",
             Opcode::UNIMP => "unimp",
+            Opcode::LR_W => "lr.w",
+            Opcode::SC_W => "sc.w",
+            Opcode::AMOSWAP_W => "amoswap.w",
+            Opcode::AMOADD_W => "amoadd.w",
+            Opcode::AMOXOR_W => "amoxor.w",
+            Opcode::AMOAND_W => "amoand.w",
+            Opcode::AMOOR_W => "amoor.w",
+            Opcode::AMOMIN_W => "amomin.w",
+            Opcode::AMOMAX_W => "amomax.w",
+            Opcode::AMOMINU_W => "amominu.w",
+            Opcode::AMOMAXU_W => "amomaxu.w",
         }
     }
 }
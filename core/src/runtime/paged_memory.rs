@@ -0,0 +1,297 @@
+use std::{collections::HashMap, sync::Arc};
+
+use nohash_hasher::BuildNoHashHasher;
+use serde::{Deserialize, Serialize};
+
+use super::MemoryRecord;
+
+/// The number of registers, which are stored outside of the page table.
+const NUM_REGISTERS: usize = 32;
+
+/// The log2 of the number of words in a page.
+pub const LOG_PAGE_SIZE: usize = 10;
+
+/// The number of words in a page.
+pub const PAGE_SIZE: usize = 1 << LOG_PAGE_SIZE;
+
+/// The number of `u64`s in the occupancy bitmap of a serialized page.
+const BITMAP_SIZE: usize = PAGE_SIZE / 64;
+
+type Page = [Option<MemoryRecord>; PAGE_SIZE];
+
+/// The memory of the VM, stored in a page table keyed by address.
+///
+/// Registers live at addresses 0 to 31, which are not word aligned, so they are kept in a separate
+/// array. The remaining addresses are word aligned and are stored in pages of [PAGE_SIZE] words.
+///
+/// Pages are shared copy-on-write: cloning the memory, e.g. for a checkpoint or when forking into
+/// an unconstrained block, only clones the page table, and a page is copied the first time it is
+/// written to by one of the clones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "SerializedMemory", into = "SerializedMemory")]
+pub struct PagedMemory {
+    registers: [Option<MemoryRecord>; NUM_REGISTERS],
+    pages: HashMap<u32, Arc<Page>, BuildNoHashHasher<u32>>,
+}
+
+/// A view into a single address of [PagedMemory], which may be occupied or vacant.
+pub enum MemoryEntry<'a> {
+    Occupied(OccupiedMemoryEntry<'a>),
+    Vacant(VacantMemoryEntry<'a>),
+}
+
+/// An address of [PagedMemory] that holds a record.
+pub struct OccupiedMemoryEntry<'a> {
+    slot: &'a mut Option<MemoryRecord>,
+}
+
+/// An address of [PagedMemory] that has never been accessed.
+pub struct VacantMemoryEntry<'a> {
+    slot: &'a mut Option<MemoryRecord>,
+}
+
+impl<'a> OccupiedMemoryEntry<'a> {
+    /// Gets a reference to the record in the entry.
+    pub fn get(&self) -> &MemoryRecord {
+        self.slot.as_ref().unwrap()
+    }
+
+    /// Converts the entry into a mutable reference to its record.
+    pub fn into_mut(self) -> &'a mut MemoryRecord {
+        self.slot.as_mut().unwrap()
+    }
+}
+
+impl<'a> VacantMemoryEntry<'a> {
+    /// Inserts a record into the entry and returns a mutable reference to it.
+    pub fn insert(self, record: MemoryRecord) -> &'a mut MemoryRecord {
+        self.slot.insert(record)
+    }
+}
+
+/// Splits a word aligned address into its page index and its offset inside the page.
+#[inline]
+fn page_and_offset(addr: u32) -> (u32, usize) {
+    debug_assert_eq!(addr % 4, 0, "address {} is not word aligned", addr);
+    let word = addr >> 2;
+    (word >> LOG_PAGE_SIZE, word as usize & (PAGE_SIZE - 1))
+}
+
+impl PagedMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the record at the given address, if it has been accessed.
+    #[inline]
+    pub fn get(&self, addr: u32) -> Option<&MemoryRecord> {
+        if (addr as usize) < NUM_REGISTERS {
+            return self.registers[addr as usize].as_ref();
+        }
+        let (page, offset) = page_and_offset(addr);
+        self.pages.get(&page).and_then(|page| page[offset].as_ref())
+    }
+
+    /// Returns a mutable reference to the slot of the given address, allocating its page if needed.
+    #[inline]
+    fn slot_mut(&mut self, addr: u32) -> &mut Option<MemoryRecord> {
+        if (addr as usize) < NUM_REGISTERS {
+            return &mut self.registers[addr as usize];
+        }
+        let (page, offset) = page_and_offset(addr);
+        let page = self
+            .pages
+            .entry(page)
+            .or_insert_with(|| Arc::new([None; PAGE_SIZE]));
+        &mut Arc::make_mut(page)[offset]
+    }
+
+    /// Gets the entry of the given address for in-place manipulation.
+    #[inline]
+    pub fn entry(&mut self, addr: u32) -> MemoryEntry<'_> {
+        let slot = self.slot_mut(addr);
+        if slot.is_some() {
+            MemoryEntry::Occupied(OccupiedMemoryEntry { slot })
+        } else {
+            MemoryEntry::Vacant(VacantMemoryEntry { slot })
+        }
+    }
+
+    /// Inserts a record at the given address, returning the previous record if there was one.
+    pub fn insert(&mut self, addr: u32, record: MemoryRecord) -> Option<MemoryRecord> {
+        self.slot_mut(addr).replace(record)
+    }
+
+    /// Removes the record at the given address, returning it if there was one.
+    pub fn remove(&mut self, addr: u32) -> Option<MemoryRecord> {
+        if (addr as usize) >= NUM_REGISTERS && !self.pages.contains_key(&page_and_offset(addr).0) {
+            return None;
+        }
+        self.slot_mut(addr).take()
+    }
+
+    /// The number of pages that have been allocated.
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Iterates over the accessed addresses and their records.
+    ///
+    /// Registers come first, in order, followed by the pages in an unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &MemoryRecord)> {
+        let registers = self
+            .registers
+            .iter()
+            .enumerate()
+            .filter_map(|(addr, record)| record.as_ref().map(|record| (addr as u32, record)));
+        let pages = self.pages.iter().flat_map(|(index, page)| {
+            page.iter().enumerate().filter_map(move |(offset, record)| {
+                record
+                    .as_ref()
+                    .map(|record| (((index << LOG_PAGE_SIZE) + offset as u32) << 2, record))
+            })
+        });
+        registers.chain(pages)
+    }
+}
+
+/// The serialized form of [PagedMemory], which only stores the accessed words of each page.
+#[derive(Serialize, Deserialize)]
+struct SerializedMemory {
+    registers: Vec<Option<MemoryRecord>>,
+    pages: Vec<SerializedPage>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedPage {
+    index: u32,
+    /// A bitmap of the occupied words of the page.
+    occupied: [u64; BITMAP_SIZE],
+    /// The records of the occupied words, in order.
+    records: Vec<MemoryRecord>,
+}
+
+impl From<PagedMemory> for SerializedMemory {
+    fn from(memory: PagedMemory) -> Self {
+        let mut pages = memory
+            .pages
+            .iter()
+            .map(|(&index, page)| {
+                let mut occupied = [0u64; BITMAP_SIZE];
+                let mut records = Vec::new();
+                for (offset, record) in page.iter().enumerate() {
+                    if let Some(record) = record {
+                        occupied[offset / 64] |= 1 << (offset % 64);
+                        records.push(*record);
+                    }
+                }
+                SerializedPage {
+                    index,
+                    occupied,
+                    records,
+                }
+            })
+            .collect::<Vec<_>>();
+        pages.sort_unstable_by_key(|page| page.index);
+        Self {
+            registers: memory.registers.to_vec(),
+            pages,
+        }
+    }
+}
+
+impl From<SerializedMemory> for PagedMemory {
+    fn from(serialized: SerializedMemory) -> Self {
+        let mut memory = PagedMemory::default();
+        for (register, record) in memory.registers.iter_mut().zip(serialized.registers) {
+            *register = record;
+        }
+        for serialized_page in serialized.pages {
+            let mut page = [None; PAGE_SIZE];
+            let mut records = serialized_page.records.into_iter();
+            for (offset, slot) in page.iter_mut().enumerate() {
+                if serialized_page.occupied[offset / 64] & (1 << (offset % 64)) != 0 {
+                    *slot = records.next();
+                }
+            }
+            memory.pages.insert(serialized_page.index, Arc::new(page));
+        }
+        memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(value: u32) -> MemoryRecord {
+        MemoryRecord {
+            value,
+            shard: 1,
+            timestamp: value,
+        }
+    }
+
+    #[test]
+    fn test_paged_memory() {
+        let mut memory = PagedMemory::new();
+        let addrs = [1, 31, 32, 0x1000, 0x1004, 0x7800_0000, 0xFFFF_FFFC];
+        for &addr in addrs.iter() {
+            assert!(memory.get(addr).is_none());
+            assert!(memory.insert(addr, record(addr)).is_none());
+        }
+        for &addr in addrs.iter() {
+            assert_eq!(memory.get(addr).unwrap().value, addr);
+        }
+
+        let mut iterated = memory.iter().map(|(addr, _)| addr).collect::<Vec<_>>();
+        iterated.sort_unstable();
+        assert_eq!(iterated, addrs);
+
+        match memory.entry(0x1000) {
+            MemoryEntry::Occupied(entry) => entry.into_mut().value = 7,
+            MemoryEntry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+        assert_eq!(memory.get(0x1000).unwrap().value, 7);
+        assert!(matches!(memory.entry(0x1008), MemoryEntry::Vacant(_)));
+
+        assert_eq!(memory.remove(0x1004).unwrap().value, 0x1004);
+        assert!(memory.get(0x1004).is_none());
+        assert!(memory.remove(0x2000_0000).is_none());
+    }
+
+    #[test]
+    fn test_paged_memory_copy_on_write() {
+        let mut memory = PagedMemory::new();
+        memory.insert(0x1000, record(1));
+
+        let snapshot = memory.clone();
+        memory.insert(0x1000, record(2));
+        memory.insert(0x1004, record(3));
+
+        assert_eq!(snapshot.get(0x1000).unwrap().value, 1);
+        assert!(snapshot.get(0x1004).is_none());
+        assert_eq!(memory.get(0x1000).unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_paged_memory_serialization() {
+        let mut memory = PagedMemory::new();
+        memory.insert(5, record(5));
+        for addr in (0x2000..0x3000).step_by(12) {
+            memory.insert(addr, record(addr));
+        }
+
+        let bytes = bincode::serialize(&memory).unwrap();
+        let deserialized: PagedMemory = bincode::deserialize(&bytes).unwrap();
+
+        let mut expected = memory.iter().map(|(a, r)| (a, r.value)).collect::<Vec<_>>();
+        let mut actual = deserialized
+            .iter()
+            .map(|(a, r)| (a, r.value))
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        actual.sort_unstable();
+        assert_eq!(expected, actual);
+    }
+}
@@ -17,4 +17,39 @@ pub struct Program {
 
     /// The initial memory image, useful for global constants.
     pub memory_image: BTreeMap<u32, u32>,
+
+    /// The address of each instruction if the program contains compressed instructions.  When
+    /// this is empty, the instruction at index `i` is located at `pc_base + 4 * i`.
+    #[serde(default)]
+    pub instruction_pcs: Vec<u32>,
+}
+
+impl Program {
+    /// Returns the address of the instruction at the given index.
+    pub fn instruction_pc(&self, index: usize) -> u32 {
+        if self.instruction_pcs.is_empty() {
+            self.pc_base + index as u32 * 4
+        } else {
+            self.instruction_pcs[index]
+        }
+    }
+
+    /// Returns the index of the instruction at the given address.
+    pub fn instruction_index(&self, pc: u32) -> usize {
+        if self.instruction_pcs.is_empty() {
+            ((pc - self.pc_base) / 4) as usize
+        } else {
+            self.instruction_pcs
+                .binary_search(&pc)
+                .unwrap_or_else(|_| panic!("no instruction at pc {:#x}", pc))
+        }
+    }
+
+    /// Returns the size of the program's instructions in bytes.
+    pub fn code_size(&self) -> u32 {
+        match (self.instruction_pcs.last(), self.instructions.last()) {
+            (Some(pc), Some(instruction)) => pc - self.pc_base + instruction.size(),
+            _ => (self.instructions.len() * 4) as u32,
+        }
+    }
 }
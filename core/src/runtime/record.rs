@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 use p3_field::AbstractField;
+use p3_keccak_air::NUM_ROUNDS;
 use serde::{Deserialize, Serialize};
 
 use super::program::Program;
@@ -17,15 +18,53 @@ use crate::cpu::CpuEvent;
 use crate::runtime::MemoryInitializeFinalizeEvent;
 use crate::runtime::MemoryRecordEnum;
 use crate::stark::MachineRecord;
-use crate::syscall::precompiles::blake3::Blake3CompressInnerEvent;
+use crate::syscall::precompiles::bigint::BigIntMulModEvent;
+use crate::syscall::precompiles::blake3::{Blake3CompressInnerEvent, OPERATION_COUNT, ROUND_COUNT};
 use crate::syscall::precompiles::edwards::EdDecompressEvent;
 use crate::syscall::precompiles::keccak256::KeccakPermuteEvent;
+use crate::syscall::precompiles::memcopy::{MemCopyEvent, MemSetEvent};
 use crate::syscall::precompiles::sha256::{ShaCompressEvent, ShaExtendEvent};
-use crate::syscall::precompiles::uint256::Uint256MulEvent;
+use crate::syscall::precompiles::uint256::{Uint256AddSubEvent, Uint256MulEvent};
 use crate::syscall::precompiles::ECDecompressEvent;
 use crate::syscall::precompiles::{ECAddEvent, ECDoubleEvent};
 use crate::utils::SP1CoreOpts;
 
+/// Calls the macro `$callback` with its arguments followed by `;` and a list of `events: len`
+/// pairs, one for each precompile, where `events` is the field of its events in [ExecutionRecord]
+/// and `len` the field of its limit in [ShardingConfig].
+///
+/// A new precompile only needs to be added here to be counted, appended and sharded.
+macro_rules! with_precompile_events {
+    ($callback:ident!($($args:tt)*)) => {
+        $callback!(
+            $($args)*;
+            sha_extend_events: sha_extend_len,
+            sha_compress_events: sha_compress_len,
+            keccak_permute_events: keccak_len,
+            ed_add_events: ed_add_len,
+            ed_decompress_events: ed_decompress_len,
+            k256_decompress_events: k256_decompress_len,
+            secp256k1_add_events: secp256k1_add_len,
+            secp256k1_double_events: secp256k1_double_len,
+            bn254_add_events: bn254_add_len,
+            bn254_double_events: bn254_double_len,
+            blake3_compress_inner_events: blake3_compress_inner_len,
+            bls12381_add_events: bls12381_add_len,
+            bls12381_double_events: bls12381_double_len,
+            bls12381_decompress_events: bls12381_decompress_len,
+            uint256_mul_events: uint256_mul_len,
+            uint256_addmod_events: uint256_addmod_len,
+            uint256_submod_events: uint256_submod_len,
+            uint512_mulmod_events: uint512_mulmod_len,
+            uint1024_mulmod_events: uint1024_mulmod_len,
+            uint2048_mulmod_events: uint2048_mulmod_len,
+            uint4096_mulmod_events: uint4096_mulmod_len,
+            memcpy_events: memcpy_len,
+            memset_events: memset_len,
+        )
+    };
+}
+
 /// A record of the execution of a program. Contains event data for everything that happened during
 /// the execution of the shard.
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -95,6 +134,22 @@ pub struct ExecutionRecord {
 
     pub uint256_mul_events: Vec<Uint256MulEvent>,
 
+    pub uint256_addmod_events: Vec<Uint256AddSubEvent>,
+
+    pub uint256_submod_events: Vec<Uint256AddSubEvent>,
+
+    pub uint512_mulmod_events: Vec<BigIntMulModEvent>,
+
+    pub uint1024_mulmod_events: Vec<BigIntMulModEvent>,
+
+    pub uint2048_mulmod_events: Vec<BigIntMulModEvent>,
+
+    pub uint4096_mulmod_events: Vec<BigIntMulModEvent>,
+
+    pub memcpy_events: Vec<MemCopyEvent>,
+
+    pub memset_events: Vec<MemSetEvent>,
+
     pub memory_initialize_events: Vec<MemoryInitializeFinalizeEvent>,
 
     pub memory_finalize_events: Vec<MemoryInitializeFinalizeEvent>,
@@ -105,6 +160,23 @@ pub struct ExecutionRecord {
     pub public_values: PublicValues<u32, u32>,
 }
 
+/// The number of rows of the SHA-256 extend trace per event.
+pub(crate) const SHA_EXTEND_ROWS_PER_EVENT: usize = 48;
+
+/// The number of rows of the SHA-256 compress trace per event.
+pub(crate) const SHA_COMPRESS_ROWS_PER_EVENT: usize = 80;
+
+/// The number of rows of the Keccak permutation trace per event.
+pub(crate) const KECCAK_ROWS_PER_EVENT: usize = NUM_ROUNDS;
+
+/// The number of rows of the Blake3 compress trace per event.
+pub(crate) const BLAKE3_COMPRESS_ROWS_PER_EVENT: usize = ROUND_COUNT * OPERATION_COUNT;
+
+/// The maximum number of events of each kind in a shard.
+///
+/// Precompile events are kept in the shard they were executed in up to their limit, and the rest
+/// are moved to precompile shards, which have no CPU events. The precompile limits are chosen so
+/// that each precompile trace has about as many rows as a full CPU trace.
 pub struct ShardingConfig {
     pub shard_size: usize,
     pub add_len: usize,
@@ -116,25 +188,36 @@ pub struct ShardingConfig {
     pub divrem_len: usize,
     pub lt_len: usize,
     pub field_len: usize,
+    pub sha_extend_len: usize,
+    pub sha_compress_len: usize,
     pub keccak_len: usize,
+    pub ed_add_len: usize,
+    pub ed_decompress_len: usize,
+    pub k256_decompress_len: usize,
     pub secp256k1_add_len: usize,
     pub secp256k1_double_len: usize,
     pub bn254_add_len: usize,
     pub bn254_double_len: usize,
+    pub blake3_compress_inner_len: usize,
     pub bls12381_add_len: usize,
     pub bls12381_double_len: usize,
+    pub bls12381_decompress_len: usize,
     pub uint256_mul_len: usize,
+    pub uint256_addmod_len: usize,
+    pub uint256_submod_len: usize,
+    pub uint512_mulmod_len: usize,
+    pub uint1024_mulmod_len: usize,
+    pub uint2048_mulmod_len: usize,
+    pub uint4096_mulmod_len: usize,
+    pub memcpy_len: usize,
+    pub memset_len: usize,
 }
 
 impl ShardingConfig {
-    pub const fn shard_size(&self) -> usize {
-        self.shard_size
-    }
-}
-
-impl Default for ShardingConfig {
-    fn default() -> Self {
-        let shard_size = SP1CoreOpts::default().shard_size;
+    /// Creates a sharding config where every chip is sized to match the given shard size.
+    pub fn new(shard_size: usize) -> Self {
+        // The number of events whose rows fill a trace of `shard_size` rows.
+        let events = |rows_per_event: usize| (shard_size / rows_per_event).max(1);
         Self {
             shard_size,
             add_len: shard_size,
@@ -146,16 +229,43 @@ impl Default for ShardingConfig {
             mul_len: shard_size,
             shift_right_len: shard_size,
             field_len: shard_size * 4,
-            keccak_len: shard_size,
+            sha_extend_len: events(SHA_EXTEND_ROWS_PER_EVENT),
+            sha_compress_len: events(SHA_COMPRESS_ROWS_PER_EVENT),
+            keccak_len: events(KECCAK_ROWS_PER_EVENT),
+            ed_add_len: shard_size,
+            ed_decompress_len: shard_size,
+            k256_decompress_len: shard_size,
             secp256k1_add_len: shard_size,
             secp256k1_double_len: shard_size,
             bn254_add_len: shard_size,
             bn254_double_len: shard_size,
+            blake3_compress_inner_len: events(BLAKE3_COMPRESS_ROWS_PER_EVENT),
             bls12381_add_len: shard_size,
             bls12381_double_len: shard_size,
+            bls12381_decompress_len: shard_size,
             uint256_mul_len: shard_size,
+            uint256_addmod_len: shard_size,
+            uint256_submod_len: shard_size,
+            // The wider multiplications have proportionally fewer events, so that their traces take
+            // about as much space as the uint256 one.
+            uint512_mulmod_len: events(2),
+            uint1024_mulmod_len: events(4),
+            uint2048_mulmod_len: events(8),
+            uint4096_mulmod_len: events(16),
+            memcpy_len: shard_size,
+            memset_len: shard_size,
         }
     }
+
+    pub const fn shard_size(&self) -> usize {
+        self.shard_size
+    }
+}
+
+impl Default for ShardingConfig {
+    fn default() -> Self {
+        Self::new(SP1CoreOpts::default().shard_size)
+    }
 }
 
 impl MachineRecord for ExecutionRecord {
@@ -186,61 +296,12 @@ impl MachineRecord for ExecutionRecord {
         );
         stats.insert("divrem_events".to_string(), self.divrem_events.len());
         stats.insert("lt_events".to_string(), self.lt_events.len());
-        stats.insert(
-            "sha_extend_events".to_string(),
-            self.sha_extend_events.len(),
-        );
-        stats.insert(
-            "sha_compress_events".to_string(),
-            self.sha_compress_events.len(),
-        );
-        stats.insert(
-            "keccak_permute_events".to_string(),
-            self.keccak_permute_events.len(),
-        );
-        stats.insert("ed_add_events".to_string(), self.ed_add_events.len());
-        stats.insert(
-            "ed_decompress_events".to_string(),
-            self.ed_decompress_events.len(),
-        );
-        stats.insert(
-            "secp256k1_add_events".to_string(),
-            self.secp256k1_add_events.len(),
-        );
-        stats.insert(
-            "secp256k1_double_events".to_string(),
-            self.secp256k1_double_events.len(),
-        );
-        stats.insert("bn254_add_events".to_string(), self.bn254_add_events.len());
-        stats.insert(
-            "bn254_double_events".to_string(),
-            self.bn254_double_events.len(),
-        );
-        stats.insert(
-            "k256_decompress_events".to_string(),
-            self.k256_decompress_events.len(),
-        );
-        stats.insert(
-            "blake3_compress_inner_events".to_string(),
-            self.blake3_compress_inner_events.len(),
-        );
-        stats.insert(
-            "bls12381_add_events".to_string(),
-            self.bls12381_add_events.len(),
-        );
-        stats.insert(
-            "bls12381_double_events".to_string(),
-            self.bls12381_double_events.len(),
-        );
-        stats.insert(
-            "uint256_mul_events".to_string(),
-            self.uint256_mul_events.len(),
-        );
-
-        stats.insert(
-            "bls12381_decompress_events".to_string(),
-            self.bls12381_decompress_events.len(),
-        );
+        macro_rules! insert_precompile_stats {
+            ($record:ident; $($events:ident: $len:ident),* $(,)?) => {
+                $(stats.insert(stringify!($events).to_string(), $record.$events.len());)*
+            };
+        }
+        with_precompile_events!(insert_precompile_stats!(self));
         stats
     }
 
@@ -255,33 +316,12 @@ impl MachineRecord for ExecutionRecord {
             .append(&mut other.shift_right_events);
         self.divrem_events.append(&mut other.divrem_events);
         self.lt_events.append(&mut other.lt_events);
-        self.sha_extend_events.append(&mut other.sha_extend_events);
-        self.sha_compress_events
-            .append(&mut other.sha_compress_events);
-        self.keccak_permute_events
-            .append(&mut other.keccak_permute_events);
-        self.ed_add_events.append(&mut other.ed_add_events);
-        self.ed_decompress_events
-            .append(&mut other.ed_decompress_events);
-        self.secp256k1_add_events
-            .append(&mut other.secp256k1_add_events);
-        self.secp256k1_double_events
-            .append(&mut other.secp256k1_double_events);
-        self.bn254_add_events.append(&mut other.bn254_add_events);
-        self.bn254_double_events
-            .append(&mut other.bn254_double_events);
-        self.k256_decompress_events
-            .append(&mut other.k256_decompress_events);
-        self.blake3_compress_inner_events
-            .append(&mut other.blake3_compress_inner_events);
-        self.bls12381_add_events
-            .append(&mut other.bls12381_add_events);
-        self.bls12381_double_events
-            .append(&mut other.bls12381_double_events);
-        self.uint256_mul_events
-            .append(&mut other.uint256_mul_events);
-        self.bls12381_decompress_events
-            .append(&mut other.bls12381_decompress_events);
+        macro_rules! append_precompile_events {
+            ($record:ident, $other:ident; $($events:ident: $len:ident),* $(,)?) => {
+                $($record.$events.append(&mut $other.$events);)*
+            };
+        }
+        with_precompile_events!(append_precompile_events!(self, other));
 
         // Merge the byte lookups.
         for (shard, events_map) in std::mem::take(&mut other.byte_lookups).into_iter() {
@@ -424,98 +464,23 @@ impl MachineRecord for ExecutionRecord {
             shard.lt_events.extend_from_slice(lt_chunk);
         }
 
-        // Keccak-256 permute events.
-        for (keccak_chunk, shard) in take(&mut self.keccak_permute_events)
-            .chunks_mut(config.keccak_len)
-            .zip(shards.iter_mut())
-        {
-            shard.keccak_permute_events.extend_from_slice(keccak_chunk);
-        }
-
-        // secp256k1 curve add events.
-        for (secp256k1_add_chunk, shard) in take(&mut self.secp256k1_add_events)
-            .chunks_mut(config.secp256k1_add_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .secp256k1_add_events
-                .extend_from_slice(secp256k1_add_chunk);
+        // Keep each precompile event in the shard it was executed in, up to the limit of its chip,
+        // and collect the rest to be proven in precompile shards.
+        let mut spilled = ExecutionRecord::new(0, self.program.clone());
+        macro_rules! distribute_precompile_events {
+            ($record:ident, $config:ident; $($events:ident: $len:ident),* $(,)?) => {
+                $(
+                    spilled.$events = distribute_precompile_events(
+                        take(&mut $record.$events),
+                        &mut shards,
+                        $config.$len,
+                        |event| event.shard,
+                        |shard| &mut shard.$events,
+                    );
+                )*
+            };
         }
-
-        // secp256k1 curve double events.
-        for (secp256k1_double_chunk, shard) in take(&mut self.secp256k1_double_events)
-            .chunks_mut(config.secp256k1_double_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .secp256k1_double_events
-                .extend_from_slice(secp256k1_double_chunk);
-        }
-
-        // bn254 curve add events.
-        for (bn254_add_chunk, shard) in take(&mut self.bn254_add_events)
-            .chunks_mut(config.bn254_add_len)
-            .zip(shards.iter_mut())
-        {
-            shard.bn254_add_events.extend_from_slice(bn254_add_chunk);
-        }
-
-        // bn254 curve double events.
-        for (bn254_double_chunk, shard) in take(&mut self.bn254_double_events)
-            .chunks_mut(config.bn254_double_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .bn254_double_events
-                .extend_from_slice(bn254_double_chunk);
-        }
-
-        // BLS12-381 curve add events.
-        for (bls12381_add_chunk, shard) in take(&mut self.bls12381_add_events)
-            .chunks_mut(config.bls12381_add_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .bls12381_add_events
-                .extend_from_slice(bls12381_add_chunk);
-        }
-
-        // BLS12-381 curve double events.
-        for (bls12381_double_chunk, shard) in take(&mut self.bls12381_double_events)
-            .chunks_mut(config.bls12381_double_len)
-            .zip(shards.iter_mut())
-        {
-            shard
-                .bls12381_double_events
-                .extend_from_slice(bls12381_double_chunk);
-        }
-
-        // Put the precompile events in the first shard.
-        let first = shards.first_mut().unwrap();
-
-        // SHA-256 extend events.
-        first.sha_extend_events = std::mem::take(&mut self.sha_extend_events);
-
-        // SHA-256 compress events.
-        first.sha_compress_events = std::mem::take(&mut self.sha_compress_events);
-
-        // Edwards curve add events.
-        first.ed_add_events = std::mem::take(&mut self.ed_add_events);
-
-        // Edwards curve decompress events.
-        first.ed_decompress_events = std::mem::take(&mut self.ed_decompress_events);
-
-        // K256 curve decompress events.
-        first.k256_decompress_events = std::mem::take(&mut self.k256_decompress_events);
-
-        // Blake3 compress events .
-        first.blake3_compress_inner_events = std::mem::take(&mut self.blake3_compress_inner_events);
-
-        // Uint256 mul arithmetic events.
-        first.uint256_mul_events = std::mem::take(&mut self.uint256_mul_events);
-
-        // Bls12-381 decompress events .
-        first.bls12381_decompress_events = std::mem::take(&mut self.bls12381_decompress_events);
+        with_precompile_events!(distribute_precompile_events!(self, config));
 
         // Put the memory records in the last shard.
         let last_shard = shards.last_mut().unwrap();
@@ -527,6 +492,11 @@ impl MachineRecord for ExecutionRecord {
             .memory_finalize_events
             .extend_from_slice(&self.memory_finalize_events);
 
+        // Prove the spilled precompile events in shards following the last CPU shard.
+        let last_index = last_shard.index;
+        let last_public_values = last_shard.public_values;
+        shards.extend(spilled.precompile_shards(last_index + 1, last_public_values, config));
+
         shards
     }
 
@@ -546,6 +516,48 @@ impl ExecutionRecord {
         }
     }
 
+    /// Splits the precompile events of the record into precompile shards, which have no CPU events
+    /// and are indexed consecutively from `first_index`.
+    ///
+    /// Precompile shards don't execute any instructions, so their start and next pc are both the
+    /// next pc of `public_values`, the public values of the shard they follow.
+    pub fn precompile_shards(
+        mut self,
+        first_index: u32,
+        public_values: PublicValues<u32, u32>,
+        config: &ShardingConfig,
+    ) -> Vec<ExecutionRecord> {
+        let mut shards = Vec::new();
+        macro_rules! fill_precompile_shards {
+            ($record:ident, $config:ident; $($events:ident: $len:ident),* $(,)?) => {
+                $(
+                    fill_precompile_shards(
+                        take(&mut $record.$events),
+                        &mut shards,
+                        $config.$len,
+                        |shard| &mut shard.$events,
+                    );
+                )*
+            };
+        }
+        with_precompile_events!(fill_precompile_shards!(self, config));
+
+        for (i, shard) in shards.iter_mut().enumerate() {
+            let index = first_index + i as u32;
+            shard.index = index;
+            shard.program = self.program.clone();
+            shard.public_values = public_values;
+            shard.public_values.shard = index;
+            shard.public_values.start_pc = public_values.next_pc;
+        }
+        shards
+    }
+
+    /// Whether the record is a precompile shard, i.e. it has no CPU events.
+    pub fn is_precompile_shard(&self) -> bool {
+        self.cpu_events.is_empty()
+    }
+
     pub fn add_mul_event(&mut self, mul_event: AluEvent) {
         self.mul_events.push(mul_event);
     }
@@ -589,6 +601,46 @@ impl ExecutionRecord {
     }
 }
 
+/// Moves precompile events into the CPU shard they were executed in, keeping at most `max_len`
+/// events per shard, and returns the events that didn't fit.
+fn distribute_precompile_events<E>(
+    events: Vec<E>,
+    shards: &mut [ExecutionRecord],
+    max_len: usize,
+    event_shard: impl Fn(&E) -> u32,
+    shard_events: impl Fn(&mut ExecutionRecord) -> &mut Vec<E>,
+) -> Vec<E> {
+    let first_index = shards[0].index;
+    let mut spilled = Vec::new();
+    for event in events {
+        let shard = event_shard(&event)
+            .checked_sub(first_index)
+            .and_then(|i| shards.get_mut(i as usize))
+            .map(&shard_events);
+        match shard {
+            Some(events) if events.len() < max_len => events.push(event),
+            _ => spilled.push(event),
+        }
+    }
+    spilled
+}
+
+/// Splits precompile events into chunks of `max_len` events, adding the i-th chunk to the i-th
+/// precompile shard and creating shards as needed.
+fn fill_precompile_shards<E: Clone>(
+    events: Vec<E>,
+    shards: &mut Vec<ExecutionRecord>,
+    max_len: usize,
+    shard_events: impl Fn(&mut ExecutionRecord) -> &mut Vec<E>,
+) {
+    for (i, chunk) in events.chunks(max_len).enumerate() {
+        if i == shards.len() {
+            shards.push(ExecutionRecord::default());
+        }
+        shard_events(&mut shards[i]).extend_from_slice(chunk);
+    }
+}
+
 impl ByteRecord for ExecutionRecord {
     fn add_byte_lookup_event(&mut self, blu_event: ByteLookupEvent) {
         *self
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use nohash_hasher::BuildNoHashHasher;
 use serde::{Deserialize, Serialize};
@@ -9,7 +9,7 @@ use crate::{
     utils::BabyBearPoseidon2,
 };
 
-use super::{ExecutionRecord, MemoryAccessRecord, MemoryRecord};
+use super::{ExecutionRecord, MemoryAccessRecord, PagedMemory};
 
 /// Holds data describing the current state of a program's execution.
 #[serde_as]
@@ -34,7 +34,7 @@ pub struct ExecutionState {
 
     /// The memory which instructions operate over. Values contain the memory value and last shard
     /// + timestamp that each memory address was accessed.
-    pub memory: HashMap<u32, MemoryRecord, BuildNoHashHasher<u32>>,
+    pub memory: PagedMemory,
 
     /// Uninitialized memory addresses that have a specific value they should be initialized with.
     /// SyscallHintRead uses this to write hint data into uninitialized memory.
@@ -55,6 +55,9 @@ pub struct ExecutionState {
     /// A ptr to the current position in the proof stream, incremented after verifying a proof.
     pub proof_stream_ptr: usize,
 
+    /// The environment variables of the program, served to it through the input stream.
+    pub env: BTreeMap<String, String>,
+
     /// A stream of public values from the program (global to entire program).
     pub public_values_stream: Vec<u8>,
 
@@ -71,7 +74,7 @@ impl ExecutionState {
             clk: 0,
             channel: 0,
             pc: pc_start,
-            memory: HashMap::default(),
+            memory: PagedMemory::new(),
             uninitialized_memory: HashMap::default(),
             input_stream: Vec::new(),
             input_stream_ptr: 0,
@@ -79,6 +82,7 @@ impl ExecutionState {
             public_values_stream_ptr: 0,
             proof_stream: Vec::new(),
             proof_stream_ptr: 0,
+            env: BTreeMap::new(),
         }
     }
 }
@@ -95,8 +99,8 @@ pub(crate) struct ForkState {
     /// Original program counter
     pub(crate) pc: u32,
 
-    /// Only contains the original memory values for addresses that have been modified
-    pub(crate) memory_diff: HashMap<u32, Option<MemoryRecord>, BuildNoHashHasher<u32>>,
+    /// Original memory, which shares its pages copy-on-write with the memory of the fork
+    pub(crate) memory: PagedMemory,
 
     /// Full record from original state
     pub(crate) op_record: MemoryAccessRecord,
@@ -4,13 +4,15 @@ use std::sync::Arc;
 
 use strum_macros::EnumIter;
 
-use crate::runtime::{Register, Runtime};
+use crate::runtime::{ExecutionError, Register, Runtime};
 use crate::stark::Blake3CompressInnerChip;
+use crate::syscall::precompiles::bigint::BigIntMulModChip;
 use crate::syscall::precompiles::edwards::EdAddAssignChip;
 use crate::syscall::precompiles::edwards::EdDecompressChip;
 use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+use crate::syscall::precompiles::memcopy::{MemCopyChip, MemSetChip};
 use crate::syscall::precompiles::sha256::{ShaCompressChip, ShaExtendChip};
-use crate::syscall::precompiles::uint256::Uint256MulChip;
+use crate::syscall::precompiles::uint256::{Uint256AddSubChip, Uint256MulChip};
 use crate::syscall::precompiles::weierstrass::WeierstrassAddAssignChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassDecompressChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
@@ -18,6 +20,7 @@ use crate::syscall::{
     SyscallCommit, SyscallCommitDeferred, SyscallEnterUnconstrained, SyscallExitUnconstrained,
     SyscallHalt, SyscallHintLen, SyscallHintRead, SyscallVerifySP1Proof, SyscallWrite,
 };
+use crate::utils::ec::bigint::{U1024Field, U2048Field, U4096Field, U512Field};
 use crate::utils::ec::edwards::ed25519::{Ed25519, Ed25519Parameters};
 use crate::utils::ec::weierstrass::bls12_381::Bls12381;
 use crate::utils::ec::weierstrass::{bn254::Bn254, secp256k1::Secp256k1};
@@ -103,6 +106,30 @@ pub enum SyscallCode {
 
     /// Executes the `BLS12381_DOUBLE` precompile.
     BLS12381_DOUBLE = 0x00_00_01_1F,
+
+    /// Executes the `UINT256_ADDMOD` precompile.
+    UINT256_ADDMOD = 0x00_00_01_20,
+
+    /// Executes the `UINT256_SUBMOD` precompile.
+    UINT256_SUBMOD = 0x00_00_01_21,
+
+    /// Executes the `UINT512_MULMOD` precompile.
+    UINT512_MULMOD = 0x00_00_01_22,
+
+    /// Executes the `UINT1024_MULMOD` precompile.
+    UINT1024_MULMOD = 0x00_00_01_23,
+
+    /// Executes the `UINT2048_MULMOD` precompile.
+    UINT2048_MULMOD = 0x00_00_01_24,
+
+    /// Executes the `UINT4096_MULMOD` precompile.
+    UINT4096_MULMOD = 0x00_00_01_25,
+
+    /// Executes the `MEMCPY` precompile.
+    MEMCPY = 0x00_01_01_26,
+
+    /// Executes the `MEMSET` precompile.
+    MEMSET = 0x00_00_01_27,
 }
 
 impl SyscallCode {
@@ -133,6 +160,14 @@ impl SyscallCode {
             0x00_00_00_F1 => SyscallCode::HINT_READ,
             0x00_00_01_1D => SyscallCode::UINT256_MUL,
             0x00_00_01_1C => SyscallCode::BLS12381_DECOMPRESS,
+            0x00_00_01_20 => SyscallCode::UINT256_ADDMOD,
+            0x00_00_01_21 => SyscallCode::UINT256_SUBMOD,
+            0x00_00_01_22 => SyscallCode::UINT512_MULMOD,
+            0x00_00_01_23 => SyscallCode::UINT1024_MULMOD,
+            0x00_00_01_24 => SyscallCode::UINT2048_MULMOD,
+            0x00_00_01_25 => SyscallCode::UINT4096_MULMOD,
+            0x00_01_01_26 => SyscallCode::MEMCPY,
+            0x00_00_01_27 => SyscallCode::MEMSET,
             _ => panic!("invalid syscall number: {}", value),
         }
     }
@@ -179,6 +214,8 @@ pub struct SyscallContext<'a> {
     pub(crate) next_pc: u32,
     /// This is the exit_code used for the HALT syscall
     pub(crate) exit_code: u32,
+    /// An error raised by the syscall, returned by the runtime once the syscall has executed.
+    pub(crate) error: Option<ExecutionError>,
     pub(crate) rt: &'a mut Runtime,
 }
 
@@ -191,6 +228,7 @@ impl<'a> SyscallContext<'a> {
             clk,
             next_pc: runtime.state.pc.wrapping_add(4),
             exit_code: 0,
+            error: None,
             rt: runtime,
         }
     }
@@ -346,6 +384,32 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
         Arc::new(WeierstrassDecompressChip::<Bls12381>::new()),
     );
     syscall_map.insert(SyscallCode::UINT256_MUL, Arc::new(Uint256MulChip::new()));
+    syscall_map.insert(
+        SyscallCode::UINT256_ADDMOD,
+        Arc::new(Uint256AddSubChip::add()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT256_SUBMOD,
+        Arc::new(Uint256AddSubChip::sub()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT512_MULMOD,
+        Arc::new(BigIntMulModChip::<U512Field>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT1024_MULMOD,
+        Arc::new(BigIntMulModChip::<U1024Field>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT2048_MULMOD,
+        Arc::new(BigIntMulModChip::<U2048Field>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT4096_MULMOD,
+        Arc::new(BigIntMulModChip::<U4096Field>::new()),
+    );
+    syscall_map.insert(SyscallCode::MEMCPY, Arc::new(MemCopyChip::new()));
+    syscall_map.insert(SyscallCode::MEMSET, Arc::new(MemSetChip::new()));
 
     syscall_map
 }
@@ -431,6 +495,26 @@ mod tests {
                 SyscallCode::UINT256_MUL => {
                     assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_MUL)
                 }
+                SyscallCode::UINT256_ADDMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_ADDMOD)
+                }
+                SyscallCode::UINT256_SUBMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_SUBMOD)
+                }
+                SyscallCode::UINT512_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT512_MULMOD)
+                }
+                SyscallCode::UINT1024_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT1024_MULMOD)
+                }
+                SyscallCode::UINT2048_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT2048_MULMOD)
+                }
+                SyscallCode::UINT4096_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT4096_MULMOD)
+                }
+                SyscallCode::MEMCPY => assert_eq!(code as u32, sp1_zkvm::syscalls::MEMCPY),
+                SyscallCode::MEMSET => assert_eq!(code as u32, sp1_zkvm::syscalls::MEMSET),
                 SyscallCode::COMMIT => assert_eq!(code as u32, sp1_zkvm::syscalls::COMMIT),
                 SyscallCode::COMMIT_DEFERRED_PROOFS => {
                     assert_eq!(code as u32, sp1_zkvm::syscalls::COMMIT_DEFERRED_PROOFS)
@@ -21,16 +21,20 @@ pub(crate) mod riscv_chips {
     pub use crate::cpu::CpuChip;
     pub use crate::memory::MemoryChip;
     pub use crate::program::ProgramChip;
+    pub use crate::syscall::precompiles::bigint::BigIntMulModChip;
     pub use crate::syscall::precompiles::blake3::Blake3CompressInnerChip;
     pub use crate::syscall::precompiles::edwards::EdAddAssignChip;
     pub use crate::syscall::precompiles::edwards::EdDecompressChip;
     pub use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+    pub use crate::syscall::precompiles::memcopy::{MemCopyChip, MemSetChip};
     pub use crate::syscall::precompiles::sha256::ShaCompressChip;
     pub use crate::syscall::precompiles::sha256::ShaExtendChip;
+    pub use crate::syscall::precompiles::uint256::Uint256AddSubChip;
     pub use crate::syscall::precompiles::uint256::Uint256MulChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassAddAssignChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassDecompressChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
+    pub use crate::utils::ec::bigint::{U1024Field, U2048Field, U4096Field, U512Field};
     pub use crate::utils::ec::edwards::ed25519::Ed25519Parameters;
     pub use crate::utils::ec::edwards::EdwardsCurve;
     pub use crate::utils::ec::weierstrass::bls12_381::Bls12381Parameters;
@@ -100,6 +104,22 @@ pub enum RiscvAir<F: PrimeField32> {
     Bls12381Double(WeierstrassDoubleAssignChip<SwCurve<Bls12381Parameters>>),
     /// A precompile for uint256 mul.
     Uint256Mul(Uint256MulChip),
+    /// A precompile for uint256 modular addition.
+    Uint256AddMod(Uint256AddSubChip),
+    /// A precompile for uint256 modular subtraction.
+    Uint256SubMod(Uint256AddSubChip),
+    /// A precompile for uint512 modular multiplication.
+    Uint512MulMod(BigIntMulModChip<U512Field>),
+    /// A precompile for uint1024 modular multiplication.
+    Uint1024MulMod(BigIntMulModChip<U1024Field>),
+    /// A precompile for uint2048 modular multiplication.
+    Uint2048MulMod(BigIntMulModChip<U2048Field>),
+    /// A precompile for uint4096 modular multiplication.
+    Uint4096MulMod(BigIntMulModChip<U4096Field>),
+    /// A precompile for copying a block of words.
+    MemCopy(MemCopyChip),
+    /// A precompile for filling a block of words with a byte.
+    MemSet(MemSetChip),
     /// A precompile for decompressing a point on the BLS12-381 curve.
     Bls12381Decompress(WeierstrassDecompressChip<SwCurve<Bls12381Parameters>>),
 }
@@ -111,7 +131,7 @@ impl<F: PrimeField32> RiscvAir<F> {
             .into_iter()
             .map(Chip::new)
             .collect::<Vec<_>>();
-        StarkMachine::new(config, chips, SP1_PROOF_NUM_PV_ELTS)
+        StarkMachine::new(config, chips, SP1_PROOF_NUM_PV_ELTS).with_precompile_shards()
     }
 
     /// Get all the different RISC-V AIRs.
@@ -150,6 +170,22 @@ impl<F: PrimeField32> RiscvAir<F> {
         chips.push(RiscvAir::Bls12381Double(bls12381_double));
         let uint256_mul = Uint256MulChip::default();
         chips.push(RiscvAir::Uint256Mul(uint256_mul));
+        let uint256_addmod = Uint256AddSubChip::add();
+        chips.push(RiscvAir::Uint256AddMod(uint256_addmod));
+        let uint256_submod = Uint256AddSubChip::sub();
+        chips.push(RiscvAir::Uint256SubMod(uint256_submod));
+        let uint512_mulmod = BigIntMulModChip::<U512Field>::new();
+        chips.push(RiscvAir::Uint512MulMod(uint512_mulmod));
+        let uint1024_mulmod = BigIntMulModChip::<U1024Field>::new();
+        chips.push(RiscvAir::Uint1024MulMod(uint1024_mulmod));
+        let uint2048_mulmod = BigIntMulModChip::<U2048Field>::new();
+        chips.push(RiscvAir::Uint2048MulMod(uint2048_mulmod));
+        let uint4096_mulmod = BigIntMulModChip::<U4096Field>::new();
+        chips.push(RiscvAir::Uint4096MulMod(uint4096_mulmod));
+        let memcpy = MemCopyChip::new();
+        chips.push(RiscvAir::MemCopy(memcpy));
+        let memset = MemSetChip::new();
+        chips.push(RiscvAir::MemSet(memset));
         let bls12381_decompress = WeierstrassDecompressChip::<SwCurve<Bls12381Parameters>>::new();
         chips.push(RiscvAir::Bls12381Decompress(bls12381_decompress));
         let add = AddSubChip::default();
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use p3_matrix::dense::RowMajorMatrix;
+
+use super::{ShardMainData, StarkGenericConfig};
+
+/// A pool of trace buffers, keyed by chip name, that are reused across shards.
+///
+/// Once a shard's traces have been committed to, their buffers can be recycled into the arena
+/// and taken back when the traces of the next shard are generated, see
+/// [MachineAir::generate_trace_into](crate::air::MachineAir::generate_trace_into). The arena
+/// holds at most as many buffers per chip as there were shards in flight at once.
+#[derive(Debug)]
+pub struct TraceArena<F> {
+    buffers: Mutex<HashMap<String, Vec<Vec<F>>>>,
+}
+
+impl<F> TraceArena<F> {
+    pub fn new() -> Self {
+        Self {
+            buffers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes an empty buffer for the trace of a chip, which is a recycled buffer if there is one.
+    pub fn take(&self, chip_name: &str) -> Vec<F> {
+        let mut buffers = self.buffers.lock().unwrap();
+        let mut buffer = buffers
+            .get_mut(chip_name)
+            .and_then(Vec::pop)
+            .unwrap_or_default();
+        buffer.clear();
+        buffer
+    }
+
+    /// Returns the buffer of a trace of a chip to the arena.
+    pub fn recycle(&self, chip_name: String, trace: RowMajorMatrix<F>) {
+        if trace.values.capacity() == 0 {
+            return;
+        }
+        let mut buffers = self.buffers.lock().unwrap();
+        buffers.entry(chip_name).or_default().push(trace.values);
+    }
+
+    /// Returns the buffers of the traces of a shard to the arena.
+    pub fn recycle_shard<SC>(&self, data: ShardMainData<SC>)
+    where
+        SC: StarkGenericConfig<Val = F>,
+    {
+        let mut names = vec![String::new(); data.traces.len()];
+        for (name, index) in data.chip_ordering {
+            names[index] = name;
+        }
+        for (name, trace) in names.into_iter().zip(data.traces) {
+            self.recycle(name, trace);
+        }
+    }
+
+    /// The number of bytes held by the buffers in the arena.
+    pub fn allocated_bytes(&self) -> usize {
+        let buffers = self.buffers.lock().unwrap();
+        buffers
+            .values()
+            .flatten()
+            .map(|buffer| buffer.capacity() * std::mem::size_of::<F>())
+            .sum()
+    }
+}
+
+impl<F> Default for TraceArena<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    #[test]
+    fn test_trace_arena_reuses_buffers() {
+        let arena = TraceArena::<BabyBear>::new();
+        assert!(arena.take("CPU").is_empty());
+
+        let trace = RowMajorMatrix::new(vec![BabyBear::one(); 64], 4);
+        let ptr = trace.values.as_ptr();
+        arena.recycle("CPU".to_string(), trace);
+        assert_eq!(
+            arena.allocated_bytes(),
+            64 * std::mem::size_of::<BabyBear>()
+        );
+
+        // The buffer is returned empty, with its allocation intact.
+        let buffer = arena.take("CPU");
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 64);
+        assert_eq!(buffer.as_ptr(), ptr);
+
+        // Buffers are not shared between chips.
+        assert_eq!(arena.allocated_bytes(), 0);
+        assert_eq!(arena.take("Program").capacity(), 0);
+    }
+}
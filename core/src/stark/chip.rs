@@ -176,6 +176,15 @@ where
         self.air.generate_trace(input, output)
     }
 
+    fn generate_trace_into(
+        &self,
+        input: &A::Record,
+        output: &mut A::Record,
+        buffer: Vec<F>,
+    ) -> RowMajorMatrix<F> {
+        self.air.generate_trace_into(input, output, buffer)
+    }
+
     fn generate_dependencies(&self, input: &A::Record, output: &mut A::Record) {
         self.air.generate_dependencies(input, output)
     }
@@ -3,6 +3,8 @@ use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{ExtensionField, Field, PrimeField};
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::utils::FriParameters;
+
 pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
@@ -65,6 +67,9 @@ pub trait StarkGenericConfig: Send + Sync + Serialize + DeserializeOwned + Clone
     /// Get the PCS used by this configuration.
     fn pcs(&self) -> &Self::Pcs;
 
+    /// Get the parameters of the FRI low-degree test of the PCS.
+    fn fri_parameters(&self) -> FriParameters;
+
     /// Initialize a new challenger.
     fn challenger(&self) -> Self::Challenger;
 }
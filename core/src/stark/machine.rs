@@ -30,6 +30,7 @@ use crate::stark::DebugConstraintBuilder;
 use crate::stark::ProverConstraintFolder;
 use crate::stark::ShardProof;
 use crate::stark::VerifierConstraintFolder;
+use crate::utils::FriParameters;
 use crate::utils::SP1CoreOpts;
 
 use super::Chip;
@@ -53,6 +54,9 @@ pub struct StarkMachine<SC: StarkGenericConfig, A> {
 
     /// The number of public values elements that the machine uses
     num_pv_elts: usize,
+
+    /// Whether shards without a CPU chip are allowed.
+    precompile_shards: bool,
 }
 
 impl<SC: StarkGenericConfig, A> StarkMachine<SC, A> {
@@ -61,8 +65,23 @@ impl<SC: StarkGenericConfig, A> StarkMachine<SC, A> {
             config,
             chips,
             num_pv_elts,
+            precompile_shards: false,
         }
     }
+
+    /// Allows shards without a CPU chip, which only prove precompile events.
+    ///
+    /// No chip constrains the public values of such shards, so whoever checks the public values
+    /// of the machine's proofs must make sure that these shards don't execute any instructions.
+    pub fn with_precompile_shards(mut self) -> Self {
+        self.precompile_shards = true;
+        self
+    }
+
+    /// Whether shards without a CPU chip are allowed.
+    pub const fn allows_precompile_shards(&self) -> bool {
+        self.precompile_shards
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -91,6 +110,8 @@ pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
     pub pc_start: Val<SC>,
     pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
     pub chip_ordering: HashMap<String, usize>,
+    /// The FRI parameters proofs for this key are generated with.
+    pub fri_parameters: FriParameters,
 }
 
 impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
@@ -235,6 +256,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                 pc_start,
                 chip_information,
                 chip_ordering,
+                fri_parameters: self.config.fri_parameters(),
             },
         )
     }
@@ -305,6 +327,11 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         SC::Challenger: Clone,
         A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
     {
+        // The proofs must be verified with the FRI parameters the key was set up with.
+        if vk.fri_parameters != self.config.fri_parameters() {
+            return Err(MachineVerificationError::InvalidFriParameters);
+        }
+
         // Observe the preprocessed commitment.
         vk.observe_into(challenger);
         tracing::debug_span!("observe challenges for all shards").in_scope(|| {
@@ -322,6 +349,11 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         tracing::debug_span!("verify shard proofs").in_scope(|| {
             for (i, shard_proof) in proof.shard_proofs.iter().enumerate() {
                 tracing::debug_span!("verifying shard", segment = i).in_scope(|| {
+                    if !self.precompile_shards && !shard_proof.contains_cpu() {
+                        return Err(MachineVerificationError::InvalidSegmentProof(
+                            VerificationError::MissingCpuChip,
+                        ));
+                    }
                     let chips = self
                         .shard_chips_ordered(&shard_proof.chip_ordering)
                         .collect::<Vec<_>>();
@@ -473,6 +505,7 @@ pub enum MachineVerificationError<SC: StarkGenericConfig> {
     DebugInteractionsFailed,
     EmptyProof,
     InvalidPublicValues(&'static str),
+    InvalidFriParameters,
 }
 
 impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
@@ -499,6 +532,9 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidPublicValues(s) => {
                 write!(f, "Invalid public values: {}", s)
             }
+            MachineVerificationError::InvalidFriParameters => {
+                write!(f, "FRI parameters do not match the verifying key")
+            }
         }
     }
 }
@@ -517,6 +553,8 @@ pub mod tests {
 
     use crate::io::SP1Stdin;
     use crate::runtime::tests::fibonacci_program;
+    use crate::runtime::tests::simple_atomic_program;
+    use crate::runtime::tests::simple_compressed_program;
     use crate::runtime::tests::simple_memory_program;
     use crate::runtime::tests::simple_program;
     use crate::runtime::tests::ssz_withdrawals_program;
@@ -524,6 +562,7 @@ pub mod tests {
     use crate::runtime::Opcode;
     use crate::runtime::Program;
     use crate::stark::RiscvAir;
+    use crate::stark::StarkGenericConfig;
     use crate::stark::StarkProvingKey;
     use crate::stark::StarkVerifyingKey;
     use crate::utils;
@@ -688,12 +727,50 @@ pub mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_fibonacci_prove_batch_spill_traces() {
+        setup_logger();
+        let program = fibonacci_program();
+        let stdin = SP1Stdin::new();
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 10;
+        opts.shard_batch_size = 2;
+
+        // Proving the shards from spilled traces gives the same proof as executing again.
+        opts.spill_traces = true;
+        let (spilled, _) = prove(program.clone(), &stdin, BabyBearPoseidon2::new(), opts).unwrap();
+        opts.spill_traces = false;
+        let (executed, _) = prove(program.clone(), &stdin, BabyBearPoseidon2::new(), opts).unwrap();
+        assert!(spilled.shard_proofs.len() > 2);
+        assert_eq!(
+            bincode::serialize(&spilled).unwrap(),
+            bincode::serialize(&executed).unwrap()
+        );
+
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (_, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &spilled, &mut challenger).unwrap();
+    }
+
     #[test]
     fn test_simple_memory_program_prove() {
         let program = simple_memory_program();
         run_test(program).unwrap();
     }
 
+    #[test]
+    fn test_simple_atomic_program_prove() {
+        let program = simple_atomic_program();
+        run_test(program).unwrap();
+    }
+
+    #[test]
+    fn test_simple_compressed_program_prove() {
+        let program = simple_compressed_program();
+        run_test(program).unwrap();
+    }
+
     #[test]
     #[ignore]
     fn test_ssz_withdrawal() {
@@ -1,4 +1,5 @@
 mod air;
+mod arena;
 mod chip;
 mod config;
 mod debug;
@@ -13,6 +14,7 @@ mod util;
 mod verifier;
 
 pub use air::*;
+pub use arena::*;
 pub use chip::*;
 pub use config::*;
 pub use debug::*;
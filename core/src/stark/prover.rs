@@ -20,7 +20,7 @@ use p3_util::log2_strict_usize;
 use super::{quotient_values, PcsProverData, StarkMachine, Val};
 use super::{types::*, StarkGenericConfig};
 use super::{Com, OpeningProof};
-use super::{StarkProvingKey, VerifierConstraintFolder};
+use super::{StarkProvingKey, TraceArena, VerifierConstraintFolder};
 use crate::air::MachineAir;
 use crate::lookup::InteractionBuilder;
 use crate::stark::record::MachineRecord;
@@ -79,7 +79,8 @@ where
         // Observe the preprocessed commitment.
         pk.observe_into(challenger);
         // Generate and commit the traces for each segment.
-        let (shard_commits, shard_data) = Self::commit_shards(machine, &shards, opts);
+        let (shard_commits, shard_data) =
+            Self::commit_shards(machine, &shards, opts).expect("failed to commit to the shards");
 
         // Observe the challenges for each segment.
         tracing::debug_span!("observing all challenges").in_scope(|| {
@@ -100,7 +101,6 @@ where
         let chunking_multiplier = opts.shard_chunking_multiplier;
         let chunk_size = std::cmp::max(chunking_multiplier * shards.len() / num_cpus::get(), 1);
         let config = machine.config();
-        let reconstruct_commitments = opts.reconstruct_commitments;
         let shard_data_chunks = chunk_vec(shard_data, chunk_size);
         let shard_chunks = chunk_vec(shards, chunk_size);
         let parent_span = tracing::debug_span!("open_shards");
@@ -116,11 +116,13 @@ where
                             tracing::debug_span!(parent: &parent_span, "prove shard opening")
                                 .in_scope(|| {
                                     let idx = shard.index() as usize;
-                                    let data = if reconstruct_commitments {
-                                        Self::commit_main(config, machine, &shard, idx)
-                                    } else {
-                                        data.materialize()
-                                            .expect("failed to materialize shard main data")
+                                    let data = match data {
+                                        ShardMainDataWrapper::Empty() => {
+                                            Self::commit_main(config, machine, &shard, idx)
+                                        }
+                                        data => data
+                                            .materialize()
+                                            .expect("failed to materialize shard main data"),
                                     };
                                     let ordering = data.chip_ordering.clone();
                                     let chips =
@@ -162,6 +164,17 @@ where
         machine: &StarkMachine<SC, A>,
         shard: &A::Record,
         index: usize,
+    ) -> ShardMainData<SC> {
+        Self::commit_main_with_arena(config, machine, shard, index, &TraceArena::new())
+    }
+
+    /// Generate and commit to the traces of a shard, taking the trace buffers from `arena`.
+    pub fn commit_main_with_arena(
+        config: &SC,
+        machine: &StarkMachine<SC, A>,
+        shard: &A::Record,
+        index: usize,
+        arena: &TraceArena<SC::Val>,
     ) -> ShardMainData<SC> {
         // Filter the chips based on what is used.
         let shard_chips = machine.shard_chips(shard).collect::<Vec<_>>();
@@ -179,7 +192,10 @@ where
                     // to avoid the unnecessary span, remove the #[instrument] macro.
                     let trace =
                         tracing::debug_span!(parent: &parent_span, "generate trace for chip", %chip_name)
-                            .in_scope(|| chip.generate_trace(shard, &mut A::Record::default()));
+                            .in_scope(|| {
+                                let buffer = arena.take(&chip_name);
+                                chip.generate_trace_into(shard, &mut A::Record::default(), buffer)
+                            });
                     (chip_name, trace)
                 })
                 .collect::<Vec<_>>()
@@ -546,7 +562,29 @@ where
         machine: &StarkMachine<SC, A>,
         shards: &[A::Record],
         opts: SP1CoreOpts,
-    ) -> (Vec<Com<SC>>, Vec<ShardMainDataWrapper<SC>>)
+    ) -> Result<(Vec<Com<SC>>, Vec<ShardMainDataWrapper<SC>>), bincode::Error>
+    where
+        F: PrimeField32,
+        EF: ExtensionField<F>,
+        SC: StarkGenericConfig<Val = F, Challenge = EF> + Send + Sync,
+        SC::Challenger: Clone,
+        PcsProverData<SC>: Send + Sync,
+        ShardMainData<SC>: Serialize + DeserializeOwned,
+    {
+        Self::commit_shards_with_arena(machine, shards, opts, &TraceArena::new())
+    }
+
+    /// Commit to the traces of each shard, reusing the trace buffers in `arena`.
+    ///
+    /// If `opts.reconstruct_commitments` is set, the traces are not kept in memory: they are
+    /// spilled to temporary files if `opts.spill_traces` is set, and otherwise dropped, so that
+    /// they must be generated again to prove the shards. Fails if a shard can't be spilled.
+    pub fn commit_shards_with_arena<F, EF>(
+        machine: &StarkMachine<SC, A>,
+        shards: &[A::Record],
+        opts: SP1CoreOpts,
+        arena: &TraceArena<F>,
+    ) -> Result<(Vec<Com<SC>>, Vec<ShardMainDataWrapper<SC>>), bincode::Error>
     where
         F: PrimeField32,
         EF: ExtensionField<F>,
@@ -560,10 +598,11 @@ where
         // Get the number of shards that is the threshold for saving shards to disk instead of
         // keeping all the shards in memory.
         let reconstruct_commitments = opts.reconstruct_commitments;
+        let spill_traces = opts.spill_traces;
         let finished = AtomicU32::new(0);
         let chunk_size = std::cmp::max(shards.len() / num_cpus::get(), 1);
         let parent_span = tracing::debug_span!("commit to all shards");
-        let (commitments, shard_main_data): (Vec<_>, Vec<_>) = parent_span.in_scope(|| {
+        let shard_main_data = parent_span.in_scope(|| {
             shards
                 .par_chunks(chunk_size)
                 .map(|shard_batch| {
@@ -573,27 +612,37 @@ where
                             tracing::debug_span!(parent: &parent_span, "commit to shard").in_scope(
                                 || {
                                     let index = shard.index();
-                                    let data =
-                                        Self::commit_main(config, machine, shard, index as usize);
+                                    let data = Self::commit_main_with_arena(
+                                        config,
+                                        machine,
+                                        shard,
+                                        index as usize,
+                                        arena,
+                                    );
                                     finished.fetch_add(1, Ordering::Relaxed);
                                     let commitment = data.main_commit.clone();
-                                    let data = if reconstruct_commitments {
-                                        ShardMainDataWrapper::Empty()
-                                    } else {
+                                    let data = if !reconstruct_commitments {
                                         data.to_in_memory()
+                                    } else if spill_traces {
+                                        let spilled = tempfile::NamedTempFile::new()
+                                            .map_err(Into::into)
+                                            .and_then(|file| data.save(file));
+                                        arena.recycle_shard(data);
+                                        spilled?
+                                    } else {
+                                        arena.recycle_shard(data);
+                                        ShardMainDataWrapper::Empty()
                                     };
-                                    (commitment, data)
+                                    Ok((commitment, data))
                                 },
                             )
                         })
                         .collect::<Vec<_>>()
                 })
                 .flatten()
-                .collect::<Vec<_>>()
-                .into_iter()
-                .unzip()
-        });
+                .collect::<Result<Vec<_>, bincode::Error>>()
+        })?;
 
-        (commitments, shard_main_data)
+        Ok(shard_main_data.into_iter().unzip())
     }
 }
@@ -2,7 +2,7 @@ use std::{
     collections::HashMap,
     fmt::Debug,
     fs::File,
-    io::{BufReader, BufWriter, Seek},
+    io::{BufReader, BufWriter, Write},
 };
 
 use bincode::{deserialize_from, Error};
@@ -11,6 +11,7 @@ use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use size::Size;
+use tempfile::{NamedTempFile, TempPath};
 use tracing::trace;
 
 use super::{Challenge, Com, OpeningProof, PcsProverData, StarkGenericConfig, Val};
@@ -48,20 +49,25 @@ impl<SC: StarkGenericConfig> ShardMainData<SC> {
         }
     }
 
-    pub fn save(&self, file: File) -> Result<ShardMainDataWrapper<SC>, Error>
+    /// Saves the data to a temporary file, which is closed once written and reopened by its path
+    /// when the data is materialized, so that spilled shards don't hold open file descriptors.
+    pub fn save(&self, file: NamedTempFile) -> Result<ShardMainDataWrapper<SC>, Error>
     where
         ShardMainData<SC>: Serialize,
     {
-        let mut writer = BufWriter::new(&file);
+        let mut writer = BufWriter::new(file.as_file());
         bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
         drop(writer);
-        let metadata = file.metadata()?;
-        let bytes_written = metadata.len();
+        let bytes_written = file.as_file().metadata()?.len();
         trace!(
             "wrote {} while saving ShardMainData",
             Size::from_bytes(bytes_written)
         );
-        Ok(ShardMainDataWrapper::TempFile(file, bytes_written))
+        Ok(ShardMainDataWrapper::TempFile(
+            file.into_temp_path(),
+            bytes_written,
+        ))
     }
 
     pub const fn to_in_memory(self) -> ShardMainDataWrapper<SC> {
@@ -71,7 +77,7 @@ impl<SC: StarkGenericConfig> ShardMainData<SC> {
 
 pub enum ShardMainDataWrapper<SC: StarkGenericConfig> {
     InMemory(ShardMainData<SC>),
-    TempFile(File, u64),
+    TempFile(TempPath, u64),
     Empty(),
 }
 
@@ -82,9 +88,8 @@ impl<SC: StarkGenericConfig> ShardMainDataWrapper<SC> {
     {
         match self {
             Self::InMemory(data) => Ok(data),
-            Self::TempFile(file, _) => {
-                let mut buffer = BufReader::new(&file);
-                buffer.seek(std::io::SeekFrom::Start(0))?;
+            Self::TempFile(path, _) => {
+                let mut buffer = BufReader::new(File::open(&path)?);
                 let data = deserialize_from(&mut buffer)?;
                 Ok(data)
             }
@@ -158,6 +163,11 @@ impl<SC: StarkGenericConfig> ShardProof<SC> {
             .map(|c| c.cumulative_sum)
             .sum()
     }
+
+    /// Whether the shard contains the CPU chip, i.e. it is not a precompile shard.
+    pub fn contains_cpu(&self) -> bool {
+        self.chip_ordering.contains_key("CPU")
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
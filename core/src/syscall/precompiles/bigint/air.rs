@@ -0,0 +1,374 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, Limbs, NumLimbs, NumWords};
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::stark::MachineRecord;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::bigint::{BigIntParameters, BigIntWidth};
+use crate::utils::{
+    bytes_to_words_le_vec, limbs_from_access, limbs_from_prev_access, pad_rows,
+    words_to_bytes_le_vec,
+};
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use generic_array::GenericArray;
+use num::{BigUint, One, Zero};
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::marker::PhantomData;
+use typenum::Unsigned;
+
+pub const fn num_bigint_mulmod_cols<P: FieldParameters + NumWords>() -> usize {
+    size_of::<BigIntMulModCols<u8, P>>()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BigIntMulModEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub x: Vec<u32>,
+    pub y_ptr: u32,
+    pub y: Vec<u32>,
+    pub modulus: Vec<u32>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+    pub y_memory_records: Vec<MemoryReadRecord>,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A chip computing `(x * y) % modulus` for unsigned integers of the width of `P`, with the same
+/// calling convention as [crate::syscall::precompiles::uint256::Uint256MulChip].
+///
+/// A zero modulus stands for `2^bits`. Otherwise, one of the inputs must be less than the modulus,
+/// so that the quotient of the product by the modulus has at most as many limbs as the inputs.
+#[derive(Default)]
+pub struct BigIntMulModChip<P> {
+    _marker: PhantomData<P>,
+}
+
+impl<P: BigIntParameters> BigIntMulModChip<P> {
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub const fn syscall_code() -> SyscallCode {
+        match P::WIDTH {
+            BigIntWidth::U512 => SyscallCode::UINT512_MULMOD,
+            BigIntWidth::U1024 => SyscallCode::UINT1024_MULMOD,
+            BigIntWidth::U2048 => SyscallCode::UINT2048_MULMOD,
+            BigIntWidth::U4096 => SyscallCode::UINT4096_MULMOD,
+        }
+    }
+
+    fn events(record: &ExecutionRecord) -> &Vec<BigIntMulModEvent> {
+        match P::WIDTH {
+            BigIntWidth::U512 => &record.uint512_mulmod_events,
+            BigIntWidth::U1024 => &record.uint1024_mulmod_events,
+            BigIntWidth::U2048 => &record.uint2048_mulmod_events,
+            BigIntWidth::U4096 => &record.uint4096_mulmod_events,
+        }
+    }
+
+    fn events_mut(record: &mut ExecutionRecord) -> &mut Vec<BigIntMulModEvent> {
+        match P::WIDTH {
+            BigIntWidth::U512 => &mut record.uint512_mulmod_events,
+            BigIntWidth::U1024 => &mut record.uint1024_mulmod_events,
+            BigIntWidth::U2048 => &mut record.uint2048_mulmod_events,
+            BigIntWidth::U4096 => &mut record.uint4096_mulmod_events,
+        }
+    }
+}
+
+/// A set of columns for the BigIntMulMod operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct BigIntMulModCols<T, P: FieldParameters + NumWords> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The pointer to the first input.
+    pub x_ptr: T,
+
+    /// The pointer to the second input, which contains the y value and the modulus.
+    pub y_ptr: T,
+
+    // Memory columns. x_memory is written to with the result.
+    pub x_memory: GenericArray<MemoryWriteCols<T>, P::WordsFieldElement>,
+    pub y_memory: GenericArray<MemoryReadCols<T>, P::WordsFieldElement>,
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, P::WordsFieldElement>,
+
+    // Columns for checking if modulus is zero. If it's zero, then use 2^bits as the modulus.
+    pub modulus_is_zero: IsZeroOperation<T>,
+
+    // Output values. We compute (x * y) % modulus.
+    pub output: FieldOpCols<T, P>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32, P: BigIntParameters> MachineAir<F> for BigIntMulModChip<P> {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match P::WIDTH {
+            BigIntWidth::U512 => "Uint512MulMod".to_string(),
+            BigIntWidth::U1024 => "Uint1024MulMod".to_string(),
+            BigIntWidth::U2048 => "Uint2048MulMod".to_string(),
+            BigIntWidth::U4096 => "Uint4096MulMod".to_string(),
+        }
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let num_cols = num_bigint_mulmod_cols::<P>();
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Self::events(input)
+            .iter()
+            .map(|event| {
+                let mut row = vec![F::zero(); num_cols];
+                let cols: &mut BigIntMulModCols<F, P> = row.as_mut_slice().borrow_mut();
+
+                let x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.x));
+                let y = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.y));
+                let modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.modulus));
+
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+
+                for i in 0..cols.x_memory.len() {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.y_memory[i].populate(
+                        event.channel,
+                        event.y_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                let modulus_bytes = words_to_bytes_le_vec(&event.modulus);
+                let modulus_byte_sum = modulus_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.modulus_is_zero, modulus_byte_sum);
+
+                let effective_modulus = if modulus.is_zero() {
+                    P::modulus()
+                } else {
+                    modulus
+                };
+                cols.output.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &x,
+                    &y,
+                    &effective_modulus,
+                    FieldOperation::Mul,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row = vec![F::zero(); num_cols];
+            let cols: &mut BigIntMulModCols<F, P> = row.as_mut_slice().borrow_mut();
+            let zero = BigUint::zero();
+            cols.output
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+            row
+        });
+
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), num_cols)
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !Self::events(shard).is_empty()
+    }
+}
+
+impl<P: BigIntParameters> Syscall for BigIntMulModChip<P> {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let num_words = <P as NumWords>::WordsFieldElement::USIZE;
+        let x_ptr = arg1;
+        if x_ptr % 4 != 0 {
+            panic!();
+        }
+        let y_ptr = arg2;
+        if y_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read x unsafely, since it's overwritten with the result, and then y and the modulus
+        // stored after it.
+        let x = rt.slice_unsafe(x_ptr, num_words);
+        let (y_memory_records, y) = rt.mr_slice(y_ptr, num_words);
+        let modulus_ptr = y_ptr + num_words as u32 * WORD_SIZE as u32;
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, num_words);
+
+        let bigint_x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let bigint_y = BigUint::from_bytes_le(&words_to_bytes_le_vec(&y));
+        let bigint_modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+
+        let result = if bigint_modulus.is_zero() {
+            (bigint_x * bigint_y) % P::modulus()
+        } else {
+            assert!(
+                bigint_x < bigint_modulus || bigint_y < bigint_modulus,
+                "one of the inputs of {} must be less than the modulus",
+                Self::syscall_code()
+            );
+            (bigint_x * bigint_y) % bigint_modulus
+        };
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(num_words * WORD_SIZE, 0u8);
+        let result = bytes_to_words_le_vec(&result_bytes);
+
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        Self::events_mut(rt.record_mut()).push(BigIntMulModEvent {
+            shard,
+            channel,
+            clk,
+            x_ptr,
+            x,
+            y_ptr,
+            y,
+            modulus,
+            x_memory_records,
+            y_memory_records,
+            modulus_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F, P: BigIntParameters> BaseAir<F> for BigIntMulModChip<P> {
+    fn width(&self) -> usize {
+        num_bigint_mulmod_cols::<P>()
+    }
+}
+
+impl<AB, P: BigIntParameters> Air<AB> for BigIntMulModChip<P>
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <P as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &BigIntMulModCols<AB::Var, P> = (*local).borrow();
+
+        // The value of x is the "prev_value" of the x_memory, since the result is written to it.
+        let x_limbs: Limbs<AB::Var, P::Limbs> = limbs_from_prev_access(&local.x_memory);
+        let y_limbs: Limbs<AB::Var, P::Limbs> = limbs_from_access(&local.y_memory);
+        let modulus_limbs: Limbs<AB::Var, P::Limbs> = limbs_from_access(&local.modulus_memory);
+
+        // If the modulus is zero, 2^bits is used instead. The sum of the bytes of the modulus
+        // can't overflow the field, as there are at most 512 of them.
+        let modulus_byte_sum = modulus_limbs
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &limb| acc + limb);
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            modulus_byte_sum,
+            local.modulus_is_zero,
+            local.is_real.into(),
+        );
+        let modulus_is_zero = local.modulus_is_zero.result;
+        let mut coeff_2_bits = vec![AB::Expr::zero(); P::NB_LIMBS];
+        coeff_2_bits.push(AB::Expr::one());
+        let modulus_polynomial: Polynomial<AB::Expr> = modulus_limbs.into();
+        let p_modulus: Polynomial<AB::Expr> = modulus_polynomial
+            * (AB::Expr::one() - modulus_is_zero.into())
+            + Polynomial::from_coefficients(&coeff_2_bits) * modulus_is_zero.into();
+
+        local.output.eval_with_modulus(
+            builder,
+            &x_limbs,
+            &y_limbs,
+            &p_modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the correct result is being written to x_memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.output.result, value_as_limbs(&local.x_memory));
+
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.y_ptr,
+            &[local.y_memory.as_slice(), local.modulus_memory.as_slice()].concat(),
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(Self::syscall_code().syscall_id()),
+            local.x_ptr,
+            local.y_ptr,
+            local.is_real,
+        );
+
+        builder.assert_bool(local.is_real);
+    }
+}
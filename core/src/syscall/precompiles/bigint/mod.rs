@@ -0,0 +1,135 @@
+mod air;
+
+pub use air::*;
+
+#[cfg(test)]
+mod tests {
+    use num::{BigUint, One, Zero};
+
+    use crate::operations::field::params::FieldParameters;
+    use crate::{
+        runtime::{Instruction, Opcode, Program, Runtime, SyscallCode},
+        utils::{
+            self, bytes_to_words_le_vec,
+            ec::{
+                bigint::{U1024Field, U2048Field, U4096Field, U512Field},
+                utils::biguint_from_limbs,
+            },
+            run_test, words_to_bytes_le_vec, SP1CoreOpts,
+        },
+    };
+
+    const WIDTHS: [(SyscallCode, usize); 4] = [
+        (SyscallCode::UINT512_MULMOD, 512),
+        (SyscallCode::UINT1024_MULMOD, 1024),
+        (SyscallCode::UINT2048_MULMOD, 2048),
+        (SyscallCode::UINT4096_MULMOD, 4096),
+    ];
+
+    /// The operations of [bigint_mulmod_program], as `(syscall, bits, x, y, modulus)`.
+    fn bigint_mulmod_cases() -> Vec<(SyscallCode, usize, BigUint, BigUint, BigUint)> {
+        WIDTHS
+            .iter()
+            .flat_map(|&(code, bits)| {
+                let pow = |exp: usize| BigUint::one() << exp;
+                let max = pow(bits) - 1u32;
+                let modulus = &max - pow(bits / 2);
+                let small_modulus = pow(bits - 40) + 7u32;
+                [
+                    (&modulus - 1u32, &modulus - 2u32, modulus),
+                    (max.clone(), &max - 2u32, BigUint::zero()),
+                    (BigUint::from(5u32), max, small_modulus),
+                ]
+                .map(|(x, y, modulus)| (code, bits, x, y, modulus))
+            })
+            .collect()
+    }
+
+    fn biguint_to_words(value: &BigUint, bits: usize) -> Vec<u32> {
+        let mut bytes = value.to_bytes_le();
+        bytes.resize(bits / 8, 0);
+        bytes_to_words_le_vec(&bytes)
+    }
+
+    /// A program that stores the operands of each case, and then calls its syscall. The result of
+    /// the case `i` is written to `x_ptr(i)`.
+    fn bigint_mulmod_program(cases: &[(SyscallCode, usize, BigUint, BigUint, BigUint)]) -> Program {
+        let mut instructions = Vec::new();
+        for (i, (code, bits, x, y, modulus)) in cases.iter().enumerate() {
+            let words = [x, y, modulus]
+                .into_iter()
+                .flat_map(|value| biguint_to_words(value, *bits));
+            for (j, word) in words.enumerate() {
+                instructions.extend([
+                    Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                    Instruction::new(Opcode::ADD, 30, 0, x_ptr(i) + j as u32 * 4, false, true),
+                    Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                ]);
+            }
+            instructions.extend([
+                Instruction::new(Opcode::ADD, 5, 0, *code as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, x_ptr(i), false, true),
+                Instruction::new(Opcode::ADD, 11, 0, x_ptr(i) + *bits as u32 / 8, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    fn x_ptr(i: usize) -> u32 {
+        0x1_0000 + i as u32 * 0x800
+    }
+
+    #[test]
+    fn test_bigint_mulmod_execute() {
+        utils::setup_logger();
+        let cases = bigint_mulmod_cases();
+        let mut runtime = Runtime::new(bigint_mulmod_program(&cases), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.uint512_mulmod_events.len(), 3);
+        assert_eq!(runtime.record.uint1024_mulmod_events.len(), 3);
+        assert_eq!(runtime.record.uint2048_mulmod_events.len(), 3);
+        assert_eq!(runtime.record.uint4096_mulmod_events.len(), 3);
+
+        for (i, (_, bits, x, y, modulus)) in cases.iter().enumerate() {
+            let modulus = if modulus.is_zero() {
+                BigUint::one() << *bits
+            } else {
+                modulus.clone()
+            };
+            let words = (0..*bits as u32 / 32)
+                .map(|j| runtime.word(x_ptr(i) + j * 4))
+                .collect::<Vec<_>>();
+            let result = BigUint::from_bytes_le(&words_to_bytes_le_vec(&words));
+            assert_eq!(result, (x * y) % modulus, "case {}", i);
+        }
+    }
+
+    #[test]
+    fn test_bigint_mulmod_prove() {
+        utils::setup_logger();
+        let program = bigint_mulmod_program(&bigint_mulmod_cases());
+        run_test(program).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "must be less than the modulus")]
+    fn test_bigint_mulmod_unreduced() {
+        let n = BigUint::from;
+        let cases = [(SyscallCode::UINT512_MULMOD, 512, n(9u32), n(8u32), n(7u32))];
+        let mut runtime = Runtime::new(bigint_mulmod_program(&cases), SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_bigint_modulus() {
+        for (modulus, bits) in [
+            (biguint_from_limbs(U512Field::MODULUS), 512),
+            (biguint_from_limbs(U1024Field::MODULUS), 1024),
+            (biguint_from_limbs(U2048Field::MODULUS), 2048),
+            (biguint_from_limbs(U4096Field::MODULUS), 4096),
+        ] {
+            assert_eq!(modulus, BigUint::one() << bits);
+        }
+    }
+}
@@ -39,7 +39,8 @@ impl KeccakPermuteChip {
 
 #[cfg(test)]
 pub mod permute_tests {
-    use crate::runtime::SyscallCode;
+    use crate::runtime::{ShardingConfig, SyscallCode};
+    use crate::stark::MachineRecord;
     use crate::utils::{run_test, SP1CoreOpts};
     use crate::{
         runtime::{Instruction, Opcode, Program, Runtime},
@@ -93,4 +94,33 @@ pub mod permute_tests {
         let program = Program::from(KECCAK_PERMUTE_ELF);
         run_test(program).unwrap();
     }
+
+    #[test]
+    fn test_keccak_permute_precompile_shards() {
+        utils::setup_logger();
+        let program = Program::from(KECCAK_PERMUTE_ELF);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.keccak_permute_events.len(), 25);
+
+        let mut config = ShardingConfig::default();
+        config.keccak_len = 10;
+        let shards = runtime.record.shard(&config);
+
+        // The single CPU shard keeps 10 events, and the other 15 are spilled into two precompile
+        // shards that follow it.
+        let num_events = shards
+            .iter()
+            .map(|shard| shard.keccak_permute_events.len())
+            .collect::<Vec<_>>();
+        assert_eq!(num_events, vec![10, 10, 5]);
+        assert!(!shards[0].is_precompile_shard());
+        for (i, shard) in shards.iter().enumerate().skip(1) {
+            assert!(shard.is_precompile_shard());
+            assert_eq!(shard.index, i as u32 + 1);
+            assert_eq!(shard.public_values.shard, i as u32 + 1);
+            assert_eq!(shard.public_values.start_pc, 0);
+            assert_eq!(shard.public_values.next_pc, 0);
+        }
+    }
 }
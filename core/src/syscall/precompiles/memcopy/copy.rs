@@ -0,0 +1,222 @@
+use crate::air::{BaseAirBuilder, MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::stark::MachineRecord;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+
+use super::BLOCK_NUM_WORDS;
+
+/// The number of columns in the MemCopyCols.
+const NUM_COLS: usize = size_of::<MemCopyCols<u8>>();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemCopyEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub dst_ptr: u32,
+    pub src_ptr: u32,
+    pub src_memory_records: Vec<MemoryReadRecord>,
+    pub dst_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A chip copying a block of [BLOCK_NUM_WORDS] words from `src_ptr` to `dst_ptr`, which are the
+/// first and second arguments of the syscall.
+///
+/// The whole source block is read before the destination is written to, so the blocks may overlap.
+#[derive(Default)]
+pub struct MemCopyChip;
+
+impl MemCopyChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the MemCopy operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct MemCopyCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The pointer to the destination block.
+    pub dst_ptr: T,
+
+    /// The pointer to the source block.
+    pub src_ptr: T,
+
+    // Memory columns. The source is read at `clk`, and the destination is written at `clk + 1`.
+    pub src_memory: [MemoryReadCols<T>; BLOCK_NUM_WORDS],
+    pub dst_memory: [MemoryWriteCols<T>; BLOCK_NUM_WORDS],
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for MemCopyChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "MemCopy".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = input
+            .memcpy_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut MemCopyCols<F> = row.as_mut_slice().borrow_mut();
+
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.dst_ptr = F::from_canonical_u32(event.dst_ptr);
+                cols.src_ptr = F::from_canonical_u32(event.src_ptr);
+
+                for i in 0..BLOCK_NUM_WORDS {
+                    cols.src_memory[i].populate(
+                        event.channel,
+                        event.src_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.dst_memory[i].populate(
+                        event.channel,
+                        event.dst_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS)
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.memcpy_events.is_empty()
+    }
+}
+
+impl Syscall for MemCopyChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let dst_ptr = arg1;
+        if dst_ptr % 4 != 0 {
+            panic!();
+        }
+        let src_ptr = arg2;
+        if src_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let (src_memory_records, block) = rt.mr_slice(src_ptr, BLOCK_NUM_WORDS);
+
+        // When we write to the destination, we want the clk to be incremented because the blocks
+        // could overlap.
+        rt.clk += 1;
+
+        let dst_memory_records = rt.mw_slice(dst_ptr, &block);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().memcpy_events.push(MemCopyEvent {
+            shard,
+            channel,
+            clk: start_clk,
+            dst_ptr,
+            src_ptr,
+            src_memory_records,
+            dst_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for MemCopyChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for MemCopyChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &MemCopyCols<AB::Var> = (*local).borrow();
+
+        // Assert that each word of the source is written to the destination.
+        for (src, dst) in local.src_memory.iter().zip(local.dst_memory.iter()) {
+            builder
+                .when(local.is_real)
+                .assert_word_eq(*dst.value(), *src.value());
+        }
+
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.src_ptr,
+            &local.src_memory,
+            local.is_real,
+        );
+        // The destination is written at `clk + 1`, since it could overlap the source.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.dst_ptr,
+            &local.dst_memory,
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::MEMCPY.syscall_id()),
+            local.dst_ptr,
+            local.src_ptr,
+            local.is_real,
+        );
+
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,148 @@
+mod copy;
+mod set;
+
+pub use copy::*;
+pub use set::*;
+
+/// The number of words copied or filled by one MEMCPY or MEMSET syscall.
+pub const BLOCK_NUM_WORDS: usize = 32;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        io::SP1Stdin,
+        runtime::{Instruction, Opcode, Program, Runtime, SyscallCode},
+        utils::{self, run_test, run_test_core, tests::MEMCOPY_ELF, SP1CoreOpts},
+    };
+
+    use super::BLOCK_NUM_WORDS;
+
+    const SRC_PTR: u32 = 0x1_0000;
+    const DST_PTR: u32 = 0x2_0000;
+
+    fn word(i: u32) -> u32 {
+        0x0101_0101u32.wrapping_mul(i + 1) ^ 0xDEAD_BEEF
+    }
+
+    /// A program that stores two blocks of words at [SRC_PTR], copies the first block to
+    /// [DST_PTR], copies the blocks onto themselves shifted by one word, and fills the block after
+    /// the destination with a byte.
+    fn memcopy_program() -> Program {
+        let mut instructions = Vec::new();
+        for i in 0..2 * BLOCK_NUM_WORDS as u32 {
+            instructions.extend([
+                Instruction::new(Opcode::ADD, 29, 0, word(i), false, true),
+                Instruction::new(Opcode::ADD, 30, 0, SRC_PTR + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        let block_bytes = BLOCK_NUM_WORDS as u32 * 4;
+        for (code, arg1, arg2) in [
+            (SyscallCode::MEMCPY, DST_PTR, SRC_PTR),
+            (SyscallCode::MEMCPY, SRC_PTR + 4, SRC_PTR),
+            (SyscallCode::MEMSET, DST_PTR + block_bytes, 0xAB),
+        ] {
+            instructions.extend([
+                Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, arg1, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, arg2, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_memcopy_execute() {
+        utils::setup_logger();
+        let mut runtime = Runtime::new(memcopy_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.memcpy_events.len(), 2);
+        assert_eq!(runtime.record.memset_events.len(), 1);
+
+        for i in 0..BLOCK_NUM_WORDS as u32 {
+            assert_eq!(runtime.word(DST_PTR + i * 4), word(i));
+            assert_eq!(runtime.word(SRC_PTR + 4 + i * 4), word(i));
+            let filled = DST_PTR + (BLOCK_NUM_WORDS as u32 + i) * 4;
+            assert_eq!(runtime.word(filled), 0xABAB_ABAB);
+        }
+        assert_eq!(runtime.word(SRC_PTR), word(0));
+        let after_shifted = SRC_PTR + (BLOCK_NUM_WORDS as u32 + 1) * 4;
+        assert_eq!(
+            runtime.word(after_shifted),
+            word(BLOCK_NUM_WORDS as u32 + 1)
+        );
+    }
+
+    #[test]
+    fn test_memcopy_prove() {
+        utils::setup_logger();
+        run_test(memcopy_program()).unwrap();
+    }
+
+    /// Runs [MEMCOPY_ELF] with the given copies as `(dst_offset, src_offset, len)` and fills as
+    /// `(offset, len, value)`, and proves it. Returns the number of MEMCPY and MEMSET syscalls
+    /// made for them, on top of those made by the rest of the program.
+    fn run_memcopy_elf(
+        copies: &[(usize, usize, usize)],
+        fills: &[(usize, usize, u8)],
+    ) -> (usize, usize) {
+        let execute = |copies: &[(usize, usize, usize)], fills: &[(usize, usize, u8)]| {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(&copies.to_vec());
+            stdin.write(&fills.to_vec());
+            let mut runtime = Runtime::new(Program::from(MEMCOPY_ELF), SP1CoreOpts::default());
+            runtime.write_inputs(&stdin);
+            runtime.run().unwrap();
+            runtime
+        };
+        let baseline = execute(&[], &[]);
+        let runtime = execute(copies, fills);
+        let syscalls = (
+            runtime.record.memcpy_events.len() - baseline.record.memcpy_events.len(),
+            runtime.record.memset_events.len() - baseline.record.memset_events.len(),
+        );
+        run_test_core(runtime).unwrap();
+        syscalls
+    }
+
+    #[test]
+    fn test_memcopy_elf_aligned_blocks() {
+        utils::setup_logger();
+        assert_eq!(run_memcopy_elf(&[(0, 0, 1024)], &[(0, 512, 0xAB)]), (8, 4));
+    }
+
+    #[test]
+    fn test_memcopy_elf_tail() {
+        utils::setup_logger();
+        // 7 blocks and 104 bytes, and 2 blocks and 4 bytes.
+        assert_eq!(run_memcopy_elf(&[(4, 8, 1000)], &[(4, 260, 0x5A)]), (7, 2));
+    }
+
+    #[test]
+    fn test_memcopy_elf_unaligned() {
+        utils::setup_logger();
+        let copies = [(1, 4, 500), (4, 1, 500)];
+        assert_eq!(run_memcopy_elf(&copies, &[(3, 400, 0xFF)]), (0, 0));
+    }
+
+    #[test]
+    fn test_memcopy_elf_short() {
+        utils::setup_logger();
+        assert_eq!(run_memcopy_elf(&[(0, 0, 127)], &[(8, 127, 0x5A)]), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "must be a byte")]
+    fn test_memset_not_byte() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::MEMSET as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x100, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+}
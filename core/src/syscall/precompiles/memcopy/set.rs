@@ -0,0 +1,203 @@
+use crate::air::{BaseAirBuilder, MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, MemoryWriteRecord, Program, Syscall, SyscallCode};
+use crate::stark::MachineRecord;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+
+use super::BLOCK_NUM_WORDS;
+
+/// The number of columns in the MemSetCols.
+const NUM_COLS: usize = size_of::<MemSetCols<u8>>();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemSetEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub dst_ptr: u32,
+    pub value: u8,
+    pub dst_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A chip filling a block of [BLOCK_NUM_WORDS] words at `dst_ptr` with the byte `value`, which are
+/// the first and second arguments of the syscall.
+#[derive(Default)]
+pub struct MemSetChip;
+
+impl MemSetChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the MemSet operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct MemSetCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The pointer to the destination block.
+    pub dst_ptr: T,
+
+    /// The byte written to every byte of the block.
+    pub value: T,
+
+    pub dst_memory: [MemoryWriteCols<T>; BLOCK_NUM_WORDS],
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for MemSetChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "MemSet".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = input
+            .memset_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut MemSetCols<F> = row.as_mut_slice().borrow_mut();
+
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.dst_ptr = F::from_canonical_u32(event.dst_ptr);
+                cols.value = F::from_canonical_u8(event.value);
+                new_byte_lookup_events.add_u8_range_checks(
+                    event.shard,
+                    event.channel,
+                    &[event.value],
+                );
+
+                for i in 0..BLOCK_NUM_WORDS {
+                    cols.dst_memory[i].populate(
+                        event.channel,
+                        event.dst_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS)
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.memset_events.is_empty()
+    }
+}
+
+impl Syscall for MemSetChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let dst_ptr = arg1;
+        if dst_ptr % 4 != 0 {
+            panic!();
+        }
+        // Only a byte is constrained to be written, so reject any other value here rather than
+        // failing to prove it.
+        let value: u8 = arg2
+            .try_into()
+            .unwrap_or_else(|_| panic!("the value of {} must be a byte", SyscallCode::MEMSET));
+
+        let word = u32::from_le_bytes([value; 4]);
+        let dst_memory_records = rt.mw_slice(dst_ptr, &[word; BLOCK_NUM_WORDS]);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().memset_events.push(MemSetEvent {
+            shard,
+            channel,
+            clk,
+            dst_ptr,
+            value,
+            dst_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for MemSetChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for MemSetChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &MemSetCols<AB::Var> = (*local).borrow();
+
+        // Assert that the value is a byte, and that every byte of the block is the value.
+        builder.slice_range_check_u8(&[local.value], local.shard, local.channel, local.is_real);
+        for dst in local.dst_memory.iter() {
+            for byte in dst.value().0 {
+                builder.when(local.is_real).assert_eq(byte, local.value);
+            }
+        }
+
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.dst_ptr,
+            &local.dst_memory,
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::MEMSET.syscall_id()),
+            local.dst_ptr,
+            local.value,
+            local.is_real,
+        );
+
+        builder.assert_bool(local.is_real);
+    }
+}
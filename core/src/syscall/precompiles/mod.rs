@@ -1,6 +1,8 @@
+pub mod bigint;
 pub mod blake3;
 pub mod edwards;
 pub mod keccak256;
+pub mod memcopy;
 pub mod sha256;
 pub mod uint256;
 pub mod weierstrass;
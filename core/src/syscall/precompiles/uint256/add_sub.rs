@@ -0,0 +1,413 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::NumWords;
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::field::range::FieldLtCols;
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::stark::MachineRecord;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, limbs_from_prev_access, pad_rows, words_to_bytes_le,
+    words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::Zero;
+use num::{BigUint, One};
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+use super::Uint256MulEvent;
+
+/// The number of columns in the Uint256AddSubCols.
+const NUM_COLS: usize = size_of::<Uint256AddSubCols<u8>>();
+
+/// An addition or subtraction of uint256 values, which has the same inputs and output as a
+/// multiplication.
+pub type Uint256AddSubEvent = Uint256MulEvent;
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// A set of columns for the Uint256AddMod and Uint256SubMod operations.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256AddSubCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The pointer to the first input.
+    pub x_ptr: T,
+
+    /// The pointer to the second input, which contains the y value and the modulus.
+    pub y_ptr: T,
+
+    // Memory columns.
+    // x_memory is written to with the result, which is why it is of type MemoryWriteCols.
+    pub x_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+    pub y_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    // Columns for checking if modulus is zero. If it's zero, then use 2^256 as the effective modulus.
+    pub modulus_is_zero: IsZeroOperation<T>,
+
+    /// Whether the result must be checked to be less than the modulus, which is the case on real
+    /// rows with a non-zero modulus.
+    pub modulus_is_not_zero: T,
+
+    // Output values. We compute (x + y) % modulus or (x - y) % modulus.
+    pub output: FieldOpCols<T, U256Field>,
+
+    /// Columns checking that the result is less than the modulus, as the output columns only
+    /// constrain it up to a multiple of the modulus.
+    pub output_range_check: FieldLtCols<T, U256Field>,
+
+    pub is_real: T,
+}
+
+/// A chip computing `(x + y) % modulus` or `(x - y) % modulus` for uint256 values, with the same
+/// calling convention as [super::Uint256MulChip].
+///
+/// A zero modulus stands for 2^256, which makes these the wrapping addition and subtraction. For
+/// any other modulus, both inputs must be reduced, as the subtraction is only constrained for
+/// `x, y < modulus`.
+pub struct Uint256AddSubChip {
+    op: FieldOperation,
+}
+
+impl Uint256AddSubChip {
+    pub const fn new(op: FieldOperation) -> Self {
+        assert!(
+            matches!(op, FieldOperation::Add | FieldOperation::Sub),
+            "only addition and subtraction are supported"
+        );
+        Self { op }
+    }
+
+    pub const fn add() -> Self {
+        Self::new(FieldOperation::Add)
+    }
+
+    pub const fn sub() -> Self {
+        Self::new(FieldOperation::Sub)
+    }
+
+    pub const fn syscall_code(&self) -> SyscallCode {
+        match self.op {
+            FieldOperation::Add => SyscallCode::UINT256_ADDMOD,
+            _ => SyscallCode::UINT256_SUBMOD,
+        }
+    }
+
+    fn events<'a>(&self, record: &'a ExecutionRecord) -> &'a Vec<Uint256AddSubEvent> {
+        match self.op {
+            FieldOperation::Add => &record.uint256_addmod_events,
+            _ => &record.uint256_submod_events,
+        }
+    }
+
+    fn events_mut<'a>(&self, record: &'a mut ExecutionRecord) -> &'a mut Vec<Uint256AddSubEvent> {
+        match self.op {
+            FieldOperation::Add => &mut record.uint256_addmod_events,
+            _ => &mut record.uint256_submod_events,
+        }
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256AddSubChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match self.op {
+            FieldOperation::Add => "Uint256AddMod".to_string(),
+            _ => "Uint256SubMod".to_string(),
+        }
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = self
+            .events(input)
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Uint256AddSubCols<F> = row.as_mut_slice().borrow_mut();
+
+                let x = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.x));
+                let y = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.y));
+                let modulus = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.modulus));
+
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.y_memory[i].populate(
+                        event.channel,
+                        event.y_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                let modulus_bytes = words_to_bytes_le_vec(&event.modulus);
+                let modulus_byte_sum = modulus_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.modulus_is_zero, modulus_byte_sum);
+
+                let effective_modulus = if modulus.is_zero() {
+                    BigUint::one() << 256
+                } else {
+                    modulus.clone()
+                };
+                let result = cols.output.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &x,
+                    &y,
+                    &effective_modulus,
+                    self.op,
+                );
+
+                if !modulus.is_zero() {
+                    cols.modulus_is_not_zero = F::one();
+                    cols.output_range_check.populate(
+                        &mut new_byte_lookup_events,
+                        event.shard,
+                        event.channel,
+                        &result,
+                        &modulus,
+                    );
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut Uint256AddSubCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            cols.output
+                .populate(&mut vec![], 0, 0, &zero, &zero, self.op);
+
+            row
+        });
+
+        RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS)
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !self.events(shard).is_empty()
+    }
+}
+
+impl Syscall for Uint256AddSubChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        if x_ptr % 4 != 0 {
+            panic!();
+        }
+        let y_ptr = arg2;
+        if y_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read x unsafely, since it's overwritten with the result, and then y and the modulus
+        // stored after it.
+        let x = rt.slice_unsafe(x_ptr, WORDS_FIELD_ELEMENT);
+        let (y_memory_records, y) = rt.mr_slice(y_ptr, WORDS_FIELD_ELEMENT);
+        let modulus_ptr = y_ptr + WORDS_FIELD_ELEMENT as u32 * WORD_SIZE as u32;
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, WORDS_FIELD_ELEMENT);
+
+        let uint256_x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let uint256_y = BigUint::from_bytes_le(&words_to_bytes_le_vec(&y));
+        let uint256_modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+
+        // The subtraction is only constrained for reduced inputs, so reject the others here rather
+        // than failing to prove them.
+        let effective_modulus = if uint256_modulus.is_zero() {
+            BigUint::one() << 256
+        } else {
+            assert!(
+                uint256_x < uint256_modulus && uint256_y < uint256_modulus,
+                "the inputs of {} must be less than the modulus",
+                self.syscall_code()
+            );
+            uint256_modulus
+        };
+        let result = match self.op {
+            FieldOperation::Add => (uint256_x + uint256_y) % &effective_modulus,
+            _ => (&effective_modulus + uint256_x - uint256_y) % &effective_modulus,
+        };
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(32, 0u8);
+        let result = bytes_to_words_le::<8>(&result_bytes);
+
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        let event = Uint256AddSubEvent {
+            shard,
+            channel,
+            clk,
+            x_ptr,
+            x,
+            y_ptr,
+            y,
+            modulus,
+            x_memory_records,
+            y_memory_records,
+            modulus_memory_records,
+        };
+        self.events_mut(rt.record_mut()).push(event);
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256AddSubChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256AddSubChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256AddSubCols<AB::Var> = (*local).borrow();
+
+        // The value of x is the "prev_value" of the x_memory, since the result is written to it.
+        let x_limbs = limbs_from_prev_access(&local.x_memory);
+        let y_limbs = limbs_from_access(&local.y_memory);
+        let modulus_limbs = limbs_from_access(&local.modulus_memory);
+
+        // A zero modulus stands for 2^256, as in the Uint256Mul operation.
+        let modulus_byte_sum = modulus_limbs
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &limb| acc + limb);
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            modulus_byte_sum,
+            local.modulus_is_zero,
+            local.is_real.into(),
+        );
+        let modulus_is_zero = local.modulus_is_zero.result;
+        let mut coeff_2_256 = Vec::new();
+        coeff_2_256.resize(32, AB::Expr::zero());
+        coeff_2_256.push(AB::Expr::one());
+        let modulus_polynomial: Polynomial<AB::Expr> = modulus_limbs.into();
+        let p_modulus: Polynomial<AB::Expr> = modulus_polynomial
+            * (AB::Expr::one() - modulus_is_zero.into())
+            + Polynomial::from_coefficients(&coeff_2_256) * modulus_is_zero.into();
+
+        // For the subtraction, this constrains `result + y = x + carry * modulus`.
+        local.output.eval_with_modulus(
+            builder,
+            &x_limbs,
+            &y_limbs,
+            &p_modulus,
+            self.op,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // The output columns only constrain the result up to a multiple of the modulus, so check
+        // that it's reduced. With a zero modulus, any 256-bit result is.
+        builder.assert_eq(
+            local.modulus_is_not_zero,
+            local.is_real * (AB::Expr::one() - modulus_is_zero.into()),
+        );
+        local.output_range_check.eval(
+            builder,
+            &local.output.result,
+            &modulus_limbs,
+            local.shard,
+            local.channel,
+            local.modulus_is_not_zero,
+        );
+
+        // Assert that the correct result is being written to x_memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.output.result, value_as_limbs(&local.x_memory));
+
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.y_ptr,
+            &[local.y_memory, local.modulus_memory].concat(),
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(self.syscall_code().syscall_id()),
+            local.x_ptr,
+            local.y_ptr,
+            local.is_real,
+        );
+
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -1,22 +1,172 @@
+mod add_sub;
 mod air;
 
+pub use add_sub::*;
 pub use air::*;
 
 #[cfg(test)]
 mod tests {
 
+    use std::borrow::BorrowMut;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use num::{BigUint, One};
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::{dense::RowMajorMatrix, Matrix};
+
+    use super::{Uint256AddSubChip, Uint256AddSubCols};
+    use crate::air::{MachineAir, Word};
     use crate::operations::field::params::FieldParameters;
+    use crate::stark::StarkGenericConfig;
     use crate::{
         io::SP1Stdin,
-        runtime::Program,
+        runtime::{ExecutionRecord, Instruction, Opcode, Program, Runtime, SyscallCode},
         utils::{
-            self,
+            self, bytes_to_words_le,
             ec::{uint256::U256Field, utils::biguint_from_limbs},
-            run_test_io,
+            run_test, run_test_io,
             tests::{UINT256_DIV_ELF, UINT256_MUL_ELF},
+            uni_stark_prove as prove, uni_stark_verify as verify, words_to_bytes_le_vec,
+            BabyBearPoseidon2, SP1CoreOpts,
         },
     };
 
+    /// The operations of [uint256_add_sub_program], as `(syscall, x, y, modulus)`.
+    fn uint256_add_sub_cases() -> Vec<(SyscallCode, BigUint, BigUint, BigUint)> {
+        use SyscallCode::{UINT256_ADDMOD as ADD, UINT256_SUBMOD as SUB};
+        let n = BigUint::from;
+        let max = U256Field::modulus() - 1u32;
+        // The largest 256-bit prime.
+        let p = U256Field::modulus() - 189u32;
+        vec![
+            (ADD, n(3u32), n(5u32), n(0u32)),
+            (ADD, max.clone(), n(2u32), n(0u32)),
+            (ADD, &p - 1u32, &p - 2u32, p.clone()),
+            (ADD, n(6u32), n(4u32), n(7u32)),
+            (SUB, n(5u32), n(3u32), n(0u32)),
+            (SUB, n(2u32), max, n(0u32)),
+            (SUB, n(1u32), &p - 1u32, p),
+            (SUB, n(4u32), n(4u32), n(7u32)),
+        ]
+    }
+
+    fn biguint_to_words(value: &BigUint) -> [u32; 8] {
+        let mut bytes = value.to_bytes_le();
+        bytes.resize(32, 0);
+        bytes_to_words_le(&bytes)
+    }
+
+    /// A program that stores the operands of each case, and then calls its syscall. The result of
+    /// the case `i` is written to `x_ptr(i)`.
+    fn uint256_add_sub_program(cases: &[(SyscallCode, BigUint, BigUint, BigUint)]) -> Program {
+        let mut instructions = Vec::new();
+        for (i, (code, x, y, modulus)) in cases.iter().enumerate() {
+            let words = [x, y, modulus].into_iter().flat_map(biguint_to_words);
+            for (j, word) in words.enumerate() {
+                instructions.extend([
+                    Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                    Instruction::new(Opcode::ADD, 30, 0, x_ptr(i) + j as u32 * 4, false, true),
+                    Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                ]);
+            }
+            instructions.extend([
+                Instruction::new(Opcode::ADD, 5, 0, *code as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, x_ptr(i), false, true),
+                Instruction::new(Opcode::ADD, 11, 0, x_ptr(i) + 32, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    fn x_ptr(i: usize) -> u32 {
+        0x1_0000 + i as u32 * 96
+    }
+
+    #[test]
+    fn test_uint256_add_sub_execute() {
+        utils::setup_logger();
+        let cases = uint256_add_sub_cases();
+        let mut runtime = Runtime::new(uint256_add_sub_program(&cases), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.uint256_addmod_events.len(), 4);
+        assert_eq!(runtime.record.uint256_submod_events.len(), 4);
+
+        for (i, (code, x, y, modulus)) in cases.iter().enumerate() {
+            let modulus = if *modulus == BigUint::from(0u32) {
+                BigUint::one() << 256
+            } else {
+                modulus.clone()
+            };
+            let expected = match code {
+                SyscallCode::UINT256_ADDMOD => (x + y) % &modulus,
+                _ => (&modulus + x - y) % &modulus,
+            };
+            let words = (0..8)
+                .map(|j| runtime.word(x_ptr(i) + j * 4))
+                .collect::<Vec<_>>();
+            let result = BigUint::from_bytes_le(&words_to_bytes_le_vec(&words));
+            assert_eq!(result, expected, "case {}", i);
+        }
+    }
+
+    #[test]
+    fn test_uint256_add_sub_prove() {
+        utils::setup_logger();
+        let program = uint256_add_sub_program(&uint256_add_sub_cases());
+        run_test(program).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "must be less than the modulus")]
+    fn test_uint256_add_sub_unreduced() {
+        let n = BigUint::from;
+        let cases = [(SyscallCode::UINT256_SUBMOD, n(9u32), n(3u32), n(7u32))];
+        let mut runtime = Runtime::new(uint256_add_sub_program(&cases), SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_uint256_add_sub_unreduced_result() {
+        utils::setup_logger();
+        let n = BigUint::from;
+        let cases = [(SyscallCode::UINT256_SUBMOD, n(4u32), n(4u32), n(7u32))];
+        let mut runtime = Runtime::new(uint256_add_sub_program(&cases), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let chip = Uint256AddSubChip::sub();
+        let mut trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&runtime.record, &mut ExecutionRecord::default());
+        let proves = |trace: RowMajorMatrix<BabyBear>| {
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                let config = BabyBearPoseidon2::new();
+                let mut challenger = config.challenger();
+                let proof = prove(&config, &chip, &mut challenger, trace);
+                let mut challenger = config.challenger();
+                verify(&config, &chip, &mut challenger, &proof)
+            }));
+            matches!(result, Ok(Ok(())))
+        };
+        assert!(proves(trace.clone()));
+
+        // Writing 7 instead of 0 satisfies `result + y = x + carry * modulus` with a carry of 1, so
+        // only the range check rejects it. No byte of 7 is less than the modulus, so no byte is
+        // flagged. Flagging one anyway is rejected by the byte lookup, which isn't checked here.
+        let width = trace.width();
+        let cols: &mut Uint256AddSubCols<BabyBear> = trace.values[..width].borrow_mut();
+        cols.output.result = U256Field::to_limbs_field::<BabyBear, _>(&n(7u32));
+        cols.output.carry = U256Field::to_limbs_field::<BabyBear, _>(&n(1u32));
+        cols.x_memory[0].access.value = Word::from(7u32);
+        let range_check = &mut cols.output_range_check;
+        for flag in range_check.byte_flags.0.iter_mut() {
+            *flag = BabyBear::zero();
+        }
+        range_check.lhs_comparison_byte = BabyBear::zero();
+        range_check.rhs_comparison_byte = BabyBear::zero();
+        assert!(!proves(trace));
+    }
+
     #[test]
     fn test_uint256_mul() {
         utils::setup_logger();
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use crate::runtime::{ForkState, Syscall, SyscallContext};
 
 pub struct SyscallEnterUnconstrained;
@@ -20,7 +18,7 @@ impl Syscall for SyscallEnterUnconstrained {
             global_clk: ctx.rt.state.global_clk,
             clk: ctx.rt.state.clk,
             pc: ctx.rt.state.pc,
-            memory_diff: HashMap::default(),
+            memory: ctx.rt.state.memory.clone(),
             record: std::mem::take(&mut ctx.rt.record),
             op_record: std::mem::take(&mut ctx.rt.memory_accesses),
             emit_events: ctx.rt.emit_events,
@@ -46,16 +44,7 @@ impl Syscall for SyscallExitUnconstrained {
             ctx.rt.state.clk = ctx.rt.unconstrained_state.clk;
             ctx.rt.state.pc = ctx.rt.unconstrained_state.pc;
             ctx.next_pc = ctx.rt.state.pc.wrapping_add(4);
-            for (addr, value) in ctx.rt.unconstrained_state.memory_diff.drain() {
-                match value {
-                    Some(value) => {
-                        ctx.rt.state.memory.insert(addr, value);
-                    }
-                    None => {
-                        ctx.rt.state.memory.remove(&addr);
-                    }
-                }
-            }
+            ctx.rt.state.memory = std::mem::take(&mut ctx.rt.unconstrained_state.memory);
             ctx.rt.record = std::mem::take(&mut ctx.rt.unconstrained_state.record);
             ctx.rt.memory_accesses = std::mem::take(&mut ctx.rt.unconstrained_state.op_record);
             ctx.rt.emit_events = ctx.rt.unconstrained_state.emit_events;
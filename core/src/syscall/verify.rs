@@ -1,7 +1,9 @@
+use thiserror::Error;
+
 use crate::{
-    runtime::{Syscall, SyscallContext},
-    stark::{RiscvAir, StarkGenericConfig},
-    utils::BabyBearPoseidon2Inner,
+    runtime::{ExecutionError, Syscall, SyscallContext},
+    stark::{ShardProof, StarkVerifyingKey},
+    utils::BabyBearPoseidon2,
 };
 
 /// Verifies an SP1 recursive verifier proof. Note that this syscall only verifies the proof during
@@ -15,8 +17,35 @@ impl SyscallVerifySP1Proof {
     }
 }
 
+/// A verifier for the deferred proofs read by the `VERIFY_SP1_PROOF` syscall.
+///
+/// The proofs in the proof stream are compressed proofs of the recursion machine, which lives in
+/// a crate that depends on this one, so the verification logic is provided by the prover.
+pub trait DeferredProofVerification: Send + Sync {
+    /// Verify a deferred proof and check that it commits to the verifying key digest and public
+    /// values digest passed to the syscall.
+    fn verify_deferred_proof(
+        &self,
+        proof: &ShardProof<BabyBearPoseidon2>,
+        vk: &StarkVerifyingKey<BabyBearPoseidon2>,
+        vk_digest: &[u32; 8],
+        pv_digest: &[u8; 32],
+    ) -> Result<(), DeferredProofVerificationError>;
+}
+
+#[derive(Error, Debug)]
+pub enum DeferredProofVerificationError {
+    #[error("no proof left in the proof stream")]
+    ProofStreamExhausted,
+    #[error("invalid proof: {0}")]
+    InvalidProof(String),
+    #[error("vkey digest {0:?} does not match the proof's vkey digest {1:?}")]
+    VkeyDigestMismatch([u32; 8], [u32; 8]),
+    #[error("public values digest does not match the proof's committed value digest")]
+    PublicValuesDigestMismatch,
+}
+
 impl Syscall for SyscallVerifySP1Proof {
-    #[allow(unused_variables, unused_mut)]
     fn execute(&self, ctx: &mut SyscallContext, vkey_ptr: u32, pv_digest_ptr: u32) -> Option<u32> {
         let rt = &mut ctx.rt;
 
@@ -25,30 +54,31 @@ impl Syscall for SyscallVerifySP1Proof {
         // pv_digest_ptr is a pointer to [u32; 8] which contains the public values digest.
         assert_eq!(pv_digest_ptr % 4, 0, "pv_digest_ptr must be word-aligned");
 
-        let vkey = (0..8)
-            .map(|i| rt.word(vkey_ptr + i * 4))
-            .collect::<Vec<u32>>();
+        let vkey: [u32; 8] = core::array::from_fn(|i| rt.word(vkey_ptr + i as u32 * 4));
 
-        let pv_digest = (0..8)
-            .map(|i| rt.word(pv_digest_ptr + i * 4))
-            .collect::<Vec<u32>>();
+        let pv_digest: [u8; 32] = (0..8)
+            .flat_map(|i| rt.word(pv_digest_ptr + i * 4).to_le_bytes())
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap();
 
-        let (proof, proof_vk) = &rt.state.proof_stream[rt.state.proof_stream_ptr];
+        let proof_index = rt.state.proof_stream_ptr;
         rt.state.proof_stream_ptr += 1;
 
-        let config = BabyBearPoseidon2Inner::new();
-        let mut challenger = config.challenger();
-        // TODO: need to use RecursionAir here
-        let machine = RiscvAir::machine(config);
-
-        // TODO: Need to import PublicValues from recursion.
-        // Assert the commit in vkey from runtime inputs matches the one from syscall.
-        // Assert that the public values digest from runtime inputs matches the one from syscall.
+        let result = match rt.state.proof_stream.get(proof_index) {
+            Some((proof, proof_vk)) => match &rt.deferred_proof_verifier {
+                Some(verifier) => {
+                    verifier.verify_deferred_proof(proof, proof_vk, &vkey, &pv_digest)
+                }
+                // Without a verifier, the proof is only checked in the recursion layer.
+                None => Ok(()),
+            },
+            None => Err(DeferredProofVerificationError::ProofStreamExhausted),
+        };
 
-        // TODO: Verify proof
-        // machine
-        //     .verify(proof_vk, proof, &mut challenger)
-        //     .expect("proof verification failed");
+        if let Err(e) = result {
+            ctx.error = Some(ExecutionError::InvalidDeferredProof(proof_index, e));
+        }
 
         None
     }
@@ -3,6 +3,18 @@ use crate::{
     utils::num_to_comma_separated,
 };
 
+/// The file descriptor the program writes the name of an environment variable to, to read its
+/// value from the input stream.
+///
+/// This MUST match the code in `zkvm/precompiles/src/io.rs`.
+pub const FD_ENV: u32 = 5;
+
+/// The file descriptor the program writes the path of a file to, to read its contents from the
+/// input stream.
+///
+/// This MUST match the code in `zkvm/precompiles/src/io.rs`.
+pub const FD_FILE: u32 = 6;
+
 pub struct SyscallWrite;
 
 impl SyscallWrite {
@@ -16,7 +28,7 @@ impl Syscall for SyscallWrite {
         let a2 = Register::X12;
         let rt = &mut ctx.rt;
         let fd = arg1;
-        if fd == 1 || fd == 2 || fd == 3 || fd == 4 {
+        if fd == 1 || fd == 2 || fd == 3 || fd == 4 || fd == FD_ENV || fd == FD_FILE {
             let write_buf = arg2;
             let nbytes = rt.register(a2);
             // Read nbytes from memory starting at write_buf.
@@ -63,6 +75,10 @@ impl Syscall for SyscallWrite {
                 }
             } else if fd == 2 {
                 let s = core::str::from_utf8(slice).unwrap();
+                if let Some(stderr) = rt.captured_stderr.as_mut() {
+                    stderr.push_str(s);
+                    return None;
+                }
                 let flush_s = update_io_buf(ctx, fd, s);
                 if !flush_s.is_empty() {
                     flush_s
@@ -73,6 +89,25 @@ impl Syscall for SyscallWrite {
                 rt.state.public_values_stream.extend_from_slice(slice);
             } else if fd == 4 {
                 rt.state.input_stream.push(slice.to_vec());
+            } else if fd == FD_ENV || fd == FD_FILE {
+                // Insert the value at the read position of the input stream, so that it's the
+                // next input the program reads. It's suffixed by whether it exists, so that the
+                // program can remove the flag without moving the value.
+                let name = String::from_utf8_lossy(slice);
+                let value = match fd {
+                    FD_ENV => rt
+                        .state
+                        .env
+                        .get(name.as_ref())
+                        .map(|value| value.as_bytes()),
+                    _ => rt.files.get(name.as_ref()).map(Vec::as_slice),
+                };
+                let response = match value {
+                    Some(value) => [value, &[1]].concat(),
+                    None => vec![0],
+                };
+                let ptr = rt.state.input_stream_ptr;
+                rt.state.input_stream.insert(ptr, response);
             } else {
                 unreachable!()
             }
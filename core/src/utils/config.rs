@@ -1,4 +1,5 @@
 use crate::stark::StarkGenericConfig;
+use crate::utils::{FriParameters, SP1SecurityConfig};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
@@ -57,16 +58,9 @@ pub fn sp1_fri_config() -> FriConfig<InnerChallengeMmcs> {
     let hash = InnerHash::new(perm.clone());
     let compress = InnerCompress::new(perm.clone());
     let challenge_mmcs = InnerChallengeMmcs::new(InnerValMmcs::new(hash, compress));
-    let num_queries = match std::env::var("FRI_QUERIES") {
-        Ok(value) => value.parse().unwrap(),
-        Err(_) => 100,
-    };
-    FriConfig {
-        log_blowup: 1,
-        num_queries,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
-    }
+    SP1SecurityConfig::from_env()
+        .core
+        .fri_config(challenge_mmcs)
 }
 
 /// The FRI config for inner recursion.
@@ -75,16 +69,9 @@ pub fn inner_fri_config() -> FriConfig<InnerChallengeMmcs> {
     let hash = InnerHash::new(perm.clone());
     let compress = InnerCompress::new(perm.clone());
     let challenge_mmcs = InnerChallengeMmcs::new(InnerValMmcs::new(hash, compress));
-    let num_queries = match std::env::var("FRI_QUERIES") {
-        Ok(value) => value.parse().unwrap(),
-        Err(_) => 100,
-    };
-    FriConfig {
-        log_blowup: 1,
-        num_queries,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
-    }
+    SP1SecurityConfig::from_env()
+        .compress
+        .fri_config(challenge_mmcs)
 }
 
 /// The recursion config used for recursive reduce circuit.
@@ -146,6 +133,10 @@ impl StarkGenericConfig for BabyBearPoseidon2Inner {
         &self.pcs
     }
 
+    fn fri_parameters(&self) -> FriParameters {
+        FriParameters::from(self.pcs.fri_config())
+    }
+
     fn challenger(&self) -> Self::Challenger {
         InnerChallenger::new(self.perm.clone())
     }
@@ -0,0 +1,64 @@
+use typenum::{U1023, U127, U128, U255, U256, U511, U512, U64};
+
+use serde::{Deserialize, Serialize};
+
+use crate::operations::field::params::{FieldParameters, NumLimbs, NumWords};
+
+/// The width of the integers of some [BigIntParameters].
+#[derive(Debug, PartialEq, Eq)]
+pub enum BigIntWidth {
+    U512,
+    U1024,
+    U2048,
+    U4096,
+}
+
+/// The parameters of unsigned integers wider than 256 bits.
+///
+/// Like [super::uint256::U256Field], these are only used as a modulus of `2^bits` by the `FieldOps`
+/// multiplication operation, and the actual modulus is given by the precompile.
+pub trait BigIntParameters: FieldParameters + NumWords {
+    const WIDTH: BigIntWidth;
+}
+
+/// The little endian bytes of `2^(8 * (LEN - 1))`.
+const fn pow_2_bytes<const LEN: usize>() -> [u8; LEN] {
+    let mut bytes = [0; LEN];
+    bytes[LEN - 1] = 1;
+    bytes
+}
+
+macro_rules! bigint_parameters {
+    ($name:ident, $width:ident, $limbs:ty, $witness:ty, $witness_offset:expr, $high_bits:expr) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+        pub struct $name;
+
+        impl FieldParameters for $name {
+            /// The modulus of `2^bits`, with one more byte than the limbs.
+            const MODULUS: &'static [u8] =
+                &pow_2_bytes::<{ <$limbs as typenum::Unsigned>::USIZE + 1 }>();
+
+            const WITNESS_OFFSET: usize = $witness_offset;
+
+            const NB_WITNESS_HIGH_BITS: usize = $high_bits;
+        }
+
+        impl NumLimbs for $name {
+            type Limbs = $limbs;
+            // As for `U256Field`, there is one more witness limb than usual for the modulus of
+            // `2^bits`.
+            type Witness = $witness;
+        }
+
+        impl BigIntParameters for $name {
+            const WIDTH: BigIntWidth = BigIntWidth::$width;
+        }
+    };
+}
+
+// The coefficients of the witness of a product of `n` limbs are less than `255 * n + 1` in absolute
+// value, so the offset is the next power of two, and the shifted witness has one more bit.
+bigint_parameters!(U512Field, U512, U64, U127, 1 << 14, 8);
+bigint_parameters!(U1024Field, U1024, U128, U255, 1 << 15, 8);
+bigint_parameters!(U2048Field, U2048, U256, U511, 1 << 16, 9);
+bigint_parameters!(U4096Field, U4096, U512, U1023, 1 << 17, 10);
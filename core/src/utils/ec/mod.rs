@@ -1,3 +1,4 @@
+pub mod bigint;
 pub mod edwards;
 pub mod scalar_mul;
 pub mod uint256;
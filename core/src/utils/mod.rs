@@ -5,6 +5,8 @@ mod logger;
 mod options;
 mod programs;
 mod prove;
+mod security;
+mod shard_sizing;
 mod tracer;
 
 pub use buffer::*;
@@ -12,6 +14,8 @@ pub use config::*;
 pub use logger::*;
 pub use options::*;
 pub use prove::*;
+pub use security::*;
+pub use shard_sizing::*;
 pub use tracer::*;
 
 #[cfg(test)]
@@ -39,6 +43,19 @@ pub fn pad_to_power_of_two<const N: usize, T: Clone + Default>(values: &mut Vec<
     values.resize(n_real_rows.next_power_of_two() * N, T::default());
 }
 
+/// Resizes `buffer` to a zeroed trace of `num_rows` rows of the given width, padded like
+/// [pad_to_power_of_two], so that the rows can be written into it directly. The allocation of
+/// `buffer` is reused.
+pub fn padded_trace_buffer<T: Clone + Default>(
+    mut buffer: Vec<T>,
+    num_rows: usize,
+    width: usize,
+) -> Vec<T> {
+    buffer.clear();
+    buffer.resize(num_rows.max(16).next_power_of_two() * width, T::default());
+    buffer
+}
+
 pub fn limbs_from_prev_access<T: Copy, N: ArrayLength, M: MemoryCols<T>>(
     cols: &[M],
 ) -> Limbs<T, N> {
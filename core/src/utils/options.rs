@@ -1,9 +1,26 @@
+use p3_field::PrimeField32;
+
+use crate::io::SP1Stdin;
+use crate::runtime::{ExecutionError, Program};
+use crate::utils::{ProverResources, ShardSizing};
+
 #[derive(Debug, Clone, Copy)]
 pub struct SP1CoreOpts {
     pub shard_size: usize,
     pub shard_batch_size: usize,
     pub shard_chunking_multiplier: usize,
     pub reconstruct_commitments: bool,
+    /// Whether to spill the committed traces of each shard to a temporary file, so that proving
+    /// the shards does not execute the program and commit to their traces a second time. Only
+    /// applies when `reconstruct_commitments` is set.
+    ///
+    /// Each spilled shard takes as much disk space as its traces and their low-degree extensions,
+    /// and all the shards are spilled before the first one is proven, so this is only enabled with
+    /// `SP1_SPILL_TRACES=true`.
+    pub spill_traces: bool,
+    /// The resources of the prover. If set, the shard size and shard batch size are chosen to fit
+    /// them, see [SP1CoreOpts::with_shard_sizing].
+    pub resources: Option<ProverResources>,
 }
 
 impl Default for SP1CoreOpts {
@@ -13,6 +30,10 @@ impl Default for SP1CoreOpts {
             shard_batch_size: 16,
             shard_chunking_multiplier: 1,
             reconstruct_commitments: true,
+            spill_traces: std::env::var("SP1_SPILL_TRACES")
+                .map(|value| value == "true")
+                .unwrap_or(false),
+            resources: ProverResources::from_env(),
         }
     }
 }
@@ -21,6 +42,34 @@ impl SP1CoreOpts {
     pub fn recursion() -> Self {
         let mut opts = Self::default();
         opts.reconstruct_commitments = false;
+        opts.spill_traces = false;
+        opts.resources = None;
         opts
     }
+
+    /// Chooses the shard size and shard batch size for proving a program to fit the resources of
+    /// the prover, by sampling the start of its execution.
+    ///
+    /// If no resources are set, the options are returned unchanged along with `None`.
+    pub fn with_shard_sizing<F: PrimeField32>(
+        mut self,
+        program: &Program,
+        stdin: &SP1Stdin,
+    ) -> Result<(Self, Option<ShardSizing>), ExecutionError> {
+        let Some(resources) = self.resources else {
+            return Ok((self, None));
+        };
+        let sizing = ShardSizing::sample::<F>(program.clone(), stdin, resources)?;
+        if sizing.shard_memory_bytes > resources.memory_bytes {
+            tracing::warn!(
+                "smallest shard does not fit in the memory budget: {}",
+                sizing
+            );
+        } else {
+            tracing::info!("chose shard sizes: {}", sizing);
+        }
+        self.shard_size = sizing.shard_size;
+        self.shard_batch_size = sizing.shard_batch_size;
+        Ok((self, Some(sizing)))
+    }
 }
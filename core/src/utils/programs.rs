@@ -108,4 +108,7 @@ pub mod tests {
 
     pub const PANIC_ELF: &[u8] =
         include_bytes!("../../../tests/panic/elf/riscv32im-succinct-zkvm-elf");
+
+    pub const MEMCOPY_ELF: &[u8] =
+        include_bytes!("../../../tests/memcopy/elf/riscv32im-succinct-zkvm-elf");
 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io;
 use std::io::{Seek, Write};
@@ -11,7 +12,7 @@ use serde::Serialize;
 use size::Size;
 use thiserror::Error;
 
-use crate::air::MachineAir;
+use crate::air::{MachineAir, PublicValues};
 use crate::io::{SP1PublicValues, SP1Stdin};
 use crate::lookup::InteractionBuilder;
 use crate::runtime::ExecutionError;
@@ -20,6 +21,7 @@ use crate::stark::DebugConstraintBuilder;
 use crate::stark::MachineProof;
 use crate::stark::ProverConstraintFolder;
 use crate::stark::StarkVerifyingKey;
+use crate::stark::TraceArena;
 use crate::stark::Val;
 use crate::stark::VerifierConstraintFolder;
 use crate::stark::{Com, PcsProverData, RiscvAir, ShardProof, StarkProvingKey, UniConfig};
@@ -99,12 +101,14 @@ where
 {
     let proving_start = Instant::now();
 
+    // Choose the shard sizes, if the resources of the prover are set.
+    let (opts, _) = opts
+        .with_shard_sizing::<SC::Val>(&program, stdin)
+        .map_err(SP1CoreProverError::ExecutionError)?;
+
     // Execute the program.
     let mut runtime = Runtime::new(program.clone(), opts);
-    runtime.write_vecs(&stdin.buffer);
-    for proof in stdin.proofs.iter() {
-        runtime.write_proof(proof.0.clone(), proof.1.clone());
-    }
+    runtime.write_inputs(stdin);
 
     // Setup the machine.
     let machine = RiscvAir::machine(config);
@@ -158,63 +162,120 @@ where
     };
 
     // For each checkpoint, generate events, shard them, commit shards, and observe in challenger.
-    let sharding_config = ShardingConfig::default();
+    //
+    // Precompile shards are deferred until after the CPU shards of every checkpoint, since the
+    // CPU shards of a checkpoint take the indices right after those of the previous checkpoint.
+    //
+    // The trace buffers of each shard are reused for the shards that are committed after it.
+    let sharding_config = ShardingConfig::new(opts.shard_size);
+    let arena = TraceArena::new();
     let mut shard_main_datas = Vec::new();
     let mut challenger = machine.config().challenger();
     vk.observe_into(&mut challenger);
-    for checkpoint_file in checkpoints.iter_mut() {
-        let mut record = trace_checkpoint(program.clone(), checkpoint_file, opts);
-        record.public_values = public_values;
-        reset_seek(&mut *checkpoint_file);
-
-        // Shard the record into shards.
-        let checkpoint_shards =
-            tracing::info_span!("shard").in_scope(|| machine.shard(record, &sharding_config));
-
+    let mut commit_and_observe = |shards: &[ExecutionRecord], challenger: &mut SC::Challenger| {
         // Commit to each shard.
         let (commitments, commit_data) = tracing::info_span!("commit")
-            .in_scope(|| LocalProver::commit_shards(&machine, &checkpoint_shards, opts));
-        shard_main_datas.push(commit_data);
+            .in_scope(|| LocalProver::commit_shards_with_arena(&machine, shards, opts, &arena))
+            .map_err(SP1CoreProverError::SerializationError)?;
+        shard_main_datas.extend(commit_data);
 
         // Observe the commitments.
-        for (commitment, shard) in commitments.into_iter().zip(checkpoint_shards.iter()) {
+        for (commitment, shard) in commitments.into_iter().zip(shards.iter()) {
             challenger.observe(commitment);
             challenger.observe_slice(&shard.public_values::<SC::Val>()[0..machine.num_pv_elts()]);
         }
-    }
+        Ok::<_, SP1CoreProverError>(())
+    };
+    let mut deferred = ExecutionRecord::new(0, program.clone().into());
+    let mut last_public_values = public_values;
+    for checkpoint_file in checkpoints.iter_mut() {
+        let mut record = trace_checkpoint(program.clone(), checkpoint_file, &stdin.files, opts);
+        record.public_values = public_values;
+        reset_seek(&mut *checkpoint_file);
+
+        // Shard the record into shards.
+        let checkpoint_shards = tracing::info_span!("shard").in_scope(|| {
+            let shards = machine.shard(record, &sharding_config);
+            defer_precompile_shards(shards, &mut deferred, &mut last_public_values)
+        });
 
-    // For each checkpoint, generate events and shard again, then prove the shards.
-    let mut shard_proofs = Vec::<ShardProof<SC>>::new();
-    for mut checkpoint_file in checkpoints.into_iter() {
-        let checkpoint_shards = {
-            let mut events = trace_checkpoint(program.clone(), &checkpoint_file, opts);
-            events.public_values = public_values;
-            reset_seek(&mut checkpoint_file);
-            tracing::debug_span!("shard").in_scope(|| machine.shard(events, &sharding_config))
+        // Commit to each shard and observe the commitments.
+        commit_and_observe(&checkpoint_shards, &mut challenger)?;
+    }
+    let precompile_shards = deferred.precompile_shards(
+        last_public_values.shard + 1,
+        last_public_values,
+        &sharding_config,
+    );
+    commit_and_observe(&precompile_shards, &mut challenger)?;
+
+    let prove_shard = |shard_data: ShardMainData<SC>| {
+        let config = machine.config();
+        let chip_ordering = shard_data.chip_ordering.clone();
+        let ordered_chips = machine
+            .shard_chips_ordered(&chip_ordering)
+            .collect::<Vec<_>>()
+            .to_vec();
+        LocalProver::prove_shard(
+            config,
+            &pk,
+            &ordered_chips,
+            shard_data,
+            &mut challenger.clone(),
+        )
+    };
+    let shard_proofs = if opts.reconstruct_commitments && !opts.spill_traces {
+        // For each checkpoint, generate events and shard again, then prove the shards.
+        let prove_shards = |shards: Vec<ExecutionRecord>| {
+            shards
+                .into_iter()
+                .map(|shard| {
+                    let config = machine.config();
+                    let index = shard.index() as usize;
+                    let shard_data = LocalProver::commit_main_with_arena(
+                        config, &machine, &shard, index, &arena,
+                    );
+                    prove_shard(shard_data)
+                })
+                .collect::<Vec<_>>()
         };
-        let mut checkpoint_proofs = checkpoint_shards
+        let mut shard_proofs = Vec::<ShardProof<SC>>::new();
+        let mut deferred = ExecutionRecord::new(0, program.clone().into());
+        let mut last_public_values = public_values;
+        for mut checkpoint_file in checkpoints.into_iter() {
+            let checkpoint_shards = {
+                let mut events =
+                    trace_checkpoint(program.clone(), &checkpoint_file, &stdin.files, opts);
+                events.public_values = public_values;
+                reset_seek(&mut checkpoint_file);
+                tracing::debug_span!("shard").in_scope(|| {
+                    let shards = machine.shard(events, &sharding_config);
+                    defer_precompile_shards(shards, &mut deferred, &mut last_public_values)
+                })
+            };
+            shard_proofs.append(&mut prove_shards(checkpoint_shards));
+        }
+        let precompile_shards = deferred.precompile_shards(
+            last_public_values.shard + 1,
+            last_public_values,
+            &sharding_config,
+        );
+        shard_proofs.append(&mut prove_shards(precompile_shards));
+        shard_proofs
+    } else {
+        // Prove the shards from their committed traces, in the order they were committed in.
+        drop(arena);
+        drop(checkpoints);
+        shard_main_datas
             .into_iter()
-            .map(|shard| {
-                let config = machine.config();
-                let shard_data =
-                    LocalProver::commit_main(config, &machine, &shard, shard.index() as usize);
-
-                let chip_ordering = shard_data.chip_ordering.clone();
-                let ordered_chips = machine
-                    .shard_chips_ordered(&chip_ordering)
-                    .collect::<Vec<_>>()
-                    .to_vec();
-                LocalProver::prove_shard(
-                    config,
-                    &pk,
-                    &ordered_chips,
-                    shard_data,
-                    &mut challenger.clone(),
-                )
+            .map(|data| {
+                let shard_data = data
+                    .materialize()
+                    .map_err(SP1CoreProverError::SerializationError)?;
+                Ok(prove_shard(shard_data))
             })
-            .collect::<Vec<_>>();
-        shard_proofs.append(&mut checkpoint_proofs);
-    }
+            .collect::<Result<Vec<_>, SP1CoreProverError>>()?
+    };
     let proof = MachineProof::<SC> { shard_proofs };
 
     // Print the summary.
@@ -237,7 +298,7 @@ pub fn run_test_io(
 ) -> Result<SP1PublicValues, crate::stark::MachineVerificationError<BabyBearPoseidon2>> {
     let runtime = tracing::info_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Runtime::new(program, SP1CoreOpts::default());
-        runtime.write_vecs(&inputs.buffer);
+        runtime.write_inputs(&inputs);
         runtime.run().unwrap();
         runtime
     });
@@ -326,15 +387,41 @@ where
     Ok(proof)
 }
 
-fn trace_checkpoint(program: Program, file: &File, opts: SP1CoreOpts) -> ExecutionRecord {
+fn trace_checkpoint(
+    program: Program,
+    file: &File,
+    files: &BTreeMap<String, Vec<u8>>,
+    opts: SP1CoreOpts,
+) -> ExecutionRecord {
     let mut reader = std::io::BufReader::new(file);
     let state = bincode::deserialize_from(&mut reader).expect("failed to deserialize state");
     let mut runtime = Runtime::recover(program.clone(), state, opts);
+    // The files aren't part of the checkpointed state, so they're written again.
+    runtime.write_files(files);
     let (events, _) =
         tracing::debug_span!("runtime.trace").in_scope(|| runtime.execute_record().unwrap());
     events
 }
 
+/// Moves the events of the precompile shards of a checkpoint into `deferred`, and returns the CPU
+/// shards. `last_public_values` is set to the public values of the last CPU shard.
+fn defer_precompile_shards(
+    shards: Vec<ExecutionRecord>,
+    deferred: &mut ExecutionRecord,
+    last_public_values: &mut PublicValues<u32, u32>,
+) -> Vec<ExecutionRecord> {
+    let (cpu_shards, precompile_shards): (Vec<_>, Vec<_>) = shards
+        .into_iter()
+        .partition(|shard| !shard.is_precompile_shard());
+    for mut shard in precompile_shards {
+        deferred.append(&mut shard);
+    }
+    if let Some(last) = cpu_shards.last() {
+        *last_public_values = last.public_values;
+    }
+    cpu_shards
+}
+
 fn reset_seek(file: &mut File) {
     file.seek(std::io::SeekFrom::Start(0))
         .expect("failed to seek to start of tempfile");
@@ -426,6 +513,7 @@ pub mod baby_bear_poseidon2 {
     use sp1_primitives::RC_16_30;
 
     use crate::stark::StarkGenericConfig;
+    use crate::utils::{FriParameters, SP1SecurityConfig};
 
     pub type Val = BabyBear;
     pub type Challenge = BinomialExtensionField<Val, 4>;
@@ -466,43 +554,23 @@ pub mod baby_bear_poseidon2 {
         )
     }
 
-    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+    fn challenge_mmcs() -> ChallengeMmcs {
         let perm = my_perm();
         let hash = MyHash::new(perm.clone());
         let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 100,
-        };
-        FriConfig {
-            log_blowup: 1,
-            num_queries,
-            proof_of_work_bits: 16,
-            mmcs: challenge_mmcs,
-        }
+        ChallengeMmcs::new(ValMmcs::new(hash, compress))
     }
 
-    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 33,
-        };
-        FriConfig {
-            log_blowup: 3,
-            num_queries,
-            proof_of_work_bits: 16,
-            mmcs: challenge_mmcs,
-        }
+    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+        SP1SecurityConfig::from_env()
+            .core
+            .fri_config(challenge_mmcs())
     }
 
-    enum BabyBearPoseidon2Type {
-        Default,
-        Compressed,
+    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
+        SP1SecurityConfig::from_env()
+            .shrink
+            .fri_config(challenge_mmcs())
     }
 
     #[derive(Deserialize)]
@@ -510,47 +578,39 @@ pub mod baby_bear_poseidon2 {
     pub struct BabyBearPoseidon2 {
         pub perm: Perm,
         pcs: Pcs,
-        config_type: BabyBearPoseidon2Type,
+        fri_parameters: FriParameters,
     }
 
     impl BabyBearPoseidon2 {
+        /// The config of core and recursion proofs.
         pub fn new() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft {};
-            let fri_config = default_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self {
-                pcs,
-                perm,
-                config_type: BabyBearPoseidon2Type::Default,
-            }
+            Self::with_fri_parameters(SP1SecurityConfig::from_env().core)
         }
 
+        /// The config of the shrink proof, which has a larger blowup and fewer queries.
         pub fn compressed() -> Self {
+            Self::with_fri_parameters(SP1SecurityConfig::from_env().shrink)
+        }
+
+        pub fn with_fri_parameters(fri_parameters: FriParameters) -> Self {
             let perm = my_perm();
             let hash = MyHash::new(perm.clone());
             let compress = MyCompress::new(perm.clone());
             let val_mmcs = ValMmcs::new(hash, compress);
             let dft = Dft {};
-            let fri_config = compressed_fri_config();
+            let fri_config = fri_parameters.fri_config(challenge_mmcs());
             let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
             Self {
                 pcs,
                 perm,
-                config_type: BabyBearPoseidon2Type::Compressed,
+                fri_parameters,
             }
         }
     }
 
     impl Clone for BabyBearPoseidon2 {
         fn clone(&self) -> Self {
-            match self.config_type {
-                BabyBearPoseidon2Type::Default => Self::new(),
-                BabyBearPoseidon2Type::Compressed => Self::compressed(),
-            }
+            Self::with_fri_parameters(self.fri_parameters)
         }
     }
 
@@ -587,6 +647,10 @@ pub mod baby_bear_poseidon2 {
             &self.pcs
         }
 
+        fn fri_parameters(&self) -> FriParameters {
+            FriParameters::from(self.pcs.fri_config())
+        }
+
         fn challenger(&self) -> Self::Challenger {
             Challenger::new(self.perm.clone())
         }
@@ -600,13 +664,14 @@ pub(super) mod baby_bear_keccak {
     use p3_commit::ExtensionMmcs;
     use p3_dft::Radix2DitParallel;
     use p3_field::extension::BinomialExtensionField;
-    use p3_fri::{FriConfig, TwoAdicFriPcs};
+    use p3_fri::TwoAdicFriPcs;
     use p3_keccak::Keccak256Hash;
     use p3_merkle_tree::FieldMerkleTreeMmcs;
     use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
     use serde::{Deserialize, Serialize};
 
     use crate::stark::StarkGenericConfig;
+    use crate::utils::{FriParameters, SP1SecurityConfig};
 
     use super::LOG_DEGREE_BOUND;
 
@@ -663,12 +728,7 @@ pub(super) mod baby_bear_keccak {
 
             let dft = Dft {};
 
-            let fri_config = FriConfig {
-                log_blowup: 1,
-                num_queries: 100,
-                proof_of_work_bits: 16,
-                mmcs: challenge_mmcs,
-            };
+            let fri_config = SP1SecurityConfig::CORE.fri_config(challenge_mmcs);
             let pcs = Pcs::new(LOG_DEGREE_BOUND, dft, val_mmcs, fri_config);
 
             Self { pcs }
@@ -700,6 +760,10 @@ pub(super) mod baby_bear_keccak {
             &self.pcs
         }
 
+        fn fri_parameters(&self) -> FriParameters {
+            FriParameters::from(self.pcs.fri_config())
+        }
+
         fn challenger(&self) -> Self::Challenger {
             let byte_hash = ByteHash {};
             Challenger::from_hasher(vec![], byte_hash)
@@ -715,12 +779,13 @@ pub(super) mod baby_bear_blake3 {
     use p3_commit::ExtensionMmcs;
     use p3_dft::Radix2DitParallel;
     use p3_field::extension::BinomialExtensionField;
-    use p3_fri::{FriConfig, TwoAdicFriPcs};
+    use p3_fri::TwoAdicFriPcs;
     use p3_merkle_tree::FieldMerkleTreeMmcs;
     use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
     use serde::{Deserialize, Serialize};
 
     use crate::stark::StarkGenericConfig;
+    use crate::utils::{FriParameters, SP1SecurityConfig};
 
     use super::LOG_DEGREE_BOUND;
 
@@ -783,16 +848,9 @@ pub(super) mod baby_bear_blake3 {
 
             let dft = Dft {};
 
-            let num_queries = match std::env::var("FRI_QUERIES") {
-                Ok(value) => value.parse().unwrap(),
-                Err(_) => 100,
-            };
-            let fri_config = FriConfig {
-                log_blowup: 1,
-                num_queries,
-                proof_of_work_bits: 16,
-                mmcs: challenge_mmcs,
-            };
+            let fri_config = SP1SecurityConfig::from_env()
+                .core
+                .fri_config(challenge_mmcs);
             let pcs = Pcs::new(LOG_DEGREE_BOUND, dft, val_mmcs, fri_config);
 
             Self { pcs }
@@ -818,6 +876,10 @@ pub(super) mod baby_bear_blake3 {
             &self.pcs
         }
 
+        fn fri_parameters(&self) -> FriParameters {
+            FriParameters::from(self.pcs.fri_config())
+        }
+
         fn challenger(&self) -> Self::Challenger {
             let byte_hash = ByteHash {};
             Challenger::from_hasher(vec![], byte_hash)
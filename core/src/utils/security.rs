@@ -0,0 +1,178 @@
+use std::sync::Once;
+
+use p3_fri::FriConfig;
+use serde::{Deserialize, Serialize};
+
+/// The parameters of the FRI low-degree test used to commit to traces.
+///
+/// The conjectured security of a proof is `log_blowup * num_queries + proof_of_work_bits` bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FriParameters {
+    /// The log2 of the blowup factor of the low-degree extension.
+    pub log_blowup: usize,
+    /// The number of FRI queries.
+    pub num_queries: usize,
+    /// The number of bits of proof of work ground before sampling the queries.
+    pub proof_of_work_bits: usize,
+}
+
+impl FriParameters {
+    pub const fn new(log_blowup: usize, num_queries: usize, proof_of_work_bits: usize) -> Self {
+        Self {
+            log_blowup,
+            num_queries,
+            proof_of_work_bits,
+        }
+    }
+
+    /// The parameters with the fewest queries that reach the given bits of conjectured security.
+    pub const fn with_security_bits(
+        security_bits: usize,
+        log_blowup: usize,
+        proof_of_work_bits: usize,
+    ) -> Self {
+        let query_bits = security_bits.saturating_sub(proof_of_work_bits);
+        Self::new(
+            log_blowup,
+            query_bits.div_ceil(log_blowup),
+            proof_of_work_bits,
+        )
+    }
+
+    /// The conjectured bits of security of the parameters.
+    pub const fn conjectured_security_bits(&self) -> usize {
+        self.log_blowup * self.num_queries + self.proof_of_work_bits
+    }
+
+    /// Builds a FRI config with these parameters.
+    pub fn fri_config<M>(&self, mmcs: M) -> FriConfig<M> {
+        FriConfig {
+            log_blowup: self.log_blowup,
+            num_queries: self.num_queries,
+            proof_of_work_bits: self.proof_of_work_bits,
+            mmcs,
+        }
+    }
+}
+
+impl<M> From<&FriConfig<M>> for FriParameters {
+    fn from(config: &FriConfig<M>) -> Self {
+        Self::new(
+            config.log_blowup,
+            config.num_queries,
+            config.proof_of_work_bits,
+        )
+    }
+}
+
+/// The FRI parameters of each stage of the SP1 prover.
+///
+/// The recursion programs of a stage verify proofs of the previous stage, so they are built with
+/// the parameters of that stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SP1SecurityConfig {
+    /// The parameters of the core proofs of RISC-V execution.
+    pub core: FriParameters,
+    /// The parameters of the recursion and reduction proofs.
+    pub compress: FriParameters,
+    /// The parameters of the shrink proof.
+    pub shrink: FriParameters,
+    /// The parameters of the wrap proof, which is verified in the SNARK.
+    pub wrap: FriParameters,
+}
+
+impl SP1SecurityConfig {
+    pub const CORE: FriParameters = FriParameters::new(1, 100, 16);
+    pub const COMPRESS: FriParameters = FriParameters::new(1, 100, 16);
+    pub const SHRINK: FriParameters = FriParameters::new(3, 33, 16);
+    pub const WRAP: FriParameters = FriParameters::new(4, 25, 16);
+
+    /// Parameters reaching the given bits of conjectured security at every stage, with the
+    /// default blowup and proof of work bits of each stage.
+    pub const fn with_security_bits(security_bits: usize) -> Self {
+        const fn stage(security_bits: usize, params: FriParameters) -> FriParameters {
+            FriParameters::with_security_bits(
+                security_bits,
+                params.log_blowup,
+                params.proof_of_work_bits,
+            )
+        }
+        Self {
+            core: stage(security_bits, Self::CORE),
+            compress: stage(security_bits, Self::COMPRESS),
+            shrink: stage(security_bits, Self::SHRINK),
+            wrap: stage(security_bits, Self::WRAP),
+        }
+    }
+
+    /// The default parameters, with the number of queries of every stage overridden by the
+    /// `FRI_QUERIES` environment variable if it is set.
+    ///
+    /// The override lowers the security of all proofs, and is only meant for development.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(value) = std::env::var("FRI_QUERIES") {
+            let num_queries = value.parse().expect("FRI_QUERIES must be an integer");
+            for params in config.stages_mut() {
+                params.num_queries = num_queries;
+            }
+            static WARN: Once = Once::new();
+            WARN.call_once(|| {
+                tracing::warn!(
+                    "FRI_QUERIES={} overrides the FRI parameters of every stage, proofs have at most {} bits of conjectured security",
+                    num_queries,
+                    config.conjectured_security_bits()
+                )
+            });
+        }
+        config
+    }
+
+    /// The conjectured bits of security of the weakest stage.
+    pub fn conjectured_security_bits(&self) -> usize {
+        [self.core, self.compress, self.shrink, self.wrap]
+            .iter()
+            .map(FriParameters::conjectured_security_bits)
+            .min()
+            .unwrap()
+    }
+
+    fn stages_mut(&mut self) -> [&mut FriParameters; 4] {
+        [
+            &mut self.core,
+            &mut self.compress,
+            &mut self.shrink,
+            &mut self.wrap,
+        ]
+    }
+}
+
+impl Default for SP1SecurityConfig {
+    fn default() -> Self {
+        Self {
+            core: Self::CORE,
+            compress: Self::COMPRESS,
+            shrink: Self::SHRINK,
+            wrap: Self::WRAP,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_security_bits() {
+        let config = SP1SecurityConfig::default();
+        assert_eq!(config.core.conjectured_security_bits(), 116);
+        assert_eq!(config.shrink.conjectured_security_bits(), 115);
+        assert_eq!(config.conjectured_security_bits(), 115);
+
+        let config = SP1SecurityConfig::with_security_bits(100);
+        assert_eq!(config.core, FriParameters::new(1, 84, 16));
+        assert_eq!(config.shrink, FriParameters::new(3, 28, 16));
+        assert_eq!(config.wrap, FriParameters::new(4, 21, 16));
+        assert!(config.conjectured_security_bits() >= 100);
+    }
+}
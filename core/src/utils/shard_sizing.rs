@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use p3_air::BaseAir;
+use p3_field::PrimeField32;
+use thiserror::Error;
+
+use crate::air::MachineAir;
+use crate::bytes::trace::NUM_ROWS as BYTE_NUM_ROWS;
+use crate::io::SP1Stdin;
+use crate::runtime::{
+    ExecutionError, Program, Runtime, KECCAK_ROWS_PER_EVENT, SHA_COMPRESS_ROWS_PER_EVENT,
+    SHA_EXTEND_ROWS_PER_EVENT,
+};
+use crate::stark::{MachineRecord, RiscvAir};
+use crate::utils::SP1CoreOpts;
+
+/// The number of cycles executed to sample the mix of events of a program.
+const SAMPLE_CYCLES: usize = 1 << 16;
+
+/// The range of shard sizes that are considered, as powers of two.
+const MIN_LOG_SHARD_SIZE: usize = 16;
+const MAX_LOG_SHARD_SIZE: usize = 22;
+
+/// A rough estimate of the memory used per cell of the main trace while proving a shard. Cells are
+/// 4 byte field elements, the trace is kept alongside its low-degree extension, and the permutation
+/// and quotient traces take about as much memory again.
+const BYTES_PER_TRACE_CELL: usize = 4 * 3 * 2;
+
+/// The memory and cores available to the prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProverResources {
+    /// The target peak memory usage, in bytes.
+    pub memory_bytes: usize,
+    /// The number of cores available for proving.
+    pub num_cores: usize,
+}
+
+impl ProverResources {
+    /// Reads the resources from the `SP1_PROVER_MEMORY_GB` and `SP1_PROVER_CORES` environment
+    /// variables. Returns `None` if no memory budget is set, or with a warning if a variable is
+    /// invalid. The number of cores defaults to the number of cpus of the machine.
+    pub fn from_env() -> Option<Self> {
+        match Self::try_from_env() {
+            Ok(resources) => resources,
+            Err(e) => {
+                tracing::warn!("ignoring the prover resources: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Reads the resources like [ProverResources::from_env], but fails if an environment variable
+    /// is invalid instead of ignoring the resources.
+    pub fn try_from_env() -> Result<Option<Self>, EnvVarError> {
+        let Ok(memory_gb) = std::env::var("SP1_PROVER_MEMORY_GB") else {
+            return Ok(None);
+        };
+        let memory_gb = memory_gb
+            .parse::<f64>()
+            .ok()
+            .filter(|memory_gb| *memory_gb > 0.0)
+            .ok_or_else(|| {
+                EnvVarError::new("SP1_PROVER_MEMORY_GB", memory_gb, "a positive number")
+            })?;
+        let num_cores = match std::env::var("SP1_PROVER_CORES") {
+            Ok(cores) => cores
+                .parse()
+                .ok()
+                .filter(|cores| *cores > 0)
+                .ok_or_else(|| EnvVarError::new("SP1_PROVER_CORES", cores, "a positive integer"))?,
+            Err(_) => num_cpus::get(),
+        };
+        Ok(Some(Self {
+            memory_bytes: (memory_gb * (1u64 << 30) as f64) as usize,
+            num_cores,
+        }))
+    }
+}
+
+/// An environment variable with an invalid value.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{name} must be {expected}, got {value:?}")]
+pub struct EnvVarError {
+    pub name: &'static str,
+    pub value: String,
+    pub expected: &'static str,
+}
+
+impl EnvVarError {
+    fn new(name: &'static str, value: String, expected: &'static str) -> Self {
+        Self {
+            name,
+            value,
+            expected,
+        }
+    }
+}
+
+/// Shard sizes chosen to fit the resources of the prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardSizing {
+    pub shard_size: usize,
+    pub shard_batch_size: usize,
+    /// The estimated number of main trace cells per cycle of the program.
+    pub cells_per_cycle: usize,
+    /// The estimated memory used to prove a single shard, in bytes.
+    pub shard_memory_bytes: usize,
+    /// The resources the sizes were chosen for.
+    pub resources: ProverResources,
+}
+
+impl ShardSizing {
+    /// Chooses shard sizes from the event counts of a sample of a program's execution, as returned
+    /// by [MachineRecord::stats].
+    ///
+    /// The shard size is the largest one such that a shard fits in the memory budget, and the shard
+    /// batch size is the number of such shards that fit in the budget, capped by the number of
+    /// cores.
+    pub fn from_stats<F: PrimeField32>(
+        stats: &HashMap<String, usize>,
+        resources: ProverResources,
+    ) -> Self {
+        let cycles = stats.get("cpu_events").copied().unwrap_or(0).max(1);
+
+        // Estimate the trace area of each chip that grows with the number of cycles, and the area
+        // of the chips with a fixed size.
+        let mut cycle_cells = 0;
+        let mut fixed_cells = 0;
+        for air in RiscvAir::<F>::get_all() {
+            let width = air.width() + air.preprocessed_width();
+            match event_rows(&air) {
+                Some((events, rows_per_event)) => {
+                    let num_events = events
+                        .iter()
+                        .map(|name| stats.get(*name).copied().unwrap_or(0))
+                        .sum::<usize>();
+                    cycle_cells += num_events * rows_per_event * width;
+                }
+                None => {
+                    if let RiscvAir::ByteLookup(_) = air {
+                        fixed_cells += BYTE_NUM_ROWS * width;
+                    }
+                }
+            }
+        }
+        let cells_per_cycle = cycle_cells.div_ceil(cycles);
+
+        let shard_memory =
+            |shard_size: usize| (cells_per_cycle * shard_size + fixed_cells) * BYTES_PER_TRACE_CELL;
+        let log_shard_size = (MIN_LOG_SHARD_SIZE..=MAX_LOG_SHARD_SIZE)
+            .rev()
+            .find(|log_size| shard_memory(1 << log_size) <= resources.memory_bytes)
+            .unwrap_or(MIN_LOG_SHARD_SIZE);
+        let shard_size = 1 << log_shard_size;
+        let shard_memory_bytes = shard_memory(shard_size);
+        let shard_batch_size = (resources.memory_bytes / shard_memory_bytes)
+            .min(resources.num_cores)
+            .max(1);
+
+        Self {
+            shard_size,
+            shard_batch_size,
+            cells_per_cycle,
+            shard_memory_bytes,
+            resources,
+        }
+    }
+
+    /// Executes the first cycles of a program to sample its mix of events, and chooses shard
+    /// sizes for it.
+    pub fn sample<F: PrimeField32>(
+        program: Program,
+        stdin: &SP1Stdin,
+        resources: ProverResources,
+    ) -> Result<Self, ExecutionError> {
+        let opts = SP1CoreOpts {
+            shard_size: SAMPLE_CYCLES,
+            shard_batch_size: 1,
+            resources: None,
+            ..SP1CoreOpts::default()
+        };
+        let mut runtime = Runtime::new(program, opts);
+        runtime.write_inputs(stdin);
+        let (record, _) = runtime.execute_record()?;
+        Ok(Self::from_stats::<F>(&record.stats(), resources))
+    }
+}
+
+impl Display for ShardSizing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "shard_size={}, shard_batch_size={}, cells_per_cycle={}, shard_memory={:.2}GB, budget={:.2}GB, cores={}",
+            self.shard_size,
+            self.shard_batch_size,
+            self.cells_per_cycle,
+            self.shard_memory_bytes as f64 / (1u64 << 30) as f64,
+            self.resources.memory_bytes as f64 / (1u64 << 30) as f64,
+            self.resources.num_cores,
+        )
+    }
+}
+
+/// The events, as named in [MachineRecord::stats], that add rows to a chip, and the number of rows
+/// added per event. Returns `None` for chips whose size doesn't depend on the number of cycles.
+fn event_rows<F: PrimeField32>(air: &RiscvAir<F>) -> Option<(&'static [&'static str], usize)> {
+    let events: (&'static [&'static str], usize) = match air {
+        RiscvAir::Cpu(_) => (&["cpu_events"], 1),
+        RiscvAir::Add(_) => (&["add_events", "sub_events"], 1),
+        RiscvAir::Bitwise(_) => (&["bitwise_events"], 1),
+        RiscvAir::Mul(_) => (&["mul_events"], 1),
+        RiscvAir::DivRem(_) => (&["divrem_events"], 1),
+        RiscvAir::Lt(_) => (&["lt_events"], 1),
+        RiscvAir::ShiftLeft(_) => (&["shift_left_events"], 1),
+        RiscvAir::ShiftRight(_) => (&["shift_right_events"], 1),
+        RiscvAir::Sha256Extend(_) => (&["sha_extend_events"], SHA_EXTEND_ROWS_PER_EVENT),
+        RiscvAir::Sha256Compress(_) => (&["sha_compress_events"], SHA_COMPRESS_ROWS_PER_EVENT),
+        RiscvAir::KeccakP(_) => (&["keccak_permute_events"], KECCAK_ROWS_PER_EVENT),
+        RiscvAir::Ed25519Add(_) => (&["ed_add_events"], 1),
+        RiscvAir::Ed25519Decompress(_) => (&["ed_decompress_events"], 1),
+        RiscvAir::K256Decompress(_) => (&["k256_decompress_events"], 1),
+        RiscvAir::Secp256k1Add(_) => (&["secp256k1_add_events"], 1),
+        RiscvAir::Secp256k1Double(_) => (&["secp256k1_double_events"], 1),
+        RiscvAir::Bn254Add(_) => (&["bn254_add_events"], 1),
+        RiscvAir::Bn254Double(_) => (&["bn254_double_events"], 1),
+        RiscvAir::Bls12381Add(_) => (&["bls12381_add_events"], 1),
+        RiscvAir::Bls12381Double(_) => (&["bls12381_double_events"], 1),
+        RiscvAir::Bls12381Decompress(_) => (&["bls12381_decompress_events"], 1),
+        RiscvAir::Uint256Mul(_) => (&["uint256_mul_events"], 1),
+        RiscvAir::Uint256AddMod(_) => (&["uint256_addmod_events"], 1),
+        RiscvAir::Uint256SubMod(_) => (&["uint256_submod_events"], 1),
+        RiscvAir::Uint512MulMod(_) => (&["uint512_mulmod_events"], 1),
+        RiscvAir::Uint1024MulMod(_) => (&["uint1024_mulmod_events"], 1),
+        RiscvAir::Uint2048MulMod(_) => (&["uint2048_mulmod_events"], 1),
+        RiscvAir::Uint4096MulMod(_) => (&["uint4096_mulmod_events"], 1),
+        RiscvAir::MemCopy(_) => (&["memcpy_events"], 1),
+        RiscvAir::MemSet(_) => (&["memset_events"], 1),
+        _ => return None,
+    };
+    Some(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    #[test]
+    fn test_shard_sizing() {
+        let stats = [
+            ("cpu_events", 1000),
+            ("add_events", 500),
+            ("lt_events", 100),
+        ]
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect::<HashMap<_, _>>();
+
+        let large = ProverResources {
+            memory_bytes: 1 << 40,
+            num_cores: 8,
+        };
+        let sizing = ShardSizing::from_stats::<BabyBear>(&stats, large);
+        assert_eq!(sizing.shard_size, 1 << MAX_LOG_SHARD_SIZE);
+        assert_eq!(sizing.shard_batch_size, 8);
+
+        // A smaller budget gives smaller shards, and never less than one shard per batch.
+        let small = ProverResources {
+            memory_bytes: sizing.shard_memory_bytes / 4,
+            num_cores: 8,
+        };
+        let sizing = ShardSizing::from_stats::<BabyBear>(&stats, small);
+        assert!(sizing.shard_size < 1 << MAX_LOG_SHARD_SIZE);
+        assert!(sizing.shard_memory_bytes <= small.memory_bytes);
+        assert!(sizing.shard_batch_size >= 1);
+    }
+}
@@ -32,6 +32,7 @@ use syn::Data;
 use syn::DeriveInput;
 use syn::GenericParam;
 use syn::ItemFn;
+use syn::ItemMod;
 
 #[proc_macro_derive(AlignedBorrow)]
 pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
@@ -172,6 +173,13 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let generate_trace_into_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as #sp1_core_path::air::MachineAir<F>>::generate_trace_into(x, input, output, buffer)
+                }
+            });
+
             let generate_dependencies_arms = variants.iter().map(|(variant_name, field)| {
                 let field_ty = &field.ty;
                 quote! {
@@ -223,6 +231,17 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                         }
                     }
 
+                    fn generate_trace_into(
+                        &self,
+                        input: &#execution_record_path,
+                        output: &mut #execution_record_path,
+                        buffer: Vec<F>,
+                    ) -> p3_matrix::dense::RowMajorMatrix<F> {
+                        match self {
+                            #(#generate_trace_into_arms,)*
+                        }
+                    }
+
                     fn generate_dependencies(
                         &self,
                         input: &#execution_record_path,
@@ -303,6 +322,110 @@ pub fn cycle_tracker(_attr: TokenStream, item: TokenStream) -> TokenStream {
     result.into()
 }
 
+/// Turns the `#[test]` functions of a module into tests that run inside the zkVM, with
+/// `cargo prove test`.
+///
+/// The module gets a `run` function to pass to `sp1_zkvm::entrypoint!`, which reads the name of a
+/// test from stdin and runs it. If no name is given, it commits the list of tests to the public
+/// values instead. Tests can be marked with `#[should_panic]`, optionally with an expected
+/// message, as in `#[should_panic = "message"]` or `#[should_panic(expected = "message")]`.
+///
+/// ```ignore
+/// #![no_main]
+/// sp1_zkvm::entrypoint!(tests::run);
+///
+/// #[sp1_derive::tests]
+/// mod tests {
+///     #[test]
+///     fn adds() {
+///         assert_eq!(1 + 1, 2);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn tests(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let module = parse_macro_input!(item as ItemMod);
+    expand_tests(module).into()
+}
+
+fn expand_tests(mut module: ItemMod) -> proc_macro2::TokenStream {
+    let Some((_, items)) = module.content.as_mut() else {
+        return syn::Error::new_spanned(&module, "expected a module with a body")
+            .to_compile_error();
+    };
+
+    let mut tests = Vec::new();
+    for item in items.iter_mut() {
+        let syn::Item::Fn(function) = item else {
+            continue;
+        };
+        let is_test = function.attrs.iter().any(|attr| attr.path.is_ident("test"));
+        if !is_test {
+            continue;
+        }
+        let mut should_panic = false;
+        let mut expected = None;
+        for attr in function.attrs.iter() {
+            if attr.path.is_ident("should_panic") {
+                should_panic = true;
+                expected = should_panic_expected(attr);
+            }
+        }
+        // The attributes are removed, as `#[test]` functions are not compiled into the program.
+        function
+            .attrs
+            .retain(|attr| !attr.path.is_ident("test") && !attr.path.is_ident("should_panic"));
+        tests.push((function.sig.ident.clone(), should_panic, expected));
+    }
+
+    let names = tests.iter().map(|(name, _, _)| name.to_string());
+    let functions = tests.iter().map(|(name, _, _)| name);
+    let cases = tests.iter().map(|(name, should_panic, expected)| {
+        let expected = match expected {
+            Some(expected) => quote!(Some(#expected.to_string())),
+            None => quote!(None),
+        };
+        quote!((stringify!(#name).to_string(), #should_panic, #expected))
+    });
+    items.push(parse_quote! {
+        /// Runs the test named by the next input, or commits the list of tests if there is none.
+        pub fn run() {
+            let tests: &[(&str, fn())] = &[#((#names, #functions)),*];
+            match ::sp1_zkvm::io::read::<Option<String>>() {
+                Some(name) => match tests.iter().find(|(test, _)| *test == name) {
+                    Some((_, test)) => test(),
+                    None => panic!("no test named {}", name),
+                },
+                None => {
+                    let cases: Vec<(String, bool, Option<String>)> = vec![#(#cases),*];
+                    ::sp1_zkvm::io::commit(&cases);
+                }
+            }
+        }
+    });
+
+    quote!(#module)
+}
+
+/// The expected panic message of a `#[should_panic]` attribute, given either as
+/// `#[should_panic = "message"]` or `#[should_panic(expected = "message")]`.
+fn should_panic_expected(attr: &syn::Attribute) -> Option<String> {
+    let lit = match attr.parse_meta().ok()? {
+        syn::Meta::NameValue(meta) => meta.lit,
+        syn::Meta::List(list) => list.nested.into_iter().find_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) if meta.path.is_ident("expected") => {
+                Some(meta.lit)
+            }
+            _ => None,
+        })?,
+        syn::Meta::Path(_) => return None,
+    };
+    match lit {
+        syn::Lit::Str(lit_str) => Some(lit_str.value()),
+        _ => None,
+    }
+}
+
 fn find_sp1_core_path(attrs: &[syn::Attribute]) -> syn::Ident {
     for attr in attrs {
         if attr.path.is_ident("sp1_core_path") {
@@ -360,3 +483,80 @@ fn find_builder_path(attrs: &[syn::Attribute]) -> syn::Path {
     }
     parse_quote!(crate::air::SP1AirBuilder<F = F>)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_tests() {
+        let module: ItemMod = parse_quote! {
+            mod tests {
+                fn helper() {}
+
+                #[test]
+                fn passes() {}
+
+                #[test]
+                #[should_panic]
+                fn panics() {}
+
+                #[test]
+                #[should_panic = "overflow"]
+                fn overflows() {}
+
+                #[test]
+                #[should_panic(expected = "bounds")]
+                fn out_of_bounds() {}
+            }
+        };
+        let expanded: ItemMod = syn::parse2(expand_tests(module)).unwrap();
+        let (_, items) = expanded.content.unwrap();
+
+        // The test attributes are removed, and a `run` function is added.
+        let functions = items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(function) => Some(function),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(functions.len(), 6);
+        assert!(functions
+            .iter()
+            .all(|function| function.attrs.iter().all(|attr| {
+                !attr.path.is_ident("test") && !attr.path.is_ident("should_panic")
+            })));
+        let run = functions.last().unwrap();
+        assert_eq!(run.sig.ident, "run");
+
+        // The tests are listed in order, with whether they should panic and the expected message.
+        let run = quote!(#run).to_string();
+        let cases = [
+            quote!((stringify!(passes).to_string(), false, None)),
+            quote!((stringify!(panics).to_string(), true, None)),
+            quote!((
+                stringify!(overflows).to_string(),
+                true,
+                Some("overflow".to_string())
+            )),
+            quote!((
+                stringify!(out_of_bounds).to_string(),
+                true,
+                Some("bounds".to_string())
+            )),
+        ];
+        let cases = quote!(vec![#(#cases),*]).to_string();
+        assert!(run.contains(&cases), "{} does not contain {}", run, cases);
+        assert!(!run.contains("helper"));
+    }
+
+    #[test]
+    fn test_expand_tests_without_body() {
+        let module: ItemMod = parse_quote!(
+            mod tests;
+        );
+        let expanded = expand_tests(module).to_string();
+        assert!(expanded.contains("expected a module with a body"));
+    }
+}
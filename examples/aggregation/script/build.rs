@@ -1,5 +1,8 @@
 fn main() {
-    sp1_helper::build_program(&format!("{}/../program", env!("CARGO_MANIFEST_DIR")));
+    sp1_helper::build_program(&format!(
+        "{}/../../../sdk/programs/aggregation",
+        env!("CARGO_MANIFEST_DIR")
+    ));
     sp1_helper::build_program(&format!(
         "{}/../../fibonacci/program",
         env!("CARGO_MANIFEST_DIR")
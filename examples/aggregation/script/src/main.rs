@@ -1,22 +1,12 @@
 //! A simple example showing how to aggregate proofs of multiple programs with SP1.
 
-use sp1_sdk::{HashableKey, ProverClient, SP1CompressedProof, SP1Stdin, SP1VerifyingKey};
-
-/// A program that aggregates the proofs of the simple program.
-const AGGREGATION_ELF: &[u8] = include_bytes!("../../program/elf/riscv32im-succinct-zkvm-elf");
+use sp1_sdk::aggregation::{aggregation_inclusion_proof, verify_aggregation_inclusion};
+use sp1_sdk::{AggregationCommitment, ProverClient, SP1Stdin};
 
 /// A program that just runs a simple computation.
 const FIBONACCI_ELF: &[u8] =
     include_bytes!("../../../fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
 
-/// An input to the aggregation program.
-///
-/// Consists of a proof and a verification key.
-struct AggregationInput {
-    pub proof: SP1CompressedProof,
-    pub vk: SP1VerifyingKey,
-}
-
 fn main() {
     // Setup the logger.
     sp1_sdk::utils::setup_logger();
@@ -25,76 +15,49 @@ fn main() {
     let client = ProverClient::new();
 
     // Setup the proving and verifying keys.
-    let (aggregation_pk, _) = client.setup(AGGREGATION_ELF);
     let (fibonacci_pk, fibonacci_vk) = client.setup(FIBONACCI_ELF);
 
     // Generate the fibonacci proofs.
-    let proof_1 = tracing::info_span!("generate fibonacci proof n=10").in_scope(|| {
-        let mut stdin = SP1Stdin::new();
-        stdin.write(&10);
-        client
-            .prove_compressed(&fibonacci_pk, stdin)
-            .expect("proving failed")
-    });
-    let proof_2 = tracing::info_span!("generate fibonacci proof n=20").in_scope(|| {
-        let mut stdin = SP1Stdin::new();
-        stdin.write(&20);
-        client
-            .prove_compressed(&fibonacci_pk, stdin)
-            .expect("proving failed")
-    });
-    let proof_3 = tracing::info_span!("generate fibonacci proof n=30").in_scope(|| {
-        let mut stdin = SP1Stdin::new();
-        stdin.write(&30);
+    let proofs = [10, 20, 30]
+        .into_iter()
+        .map(|n| {
+            tracing::info_span!("generate fibonacci proof", n).in_scope(|| {
+                let mut stdin = SP1Stdin::new();
+                stdin.write(&n);
+                client
+                    .prove_compressed(&fibonacci_pk, stdin)
+                    .expect("proving failed")
+            })
+        })
+        .collect::<Vec<_>>();
+    let vks = vec![fibonacci_vk; proofs.len()];
+    // The fibonacci program commits to the SHA-256 hash of its public values, not a Merkle root.
+    let merkle_hashes = vec![None; proofs.len()];
+    let public_values = proofs
+        .iter()
+        .map(|proof| proof.public_values.clone())
+        .collect::<Vec<_>>();
+
+    // Aggregate the proofs, committing to them with a Merkle root.
+    let commitment = AggregationCommitment::MerkleRoot;
+    let proof = tracing::info_span!("aggregate the proofs").in_scope(|| {
         client
-            .prove_compressed(&fibonacci_pk, stdin)
+            .aggregate(proofs, &vks, &merkle_hashes, commitment)
             .expect("proving failed")
     });
 
-    // Setup the inputs to the aggregation program.
-    let input_1 = AggregationInput {
-        proof: proof_1,
-        vk: fibonacci_vk.clone(),
-    };
-    let input_2 = AggregationInput {
-        proof: proof_2,
-        vk: fibonacci_vk.clone(),
-    };
-    let input_3 = AggregationInput {
-        proof: proof_3,
-        vk: fibonacci_vk.clone(),
-    };
-    let inputs = vec![input_1, input_2, input_3];
-
-    // Aggregate the proofs.
-    tracing::info_span!("aggregate the proofs").in_scope(|| {
-        let mut stdin = SP1Stdin::new();
-
-        // Write the verification keys.
-        let vkeys = inputs
-            .iter()
-            .map(|input| input.vk.hash_u32())
-            .collect::<Vec<_>>();
-        stdin.write::<Vec<[u32; 8]>>(&vkeys);
-
-        // Write the public values.
-        let public_values = inputs
-            .iter()
-            .map(|input| input.proof.public_values.to_vec())
-            .collect::<Vec<_>>();
-        stdin.write::<Vec<Vec<u8>>>(&public_values);
-
-        // Write the proofs.
-        //
-        // Note: this data will not actually be read by the aggregation program, instead it will be
-        // witnessed by the prover during the recursive aggregation process inside SP1 itself.
-        for input in inputs {
-            stdin.write_proof(input.proof.proof, input.vk.vk);
-        }
-
-        // Generate the plonk bn254 proof.
-        client
-            .prove_plonk(&aggregation_pk, stdin)
-            .expect("proving failed");
-    });
+    // Verify the aggregated proof.
+    client
+        .verify_aggregated(&proof, &vks, &public_values, commitment)
+        .expect("verification failed");
+
+    // Prove that the second proof is part of the aggregation, and check it against the root alone.
+    let inclusion_proof = aggregation_inclusion_proof(&vks, &public_values, 1);
+    verify_aggregation_inclusion(
+        proof.public_values.as_slice(),
+        &vks[1],
+        &public_values[1],
+        &inclusion_proof,
+    )
+    .expect("inclusion verification failed");
 }
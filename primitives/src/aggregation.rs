@@ -0,0 +1,102 @@
+//! The statement committed by a program that aggregates proofs of other programs.
+//!
+//! An aggregation program verifies a list of proofs, each given by the digest of its verifying key
+//! and its public values, and commits to the list with an [AggregationCommitment]. The encoding is
+//! shared by the program and the host, so that the host can check the public values of the
+//! aggregated proof, and prove that a single proof is part of the aggregation.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::merkle::MerkleHash;
+
+/// The hash of the Merkle tree of [AggregationCommitment::MerkleRoot].
+pub const AGGREGATION_MERKLE_HASH: MerkleHash = MerkleHash::Sha256;
+
+/// How the list of aggregated proofs is committed to the public values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AggregationCommitment {
+    /// The full list, encoded as
+    /// `( vkeys.len() || vkeys || public_values[0].len() || public_values[0] || ... )`, with
+    /// lengths as big endian u32s like `abi.encodePacked` does.
+    List,
+    /// The 32 byte root of a SHA-256 Merkle tree with one leaf per proof, see [crate::merkle] and
+    /// [aggregation_leaf].
+    MerkleRoot,
+}
+
+/// Converts a verifying key digest to bytes, as it's committed.
+pub fn vkey_digest_to_bytes(vkey: &[u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(vkey.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+/// Commits to the list of aggregated proofs with the given scheme.
+pub fn commit_aggregation(
+    commitment: AggregationCommitment,
+    vkeys: &[[u32; 8]],
+    public_values: &[Vec<u8>],
+) -> Vec<u8> {
+    assert_eq!(vkeys.len(), public_values.len());
+    match commitment {
+        AggregationCommitment::List => {
+            let mut res = Vec::with_capacity(
+                4 + vkeys.len() * 36 + public_values.iter().map(Vec::len).sum::<usize>(),
+            );
+            res.extend_from_slice(&(vkeys.len() as u32).to_be_bytes());
+            for vkey in vkeys.iter() {
+                res.extend_from_slice(&vkey_digest_to_bytes(vkey));
+            }
+            for values in public_values.iter() {
+                res.extend_from_slice(&(values.len() as u32).to_be_bytes());
+                res.extend_from_slice(values);
+            }
+            res
+        }
+        AggregationCommitment::MerkleRoot => {
+            let leaves = aggregation_leaves(vkeys, public_values);
+            AGGREGATION_MERKLE_HASH.root(&leaves).to_vec()
+        }
+    }
+}
+
+/// The leaf of a proof in the Merkle tree, the leaf of `vkey || sha256(public_values)`.
+pub fn aggregation_leaf(vkey: &[u32; 8], public_values: &[u8]) -> [u8; 32] {
+    let data = [
+        vkey_digest_to_bytes(vkey).as_slice(),
+        Sha256::digest(public_values).as_slice(),
+    ]
+    .concat();
+    AGGREGATION_MERKLE_HASH.leaf(&data)
+}
+
+/// The leaves of the Merkle tree of a list of aggregated proofs.
+pub fn aggregation_leaves(vkeys: &[[u32; 8]], public_values: &[Vec<u8>]) -> Vec<[u8; 32]> {
+    vkeys
+        .iter()
+        .zip(public_values.iter())
+        .map(|(vkey, values)| aggregation_leaf(vkey, values))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_aggregation() {
+        let vkeys = [[1u32; 8], [2u32; 8]];
+        let public_values = vec![vec![1, 2, 3], vec![]];
+
+        let list = commit_aggregation(AggregationCommitment::List, &vkeys, &public_values);
+        assert_eq!(list.len(), 4 + 2 * 32 + 4 + 3 + 4);
+        assert_eq!(&list[..4], &2u32.to_be_bytes());
+
+        let root = commit_aggregation(AggregationCommitment::MerkleRoot, &vkeys, &public_values);
+        let leaves = aggregation_leaves(&vkeys, &public_values);
+        assert_eq!(root, AGGREGATION_MERKLE_HASH.root(&leaves).to_vec());
+    }
+}
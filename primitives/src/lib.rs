@@ -1,6 +1,14 @@
 //! sp1-primitives contains types and functions that are used in both sp1-core and sp1-zkvm.
 //! Because it is imported in the zkvm entrypoint, it should be kept minimal.
 
+pub mod aggregation;
+pub mod merkle;
+
+/// The exit code a program halts with when its heap is exhausted.
+///
+/// Programs halt with a byte exit code, so this code can't be confused with one of theirs.
+pub const OUT_OF_MEMORY_EXIT_CODE: u32 = 0x100;
+
 use lazy_static::lazy_static;
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
 use p3_field::AbstractField;
@@ -0,0 +1,284 @@
+//! Binary Merkle trees over SHA-256 or Keccak-256, shared by programs and the host.
+//!
+//! A leaf is `H(0x00 || data)` and an inner node is `H(0x01 || left || right)`, so that a leaf
+//! can't be passed off as a node. The leaves are padded with zeros up to a power of two, so the
+//! path of a leaf is given by the bits of its index. Both are easy to recompute in Solidity, e.g.
+//! `keccak256(abi.encodePacked(bytes1(0x00), data))` for a leaf.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tiny_keccak::{Hasher, Keccak};
+
+/// The hash function of a Merkle tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MerkleHash {
+    Sha256,
+    Keccak256,
+}
+
+/// The prefix of the data hashed into a leaf.
+const LEAF_PREFIX: u8 = 0;
+
+/// The prefix of the data hashed into an inner node.
+const NODE_PREFIX: u8 = 1;
+
+/// The leaf the tree is padded with up to a power of two leaves.
+pub const EMPTY_LEAF: [u8; 32] = [0; 32];
+
+/// A proof that a leaf is part of a Merkle tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// The index of the leaf in the tree.
+    pub index: usize,
+    /// The siblings on the path from the leaf to the root, starting from the leaf.
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleHash {
+    /// Hashes the concatenation of the given slices.
+    fn hash(&self, inputs: &[&[u8]]) -> [u8; 32] {
+        match self {
+            MerkleHash::Sha256 => {
+                let mut hasher = Sha256::new();
+                for input in inputs {
+                    hasher.update(input);
+                }
+                hasher.finalize().into()
+            }
+            MerkleHash::Keccak256 => {
+                let mut hasher = Keccak::v256();
+                for input in inputs {
+                    hasher.update(input);
+                }
+                let mut output = [0u8; 32];
+                hasher.finalize(&mut output);
+                output
+            }
+        }
+    }
+
+    /// The leaf of the given data, `H(0x00 || data)`.
+    pub fn leaf(&self, data: &[u8]) -> [u8; 32] {
+        self.hash(&[&[LEAF_PREFIX], data])
+    }
+
+    /// The parent of two sibling nodes, `H(0x01 || left || right)`.
+    pub fn node(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        self.hash(&[&[NODE_PREFIX], left, right])
+    }
+
+    /// The levels of the tree, from the leaves padded to a power of two up to the root.
+    fn levels(&self, leaves: &[[u8; 32]]) -> Vec<Vec<[u8; 32]>> {
+        let mut level = leaves.to_vec();
+        level.resize(leaves.len().max(1).next_power_of_two(), EMPTY_LEAF);
+        let mut levels = vec![level];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| self.node(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+
+    /// The root of the tree of the given leaves.
+    pub fn root(&self, leaves: &[[u8; 32]]) -> [u8; 32] {
+        self.levels(leaves).last().unwrap()[0]
+    }
+
+    /// Proves that the leaf at `index` is part of the tree of the given leaves.
+    pub fn prove(&self, leaves: &[[u8; 32]], index: usize) -> MerkleProof {
+        assert!(index < leaves.len(), "leaf index out of bounds");
+        let levels = self.levels(leaves);
+        let siblings = levels[..levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| level[(index >> height) ^ 1])
+            .collect();
+        MerkleProof { index, siblings }
+    }
+
+    /// Checks that `leaf` is part of the tree with the given root.
+    pub fn verify(&self, root: &[u8; 32], leaf: &[u8; 32], proof: &MerkleProof) -> bool {
+        let depth = proof.siblings.len();
+        if depth < usize::BITS as usize && proof.index >> depth != 0 {
+            return false;
+        }
+        let computed = proof
+            .siblings
+            .iter()
+            .enumerate()
+            .fold(*leaf, |node, (height, sibling)| {
+                if (proof.index >> height) & 1 == 0 {
+                    self.node(&node, sibling)
+                } else {
+                    self.node(sibling, &node)
+                }
+            });
+        computed == *root
+    }
+}
+
+/// Splits public values committed with `sp1_zkvm::merkle` into the values of the leaves, each
+/// prefixed by its length as a little endian u32, or returns `None` if they are not such a list.
+pub fn split_leaves(public_values: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut leaves = Vec::new();
+    let mut rest = public_values;
+    while !rest.is_empty() {
+        if rest.len() < 4 {
+            return None;
+        }
+        let (len, tail) = rest.split_at(4);
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        if tail.len() < len {
+            return None;
+        }
+        let (leaf, tail) = tail.split_at(len);
+        leaves.push(leaf);
+        rest = tail;
+    }
+    Some(leaves)
+}
+
+/// The digest a proof commits to for the given public values: their SHA-256 hash, or with a
+/// `merkle_hash` the root of the tree of their leaves, as committed with `sp1_zkvm::merkle`.
+///
+/// Returns `None` if the public values are not a list of leaves when a `merkle_hash` is given.
+pub fn public_values_digest(
+    public_values: &[u8],
+    merkle_hash: Option<MerkleHash>,
+) -> Option<[u8; 32]> {
+    match merkle_hash {
+        None => Some(Sha256::digest(public_values).into()),
+        Some(hash) => {
+            let leaves = split_leaves(public_values)?
+                .into_iter()
+                .map(|leaf| hash.leaf(leaf))
+                .collect::<Vec<_>>();
+            Some(hash.root(&leaves))
+        }
+    }
+}
+
+/// A Merkle tree built one leaf at a time, keeping only the roots of its full subtrees.
+#[derive(Debug, Clone)]
+pub struct MerkleTreeBuilder {
+    hash: MerkleHash,
+    /// The root of the full subtree of `2^height` leaves at `height`, for each set bit of the
+    /// number of leaves.
+    subtrees: Vec<Option<[u8; 32]>>,
+    num_leaves: usize,
+}
+
+impl MerkleTreeBuilder {
+    pub fn new(hash: MerkleHash) -> Self {
+        Self {
+            hash,
+            subtrees: Vec::new(),
+            num_leaves: 0,
+        }
+    }
+
+    pub fn hash(&self) -> MerkleHash {
+        self.hash
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Appends the leaf of the given data.
+    pub fn push(&mut self, data: &[u8]) {
+        let mut node = self.hash.leaf(data);
+        let mut height = 0;
+        while let Some(Some(left)) = self.subtrees.get(height) {
+            node = self.hash.node(left, &node);
+            self.subtrees[height] = None;
+            height += 1;
+        }
+        if height == self.subtrees.len() {
+            self.subtrees.push(None);
+        }
+        self.subtrees[height] = Some(node);
+        self.num_leaves += 1;
+    }
+
+    /// The root of the tree of the leaves appended so far, padded like [MerkleHash::root] does.
+    pub fn root(&self) -> [u8; 32] {
+        if self.num_leaves == 0 {
+            return EMPTY_LEAF;
+        }
+        // Complete the tree from the smallest subtree up, hashing in subtrees of empty leaves.
+        let mut empty = EMPTY_LEAF;
+        let mut node: Option<[u8; 32]> = None;
+        let top = self.num_leaves.next_power_of_two().trailing_zeros() as usize;
+        for height in 0..top {
+            let subtree = self.subtrees.get(height).copied().flatten();
+            node = match (subtree, node) {
+                (Some(left), Some(right)) => Some(self.hash.node(&left, &right)),
+                (Some(left), None) => Some(self.hash.node(&left, &empty)),
+                (None, Some(left)) => Some(self.hash.node(&left, &empty)),
+                (None, None) => None,
+            };
+            empty = self.hash.node(&empty, &empty);
+        }
+        node.or_else(|| self.subtrees[top]).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_proofs() {
+        for hash in [MerkleHash::Sha256, MerkleHash::Keccak256] {
+            for num_leaves in 1..=9u8 {
+                let leaves = (0..num_leaves).map(|i| hash.leaf(&[i])).collect::<Vec<_>>();
+                let root = hash.root(&leaves);
+                for (index, leaf) in leaves.iter().enumerate() {
+                    let mut proof = hash.prove(&leaves, index);
+                    assert!(hash.verify(&root, leaf, &proof));
+                    assert!(!hash.verify(&root, &EMPTY_LEAF, &proof));
+                    proof.index += 1 << proof.siblings.len();
+                    assert!(!hash.verify(&root, leaf, &proof));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_merkle_tree_builder() {
+        for hash in [MerkleHash::Sha256, MerkleHash::Keccak256] {
+            let mut builder = MerkleTreeBuilder::new(hash);
+            let mut leaves = Vec::new();
+            assert_eq!(builder.root(), EMPTY_LEAF);
+            for i in 0..17u8 {
+                builder.push(&[i; 3]);
+                leaves.push(hash.leaf(&[i; 3]));
+                assert_eq!(builder.root(), hash.root(&leaves));
+            }
+        }
+    }
+
+    #[test]
+    fn test_public_values_digest() {
+        // Two leaves, as committed with `sp1_zkvm::merkle`.
+        let public_values = [1, 0, 0, 0, 7, 2, 0, 0, 0, 8, 9];
+        assert_eq!(
+            public_values_digest(&public_values, None),
+            Some(Sha256::digest(public_values).into())
+        );
+        for hash in [MerkleHash::Sha256, MerkleHash::Keccak256] {
+            let leaves = [hash.leaf(&[7]), hash.leaf(&[8, 9])];
+            assert_eq!(
+                public_values_digest(&public_values, Some(hash)),
+                Some(hash.root(&leaves))
+            );
+            assert_eq!(public_values_digest(&public_values[..10], Some(hash)), None);
+        }
+    }
+}
@@ -2,17 +2,30 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use sp1_core::utils::setup_logger;
-use sp1_prover::build::build_plonk_bn254_artifacts_with_dummy;
+use sp1_prover::build::{
+    build_plonk_bn254_artifacts_offline_with_dummy, build_plonk_bn254_artifacts_with_dummy,
+};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    #[clap(short, long)]
-    build_dir: PathBuf,
+    #[clap(short, long, required_unless_present = "srs")]
+    build_dir: Option<PathBuf>,
+
+    /// Build from a local trusted setup and add the artifacts to the local registry.
+    #[clap(long)]
+    srs: Option<PathBuf>,
 }
 
 pub fn main() {
     setup_logger();
     let args = Args::parse();
-    build_plonk_bn254_artifacts_with_dummy(args.build_dir);
+    match args.srs {
+        Some(srs) => {
+            let dir = build_plonk_bn254_artifacts_offline_with_dummy(&srs)
+                .expect("failed to build plonk bn254 artifacts");
+            println!("[sp1] artifacts available at {}", dir.display());
+        }
+        None => build_plonk_bn254_artifacts_with_dummy(args.build_dir.unwrap()),
+    }
 }
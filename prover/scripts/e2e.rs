@@ -70,7 +70,12 @@ pub fn main() {
     PlonkBn254Prover::test(constraints.clone(), witness.clone());
 
     tracing::info!("sanity check gnark build");
-    PlonkBn254Prover::build(constraints.clone(), witness.clone(), build_dir.clone());
+    PlonkBn254Prover::build(
+        constraints.clone(),
+        witness.clone(),
+        build_dir.clone(),
+        true,
+    );
 
     tracing::info!("sanity check gnark prove");
     let plonk_bn254_prover = PlonkBn254Prover::new();
@@ -60,6 +60,7 @@ fn main() {
             buffer: vec![bincode::serialize::<u32>(&iterations).unwrap()],
             ptr: 0,
             proofs: vec![],
+            ..SP1Stdin::new()
         };
         let leaf_proving_start = Instant::now();
         let proof = prover.prove_core(&pk, &stdin).unwrap();
@@ -1,5 +1,7 @@
 use std::borrow::Borrow;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
 
 use p3_baby_bear::BabyBear;
 use sp1_core::stark::StarkVerifyingKey;
@@ -13,11 +15,36 @@ pub use sp1_recursion_core::stark::utils::sp1_dev_mode;
 use sp1_recursion_gnark_ffi::PlonkBn254Prover;
 
 use crate::install::{install_plonk_bn254_artifacts, PLONK_BN254_ARTIFACTS_COMMIT};
+use crate::registry::{hash_file, ArtifactRegistry};
 use crate::utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes};
 use crate::{OuterSC, SP1Prover};
 
 /// Tries to install the PLONK artifacts if they are not already installed.
+///
+/// Artifacts pinned to [PLONK_BN254_ARTIFACTS_COMMIT] in the local [ArtifactRegistry] take
+/// precedence and are used without any network access. If the pinned artifacts fail their
+/// integrity check, this panics instead of downloading them, unless
+/// `SP1_ARTIFACTS_ALLOW_DOWNLOAD=true` is set.
 pub fn try_install_plonk_bn254_artifacts() -> PathBuf {
+    match ArtifactRegistry::local().resolve_current() {
+        Ok(Some(registry_dir)) => {
+            println!(
+                "[sp1] using plonk bn254 artifacts from the local registry at {}",
+                registry_dir.display()
+            );
+            return registry_dir;
+        }
+        Ok(None) => {}
+        Err(e) if allow_artifacts_download() => println!(
+            "[sp1] failed to resolve plonk bn254 artifacts from the local registry, falling back to the download: {}",
+            e
+        ),
+        Err(e) => panic!(
+            "failed to resolve plonk bn254 artifacts from the local registry: {:?}. set SP1_ARTIFACTS_ALLOW_DOWNLOAD=true to download them instead",
+            e
+        ),
+    }
+
     let build_dir = plonk_bn254_artifacts_dir();
 
     if build_dir.exists() {
@@ -46,10 +73,61 @@ pub fn try_build_plonk_bn254_artifacts_dev(
 ) -> PathBuf {
     let build_dir = plonk_bn254_artifacts_dev_dir();
     println!("[sp1] building plonk bn254 artifacts in development mode");
-    build_plonk_bn254_artifacts(template_vk, template_proof, &build_dir);
+    build_plonk_bn254_artifacts(template_vk, template_proof, &build_dir, true);
     build_dir
 }
 
+/// Builds the PLONK artifacts from a local trusted setup and pins them to
+/// [PLONK_BN254_ARTIFACTS_COMMIT] in the local [ArtifactRegistry].
+///
+/// `srs_path` must point to a KZG SRS for bn254 in gnark's serialization format, such as the one
+/// derived from the Aztec Ignition ceremony. The Lagrange SRS is derived from it, so no network
+/// access is needed. Returns the directory of the artifacts in the registry.
+pub fn build_plonk_bn254_artifacts_offline(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    srs_path: &Path,
+) -> Result<PathBuf> {
+    let registry = ArtifactRegistry::local();
+    let staging_dir = registry.staging_dir()?;
+    let staging_srs_path = staging_dir.path().join("srs.bin");
+    std::fs::copy(srs_path, &staging_srs_path)
+        .with_context(|| format!("failed to copy trusted setup from {:?}", srs_path))?;
+
+    println!("[sp1] building plonk bn254 artifacts from a local trusted setup");
+    build_plonk_bn254_artifacts(template_vk, template_proof, staging_dir.path(), false);
+
+    // Never pin artifacts that were not built on the given trusted setup.
+    if hash_file(&staging_srs_path)? != hash_file(srs_path)? {
+        return Err(anyhow!(
+            "the trusted setup was replaced while building the artifacts, refusing to pin them"
+        ));
+    }
+
+    let digest = registry.insert(staging_dir.path())?;
+    registry.pin(PLONK_BN254_ARTIFACTS_COMMIT, &digest)?;
+    println!(
+        "[sp1] pinned plonk bn254 artifacts {} to {}",
+        digest, PLONK_BN254_ARTIFACTS_COMMIT
+    );
+    Ok(registry.object_dir(&digest))
+}
+
+/// Builds the PLONK artifacts from a local trusted setup with a dummy proof. See
+/// [build_plonk_bn254_artifacts_offline].
+pub fn build_plonk_bn254_artifacts_offline_with_dummy(srs_path: &Path) -> Result<PathBuf> {
+    let (wrap_vk, wrapped_proof) = dummy_proof();
+    build_plonk_bn254_artifacts_offline(&wrap_vk, &wrapped_proof, srs_path)
+}
+
+/// Whether the artifacts may be downloaded when the pinned artifacts in the local registry are
+/// invalid.
+fn allow_artifacts_download() -> bool {
+    std::env::var("SP1_ARTIFACTS_ALLOW_DOWNLOAD")
+        .map(|val| val == "true")
+        .unwrap_or(false)
+}
+
 /// Gets the directory where the PLONK artifacts are installed.
 fn plonk_bn254_artifacts_dir() -> PathBuf {
     dirs::home_dir()
@@ -72,24 +150,28 @@ pub fn plonk_bn254_artifacts_dev_dir() -> PathBuf {
 
 /// Build the plonk bn254 artifacts to the given directory for the given verification key and template
 /// proof.
+///
+/// In dev mode, the artifacts are built on an unsafe SRS generated locally. Otherwise, the trusted
+/// setup at `srs.bin` in the build directory is used, and downloaded if it doesn't exist.
 pub fn build_plonk_bn254_artifacts(
     template_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: &ShardProof<OuterSC>,
     build_dir: impl Into<PathBuf>,
+    dev_mode: bool,
 ) {
     let build_dir = build_dir.into();
     std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
     let (constraints, witness) = build_constraints_and_witness(template_vk, template_proof);
-    PlonkBn254Prover::build(constraints, witness, build_dir);
+    PlonkBn254Prover::build(constraints, witness, build_dir, dev_mode);
 }
 
-/// Builds the plonk bn254 artifacts to the given directory.
+/// Builds the plonk bn254 artifacts to the given directory from the trusted setup.
 ///
 /// This may take a while as it needs to first generate a dummy proof and then it needs to compile
 /// the circuit.
 pub fn build_plonk_bn254_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
     let (wrap_vk, wrapped_proof) = dummy_proof();
-    crate::build::build_plonk_bn254_artifacts(&wrap_vk, &wrapped_proof, build_dir.into());
+    crate::build::build_plonk_bn254_artifacts(&wrap_vk, &wrapped_proof, build_dir.into(), false);
 }
 
 /// Build the verifier constraints and template witness for the circuit.
@@ -0,0 +1,167 @@
+//! Core proofs under a selectable STARK config.
+//!
+//! The STARK config of a core proof selects the hash used to commit to its traces. Only proofs
+//! under [CoreSC] can be compressed by the recursion programs, but proofs under
+//! [BabyBearKeccak](sp1_core::utils::BabyBearKeccak) or
+//! [BabyBearBlake3](sp1_core::utils::BabyBearBlake3) have Merkle commitments that are cheap to
+//! check with those hashes outside of SP1, e.g. on the EVM.
+
+use p3_air::Air;
+use p3_baby_bear::BabyBear;
+use serde::{de::DeserializeOwned, Serialize};
+use sp1_core::{
+    io::{SP1PublicValues, SP1Stdin},
+    runtime::Program,
+    stark::{
+        Com, MachineProof, MachineVerificationError, OpeningProof, PcsProverData, RiscvAir,
+        ShardMainData, StarkGenericConfig, StarkMachine, StarkVerifyingKey,
+        VerifierConstraintFolder,
+    },
+    utils::{SP1CoreOpts, SP1CoreProverError},
+};
+use tracing::instrument;
+
+use crate::{
+    verify::verify_shard_transitions, CoreSC, SP1CoreProof, SP1CoreProofData, SP1ProvingKey,
+    SP1VerifyingKey,
+};
+
+/// A STARK config that core proofs can be generated and verified with.
+pub trait SP1CoreConfig: StarkGenericConfig<Val = BabyBear> + Default {
+    /// Proves the execution of a program, returning the proof and the public values stream.
+    fn prove_program(
+        self,
+        program: Program,
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+    ) -> Result<(MachineProof<Self>, Vec<u8>), SP1CoreProverError>;
+
+    /// Verifies the shard proofs and the lookup bus of a proof.
+    fn verify_machine(
+        machine: &StarkMachine<Self, RiscvAir<BabyBear>>,
+        vk: &StarkVerifyingKey<Self>,
+        proof: &MachineProof<Self>,
+    ) -> Result<(), MachineVerificationError<Self>>;
+}
+
+impl<SC> SP1CoreConfig for SC
+where
+    SC: StarkGenericConfig<Val = BabyBear> + Default + Send + Sync,
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    RiscvAir<BabyBear>: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    fn prove_program(
+        self,
+        program: Program,
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+    ) -> Result<(MachineProof<Self>, Vec<u8>), SP1CoreProverError> {
+        sp1_core::utils::prove(program, stdin, self, opts)
+    }
+
+    fn verify_machine(
+        machine: &StarkMachine<Self, RiscvAir<BabyBear>>,
+        vk: &StarkVerifyingKey<Self>,
+        proof: &MachineProof<Self>,
+    ) -> Result<(), MachineVerificationError<Self>> {
+        let mut challenger = machine.config().challenger();
+        machine.verify(vk, proof, &mut challenger)
+    }
+}
+
+/// A prover of core proofs under the STARK config `SC`. See the
+/// [module documentation](self) for details.
+pub struct SP1CoreProver<SC: SP1CoreConfig = CoreSC> {
+    pub machine: StarkMachine<SC, RiscvAir<BabyBear>>,
+}
+
+impl<SC: SP1CoreConfig> SP1CoreProver<SC> {
+    /// Initializes a new [SP1CoreProver] with the default config.
+    pub fn new() -> Self {
+        Self::with_config(SC::default())
+    }
+
+    pub fn with_config(config: SC) -> Self {
+        Self {
+            machine: RiscvAir::machine(config),
+        }
+    }
+
+    /// Creates a proving key and a verifying key for a given RISC-V ELF.
+    #[instrument(name = "setup", level = "debug", skip_all)]
+    pub fn setup(&self, elf: &[u8]) -> (SP1ProvingKey<SC>, SP1VerifyingKey<SC>) {
+        let program = Program::from(elf);
+        let (pk, vk) = self.machine.setup(&program);
+        let vk = SP1VerifyingKey { vk };
+        let pk = SP1ProvingKey {
+            pk,
+            elf: elf.to_vec(),
+            vk: vk.clone(),
+        };
+        (pk, vk)
+    }
+
+    /// Generates shard proofs of the execution of a RISC-V program.
+    #[instrument(name = "prove_core", level = "info", skip_all)]
+    pub fn prove(
+        &self,
+        pk: &SP1ProvingKey<SC>,
+        stdin: &SP1Stdin,
+    ) -> Result<SP1CoreProof<SC>, SP1CoreProverError> {
+        let program = Program::from(&pk.elf);
+        let config = self.machine.config().clone();
+        let (proof, public_values_stream) =
+            config.prove_program(program, stdin, SP1CoreOpts::default())?;
+        Ok(SP1CoreProof {
+            proof: SP1CoreProofData(proof.shard_proofs),
+            stdin: stdin.clone(),
+            public_values: SP1PublicValues::from(&public_values_stream),
+        })
+    }
+
+    /// Verifies a core proof by verifying the shards, verifying the lookup bus, and verifying that
+    /// the shards are contiguous and complete.
+    pub fn verify(
+        &self,
+        proof: &SP1CoreProofData<SC>,
+        vk: &SP1VerifyingKey<SC>,
+    ) -> Result<(), MachineVerificationError<SC>> {
+        let machine_proof = MachineProof {
+            shard_proofs: proof.0.to_vec(),
+        };
+        SC::verify_machine(&self.machine, &vk.vk, &machine_proof)?;
+        verify_shard_transitions(&proof.0, vk.vk.pc_start)
+    }
+}
+
+impl<SC: SP1CoreConfig> Default for SP1CoreProver<SC> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_core::utils::{setup_logger, BabyBearBlake3, BabyBearKeccak};
+
+    use super::*;
+
+    fn prove_and_verify<SC: SP1CoreConfig>(elf: &[u8]) {
+        let prover = SP1CoreProver::<SC>::new();
+        let (pk, vk) = prover.setup(elf);
+        let proof = prover.prove(&pk, &SP1Stdin::new()).unwrap();
+        prover.verify(&proof.proof, &vk).unwrap();
+    }
+
+    #[test]
+    fn test_core_proof_hash_configs() {
+        setup_logger();
+        let elf = include_bytes!("../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        prove_and_verify::<BabyBearKeccak>(elf);
+        prove_and_verify::<BabyBearBlake3>(elf);
+    }
+}
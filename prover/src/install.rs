@@ -10,6 +10,10 @@ use crate::utils::block_on;
 pub const PLONK_BN254_ARTIFACTS_URL_BASE: &str = "https://sp1-circuits.s3-us-east-2.amazonaws.com";
 
 /// The current version of the plonk bn254 artifacts.
+///
+/// The artifacts are built from the wrap circuit, so any change to the core chips or to the
+/// recursion programs changes them. After such a change, the artifacts are released with
+/// `make -C prover`, and this is set to the commit they were built from.
 pub const PLONK_BN254_ARTIFACTS_COMMIT: &str = "e48c01ec";
 
 /// Install the latest plonk bn254 artifacts.
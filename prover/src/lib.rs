@@ -11,14 +11,19 @@
 #![allow(clippy::new_without_default)]
 
 pub mod build;
+pub mod core_prover;
 pub mod install;
+pub mod registry;
 pub mod types;
 pub mod utils;
 pub mod verify;
 
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::sync::Arc;
 
+pub use core_prover::{SP1CoreConfig, SP1CoreProver};
 use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
 use p3_field::{AbstractField, PrimeField};
@@ -29,7 +34,9 @@ pub use sp1_core::io::{SP1PublicValues, SP1Stdin};
 use sp1_core::runtime::{ExecutionError, ExecutionReport, Runtime};
 use sp1_core::stark::{Challenge, StarkProvingKey};
 use sp1_core::stark::{Challenger, MachineVerificationError};
-use sp1_core::utils::{SP1CoreOpts, DIGEST_SIZE};
+use sp1_core::syscall::DeferredProofVerification;
+pub use sp1_core::utils::{BabyBearBlake3, BabyBearKeccak};
+use sp1_core::utils::{InnerPerm, SP1CoreOpts, SP1SecurityConfig, DIGEST_SIZE};
 use sp1_core::{
     runtime::Program,
     stark::{
@@ -43,8 +50,14 @@ use sp1_recursion_compiler::config::InnerConfig;
 use sp1_recursion_compiler::ir::Witness;
 use sp1_recursion_core::{
     air::RecursionPublicValues,
-    runtime::{RecursionProgram, Runtime as RecursionRuntime},
-    stark::{config::BabyBearPoseidon2Outer, RecursionAir},
+    runtime::{
+        ExecutionRecord as RecursionRecord, RecursionProfile, RecursionProgram,
+        Runtime as RecursionRuntime,
+    },
+    stark::{
+        config::{outer_fri_parameters, BabyBearPoseidon2Outer},
+        RecursionAir,
+    },
 };
 pub use sp1_recursion_gnark_ffi::plonk_bn254::PlonkBn254Proof;
 use sp1_recursion_gnark_ffi::plonk_bn254::PlonkBn254Prover;
@@ -59,6 +72,7 @@ pub use sp1_recursion_program::machine::{
 use tracing::instrument;
 pub use types::*;
 use utils::words_to_bytes;
+use verify::SP1DeferredProofVerifier;
 
 /// The configuration for the core prover.
 pub type CoreSC = BabyBearPoseidon2;
@@ -135,17 +149,38 @@ pub struct SP1Prover {
 
     /// The machine used for proving the wrapping step.
     pub wrap_machine: StarkMachine<OuterSC, WrapAir<<OuterSC as StarkGenericConfig>::Val>>,
+
+    /// The FRI parameters of each stage.
+    pub security: SP1SecurityConfig,
+
+    /// The verifier of the deferred proofs read during [SP1Prover::execute_verified].
+    pub deferred_proof_verifier: Arc<SP1DeferredProofVerifier>,
 }
 
 impl SP1Prover {
-    /// Initializes a new [SP1Prover].
-    #[instrument(name = "initialize prover", level = "debug", skip_all)]
+    /// Initializes a new [SP1Prover] with the default FRI parameters, see
+    /// [SP1SecurityConfig::from_env].
     pub fn new() -> Self {
-        let core_machine = RiscvAir::machine(CoreSC::default());
+        let mut security = SP1SecurityConfig::from_env();
+        security.wrap = outer_fri_parameters();
+        Self::with_security(security)
+    }
+
+    /// Initializes a new [SP1Prover] with the given FRI parameters for each stage.
+    ///
+    /// The recursion programs are built to verify proofs with these parameters, so the verifying
+    /// keys of the recursion programs, and the PLONK circuit, depend on them.
+    #[instrument(name = "initialize prover", level = "debug", skip_all)]
+    pub fn with_security(security: SP1SecurityConfig) -> Self {
+        tracing::debug!(
+            "conjectured security: {} bits",
+            security.conjectured_security_bits()
+        );
+        let core_machine = RiscvAir::machine(CoreSC::with_fri_parameters(security.core));
 
         // Get the recursive verifier and setup the proving and verifying keys.
         let recursion_program = SP1RecursiveVerifier::<InnerConfig, _>::build(&core_machine);
-        let compress_machine = ReduceAir::machine(InnerSC::default());
+        let compress_machine = ReduceAir::machine(InnerSC::with_fri_parameters(security.compress));
         let (rec_pk, rec_vk) = compress_machine.setup(&recursion_program);
 
         // Get the deferred program and keys.
@@ -163,15 +198,18 @@ impl SP1Prover {
         // Get the compress program, machine, and keys.
         let shrink_program =
             SP1RootVerifier::<InnerConfig, _, _>::build(&compress_machine, &compress_vk, true);
-        let shrink_machine = CompressAir::wrap_machine_dyn(InnerSC::compressed());
+        let shrink_machine =
+            CompressAir::wrap_machine_dyn(InnerSC::with_fri_parameters(security.shrink));
         let (shrink_pk, shrink_vk) = shrink_machine.setup(&shrink_program);
 
         // Get the wrap program, machine, and keys.
         let wrap_program =
             SP1RootVerifier::<InnerConfig, _, _>::build(&shrink_machine, &shrink_vk, false);
-        let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
+        let wrap_machine = WrapAir::wrap_machine(OuterSC::with_fri_parameters(security.wrap));
         let (wrap_pk, wrap_vk) = wrap_machine.setup(&wrap_program);
 
+        let deferred_proof_verifier = Arc::new(SP1DeferredProofVerifier::new(compress_vk.clone()));
+
         Self {
             recursion_program,
             rec_pk,
@@ -192,6 +230,8 @@ impl SP1Prover {
             compress_machine,
             shrink_machine,
             wrap_machine,
+            security,
+            deferred_proof_verifier,
         }
     }
 
@@ -214,14 +254,36 @@ impl SP1Prover {
     pub fn execute(
         elf: &[u8],
         stdin: &SP1Stdin,
+    ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
+        Self::execute_with_verifier(elf, stdin, None)
+    }
+
+    /// Executes an SP1 program like [SP1Prover::execute], but also verifies the deferred proofs in
+    /// `stdin` when the program reads them, so an invalid proof or a mismatched vkey or public
+    /// values digest fails execution.
+    #[instrument(name = "execute", level = "info", skip_all)]
+    pub fn execute_verified(
+        &self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+    ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
+        let verifier: Arc<dyn DeferredProofVerification> = self.deferred_proof_verifier.clone();
+        Self::execute_with_verifier(elf, stdin, Some(verifier))
+    }
+
+    /// Executes an SP1 program, verifying the deferred proofs it reads with `verifier` if given.
+    pub fn execute_with_verifier(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        verifier: Option<Arc<dyn DeferredProofVerification>>,
     ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
         let program = Program::from(elf);
-        let opts = SP1CoreOpts::default();
+        let (opts, shard_sizing) =
+            SP1CoreOpts::default().with_shard_sizing::<BabyBear>(&program, stdin)?;
         let mut runtime = Runtime::new(program, opts);
-        runtime.write_vecs(&stdin.buffer);
-        for (proof, vkey) in stdin.proofs.iter() {
-            runtime.write_proof(proof.clone(), vkey.clone());
-        }
+        runtime.report.shard_sizing = shard_sizing;
+        runtime.deferred_proof_verifier = verifier;
+        runtime.write_inputs(stdin);
         runtime.run_untraced()?;
         Ok((
             SP1PublicValues::from(&runtime.state.public_values_stream),
@@ -237,7 +299,7 @@ impl SP1Prover {
         pk: &SP1ProvingKey,
         stdin: &SP1Stdin,
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
-        let config = CoreSC::default();
+        let config = self.core_machine.config().clone();
         let program = Program::from(&pk.elf);
         let opts = SP1CoreOpts::default();
         let (proof, public_values_stream) = sp1_core::utils::prove(program, stdin, config, opts)?;
@@ -366,13 +428,36 @@ impl SP1Prover {
     }
 
     /// Reduce shards proofs to a single shard proof using the recursion prover.
-    #[instrument(name = "compress", level = "info", skip_all)]
     pub fn compress(
         &self,
         vk: &SP1VerifyingKey,
         proof: SP1CoreProof,
         deferred_proofs: Vec<ShardProof<InnerSC>>,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.compress_with_profile(vk, proof, deferred_proofs)
+            .map(|(proof, _)| proof)
+    }
+
+    /// Reduce shards proofs to a single shard proof like [SP1Prover::compress], and also return
+    /// the profiles of the recursion programs that were executed.
+    ///
+    /// The profiles are keyed by the program that was executed: [ReduceProgramType::Core] for the
+    /// programs verifying core shards, [ReduceProgramType::Deferred] for the programs verifying
+    /// deferred proofs and [ReduceProgramType::Reduce] for the compress programs. The profiles of
+    /// all the executions of a program are merged.
+    #[instrument(name = "compress", level = "info", skip_all)]
+    pub fn compress_with_profile(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: SP1CoreProof,
+        deferred_proofs: Vec<ShardProof<InnerSC>>,
+    ) -> Result<
+        (
+            SP1ReduceProof<InnerSC>,
+            BTreeMap<ReduceProgramType, RecursionProfile>,
+        ),
+        SP1RecursionProverError,
+    > {
         // Set the batch size for the reduction tree.
         let batch_size = 2;
 
@@ -401,28 +486,23 @@ impl SP1Prover {
             let proofs = inputs
                 .into_par_iter()
                 .map(|input| {
-                    let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+                    let (record, profile) = Self::execute_recursion_program(
                         &self.recursion_program,
-                        self.compress_machine.config().perm.clone(),
+                        &self.compress_machine.config().perm,
+                        input,
                     );
 
-                    let mut witness_stream = Vec::new();
-                    witness_stream.extend(input.write());
-
-                    runtime.witness_stream = witness_stream.into();
-                    runtime.run();
-                    runtime.print_stats();
-
                     let pk = &self.rec_pk;
                     let mut recursive_challenger = self.compress_machine.config().challenger();
                     (
                         self.compress_machine.prove::<LocalProver<_, _>>(
                             pk,
-                            runtime.record,
+                            record,
                             &mut recursive_challenger,
                             opts,
                         ),
                         ReduceProgramType::Core,
+                        profile,
                     )
                 })
                 .collect::<Vec<_>>();
@@ -434,28 +514,23 @@ impl SP1Prover {
             let proofs = inputs
                 .into_par_iter()
                 .map(|input| {
-                    let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+                    let (record, profile) = Self::execute_recursion_program(
                         &self.deferred_program,
-                        self.compress_machine.config().perm.clone(),
+                        &self.compress_machine.config().perm,
+                        input,
                     );
 
-                    let mut witness_stream = Vec::new();
-                    witness_stream.extend(input.write());
-
-                    runtime.witness_stream = witness_stream.into();
-                    runtime.run();
-                    runtime.print_stats();
-
                     let pk = &self.deferred_pk;
                     let mut recursive_challenger = self.compress_machine.config().challenger();
                     (
                         self.compress_machine.prove::<LocalProver<_, _>>(
                             pk,
-                            runtime.record,
+                            record,
                             &mut recursive_challenger,
                             opts,
                         ),
                         ReduceProgramType::Deferred,
+                        profile,
                     )
                 })
                 .collect::<Vec<_>>();
@@ -463,10 +538,12 @@ impl SP1Prover {
         }
 
         // Chain all the individual shard proofs.
-        let mut reduce_proofs = first_layer_proofs
-            .into_iter()
-            .flat_map(|(proof, kind)| proof.shard_proofs.into_iter().map(move |p| (p, kind)))
-            .collect::<Vec<_>>();
+        let mut profiles = BTreeMap::<ReduceProgramType, RecursionProfile>::new();
+        let mut reduce_proofs = Vec::new();
+        for (proof, kind, program_profile) in first_layer_proofs {
+            profiles.entry(kind).or_default().merge(&program_profile);
+            reduce_proofs.extend(proof.shard_proofs.into_iter().map(|p| (p, kind)));
+        }
 
         // Iterate over the recursive proof batches until there is one proof remaining.
         let mut is_complete;
@@ -477,7 +554,7 @@ impl SP1Prover {
             let compress_inputs = reduce_proofs.chunks(batch_size).collect::<Vec<_>>();
             let batched_compress_inputs =
                 compress_inputs.chunks(shard_batch_size).collect::<Vec<_>>();
-            reduce_proofs = batched_compress_inputs
+            let layer = batched_compress_inputs
                 .into_iter()
                 .flat_map(|batches| {
                     batches
@@ -494,16 +571,21 @@ impl SP1Prover {
                                 is_complete,
                             };
 
-                            let proof = self.compress_machine_proof(
+                            let (proof, profile) = self.compress_machine_proof_with_profile(
                                 input,
                                 &self.compress_program,
                                 &self.compress_pk,
                             );
-                            (proof, ReduceProgramType::Reduce)
+                            (proof, ReduceProgramType::Reduce, profile)
                         })
                         .collect::<Vec<_>>()
                 })
                 .collect::<Vec<_>>();
+            reduce_proofs = Vec::with_capacity(layer.len());
+            for (proof, kind, program_profile) in layer {
+                profiles.entry(kind).or_default().merge(&program_profile);
+                reduce_proofs.push((proof, kind));
+            }
 
             if reduce_proofs.len() == 1 {
                 break;
@@ -512,9 +594,12 @@ impl SP1Prover {
         debug_assert_eq!(reduce_proofs.len(), 1);
         let reduce_proof = reduce_proofs.pop().unwrap();
 
-        Ok(SP1ReduceProof {
-            proof: reduce_proof.0,
-        })
+        Ok((
+            SP1ReduceProof {
+                proof: reduce_proof.0,
+            },
+            profiles,
+        ))
     }
 
     pub fn compress_machine_proof(
@@ -523,10 +608,40 @@ impl SP1Prover {
         program: &RecursionProgram<BabyBear>,
         pk: &StarkProvingKey<InnerSC>,
     ) -> ShardProof<InnerSC> {
-        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
-            program,
-            self.compress_machine.config().perm.clone(),
-        );
+        self.compress_machine_proof_with_profile(input, program, pk)
+            .0
+    }
+
+    /// Like [SP1Prover::compress_machine_proof], and also return the profile of the program.
+    pub fn compress_machine_proof_with_profile(
+        &self,
+        input: impl Hintable<InnerConfig>,
+        program: &RecursionProgram<BabyBear>,
+        pk: &StarkProvingKey<InnerSC>,
+    ) -> (ShardProof<InnerSC>, RecursionProfile) {
+        let (record, profile) =
+            Self::execute_recursion_program(program, &self.compress_machine.config().perm, &input);
+
+        let opts = SP1CoreOpts::recursion();
+        let mut recursive_challenger = self.compress_machine.config().challenger();
+        let proof = self
+            .compress_machine
+            .prove::<LocalProver<_, _>>(pk, record, &mut recursive_challenger, opts)
+            .shard_proofs
+            .pop()
+            .unwrap();
+        (proof, profile)
+    }
+
+    /// Executes a recursion program with the witness of `input`, and returns its execution record
+    /// along with a profile of the execution.
+    fn execute_recursion_program(
+        program: &RecursionProgram<BabyBear>,
+        perm: &InnerPerm,
+        input: &impl Hintable<InnerConfig>,
+    ) -> (RecursionRecord<BabyBear>, RecursionProfile) {
+        let mut runtime =
+            RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(program, perm.clone());
 
         let mut witness_stream = Vec::new();
         witness_stream.extend(input.write());
@@ -535,21 +650,26 @@ impl SP1Prover {
         runtime.run();
         runtime.print_stats();
 
-        let opts = SP1CoreOpts::recursion();
-        let mut recursive_challenger = self.compress_machine.config().challenger();
-        self.compress_machine
-            .prove::<LocalProver<_, _>>(pk, runtime.record, &mut recursive_challenger, opts)
-            .shard_proofs
-            .pop()
-            .unwrap()
+        let profile = runtime.profile();
+        (runtime.record, profile)
     }
 
     /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field.
-    #[instrument(name = "shrink", level = "info", skip_all)]
     pub fn shrink(
         &self,
         reduced_proof: SP1ReduceProof<InnerSC>,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.shrink_with_profile(reduced_proof)
+            .map(|(proof, _)| proof)
+    }
+
+    /// Shrink a reduce proof like [SP1Prover::shrink], and also return the profile of the shrink
+    /// program.
+    #[instrument(name = "shrink", level = "info", skip_all)]
+    pub fn shrink_with_profile(
+        &self,
+        reduced_proof: SP1ReduceProof<InnerSC>,
+    ) -> Result<(SP1ReduceProof<InnerSC>, RecursionProfile), SP1RecursionProverError> {
         // Make the compress proof.
         let input = SP1RootMemoryLayout {
             machine: &self.compress_machine,
@@ -558,17 +678,11 @@ impl SP1Prover {
         };
 
         // Run the compress program.
-        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+        let (record, profile) = Self::execute_recursion_program(
             &self.shrink_program,
-            self.shrink_machine.config().perm.clone(),
+            &self.shrink_machine.config().perm,
+            &input,
         );
-
-        let mut witness_stream = Vec::new();
-        witness_stream.extend(input.write());
-
-        runtime.witness_stream = witness_stream.into();
-        runtime.run();
-        runtime.print_stats();
         tracing::debug!("Compress program executed successfully");
 
         // Prove the compress program.
@@ -576,22 +690,35 @@ impl SP1Prover {
         let mut compress_challenger = self.shrink_machine.config().challenger();
         let mut compress_proof = self.shrink_machine.prove::<LocalProver<_, _>>(
             &self.shrink_pk,
-            runtime.record,
+            record,
             &mut compress_challenger,
             opts,
         );
 
-        Ok(SP1ReduceProof {
-            proof: compress_proof.shard_proofs.pop().unwrap(),
-        })
+        Ok((
+            SP1ReduceProof {
+                proof: compress_proof.shard_proofs.pop().unwrap(),
+            },
+            profile,
+        ))
     }
 
     /// Wrap a reduce proof into a STARK proven over a SNARK-friendly field.
-    #[instrument(name = "wrap_bn254", level = "info", skip_all)]
     pub fn wrap_bn254(
         &self,
         compressed_proof: SP1ReduceProof<InnerSC>,
     ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
+        self.wrap_bn254_with_profile(compressed_proof)
+            .map(|(proof, _)| proof)
+    }
+
+    /// Wrap a reduce proof like [SP1Prover::wrap_bn254], and also return the profile of the wrap
+    /// program.
+    #[instrument(name = "wrap_bn254", level = "info", skip_all)]
+    pub fn wrap_bn254_with_profile(
+        &self,
+        compressed_proof: SP1ReduceProof<InnerSC>,
+    ) -> Result<(SP1ReduceProof<OuterSC>, RecursionProfile), SP1RecursionProverError> {
         let input = SP1RootMemoryLayout {
             machine: &self.shrink_machine,
             proof: compressed_proof.proof,
@@ -599,17 +726,11 @@ impl SP1Prover {
         };
 
         // Run the compress program.
-        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+        let (record, profile) = Self::execute_recursion_program(
             &self.wrap_program,
-            self.shrink_machine.config().perm.clone(),
+            &self.shrink_machine.config().perm,
+            &input,
         );
-
-        let mut witness_stream = Vec::new();
-        witness_stream.extend(input.write());
-
-        runtime.witness_stream = witness_stream.into();
-        runtime.run();
-        runtime.print_stats();
         tracing::debug!("Wrap program executed successfully");
 
         // Prove the wrap program.
@@ -618,7 +739,7 @@ impl SP1Prover {
         let time = std::time::Instant::now();
         let mut wrap_proof = self.wrap_machine.prove::<LocalProver<_, _>>(
             &self.wrap_pk,
-            runtime.record,
+            record,
             &mut wrap_challenger,
             opts,
         );
@@ -637,9 +758,12 @@ impl SP1Prover {
         }
         tracing::info!("Wrapping successful");
 
-        Ok(SP1ReduceProof {
-            proof: wrap_proof.shard_proofs.pop().unwrap(),
-        })
+        Ok((
+            SP1ReduceProof {
+                proof: wrap_proof.shard_proofs.pop().unwrap(),
+            },
+            profile,
+        ))
     }
 
     /// Wrap the STARK proven over a SNARK-friendly field into a PLONK proof.
@@ -700,10 +824,13 @@ mod tests {
     use super::*;
 
     use anyhow::Result;
+    use num_bigint::BigUint;
     use p3_field::PrimeField32;
     use serial_test::serial;
     use sp1_core::io::SP1Stdin;
+    use sp1_core::syscall::DeferredProofVerificationError;
     use sp1_core::utils::setup_logger;
+    use sp1_primitives::merkle::MerkleHash;
 
     /// Tests an end-to-end workflow of proving a program across the entire proof generation
     /// pipeline.
@@ -732,7 +859,14 @@ mod tests {
         prover.verify(&core_proof.proof, &vk)?;
 
         tracing::info!("compress");
-        let compressed_proof = prover.compress(&vk, core_proof, vec![])?;
+        let (compressed_proof, profiles) = prover.compress_with_profile(&vk, core_proof, vec![])?;
+        for kind in [ReduceProgramType::Core, ReduceProgramType::Reduce] {
+            let profile = &profiles[&kind];
+            assert!(profile.cycles > 0);
+            assert!(profile.chips.poseidon2 > 0);
+            assert_eq!(profile.opcodes.values().sum::<usize>(), profile.cycles);
+        }
+        assert!(!profiles.contains_key(&ReduceProgramType::Deferred));
 
         tracing::info!("verify compressed");
         prover.verify_compressed(&compressed_proof, &vk)?;
@@ -775,7 +909,13 @@ mod tests {
         let plonk_bn254_proof = prover.wrap_plonk_bn254(wrapped_bn254_proof, &artifacts_dir);
         println!("{:?}", plonk_bn254_proof);
 
-        prover.verify_plonk_bn254(&plonk_bn254_proof, &vk, &public_values, &artifacts_dir)?;
+        prover.verify_plonk_bn254(
+            &plonk_bn254_proof,
+            &vk,
+            &public_values,
+            None,
+            &artifacts_dir,
+        )?;
 
         Ok(())
     }
@@ -866,4 +1006,102 @@ mod tests {
 
         Ok(())
     }
+
+    /// Returns the stdin of the verify program for a compressed proof of the keccak program.
+    fn verify_program_stdin(prover: &SP1Prover) -> Result<SP1Stdin> {
+        let keccak_elf = include_bytes!("../../tests/keccak256/elf/riscv32im-succinct-zkvm-elf");
+        let (keccak_pk, keccak_vk) = prover.setup(keccak_elf);
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&1usize);
+        stdin.write(&vec![0u8, 0, 0]);
+        let deferred_proof = prover.prove_core(&keccak_pk, &stdin)?;
+        let pv = deferred_proof.public_values.as_slice().to_vec();
+        let deferred_reduce = prover.compress(&keccak_vk, deferred_proof, vec![])?;
+
+        let mut stdin = SP1Stdin::new();
+        let vkey_digest: [u32; 8] = keccak_vk.hash_babybear().map(|n| n.as_canonical_u32());
+        stdin.write(&vkey_digest);
+        stdin.write(&vec![pv]);
+        stdin.write_proof(deferred_reduce.proof, keccak_vk.vk);
+        Ok(stdin)
+    }
+
+    /// Tests that a valid deferred proof is accepted when executing with verification.
+    #[test]
+    #[serial]
+    fn test_execute_verified_deferred_proof() -> Result<()> {
+        setup_logger();
+        let verify_elf = include_bytes!("../../tests/verify-proof/elf/riscv32im-succinct-zkvm-elf");
+        let prover = SP1Prover::new();
+        let stdin = verify_program_stdin(&prover)?;
+
+        prover.execute_verified(verify_elf, &stdin)?;
+
+        Ok(())
+    }
+
+    /// Tests that a tampered deferred proof fails execution with verification, but not without.
+    #[test]
+    #[serial]
+    fn test_execute_verified_tampered_deferred_proof() -> Result<()> {
+        setup_logger();
+        let verify_elf = include_bytes!("../../tests/verify-proof/elf/riscv32im-succinct-zkvm-elf");
+        let prover = SP1Prover::new();
+        let mut stdin = verify_program_stdin(&prover)?;
+
+        let public_values = &mut stdin.proofs[0].0.public_values;
+        public_values[0] += BabyBear::one();
+
+        let result = prover.execute_verified(verify_elf, &stdin);
+        assert!(matches!(
+            result,
+            Err(ExecutionError::InvalidDeferredProof(
+                0,
+                DeferredProofVerificationError::InvalidProof(_)
+            ))
+        ));
+        SP1Prover::execute(verify_elf, &stdin)?;
+
+        Ok(())
+    }
+
+    /// Tests that the PLONK public inputs are checked against the public values committed as
+    /// requested, either hashed or as the root of their Merkle tree.
+    #[test]
+    fn test_verify_plonk_bn254_public_inputs() -> Result<()> {
+        let elf = include_bytes!("../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        let prover = SP1Prover::new();
+        let (_, vk) = prover.setup(elf);
+
+        // Two leaves, as committed with `sp1_zkvm::merkle`.
+        let public_values = SP1PublicValues::from(&[1, 0, 0, 0, 7, 2, 0, 0, 0, 8, 9]);
+        let keccak_root = public_values
+            .merkle_root_hash(MerkleHash::Keccak256)
+            .unwrap();
+        let inputs = |digest: BigUint| {
+            [
+                vk.hash_bn254().as_canonical_biguint().to_string(),
+                digest.to_string(),
+            ]
+        };
+        let verify = |merkle_hash, digest| {
+            verify::verify_plonk_bn254_public_inputs(
+                &vk,
+                &public_values,
+                merkle_hash,
+                &inputs(digest),
+            )
+        };
+
+        verify(None, public_values.hash())?;
+        verify(Some(MerkleHash::Keccak256), keccak_root.clone())?;
+
+        // Only the requested interpretation of the digest is accepted.
+        assert!(verify(None, keccak_root.clone()).is_err());
+        assert!(verify(Some(MerkleHash::Sha256), keccak_root).is_err());
+        assert!(verify(Some(MerkleHash::Keccak256), public_values.hash()).is_err());
+
+        Ok(())
+    }
 }
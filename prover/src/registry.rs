@@ -0,0 +1,313 @@
+//! A content-addressed local registry for PLONK bn254 artifacts.
+//!
+//! Artifacts built locally (for example on an air-gapped machine) are stored under a digest of
+//! their contents, together with a manifest of the sha256 hash of every file. A pin maps a name,
+//! such as [PLONK_BN254_ARTIFACTS_COMMIT], to a digest so that the prover can resolve artifacts
+//! without network access. The hashes are checked when a pin is resolved. Since the artifacts are
+//! several gigabytes, the size and modification time of every verified file are recorded in a
+//! marker, and the files are only hashed again when they no longer match it.
+//!
+//! The registry is laid out as follows:
+//!
+//! ```text
+//! <root>/objects/<digest>/manifest.json
+//! <root>/objects/<digest>/verified.json
+//! <root>/objects/<digest>/<artifact files>
+//! <root>/pins/<name>
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::install::PLONK_BN254_ARTIFACTS_COMMIT;
+
+/// The name of the manifest file inside each registry entry.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// The name of the marker file recording the files of a registry entry that were last verified.
+pub const VERIFIED_FILE: &str = "verified.json";
+
+/// The sha256 hashes of the files in a set of artifacts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    /// Maps each file name to the hex encoded sha256 hash of its contents.
+    pub files: BTreeMap<String, String>,
+}
+
+impl ArtifactManifest {
+    /// Computes the manifest of all the files in a directory.
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        for entry in fs::read_dir(dir).with_context(|| format!("failed to read {:?}", dir))? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == MANIFEST_FILE || name == VERIFIED_FILE {
+                continue;
+            }
+            files.insert(name, hash_file(&entry.path())?);
+        }
+        Ok(Self { files })
+    }
+
+    /// The content address of the artifacts, which is the sha256 hash of the manifest.
+    pub fn digest(&self) -> String {
+        let serialized = serde_json::to_vec(self).expect("failed to serialize manifest");
+        hex::encode(Sha256::digest(serialized))
+    }
+}
+
+/// A local registry of PLONK bn254 artifacts. See the [module documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct ArtifactRegistry {
+    root: PathBuf,
+}
+
+impl ArtifactRegistry {
+    /// Creates a registry rooted at the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The default registry, located at `SP1_ARTIFACTS_REGISTRY` if it is set and at
+    /// `~/.sp1/circuits/plonk_bn254/registry` otherwise.
+    pub fn local() -> Self {
+        match std::env::var("SP1_ARTIFACTS_REGISTRY") {
+            Ok(root) => Self::new(root),
+            Err(_) => Self::new(
+                dirs::home_dir()
+                    .unwrap()
+                    .join(".sp1")
+                    .join("circuits")
+                    .join("plonk_bn254")
+                    .join("registry"),
+            ),
+        }
+    }
+
+    /// The root directory of the registry.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The directory where the artifacts with the given digest are stored.
+    pub fn object_dir(&self, digest: &str) -> PathBuf {
+        self.root.join("objects").join(digest)
+    }
+
+    fn pin_path(&self, name: &str) -> PathBuf {
+        self.root.join("pins").join(name)
+    }
+
+    /// Creates a staging directory inside the registry to build artifacts in.
+    pub fn staging_dir(&self) -> Result<tempfile::TempDir> {
+        let staging = self.root.join("staging");
+        fs::create_dir_all(&staging)?;
+        Ok(tempfile::Builder::new().prefix("build").tempdir_in(staging)?)
+    }
+
+    /// Copies the artifacts in `dir` into the registry and returns their digest.
+    pub fn insert(&self, dir: &Path) -> Result<String> {
+        let manifest = ArtifactManifest::from_dir(dir)?;
+        let digest = manifest.digest();
+        let object_dir = self.object_dir(&digest);
+
+        if object_dir.exists() {
+            self.verify(&digest)?;
+            return Ok(digest);
+        }
+
+        // Copy into a temporary directory first so that a partially written entry is never
+        // visible under its digest.
+        fs::create_dir_all(self.root.join("objects"))?;
+        let tmp_dir = tempfile::Builder::new()
+            .prefix(".tmp")
+            .tempdir_in(self.root.join("objects"))?;
+        for name in manifest.files.keys() {
+            fs::copy(dir.join(name), tmp_dir.path().join(name))
+                .with_context(|| format!("failed to copy {}", name))?;
+        }
+        let manifest_file = File::create(tmp_dir.path().join(MANIFEST_FILE))?;
+        serde_json::to_writer_pretty(manifest_file, &manifest)?;
+        fs::rename(tmp_dir.into_path(), &object_dir)?;
+
+        self.verify(&digest)?;
+        Ok(digest)
+    }
+
+    /// Checks that the artifacts stored under `digest` match their manifest.
+    pub fn verify(&self, digest: &str) -> Result<ArtifactManifest> {
+        let object_dir = self.object_dir(digest);
+        let manifest_file = File::open(object_dir.join(MANIFEST_FILE))
+            .with_context(|| format!("no artifacts with digest {} in the registry", digest))?;
+        let manifest: ArtifactManifest = serde_json::from_reader(manifest_file)?;
+
+        if manifest.digest() != digest {
+            return Err(anyhow!("manifest of artifacts {} has been modified", digest));
+        }
+        for (name, expected) in manifest.files.iter() {
+            let actual = hash_file(&object_dir.join(name))?;
+            if &actual != expected {
+                return Err(anyhow!(
+                    "artifact {} of {} has hash {}, expected {}",
+                    name,
+                    digest,
+                    actual,
+                    expected
+                ));
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Checks the artifacts stored under `digest` like [ArtifactRegistry::verify], but skips
+    /// hashing them if none of the files changed since they were last verified.
+    pub fn verify_cached(&self, digest: &str) -> Result<()> {
+        let object_dir = self.object_dir(digest);
+        let marker_path = object_dir.join(VERIFIED_FILE);
+        let marker = File::open(&marker_path)
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, VerifiedMarker>(file).ok());
+        if let (Some(marker), Ok(current)) = (marker, VerifiedMarker::new(digest, &object_dir)) {
+            if marker == current {
+                return Ok(());
+            }
+        }
+
+        let manifest = self.verify(digest)?;
+        let mut files = manifest.files.keys().cloned().collect::<Vec<_>>();
+        files.push(MANIFEST_FILE.to_string());
+        let marker = VerifiedMarker::from_files(digest, &object_dir, &files)?;
+
+        // Write to a temporary file first so that a marker is never partially written.
+        let mut tmp_file = tempfile::NamedTempFile::new_in(&object_dir)?;
+        serde_json::to_writer(&mut tmp_file, &marker)?;
+        tmp_file.persist(marker_path)?;
+        Ok(())
+    }
+
+    /// Pins `name` to the artifacts with the given digest.
+    pub fn pin(&self, name: &str, digest: &str) -> Result<()> {
+        self.verify(digest)?;
+        let pins_dir = self.root.join("pins");
+        fs::create_dir_all(&pins_dir)?;
+
+        // Write to a temporary file first so that a pin is never partially written.
+        let mut tmp_file = tempfile::NamedTempFile::new_in(&pins_dir)?;
+        tmp_file.write_all(digest.as_bytes())?;
+        tmp_file.persist(self.pin_path(name))?;
+        Ok(())
+    }
+
+    /// Returns the digest `name` is pinned to, if any.
+    pub fn pinned(&self, name: &str) -> Result<Option<String>> {
+        match fs::read_to_string(self.pin_path(name)) {
+            Ok(digest) => Ok(Some(digest.trim().to_string())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Resolves the directory of the artifacts pinned to `name`, checking their integrity with
+    /// [ArtifactRegistry::verify_cached].
+    pub fn resolve(&self, name: &str) -> Result<Option<PathBuf>> {
+        match self.pinned(name)? {
+            Some(digest) => {
+                self.verify_cached(&digest)?;
+                Ok(Some(self.object_dir(&digest)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Resolves the artifacts pinned to the current [PLONK_BN254_ARTIFACTS_COMMIT].
+    pub fn resolve_current(&self) -> Result<Option<PathBuf>> {
+        self.resolve(PLONK_BN254_ARTIFACTS_COMMIT)
+    }
+}
+
+/// The size and modification time of the files of a registry entry when it was last verified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct VerifiedMarker {
+    digest: String,
+    /// Maps each file name to its size and modification time in nanoseconds since the epoch.
+    files: BTreeMap<String, (u64, u128)>,
+}
+
+impl VerifiedMarker {
+    /// Records the current metadata of the files of the entry in `object_dir`.
+    fn new(digest: &str, object_dir: &Path) -> Result<Self> {
+        let manifest_file = File::open(object_dir.join(MANIFEST_FILE))?;
+        let manifest: ArtifactManifest = serde_json::from_reader(manifest_file)?;
+        let mut files = manifest.files.keys().cloned().collect::<Vec<_>>();
+        files.push(MANIFEST_FILE.to_string());
+        Self::from_files(digest, object_dir, &files)
+    }
+
+    fn from_files(digest: &str, object_dir: &Path, names: &[String]) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        for name in names {
+            let metadata = fs::metadata(object_dir.join(name))
+                .with_context(|| format!("failed to read the metadata of {}", name))?;
+            let modified = metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_nanos();
+            files.insert(name.clone(), (metadata.len(), modified));
+        }
+        Ok(Self {
+            digest: digest.to_string(),
+            files,
+        })
+    }
+}
+
+/// Computes the hex encoded sha256 hash of a file.
+pub(crate) fn hash_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).with_context(|| format!("failed to open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_insert_and_resolve() {
+        let root = tempfile::tempdir().unwrap();
+        let registry = ArtifactRegistry::new(root.path());
+
+        let artifacts = tempfile::tempdir().unwrap();
+        fs::write(artifacts.path().join("vk.bin"), b"vk").unwrap();
+        fs::write(artifacts.path().join("pk.bin"), b"pk").unwrap();
+
+        let digest = registry.insert(artifacts.path()).unwrap();
+        assert_eq!(registry.insert(artifacts.path()).unwrap(), digest);
+        assert!(registry.resolve("test").unwrap().is_none());
+
+        registry.pin("test", &digest).unwrap();
+        registry.pin("test", &digest).unwrap();
+        assert_eq!(fs::read_dir(root.path().join("pins")).unwrap().count(), 1);
+        let dir = registry.resolve("test").unwrap().unwrap();
+        assert_eq!(fs::read(dir.join("vk.bin")).unwrap(), b"vk");
+
+        assert!(dir.join(VERIFIED_FILE).exists());
+        assert_eq!(registry.resolve("test").unwrap().unwrap(), dir);
+
+        // Tampering with an artifact is detected when resolving.
+        fs::write(dir.join("vk.bin"), b"tampered").unwrap();
+        assert!(registry.resolve("test").is_err());
+    }
+}
@@ -23,18 +23,26 @@ use crate::{utils::babybear_bytes_to_bn254, words_to_bytes};
 use crate::{utils::babybears_to_bn254, CoreSC, InnerSC};
 
 /// The information necessary to generate a proof for a given RISC-V program.
+///
+/// Keys for configs other than [CoreSC] can only be used with an [crate::SP1CoreProver].
 #[derive(Clone, Serialize, Deserialize)]
-pub struct SP1ProvingKey {
-    pub pk: StarkProvingKey<CoreSC>,
+#[serde(bound(serialize = "StarkProvingKey<SC>: Serialize, SP1VerifyingKey<SC>: Serialize"))]
+#[serde(bound(
+    deserialize = "StarkProvingKey<SC>: DeserializeOwned, SP1VerifyingKey<SC>: DeserializeOwned"
+))]
+pub struct SP1ProvingKey<SC: StarkGenericConfig = CoreSC> {
+    pub pk: StarkProvingKey<SC>,
     pub elf: Vec<u8>,
     /// Verifying key is also included as we need it for recursion
-    pub vk: SP1VerifyingKey,
+    pub vk: SP1VerifyingKey<SC>,
 }
 
 /// The information necessary to verify a proof for a given RISC-V program.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct SP1VerifyingKey {
-    pub vk: StarkVerifyingKey<CoreSC>,
+#[serde(bound(serialize = "StarkVerifyingKey<SC>: Serialize"))]
+#[serde(bound(deserialize = "StarkVerifyingKey<SC>: DeserializeOwned"))]
+pub struct SP1VerifyingKey<SC: StarkGenericConfig = CoreSC> {
+    pub vk: StarkVerifyingKey<SC>,
 }
 
 /// A trait for keys that can be hashed into a digest.
@@ -137,7 +145,7 @@ impl<P: std::fmt::Debug + Clone> std::fmt::Debug for SP1ProofWithMetadata<P> {
 }
 
 /// A proof of an SP1 program without any wrapping.
-pub type SP1CoreProof = SP1ProofWithMetadata<SP1CoreProofData>;
+pub type SP1CoreProof<SC = CoreSC> = SP1ProofWithMetadata<SP1CoreProofData<SC>>;
 
 /// An SP1 proof that has been recursively reduced into a single proof. This proof can be verified
 /// within SP1 programs.
@@ -150,7 +158,9 @@ pub type SP1PlonkBn254Proof = SP1ProofWithMetadata<SP1PlonkBn254ProofData>;
 pub type SP1PlonkProof = SP1ProofWithMetadata<SP1PlonkProofData>;
 
 #[derive(Serialize, Deserialize, Clone)]
-pub struct SP1CoreProofData(pub Vec<ShardProof<CoreSC>>);
+#[serde(bound(serialize = "ShardProof<SC>: Serialize"))]
+#[serde(bound(deserialize = "ShardProof<SC>: Deserialize<'de>"))]
+pub struct SP1CoreProofData<SC: StarkGenericConfig = CoreSC>(pub Vec<ShardProof<SC>>);
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SP1ReducedProofData(pub ShardProof<InnerSC>);
 
@@ -30,7 +30,7 @@ impl SP1CoreProofData {
 pub fn get_cycles(elf: &[u8], stdin: &SP1Stdin) -> u64 {
     let program = Program::from(elf);
     let mut runtime = Runtime::new(program, SP1CoreOpts::default());
-    runtime.write_vecs(&stdin.buffer);
+    runtime.write_inputs(stdin);
     runtime.dry_run();
     runtime.state.global_clk
 }
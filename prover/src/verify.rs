@@ -3,19 +3,25 @@ use std::{borrow::Borrow, path::Path, str::FromStr};
 use anyhow::Result;
 use num_bigint::BigUint;
 use p3_baby_bear::BabyBear;
-use p3_field::{AbstractField, PrimeField};
+use p3_field::{AbstractField, PrimeField, PrimeField32};
 use sp1_core::{
     air::PublicValues,
     io::SP1PublicValues,
-    stark::{MachineProof, MachineVerificationError, StarkGenericConfig},
+    stark::{
+        MachineProof, MachineVerificationError, ShardProof, StarkGenericConfig, StarkMachine,
+        StarkVerifyingKey,
+    },
+    syscall::{DeferredProofVerification, DeferredProofVerificationError},
     utils::BabyBearPoseidon2,
 };
+use sp1_primitives::merkle::MerkleHash;
 use sp1_recursion_core::{air::RecursionPublicValues, stark::config::BabyBearPoseidon2Outer};
 use sp1_recursion_gnark_ffi::{PlonkBn254Proof, PlonkBn254Prover};
 use thiserror::Error;
 
 use crate::{
-    CoreSC, HashableKey, OuterSC, SP1CoreProofData, SP1Prover, SP1ReduceProof, SP1VerifyingKey,
+    words_to_bytes, CoreSC, HashableKey, InnerSC, OuterSC, ReduceAir, SP1CoreProofData, SP1Prover,
+    SP1ReduceProof, SP1VerifyingKey,
 };
 
 #[derive(Error, Debug)]
@@ -45,62 +51,7 @@ impl SP1Prover {
         self.core_machine
             .verify(&vk.vk, &machine_proof, &mut challenger)?;
 
-        // Verify shard transitions
-        for (i, shard_proof) in proof.0.iter().enumerate() {
-            let public_values = PublicValues::from_vec(shard_proof.public_values.clone());
-            // Verify shard transitions
-            if i == 0 {
-                // If it's the first shard, index should be 1.
-                if public_values.shard != BabyBear::one() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "first shard not 1",
-                    ));
-                }
-                if public_values.start_pc != vk.vk.pc_start {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "wrong pc_start",
-                    ));
-                }
-            } else {
-                let prev_shard_proof = &proof.0[i - 1];
-                let prev_public_values =
-                    PublicValues::from_vec(prev_shard_proof.public_values.clone());
-                // For non-first shards, the index should be the previous index + 1.
-                if public_values.shard != prev_public_values.shard + BabyBear::one() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "non incremental shard index",
-                    ));
-                }
-                // Start pc should be what the next pc declared in the previous shard was.
-                if public_values.start_pc != prev_public_values.next_pc {
-                    return Err(MachineVerificationError::InvalidPublicValues("pc mismatch"));
-                }
-                // Digests and exit code should be the same in all shards.
-                if public_values.committed_value_digest != prev_public_values.committed_value_digest
-                    || public_values.deferred_proofs_digest
-                        != prev_public_values.deferred_proofs_digest
-                    || public_values.exit_code != prev_public_values.exit_code
-                {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "digest or exit code mismatch",
-                    ));
-                }
-                // The last shard should be halted. Halt is signaled with next_pc == 0.
-                if i == proof.0.len() - 1 && public_values.next_pc != BabyBear::zero() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "last shard isn't halted",
-                    ));
-                }
-                // All non-last shards should not be halted.
-                if i != proof.0.len() - 1 && public_values.next_pc == BabyBear::zero() {
-                    return Err(MachineVerificationError::InvalidPublicValues(
-                        "non-last shard is halted",
-                    ));
-                }
-            }
-        }
-
-        Ok(())
+        verify_shard_transitions(&proof.0, vk.vk.pc_start)
     }
 
     /// Verify a compressed proof.
@@ -109,23 +60,8 @@ impl SP1Prover {
         proof: &SP1ReduceProof<BabyBearPoseidon2>,
         vk: &SP1VerifyingKey,
     ) -> Result<(), MachineVerificationError<CoreSC>> {
-        let mut challenger = self.compress_machine.config().challenger();
-        let machine_proof = MachineProof {
-            shard_proofs: vec![proof.proof.clone()],
-        };
-        self.compress_machine
-            .verify(&self.compress_vk, &machine_proof, &mut challenger)?;
-
-        // Validate public values
-        let public_values: &RecursionPublicValues<_> =
-            proof.proof.public_values.as_slice().borrow();
-
-        // `is_complete` should be 1. In the reduce program, this ensures that the proof is fully reduced.
-        if public_values.is_complete != BabyBear::one() {
-            return Err(MachineVerificationError::InvalidPublicValues(
-                "is_complete is not 1",
-            ));
-        }
+        let public_values =
+            verify_compressed_shard(&self.compress_machine, &self.compress_vk, &proof.proof)?;
 
         // Verify that the proof is for the sp1 vkey we are expecting.
         let vkey_hash = vk.hash_babybear();
@@ -135,14 +71,6 @@ impl SP1Prover {
             ));
         }
 
-        // Verify that the reduce program is the one we are expecting.
-        let recursion_vkey_hash = self.compress_vk.hash_babybear();
-        if public_values.compress_vk_digest != recursion_vkey_hash {
-            return Err(MachineVerificationError::InvalidPublicValues(
-                "recursion vk hash mismatch",
-            ));
-        }
-
         Ok(())
     }
 
@@ -217,11 +145,15 @@ impl SP1Prover {
     }
 
     /// Verifies a PLONK proof using the circuit artifacts in the build directory.
+    ///
+    /// The proof must commit to the public values as described by `merkle_hash`, see
+    /// [verify_plonk_bn254_public_inputs].
     pub fn verify_plonk_bn254(
         &self,
         proof: &PlonkBn254Proof,
         vk: &SP1VerifyingKey,
         public_values: &SP1PublicValues,
+        merkle_hash: Option<MerkleHash>,
         build_dir: &Path,
     ) -> Result<()> {
         let prover = PlonkBn254Prover::new();
@@ -232,16 +164,185 @@ impl SP1Prover {
         // Verify the proof with the corresponding public inputs.
         prover.verify(proof, &vkey_hash, &committed_values_digest, build_dir);
 
-        verify_plonk_bn254_public_inputs(vk, public_values, &proof.public_inputs)?;
+        verify_plonk_bn254_public_inputs(vk, public_values, merkle_hash, &proof.public_inputs)?;
+
+        Ok(())
+    }
+}
+
+/// Verifies that the shards of a core proof are contiguous and complete: the shard indices start at
+/// 1 and increase by one, the program starts at `pc_start` and each shard starts where the previous
+/// one ended, the digests and exit code agree across shards, and execution halts in the last shard.
+pub fn verify_shard_transitions<SC: StarkGenericConfig<Val = BabyBear>>(
+    proof: &[ShardProof<SC>],
+    pc_start: BabyBear,
+) -> Result<(), MachineVerificationError<SC>> {
+    for (i, shard_proof) in proof.iter().enumerate() {
+        let public_values = PublicValues::from_vec(shard_proof.public_values.clone());
+        // Verify shard transitions
+        if i == 0 {
+            // If it's the first shard, index should be 1.
+            if public_values.shard != BabyBear::one() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "first shard not 1",
+                ));
+            }
+            if public_values.start_pc != pc_start {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "wrong pc_start",
+                ));
+            }
+        } else {
+            let prev_shard_proof = &proof[i - 1];
+            let prev_public_values = PublicValues::from_vec(prev_shard_proof.public_values.clone());
+            // For non-first shards, the index should be the previous index + 1.
+            if public_values.shard != prev_public_values.shard + BabyBear::one() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "non incremental shard index",
+                ));
+            }
+            // Start pc should be what the next pc declared in the previous shard was.
+            if public_values.start_pc != prev_public_values.next_pc {
+                return Err(MachineVerificationError::InvalidPublicValues("pc mismatch"));
+            }
+            // Digests and exit code should be the same in all shards.
+            if public_values.committed_value_digest != prev_public_values.committed_value_digest
+                || public_values.deferred_proofs_digest != prev_public_values.deferred_proofs_digest
+                || public_values.exit_code != prev_public_values.exit_code
+            {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "digest or exit code mismatch",
+                ));
+            }
+            // The last shard should be halted. Halt is signaled with next_pc == 0.
+            if i == proof.len() - 1 && public_values.next_pc != BabyBear::zero() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "last shard isn't halted",
+                ));
+            }
+        }
+
+        // Only precompile shards, which have no CPU chip, may follow a halted shard.
+        if shard_proof.contains_cpu() && public_values.start_pc == BabyBear::zero() {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "shard executes after halting",
+            ));
+        }
+        // Precompile shards don't execute any instructions.
+        if !shard_proof.contains_cpu() && public_values.next_pc != public_values.start_pc {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "precompile shard changes pc",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies a proof of the compress program with the given verifying key, and returns its public
+/// values, without checking which program it is for.
+fn verify_compressed_shard<'a>(
+    machine: &StarkMachine<InnerSC, ReduceAir<BabyBear>>,
+    compress_vk: &StarkVerifyingKey<InnerSC>,
+    proof: &'a ShardProof<InnerSC>,
+) -> Result<&'a RecursionPublicValues<BabyBear>, MachineVerificationError<InnerSC>> {
+    let mut challenger = machine.config().challenger();
+    let machine_proof = MachineProof {
+        shard_proofs: vec![proof.clone()],
+    };
+    machine.verify(compress_vk, &machine_proof, &mut challenger)?;
+
+    // Validate public values
+    let public_values: &RecursionPublicValues<_> = proof.public_values.as_slice().borrow();
+
+    // `is_complete` should be 1. In the reduce program, this ensures that the proof is fully reduced.
+    if public_values.is_complete != BabyBear::one() {
+        return Err(MachineVerificationError::InvalidPublicValues(
+            "is_complete is not 1",
+        ));
+    }
+
+    // Verify that the reduce program is the one we are expecting.
+    let recursion_vkey_hash = compress_vk.hash_babybear();
+    if public_values.compress_vk_digest != recursion_vkey_hash {
+        return Err(MachineVerificationError::InvalidPublicValues(
+            "recursion vk hash mismatch",
+        ));
+    }
+
+    Ok(public_values)
+}
+
+/// Verifies the compressed proofs read by the `VERIFY_SP1_PROOF` syscall during execution.
+///
+/// [SP1Prover] builds one when it is initialized, and shares it with every execution.
+pub struct SP1DeferredProofVerifier {
+    machine: StarkMachine<InnerSC, ReduceAir<BabyBear>>,
+    compress_vk: StarkVerifyingKey<InnerSC>,
+}
+
+impl SP1DeferredProofVerifier {
+    /// Creates a new [SP1DeferredProofVerifier] for proofs of the given compress program.
+    pub fn new(compress_vk: StarkVerifyingKey<InnerSC>) -> Self {
+        Self {
+            machine: ReduceAir::machine(InnerSC::with_fri_parameters(compress_vk.fri_parameters)),
+            compress_vk,
+        }
+    }
+}
+
+impl DeferredProofVerification for SP1DeferredProofVerifier {
+    fn verify_deferred_proof(
+        &self,
+        proof: &ShardProof<BabyBearPoseidon2>,
+        vk: &StarkVerifyingKey<BabyBearPoseidon2>,
+        vk_digest: &[u32; 8],
+        pv_digest: &[u8; 32],
+    ) -> Result<(), DeferredProofVerificationError> {
+        let public_values = verify_compressed_shard(&self.machine, &self.compress_vk, proof)
+            .map_err(|e| DeferredProofVerificationError::InvalidProof(e.to_string()))?;
+
+        // Verify that the proof is for the program of the verifying key it was written with.
+        if public_values.sp1_vk_digest != vk.hash_babybear() {
+            return Err(DeferredProofVerificationError::InvalidProof(
+                "sp1 vk hash mismatch".to_string(),
+            ));
+        }
+
+        // Verify that the proof is for the sp1 vkey passed to the syscall.
+        let proof_vk_digest = public_values.sp1_vk_digest.map(|x| x.as_canonical_u32());
+        if proof_vk_digest != *vk_digest {
+            return Err(DeferredProofVerificationError::VkeyDigestMismatch(
+                *vk_digest,
+                proof_vk_digest,
+            ));
+        }
+
+        // Verify that the proof commits to the public values digest passed to the syscall.
+        let committed_value_digest = words_to_bytes(&public_values.committed_value_digest);
+        if committed_value_digest
+            .iter()
+            .zip(pv_digest.iter())
+            .any(|(a, b)| a.as_canonical_u32() != *b as u32)
+        {
+            return Err(DeferredProofVerificationError::PublicValuesDigestMismatch);
+        }
 
         Ok(())
     }
 }
 
 /// Verify the vk_hash and public_values_hash in the public inputs of the PlonkBn254Proof match the expected values.
+///
+/// With no `merkle_hash`, the public values must be committed by their SHA-256 hash, as done by
+/// default. With a `merkle_hash`, they must be committed by the root of their Merkle tree with
+/// that hash, as done by programs using `sp1_zkvm::merkle`. Note that the stock Solidity verifier
+/// hashes `publicValues` with SHA-256, so proofs of Merkle roots need a contract that takes the
+/// root instead.
 pub fn verify_plonk_bn254_public_inputs(
     vk: &SP1VerifyingKey,
     public_values: &SP1PublicValues,
+    merkle_hash: Option<MerkleHash>,
     plonk_bn254_public_inputs: &[String],
 ) -> Result<()> {
     let expected_vk_hash = BigUint::from_str(&plonk_bn254_public_inputs[0])?;
@@ -252,8 +353,11 @@ pub fn verify_plonk_bn254_public_inputs(
         return Err(PlonkVerificationError::InvalidVerificationKey.into());
     }
 
-    let public_values_hash = public_values.hash();
-    if public_values_hash != expected_public_values_hash {
+    let public_values_hash = match merkle_hash {
+        Some(hash) => public_values.merkle_root_hash(hash),
+        None => Some(public_values.hash()),
+    };
+    if public_values_hash != Some(expected_public_values_hash) {
         return Err(PlonkVerificationError::InvalidPublicValues.into());
     }
 
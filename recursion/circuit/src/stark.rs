@@ -21,7 +21,7 @@ use sp1_recursion_compiler::ir::{Builder, Config, Ext, Felt, Var};
 use sp1_recursion_compiler::ir::{Usize, Witness};
 use sp1_recursion_compiler::prelude::SymbolicVar;
 use sp1_recursion_core::air::RecursionPublicValues;
-use sp1_recursion_core::stark::config::{outer_fri_config, BabyBearPoseidon2Outer};
+use sp1_recursion_core::stark::config::{outer_challenge_mmcs, BabyBearPoseidon2Outer};
 use sp1_recursion_core::stark::RecursionAirSkinnyDeg9;
 use sp1_recursion_program::commit::PolynomialSpaceVariable;
 use sp1_recursion_program::stark::RecursiveVerifierConstraintFolder;
@@ -206,7 +206,7 @@ where
         rounds.push(main_round);
         rounds.push(perm_round);
         rounds.push(quotient_round);
-        let config = outer_fri_config();
+        let config = vk.fri_parameters.fri_config(outer_challenge_mmcs());
         verify_two_adic_pcs(builder, &config, &proof.opening_proof, challenger, rounds);
 
         for (i, sorted_chip) in sorted_chips.iter().enumerate() {
@@ -242,7 +242,7 @@ pub fn build_wrap_circuit(
     wrap_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: ShardProof<OuterSC>,
 ) -> Vec<Constraint> {
-    let outer_config = OuterSC::new();
+    let outer_config = OuterSC::with_fri_parameters(wrap_vk.fri_parameters);
     let outer_machine = RecursionAirSkinnyDeg9::<OuterF>::wrap_machine(outer_config);
 
     let mut builder = Builder::<OuterConfig>::default();
@@ -347,6 +347,8 @@ pub fn build_wrap_circuit(
     }
     builder.assert_ext_eq(cumulative_sum, zero_ext);
 
+    builder.optimize_circuit();
+
     let mut backend = ConstraintCompiler::<OuterConfig>::default();
     backend.emit(builder.operations)
 }
@@ -3,6 +3,7 @@ use alloc::vec;
 use backtrace::Backtrace;
 use sp1_recursion_core::runtime::HEAP_PTR;
 use sp1_recursion_core::runtime::HEAP_START_ADDRESS;
+use sp1_recursion_core::runtime::STACK_START_OFFSET;
 use std::collections::BTreeSet;
 
 use p3_field::ExtensionField;
@@ -23,9 +24,6 @@ use crate::prelude::TracedVec;
 /// The zero address.
 pub(crate) const ZERO: i32 = 0;
 
-/// The offset which the stack starts.
-pub(crate) const STACK_START_OFFSET: i32 = 16;
-
 /// The address of A0.
 pub(crate) const A0: i32 = -8;
 
@@ -38,6 +36,9 @@ pub struct AsmCompiler<F, EF> {
     break_counter: usize,
     contains_break: BTreeSet<F>,
     function_labels: BTreeMap<String, F>,
+    /// The backtrace of the operation being compiled, which is attributed to the instructions
+    /// generated for it without one, e.g. the branches of loops and conditionals.
+    trace: Option<Backtrace>,
 }
 
 impl<F> Var<F> {
@@ -78,6 +79,7 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
             contains_break: BTreeSet::new(),
             function_labels: BTreeMap::new(),
             break_counter: 0,
+            trace: None,
         }
     }
 
@@ -98,8 +100,12 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
             self.push(AsmInstruction::AddFI(HEAP_PTR, ZERO, stack_size), None);
         }
 
+        // The operation whose body is being compiled, if any.
+        let parent_trace = self.trace.clone();
+
         // For each operation, generate assembly instructions.
         for (op, trace) in operations.clone() {
+            self.trace = trace.clone().or_else(|| parent_trace.clone());
             match op {
                 DslIr::ImmV(dst, src) => {
                     self.push(AsmInstruction::AddFI(dst.fp(), ZERO, src), trace);
@@ -539,6 +545,8 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                 _ => unimplemented!(),
             }
         }
+
+        self.trace = parent_trace;
     }
 
     pub fn alloc(&mut self, ptr: Ptr<F>, len: Usize<F>, size: usize, backtrace: Option<Backtrace>) {
@@ -612,6 +620,7 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
         instruction: AsmInstruction<F, EF>,
         backtrace: Option<Backtrace>,
     ) {
+        let backtrace = backtrace.or_else(|| self.trace.clone());
         self.basic_blocks
             .get_mut(block_label.as_canonical_u32() as usize)
             .unwrap_or_else(|| panic!("Missing block at label: {:?}", block_label))
@@ -619,6 +628,7 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
     }
 
     fn push(&mut self, instruction: AsmInstruction<F, EF>, backtrace: Option<Backtrace>) {
+        let backtrace = backtrace.or_else(|| self.trace.clone());
         self.basic_blocks
             .last_mut()
             .unwrap()
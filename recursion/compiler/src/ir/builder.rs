@@ -536,19 +536,19 @@ impl<'a, C: Config> IfBuilder<'a, C> {
             }
             IfCondition::Eq(lhs, rhs) => {
                 let op = DslIr::IfEq(lhs, rhs, then_instructions, Default::default());
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
             IfCondition::EqI(lhs, rhs) => {
                 let op = DslIr::IfEqI(lhs, rhs, then_instructions, Default::default());
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
             IfCondition::Ne(lhs, rhs) => {
                 let op = DslIr::IfNe(lhs, rhs, then_instructions, Default::default());
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
             IfCondition::NeI(lhs, rhs) => {
                 let op = DslIr::IfNeI(lhs, rhs, then_instructions, Default::default());
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
         }
     }
@@ -600,19 +600,19 @@ impl<'a, C: Config> IfBuilder<'a, C> {
             }
             IfCondition::Eq(lhs, rhs) => {
                 let op = DslIr::IfEq(lhs, rhs, then_instructions, else_instructions);
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
             IfCondition::EqI(lhs, rhs) => {
                 let op = DslIr::IfEqI(lhs, rhs, then_instructions, else_instructions);
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
             IfCondition::Ne(lhs, rhs) => {
                 let op = DslIr::IfNe(lhs, rhs, then_instructions, else_instructions);
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
             IfCondition::NeI(lhs, rhs) => {
                 let op = DslIr::IfNeI(lhs, rhs, then_instructions, else_instructions);
-                self.builder.operations.push(op);
+                self.builder.trace_push(op);
             }
         }
     }
@@ -725,6 +725,6 @@ impl<'a, C: Config> RangeBuilder<'a, C> {
             loop_variable,
             loop_instructions,
         );
-        self.builder.operations.push(op);
+        self.builder.trace_push(op);
     }
 }
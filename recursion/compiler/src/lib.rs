@@ -7,6 +7,7 @@ pub mod asm;
 pub mod config;
 pub mod constraints;
 pub mod ir;
+pub mod passes;
 
 pub mod prelude {
     pub use crate::asm::AsmCompiler;
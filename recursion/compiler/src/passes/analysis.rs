@@ -0,0 +1,614 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::ir::{Array, Config, DslIr, Ext, Felt, FriFoldInput, TracedVec, Usize, Var};
+
+/// A variable of the DSL, identified by its kind and index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarId {
+    Var(u32),
+    Felt(u32),
+    Ext(u32),
+}
+
+impl VarId {
+    pub const fn index(&self) -> u32 {
+        match self {
+            VarId::Var(index) | VarId::Felt(index) | VarId::Ext(index) => *index,
+        }
+    }
+}
+
+/// How an instruction accesses one of its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// The operand is written.
+    Def,
+    /// The operand is read, and can be replaced by any variable holding the same value.
+    Use,
+    /// The operand is read, but must not be replaced, e.g. the bounds of a loop, which are read
+    /// on every iteration.
+    Read,
+    /// The operand may be both read and written.
+    Clobber,
+}
+
+impl Access {
+    /// Whether the operand is read.
+    pub const fn reads(&self) -> bool {
+        !matches!(self, Access::Def)
+    }
+
+    /// Whether the operand is written.
+    pub const fn writes(&self) -> bool {
+        matches!(self, Access::Def | Access::Clobber)
+    }
+}
+
+/// A DSL variable that instructions can refer to.
+pub(crate) trait DslVar {
+    fn var_id(&self) -> VarId;
+
+    fn set_index(&mut self, index: u32);
+}
+
+impl<N> DslVar for Var<N> {
+    fn var_id(&self) -> VarId {
+        VarId::Var(self.0)
+    }
+
+    fn set_index(&mut self, index: u32) {
+        self.0 = index;
+    }
+}
+
+impl<F> DslVar for Felt<F> {
+    fn var_id(&self) -> VarId {
+        VarId::Felt(self.0)
+    }
+
+    fn set_index(&mut self, index: u32) {
+        self.0 = index;
+    }
+}
+
+impl<F, EF> DslVar for Ext<F, EF> {
+    fn var_id(&self) -> VarId {
+        VarId::Ext(self.0)
+    }
+
+    fn set_index(&mut self, index: u32) {
+        self.0 = index;
+    }
+}
+
+fn visit<T: DslVar>(access: Access, x: &mut T, f: &mut impl FnMut(Access, VarId) -> Option<VarId>) {
+    let id = x.var_id();
+    if let Some(new) = f(access, id) {
+        if access == Access::Use {
+            debug_assert_eq!(
+                std::mem::discriminant(&id),
+                std::mem::discriminant(&new),
+                "replaced {:?} with a variable of another kind",
+                id
+            );
+            x.set_index(new.index());
+        }
+    }
+}
+
+fn visit_usize<N>(
+    access: Access,
+    x: &mut Usize<N>,
+    f: &mut impl FnMut(Access, VarId) -> Option<VarId>,
+) {
+    if let Usize::Var(v) = x {
+        visit(access, v, f);
+    }
+}
+
+fn visit_array<C: Config, T: DslVar>(
+    array: &mut Array<C, T>,
+    f: &mut impl FnMut(Access, VarId) -> Option<VarId>,
+) {
+    match array {
+        Array::Fixed(values) => values.iter_mut().for_each(|x| visit(Access::Clobber, x, f)),
+        Array::Dyn(ptr, len) => {
+            visit(Access::Read, &mut ptr.address, f);
+            visit_usize(Access::Read, len, f);
+        }
+    }
+}
+
+fn visit_fri_fold_inputs<C: Config>(
+    array: &mut Array<C, FriFoldInput<C>>,
+    f: &mut impl FnMut(Access, VarId) -> Option<VarId>,
+) {
+    match array {
+        Array::Fixed(inputs) => {
+            for input in inputs.iter_mut() {
+                visit(Access::Clobber, &mut input.z, f);
+                visit(Access::Clobber, &mut input.alpha, f);
+                visit(Access::Clobber, &mut input.x, f);
+                visit(Access::Clobber, &mut input.log_height, f);
+                visit_array(&mut input.mat_opening, f);
+                visit_array(&mut input.ps_at_z, f);
+                visit_array(&mut input.alpha_pow, f);
+                visit_array(&mut input.ro, f);
+            }
+        }
+        Array::Dyn(ptr, len) => {
+            visit(Access::Read, &mut ptr.address, f);
+            visit_usize(Access::Read, len, f);
+        }
+    }
+}
+
+/// Calls `f` on every operand of an instruction with its access, reads before writes. If `f`
+/// returns a variable for an [Access::Use] operand, the operand is replaced by it.
+///
+/// The bodies of loops and branches are not visited, see [blocks_mut].
+pub(crate) fn visit_operands<C: Config>(
+    op: &mut DslIr<C>,
+    f: &mut impl FnMut(Access, VarId) -> Option<VarId>,
+) {
+    use Access::*;
+    match op {
+        DslIr::ImmV(a, _) => visit(Def, a, f),
+        DslIr::ImmF(a, _) => visit(Def, a, f),
+        DslIr::ImmE(a, _) => visit(Def, a, f),
+
+        DslIr::AddV(a, b, c)
+        | DslIr::SubV(a, b, c)
+        | DslIr::MulV(a, b, c)
+        | DslIr::LessThan(a, b, c) => {
+            visit(Use, b, f);
+            visit(Use, c, f);
+            visit(Def, a, f);
+        }
+        DslIr::AddF(a, b, c)
+        | DslIr::SubF(a, b, c)
+        | DslIr::MulF(a, b, c)
+        | DslIr::DivF(a, b, c) => {
+            visit(Use, b, f);
+            visit(Use, c, f);
+            visit(Def, a, f);
+        }
+        DslIr::AddE(a, b, c)
+        | DslIr::SubE(a, b, c)
+        | DslIr::MulE(a, b, c)
+        | DslIr::DivE(a, b, c) => {
+            visit(Use, b, f);
+            visit(Use, c, f);
+            visit(Def, a, f);
+        }
+        DslIr::AddEF(a, b, c)
+        | DslIr::SubEF(a, b, c)
+        | DslIr::MulEF(a, b, c)
+        | DslIr::DivEF(a, b, c) => {
+            visit(Use, b, f);
+            visit(Use, c, f);
+            visit(Def, a, f);
+        }
+
+        DslIr::AddVI(a, b, _) | DslIr::SubVI(a, b, _) | DslIr::MulVI(a, b, _) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::AddFI(a, b, _)
+        | DslIr::SubFI(a, b, _)
+        | DslIr::MulFI(a, b, _)
+        | DslIr::DivFI(a, b, _) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::AddEI(a, b, _)
+        | DslIr::SubEI(a, b, _)
+        | DslIr::MulEI(a, b, _)
+        | DslIr::DivEI(a, b, _) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::AddEFI(a, b, _)
+        | DslIr::SubEFI(a, b, _)
+        | DslIr::MulEFI(a, b, _)
+        | DslIr::DivEFI(a, b, _) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::AddEFFI(a, b, _) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::SubVIN(a, _, b) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::SubFIN(a, _, b) | DslIr::DivFIN(a, _, b) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::SubEIN(a, _, b) | DslIr::DivEIN(a, _, b) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::DivEFIN(a, _, b) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+
+        DslIr::NegV(a, b) | DslIr::InvV(a, b) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::NegF(a, b) | DslIr::InvF(a, b) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+        DslIr::NegE(a, b) | DslIr::InvE(a, b) => {
+            visit(Use, b, f);
+            visit(Def, a, f);
+        }
+
+        DslIr::For(start, end, _, loop_var, _) => {
+            visit_usize(Read, start, f);
+            visit_usize(Read, end, f);
+            visit(Clobber, loop_var, f);
+        }
+        DslIr::IfEq(lhs, rhs, _, _) | DslIr::IfNe(lhs, rhs, _, _) => {
+            visit(Use, lhs, f);
+            visit(Use, rhs, f);
+        }
+        DslIr::IfEqI(lhs, _, _, _) | DslIr::IfNeI(lhs, _, _, _) => visit(Use, lhs, f),
+        DslIr::Break | DslIr::Error() | DslIr::Halt | DslIr::CycleTracker(_) => {}
+
+        DslIr::AssertEqV(a, b) | DslIr::AssertNeV(a, b) => {
+            visit(Use, a, f);
+            visit(Use, b, f);
+        }
+        DslIr::AssertEqF(a, b) | DslIr::AssertNeF(a, b) => {
+            visit(Use, a, f);
+            visit(Use, b, f);
+        }
+        DslIr::AssertEqE(a, b) | DslIr::AssertNeE(a, b) => {
+            visit(Use, a, f);
+            visit(Use, b, f);
+        }
+        DslIr::AssertEqVI(a, _) | DslIr::AssertNeVI(a, _) => visit(Use, a, f),
+        DslIr::AssertEqFI(a, _) | DslIr::AssertNeFI(a, _) => visit(Use, a, f),
+        DslIr::AssertEqEI(a, _) | DslIr::AssertNeEI(a, _) => visit(Use, a, f),
+
+        DslIr::Alloc(ptr, len, _) => {
+            visit_usize(Read, len, f);
+            visit(Def, &mut ptr.address, f);
+        }
+        DslIr::LoadV(a, ptr, index) => {
+            visit(Use, &mut ptr.address, f);
+            visit_usize(Use, &mut index.index, f);
+            visit(Def, a, f);
+        }
+        DslIr::LoadF(a, ptr, index) => {
+            visit(Use, &mut ptr.address, f);
+            visit_usize(Use, &mut index.index, f);
+            visit(Def, a, f);
+        }
+        DslIr::LoadE(a, ptr, index) => {
+            visit(Use, &mut ptr.address, f);
+            visit_usize(Use, &mut index.index, f);
+            visit(Def, a, f);
+        }
+        DslIr::StoreV(a, ptr, index) => {
+            visit(Use, a, f);
+            visit(Use, &mut ptr.address, f);
+            visit_usize(Use, &mut index.index, f);
+        }
+        DslIr::StoreF(a, ptr, index) => {
+            visit(Use, a, f);
+            visit(Use, &mut ptr.address, f);
+            visit_usize(Use, &mut index.index, f);
+        }
+        DslIr::StoreE(a, ptr, index) => {
+            visit(Use, a, f);
+            visit(Use, &mut ptr.address, f);
+            visit_usize(Use, &mut index.index, f);
+        }
+
+        DslIr::CircuitNum2BitsV(value, _, bits) => {
+            visit(Use, value, f);
+            bits.iter_mut().for_each(|bit| visit(Def, bit, f));
+        }
+        DslIr::CircuitNum2BitsF(value, bits) => {
+            visit(Use, value, f);
+            bits.iter_mut().for_each(|bit| visit(Def, bit, f));
+        }
+
+        DslIr::Poseidon2PermuteBabyBear(dst, src) => {
+            visit_array(dst, f);
+            visit_array(src, f);
+        }
+        DslIr::Poseidon2CompressBabyBear(dst, left, right) => {
+            visit_array(dst, f);
+            visit_array(left, f);
+            visit_array(right, f);
+        }
+        DslIr::CircuitPoseidon2Permute(state) => {
+            state.iter_mut().for_each(|x| visit(Clobber, x, f));
+        }
+
+        DslIr::HintBitsU(bits, value) => {
+            visit_usize(Read, value, f);
+            visit_array(bits, f);
+        }
+        DslIr::HintBitsV(bits, value) => {
+            visit(Read, value, f);
+            visit_array(bits, f);
+        }
+        DslIr::HintBitsF(bits, value) => {
+            visit(Read, value, f);
+            visit_array(bits, f);
+        }
+        DslIr::PrintV(a) => visit(Use, a, f),
+        DslIr::PrintF(a) => visit(Use, a, f),
+        DslIr::PrintE(a) => visit(Use, a, f),
+        DslIr::HintExt2Felt(felts, ext) => {
+            visit(Read, ext, f);
+            visit_array(felts, f);
+        }
+        DslIr::HintLen(a) => visit(Def, a, f),
+        DslIr::HintVars(array) => visit_array(array, f),
+        DslIr::HintFelts(array) => visit_array(array, f),
+        DslIr::HintExts(array) => visit_array(array, f),
+        DslIr::WitnessVar(a, _) => visit(Def, a, f),
+        DslIr::WitnessFelt(a, _) => visit(Def, a, f),
+        DslIr::WitnessExt(a, _) => visit(Def, a, f),
+        DslIr::Commit(a, b) => {
+            visit(Use, a, f);
+            visit(Use, b, f);
+        }
+        DslIr::RegisterPublicValue(a) => visit(Use, a, f),
+        DslIr::CircuitCommitVkeyHash(a) | DslIr::CircuitCommitCommitedValuesDigest(a) => {
+            visit(Use, a, f)
+        }
+
+        DslIr::FriFold(m, inputs) => {
+            visit(Read, m, f);
+            visit_fri_fold_inputs(inputs, f);
+        }
+        DslIr::CircuitSelectV(cond, a, b, out) => {
+            visit(Use, cond, f);
+            visit(Use, a, f);
+            visit(Use, b, f);
+            visit(Def, out, f);
+        }
+        DslIr::CircuitSelectF(cond, a, b, out) => {
+            visit(Use, cond, f);
+            visit(Use, a, f);
+            visit(Use, b, f);
+            visit(Def, out, f);
+        }
+        DslIr::CircuitSelectE(cond, a, b, out) => {
+            visit(Use, cond, f);
+            visit(Use, a, f);
+            visit(Use, b, f);
+            visit(Def, out, f);
+        }
+        DslIr::CircuitExt2Felt(felts, ext) => {
+            visit(Use, ext, f);
+            felts.iter_mut().for_each(|x| visit(Def, x, f));
+        }
+        DslIr::CircuitFelts2Ext(felts, ext) => {
+            felts.iter_mut().for_each(|x| visit(Use, x, f));
+            visit(Def, ext, f);
+        }
+    }
+}
+
+/// The variables an instruction writes.
+pub(crate) fn defs<C: Config>(op: &mut DslIr<C>) -> Vec<VarId> {
+    let mut defs = Vec::new();
+    visit_operands(op, &mut |access, id| {
+        if access.writes() {
+            defs.push(id);
+        }
+        None
+    });
+    defs
+}
+
+/// The bodies of a loop or a branch.
+pub(crate) fn blocks_mut<C: Config>(op: &mut DslIr<C>) -> Vec<&mut TracedVec<DslIr<C>>> {
+    match op {
+        DslIr::For(_, _, _, _, body) => vec![body],
+        DslIr::IfEq(_, _, then_block, else_block)
+        | DslIr::IfNe(_, _, then_block, else_block)
+        | DslIr::IfEqI(_, _, then_block, else_block)
+        | DslIr::IfNeI(_, _, then_block, else_block) => vec![then_block, else_block],
+        _ => vec![],
+    }
+}
+
+/// Whether an instruction only writes its results, so it can be removed if they are never read.
+///
+/// Divisions and inversions are not pure, since they fail on zero.
+pub(crate) const fn is_pure<C: Config>(op: &DslIr<C>) -> bool {
+    matches!(
+        op,
+        DslIr::ImmV(..)
+            | DslIr::ImmF(..)
+            | DslIr::ImmE(..)
+            | DslIr::AddV(..)
+            | DslIr::AddVI(..)
+            | DslIr::AddF(..)
+            | DslIr::AddFI(..)
+            | DslIr::AddE(..)
+            | DslIr::AddEI(..)
+            | DslIr::AddEF(..)
+            | DslIr::AddEFI(..)
+            | DslIr::AddEFFI(..)
+            | DslIr::SubV(..)
+            | DslIr::SubVI(..)
+            | DslIr::SubVIN(..)
+            | DslIr::SubF(..)
+            | DslIr::SubFI(..)
+            | DslIr::SubFIN(..)
+            | DslIr::SubE(..)
+            | DslIr::SubEI(..)
+            | DslIr::SubEIN(..)
+            | DslIr::SubEFI(..)
+            | DslIr::SubEF(..)
+            | DslIr::MulV(..)
+            | DslIr::MulVI(..)
+            | DslIr::MulF(..)
+            | DslIr::MulFI(..)
+            | DslIr::MulE(..)
+            | DslIr::MulEI(..)
+            | DslIr::MulEFI(..)
+            | DslIr::MulEF(..)
+            | DslIr::NegV(..)
+            | DslIr::NegF(..)
+            | DslIr::NegE(..)
+            | DslIr::LoadV(..)
+            | DslIr::LoadF(..)
+            | DslIr::LoadE(..)
+            | DslIr::CircuitSelectV(..)
+            | DslIr::CircuitSelectF(..)
+            | DslIr::CircuitSelectE(..)
+            | DslIr::CircuitExt2Felt(..)
+            | DslIr::CircuitFelts2Ext(..)
+            | DslIr::LessThan(..)
+    )
+}
+
+/// The variables written anywhere in a block, including in nested loops and branches.
+pub(crate) fn write_set<C: Config>(block: &mut TracedVec<DslIr<C>>) -> HashSet<VarId> {
+    let mut writes = HashSet::new();
+    for op in block.vec.iter_mut() {
+        writes.extend(defs(op));
+        for body in blocks_mut(op) {
+            writes.extend(write_set(body));
+        }
+    }
+    writes
+}
+
+/// The number of instructions in a block, including the instructions in nested loops and
+/// branches.
+pub fn count_instructions<C: Config>(block: &TracedVec<DslIr<C>>) -> usize {
+    block
+        .vec
+        .iter()
+        .map(|op| match op {
+            DslIr::For(_, _, _, _, body) => 1 + count_instructions(body),
+            DslIr::IfEq(_, _, then_block, else_block)
+            | DslIr::IfNe(_, _, then_block, else_block)
+            | DslIr::IfEqI(_, _, then_block, else_block)
+            | DslIr::IfNeI(_, _, then_block, else_block) => {
+                1 + count_instructions(then_block) + count_instructions(else_block)
+            }
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Facts about the variables of a program, e.g. their constant values, at a point of a forward
+/// pass over it.
+///
+/// Every fact depends on some variables and is forgotten when one of them is written. Facts
+/// learned inside a loop or a branch are scoped to it.
+pub(crate) struct ScopedFacts<K, V> {
+    scopes: Vec<Scope<K, V>>,
+}
+
+struct Scope<K, V> {
+    facts: HashMap<K, V>,
+    dependents: HashMap<VarId, Vec<K>>,
+}
+
+impl<K, V> Default for Scope<K, V> {
+    fn default() -> Self {
+        Self {
+            facts: HashMap::new(),
+            dependents: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> ScopedFacts<K, V> {
+    pub(crate) fn new() -> Self {
+        Self {
+            scopes: vec![Scope::default()],
+        }
+    }
+
+    pub(crate) fn get(&self, key: &K) -> Option<&V> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.facts.get(key))
+    }
+
+    /// Records a fact that depends on the given variables.
+    pub(crate) fn insert(&mut self, key: K, value: V, deps: impl IntoIterator<Item = VarId>) {
+        let scope = self.scopes.last_mut().unwrap();
+        for dep in deps {
+            scope.dependents.entry(dep).or_default().push(key.clone());
+        }
+        scope.facts.insert(key, value);
+    }
+
+    /// Forgets the facts that depend on a variable.
+    ///
+    /// Only the facts of the innermost scope are forgotten: the variables written in a loop or a
+    /// branch must be killed before entering it.
+    pub(crate) fn kill(&mut self, var: VarId) {
+        let scope = self.scopes.last_mut().unwrap();
+        if let Some(keys) = scope.dependents.remove(&var) {
+            for key in keys {
+                scope.facts.remove(&key);
+            }
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+}
+
+/// Runs a forward pass over a block, calling `visit` on every instruction that is not a loop or
+/// a branch. `visit` returns the instruction to replace it with, or `None` to remove it, and must
+/// kill the variables it writes.
+///
+/// The variables written in a loop or a branch are killed before entering it, and the facts
+/// learned inside it are forgotten when leaving it.
+pub(crate) fn forward_pass<C: Config, K: Clone + Eq + Hash, V>(
+    block: &mut TracedVec<DslIr<C>>,
+    facts: &mut ScopedFacts<K, V>,
+    visit: &mut impl FnMut(DslIr<C>, &mut ScopedFacts<K, V>) -> Option<DslIr<C>>,
+) {
+    let ops = std::mem::take(block);
+    for (mut op, trace) in ops {
+        if blocks_mut(&mut op).is_empty() {
+            if let Some(op) = visit(op, facts) {
+                block.extend([(op, trace)]);
+            }
+            continue;
+        }
+        let mut writes = defs(&mut op).into_iter().collect::<HashSet<_>>();
+        for body in blocks_mut(&mut op) {
+            writes.extend(write_set(body));
+        }
+        for var in writes {
+            facts.kill(var);
+        }
+        for body in blocks_mut(&mut op) {
+            facts.push_scope();
+            forward_pass(body, facts, visit);
+            facts.pop_scope();
+        }
+        block.extend([(op, trace)]);
+    }
+}
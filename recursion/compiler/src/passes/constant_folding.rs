@@ -0,0 +1,308 @@
+use p3_field::{AbstractExtensionField, AbstractField};
+
+use super::analysis::{defs, forward_pass, DslVar, ScopedFacts, VarId};
+use super::Pass;
+use crate::ir::{Config, DslIr, Ext, Felt, TracedVec, Var};
+
+/// The value of a variable that is known at compile time.
+enum Const<C: Config> {
+    N(C::N),
+    F(C::F),
+    EF(C::EF),
+}
+
+type Consts<C> = ScopedFacts<VarId, Const<C>>;
+
+impl<C: Config> Consts<C> {
+    fn var(&self, x: &Var<C::N>) -> Option<C::N> {
+        match self.get(&x.var_id()) {
+            Some(Const::N(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn felt(&self, x: &Felt<C::F>) -> Option<C::F> {
+        match self.get(&x.var_id()) {
+            Some(Const::F(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn ext(&self, x: &Ext<C::F, C::EF>) -> Option<C::EF> {
+        match self.get(&x.var_id()) {
+            Some(Const::EF(value)) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Evaluates the arithmetic on constants at compile time, and turns arithmetic with one constant
+/// operand into its immediate form. Assertions on constants that hold are removed.
+///
+/// Only instructions that every backend supports are emitted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConstantFolding;
+
+impl<C: Config> Pass<C> for ConstantFolding {
+    fn name(&self) -> &'static str {
+        "constant folding"
+    }
+
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut consts = Consts::<C>::new();
+        forward_pass(operations, &mut consts, &mut |op, consts| {
+            let mut op = fold(op, consts)?;
+            for var in defs(&mut op) {
+                consts.kill(var);
+            }
+            match &op {
+                DslIr::ImmV(a, value) => consts.insert(a.var_id(), Const::N(*value), [a.var_id()]),
+                DslIr::ImmF(a, value) => consts.insert(a.var_id(), Const::F(*value), [a.var_id()]),
+                DslIr::ImmE(a, value) => consts.insert(a.var_id(), Const::EF(*value), [a.var_id()]),
+                _ => {}
+            }
+            Some(op)
+        });
+    }
+}
+
+/// Folds an instruction, or returns `None` if it is an assertion that holds.
+fn fold<C: Config>(op: DslIr<C>, consts: &Consts<C>) -> Option<DslIr<C>> {
+    let ext = |value: C::F| C::EF::from_base(value);
+    let folded = match op {
+        DslIr::AddV(a, b, c) => match (consts.var(&b), consts.var(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmV(a, x + y),
+            (Some(x), None) => DslIr::AddVI(a, c, x),
+            (None, Some(y)) => DslIr::AddVI(a, b, y),
+            (None, None) => op,
+        },
+        DslIr::AddVI(a, b, imm) => match consts.var(&b) {
+            Some(x) => DslIr::ImmV(a, x + imm),
+            None => op,
+        },
+        DslIr::SubV(a, b, c) => match (consts.var(&b), consts.var(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmV(a, x - y),
+            (None, Some(y)) => DslIr::AddVI(a, b, -y),
+            _ => op,
+        },
+        DslIr::SubVI(a, b, imm) => match consts.var(&b) {
+            Some(x) => DslIr::ImmV(a, x - imm),
+            None => op,
+        },
+        DslIr::SubVIN(a, imm, b) => match consts.var(&b) {
+            Some(x) => DslIr::ImmV(a, imm - x),
+            None => op,
+        },
+        DslIr::MulV(a, b, c) => match (consts.var(&b), consts.var(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmV(a, x * y),
+            (Some(x), None) => DslIr::MulVI(a, c, x),
+            (None, Some(y)) => DslIr::MulVI(a, b, y),
+            (None, None) => op,
+        },
+        DslIr::MulVI(a, b, imm) => match consts.var(&b) {
+            Some(x) => DslIr::ImmV(a, x * imm),
+            None => op,
+        },
+        DslIr::NegV(a, b) => match consts.var(&b) {
+            Some(x) => DslIr::ImmV(a, -x),
+            None => op,
+        },
+
+        DslIr::AddF(a, b, c) => match (consts.felt(&b), consts.felt(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmF(a, x + y),
+            (Some(x), None) => DslIr::AddFI(a, c, x),
+            (None, Some(y)) => DslIr::AddFI(a, b, y),
+            (None, None) => op,
+        },
+        DslIr::AddFI(a, b, imm) => match consts.felt(&b) {
+            Some(x) => DslIr::ImmF(a, x + imm),
+            None => op,
+        },
+        DslIr::SubF(a, b, c) => match (consts.felt(&b), consts.felt(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmF(a, x - y),
+            (None, Some(y)) => DslIr::AddFI(a, b, -y),
+            _ => op,
+        },
+        DslIr::SubFI(a, b, imm) => match consts.felt(&b) {
+            Some(x) => DslIr::ImmF(a, x - imm),
+            None => op,
+        },
+        DslIr::SubFIN(a, imm, b) => match consts.felt(&b) {
+            Some(x) => DslIr::ImmF(a, imm - x),
+            None => op,
+        },
+        DslIr::MulF(a, b, c) => match (consts.felt(&b), consts.felt(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmF(a, x * y),
+            _ => op,
+        },
+        DslIr::MulFI(a, b, imm) => match consts.felt(&b) {
+            Some(x) => DslIr::ImmF(a, x * imm),
+            None => op,
+        },
+        DslIr::NegF(a, b) => match consts.felt(&b) {
+            Some(x) => DslIr::ImmF(a, -x),
+            None => op,
+        },
+
+        DslIr::AddE(a, b, c) => match (consts.ext(&b), consts.ext(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmE(a, x + y),
+            (Some(x), None) => DslIr::AddEI(a, c, x),
+            (None, Some(y)) => DslIr::AddEI(a, b, y),
+            (None, None) => op,
+        },
+        DslIr::AddEI(a, b, imm) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, x + imm),
+            None => op,
+        },
+        DslIr::SubE(a, b, c) => match (consts.ext(&b), consts.ext(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmE(a, x - y),
+            (None, Some(y)) => DslIr::AddEI(a, b, -y),
+            _ => op,
+        },
+        DslIr::SubEI(a, b, imm) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, x - imm),
+            None => op,
+        },
+        DslIr::SubEIN(a, imm, b) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, imm - x),
+            None => op,
+        },
+        DslIr::MulE(a, b, c) => match (consts.ext(&b), consts.ext(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmE(a, x * y),
+            (Some(x), None) => DslIr::MulEI(a, c, x),
+            (None, Some(y)) => DslIr::MulEI(a, b, y),
+            (None, None) => op,
+        },
+        DslIr::MulEI(a, b, imm) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, x * imm),
+            None => op,
+        },
+        DslIr::NegE(a, b) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, -x),
+            None => op,
+        },
+        DslIr::AddEF(a, b, c) => match (consts.ext(&b), consts.felt(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmE(a, x + ext(y)),
+            (None, Some(y)) => DslIr::AddEFI(a, b, y),
+            _ => op,
+        },
+        DslIr::AddEFI(a, b, imm) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, x + ext(imm)),
+            None => op,
+        },
+        DslIr::AddEFFI(a, b, imm) => match consts.felt(&b) {
+            Some(x) => DslIr::ImmE(a, ext(x) + imm),
+            None => op,
+        },
+        DslIr::SubEF(a, b, c) => match (consts.ext(&b), consts.felt(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmE(a, x - ext(y)),
+            (None, Some(y)) => DslIr::AddEFI(a, b, -y),
+            _ => op,
+        },
+        DslIr::SubEFI(a, b, imm) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, x - ext(imm)),
+            None => op,
+        },
+        DslIr::MulEF(a, b, c) => match (consts.ext(&b), consts.felt(&c)) {
+            (Some(x), Some(y)) => DslIr::ImmE(a, x * ext(y)),
+            _ => op,
+        },
+        DslIr::MulEFI(a, b, imm) => match consts.ext(&b) {
+            Some(x) => DslIr::ImmE(a, x * ext(imm)),
+            None => op,
+        },
+
+        DslIr::AssertEqV(a, b) => match (consts.var(&a), consts.var(&b)) {
+            (Some(x), Some(y)) if x == y => return None,
+            (Some(_), Some(_)) => op,
+            (Some(x), None) => return fold(DslIr::AssertEqVI(b, x), consts),
+            (None, Some(y)) => return fold(DslIr::AssertEqVI(a, y), consts),
+            (None, None) => op,
+        },
+        DslIr::AssertEqVI(a, imm) if consts.var(&a) == Some(imm) => return None,
+        DslIr::AssertNeV(a, b) => match (consts.var(&a), consts.var(&b)) {
+            (Some(x), Some(y)) if x != y => return None,
+            _ => op,
+        },
+        DslIr::AssertNeVI(a, imm) => match consts.var(&a) {
+            Some(x) if x != imm => return None,
+            _ => op,
+        },
+        DslIr::AssertEqF(a, b) => match (consts.felt(&a), consts.felt(&b)) {
+            (Some(x), Some(y)) if x == y => return None,
+            (Some(_), Some(_)) => op,
+            (Some(x), None) => return fold(DslIr::AssertEqFI(b, x), consts),
+            (None, Some(y)) => return fold(DslIr::AssertEqFI(a, y), consts),
+            (None, None) => op,
+        },
+        DslIr::AssertEqFI(a, imm) if consts.felt(&a) == Some(imm) => return None,
+        DslIr::AssertNeF(a, b) => match (consts.felt(&a), consts.felt(&b)) {
+            (Some(x), Some(y)) if x != y => return None,
+            _ => op,
+        },
+        DslIr::AssertNeFI(a, imm) => match consts.felt(&a) {
+            Some(x) if x != imm => return None,
+            _ => op,
+        },
+        DslIr::AssertEqE(a, b) => match (consts.ext(&a), consts.ext(&b)) {
+            (Some(x), Some(y)) if x == y => return None,
+            (Some(_), Some(_)) => op,
+            (Some(x), None) => return fold(DslIr::AssertEqEI(b, x), consts),
+            (None, Some(y)) => return fold(DslIr::AssertEqEI(a, y), consts),
+            (None, None) => op,
+        },
+        DslIr::AssertEqEI(a, imm) if consts.ext(&a) == Some(imm) => return None,
+        DslIr::AssertNeE(a, b) => match (consts.ext(&a), consts.ext(&b)) {
+            (Some(x), Some(y)) if x != y => return None,
+            _ => op,
+        },
+        DslIr::AssertNeEI(a, imm) => match consts.ext(&a) {
+            Some(x) if x != imm => return None,
+            _ => op,
+        },
+
+        _ => op,
+    };
+    Some(folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+
+    use crate::asm::AsmBuilder;
+    use crate::ir::{Felt, Var};
+    use crate::passes::{count_instructions, Pass};
+
+    use super::*;
+
+    type F = p3_baby_bear::BabyBear;
+    type EF = p3_field::extension::BinomialExtensionField<F, 4>;
+
+    #[test]
+    fn test_constant_folding() {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let a: Felt<_> = builder.eval(F::from_canonical_u32(3));
+        let b: Felt<_> = builder.eval(a * F::from_canonical_u32(5));
+        let c: Felt<_> = builder.eval(b + a);
+        builder.assert_felt_eq(c, F::from_canonical_u32(18));
+        let n: Var<_> = builder.uninit();
+        let m: Var<_> = builder.eval(n + F::one());
+        let before = count_instructions(&builder.operations);
+
+        ConstantFolding.run(&mut builder.operations);
+
+        // The assertion holds at compile time, and `m` is computed from `n` with an immediate.
+        let ops = &builder.operations.vec;
+        assert_eq!(count_instructions(&builder.operations), before - 1);
+        assert!(!ops
+            .iter()
+            .any(|op| matches!(op, DslIr::AssertEqF(..) | DslIr::AssertEqFI(..))));
+        assert!(ops.iter().any(
+            |op| matches!(op, DslIr::ImmF(x, value) if *x == c && *value == F::from_canonical_u32(18))
+        ));
+        assert!(ops.iter().any(
+            |op| matches!(op, DslIr::AddVI(x, y, value) if *x == m && *y == n && *value == F::one())
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use super::analysis::{defs, forward_pass, visit_operands, Access, ScopedFacts, VarId};
+use super::cse::as_copy;
+use super::Pass;
+use crate::ir::{Config, DslIr, TracedVec};
+
+/// Replaces the reads of a copy of a variable by reads of the variable itself, for as long as
+/// neither is written. Dead code elimination then removes the copies that are no longer read.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CopyPropagation;
+
+impl<C: Config> Pass<C> for CopyPropagation {
+    fn name(&self) -> &'static str {
+        "copy propagation"
+    }
+
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut copies = ScopedFacts::<VarId, VarId>::new();
+        forward_pass(operations, &mut copies, &mut |mut op, copies| {
+            visit_operands(&mut op, &mut |access, id| match access {
+                Access::Use => copies.get(&id).copied(),
+                _ => None,
+            });
+            let copy = as_copy(&op);
+            if let Some((dst, src)) = copy {
+                if dst == src {
+                    return None;
+                }
+            }
+            for var in defs(&mut op) {
+                copies.kill(var);
+            }
+            if let Some((dst, src)) = copy {
+                copies.insert(dst, src, [dst, src]);
+            }
+            Some(op)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+
+    use super::*;
+    use crate::asm::AsmBuilder;
+    use crate::ir::Felt;
+    use crate::passes::analysis::DslVar;
+
+    type F = p3_baby_bear::BabyBear;
+    type EF = p3_field::extension::BinomialExtensionField<F, 4>;
+
+    #[test]
+    fn test_copy_propagation() {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let a: Felt<_> = builder.uninit();
+        let b: Felt<_> = builder.eval(a);
+        let c: Felt<_> = builder.eval(b + F::one());
+        builder.assign(a, c);
+        let d: Felt<_> = builder.eval(b * c);
+        builder.assert_felt_eq(d, F::two());
+
+        CopyPropagation.run(&mut builder.operations);
+
+        // `b` is read as `a` until `a` is written.
+        let ops = &builder.operations.vec;
+        let reads_of = |x: Felt<F>| {
+            ops.iter()
+                .filter(|op| match op {
+                    DslIr::AddFI(_, y, _) | DslIr::MulF(_, y, _) => y.var_id() == x.var_id(),
+                    _ => false,
+                })
+                .count()
+        };
+        assert_eq!(reads_of(a), 2);
+        assert_eq!(reads_of(b), 1);
+    }
+}
@@ -0,0 +1,217 @@
+use std::hash::{Hash, Hasher};
+
+use p3_field::AbstractField;
+
+use super::analysis::{defs, forward_pass, DslVar, ScopedFacts, VarId};
+use super::Pass;
+use crate::ir::{Config, DslIr, Ext, Felt, TracedVec, Var};
+
+/// An operand of an expression: a variable or an immediate.
+enum Operand<C: Config> {
+    Var(VarId),
+    N(C::N),
+    F(C::F),
+    EF(C::EF),
+}
+
+impl<C: Config> Clone for Operand<C> {
+    fn clone(&self) -> Self {
+        match self {
+            Operand::Var(id) => Operand::Var(*id),
+            Operand::N(value) => Operand::N(*value),
+            Operand::F(value) => Operand::F(*value),
+            Operand::EF(value) => Operand::EF(*value),
+        }
+    }
+}
+
+impl<C: Config> PartialEq for Operand<C> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Operand::Var(a), Operand::Var(b)) => a == b,
+            (Operand::N(a), Operand::N(b)) => a == b,
+            (Operand::F(a), Operand::F(b)) => a == b,
+            (Operand::EF(a), Operand::EF(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<C: Config> Eq for Operand<C> {}
+
+impl<C: Config> Hash for Operand<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Operand::Var(id) => id.hash(state),
+            Operand::N(value) => value.hash(state),
+            Operand::F(value) => value.hash(state),
+            Operand::EF(value) => value.hash(state),
+        }
+    }
+}
+
+/// An expression computed by an instruction, identified by its opcode and operands.
+type Expr<C> = (&'static str, Vec<Operand<C>>);
+
+/// Replaces an instruction that computes the same expression as an earlier one, whose result
+/// still holds, by a copy of that result. Copy propagation and dead code elimination then remove
+/// the copy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommonSubexpressionElimination;
+
+impl<C: Config> Pass<C> for CommonSubexpressionElimination {
+    fn name(&self) -> &'static str {
+        "common subexpression elimination"
+    }
+
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut exprs = ScopedFacts::<Expr<C>, VarId>::new();
+        forward_pass(operations, &mut exprs, &mut |mut op, exprs| {
+            let expr = expr(&op);
+            if let Some((expr, result)) = &expr {
+                if let Some(earlier) = exprs.get(expr).copied() {
+                    op = copy(*result, earlier);
+                }
+            }
+            for var in defs(&mut op) {
+                exprs.kill(var);
+            }
+            if let Some((expr, result)) = expr {
+                let mut deps = vec![result];
+                deps.extend(expr.1.iter().filter_map(|operand| match operand {
+                    Operand::Var(id) => Some(*id),
+                    _ => None,
+                }));
+                // An instruction that overwrites one of its operands computes a new expression.
+                if !deps[1..].contains(&result) {
+                    exprs.insert(expr, result, deps);
+                }
+            }
+            Some(op)
+        });
+    }
+}
+
+/// Copies a variable into another of the same kind.
+fn copy<C: Config>(dst: VarId, src: VarId) -> DslIr<C> {
+    match (dst, src) {
+        (VarId::Var(dst), VarId::Var(src)) => {
+            DslIr::AddVI(Var::new(dst), Var::new(src), C::N::zero())
+        }
+        (VarId::Felt(dst), VarId::Felt(src)) => {
+            DslIr::AddFI(Felt::new(dst), Felt::new(src), C::F::zero())
+        }
+        (VarId::Ext(dst), VarId::Ext(src)) => {
+            DslIr::AddEI(Ext::new(dst), Ext::new(src), C::EF::zero())
+        }
+        _ => unreachable!("copy between variables of different kinds"),
+    }
+}
+
+/// Whether an instruction copies a variable, and the copy's destination and source.
+pub(crate) fn as_copy<C: Config>(op: &DslIr<C>) -> Option<(VarId, VarId)> {
+    match op {
+        DslIr::AddVI(a, b, imm) if imm.is_zero() => Some((a.var_id(), b.var_id())),
+        DslIr::AddFI(a, b, imm) if imm.is_zero() => Some((a.var_id(), b.var_id())),
+        DslIr::AddEI(a, b, imm) if imm.is_zero() => Some((a.var_id(), b.var_id())),
+        DslIr::MulVI(a, b, imm) if imm.is_one() => Some((a.var_id(), b.var_id())),
+        DslIr::MulFI(a, b, imm) if imm.is_one() => Some((a.var_id(), b.var_id())),
+        DslIr::MulEI(a, b, imm) if imm.is_one() => Some((a.var_id(), b.var_id())),
+        _ => None,
+    }
+}
+
+/// The expression an instruction computes and the variable it is written to, for the pure
+/// arithmetic instructions that are not copies.
+fn expr<C: Config>(op: &DslIr<C>) -> Option<(Expr<C>, VarId)> {
+    if as_copy(op).is_some() {
+        return None;
+    }
+    let (name, mut operands, result): (_, Vec<Operand<C>>, VarId) = match op {
+        DslIr::ImmV(a, imm) => ("ImmV", vec![Operand::N(*imm)], a.var_id()),
+        DslIr::ImmF(a, imm) => ("ImmF", vec![Operand::F(*imm)], a.var_id()),
+        DslIr::ImmE(a, imm) => ("ImmE", vec![Operand::EF(*imm)], a.var_id()),
+        DslIr::AddV(a, b, c) => ("AddV", vec![var(b), var(c)], a.var_id()),
+        DslIr::AddVI(a, b, imm) => ("AddVI", vec![var(b), Operand::N(*imm)], a.var_id()),
+        DslIr::SubV(a, b, c) => ("SubV", vec![var(b), var(c)], a.var_id()),
+        DslIr::MulV(a, b, c) => ("MulV", vec![var(b), var(c)], a.var_id()),
+        DslIr::MulVI(a, b, imm) => ("MulVI", vec![var(b), Operand::N(*imm)], a.var_id()),
+        DslIr::NegV(a, b) => ("NegV", vec![var(b)], a.var_id()),
+        DslIr::AddF(a, b, c) => ("AddF", vec![var(b), var(c)], a.var_id()),
+        DslIr::AddFI(a, b, imm) => ("AddFI", vec![var(b), Operand::F(*imm)], a.var_id()),
+        DslIr::SubF(a, b, c) => ("SubF", vec![var(b), var(c)], a.var_id()),
+        DslIr::MulF(a, b, c) => ("MulF", vec![var(b), var(c)], a.var_id()),
+        DslIr::MulFI(a, b, imm) => ("MulFI", vec![var(b), Operand::F(*imm)], a.var_id()),
+        DslIr::NegF(a, b) => ("NegF", vec![var(b)], a.var_id()),
+        DslIr::AddE(a, b, c) => ("AddE", vec![var(b), var(c)], a.var_id()),
+        DslIr::AddEI(a, b, imm) => ("AddEI", vec![var(b), Operand::EF(*imm)], a.var_id()),
+        DslIr::AddEF(a, b, c) => ("AddEF", vec![var(b), var(c)], a.var_id()),
+        DslIr::AddEFI(a, b, imm) => ("AddEFI", vec![var(b), Operand::F(*imm)], a.var_id()),
+        DslIr::AddEFFI(a, b, imm) => ("AddEFFI", vec![var(b), Operand::EF(*imm)], a.var_id()),
+        DslIr::SubE(a, b, c) => ("SubE", vec![var(b), var(c)], a.var_id()),
+        DslIr::SubEF(a, b, c) => ("SubEF", vec![var(b), var(c)], a.var_id()),
+        DslIr::MulE(a, b, c) => ("MulE", vec![var(b), var(c)], a.var_id()),
+        DslIr::MulEI(a, b, imm) => ("MulEI", vec![var(b), Operand::EF(*imm)], a.var_id()),
+        DslIr::MulEF(a, b, c) => ("MulEF", vec![var(b), var(c)], a.var_id()),
+        DslIr::NegE(a, b) => ("NegE", vec![var(b)], a.var_id()),
+        DslIr::CircuitSelectV(cond, a, b, out) => {
+            ("SelectV", vec![var(cond), var(a), var(b)], out.var_id())
+        }
+        DslIr::CircuitSelectF(cond, a, b, out) => {
+            ("SelectF", vec![var(cond), var(a), var(b)], out.var_id())
+        }
+        DslIr::CircuitSelectE(cond, a, b, out) => {
+            ("SelectE", vec![var(cond), var(a), var(b)], out.var_id())
+        }
+        _ => return None,
+    };
+    // Addition and multiplication commute.
+    if matches!(name, "AddV" | "MulV" | "AddF" | "MulF" | "AddE" | "MulE") {
+        let key = |operand: &Operand<C>| match operand {
+            Operand::Var(id) => id.index(),
+            _ => unreachable!(),
+        };
+        operands.sort_by_key(key);
+    }
+    Some(((name, operands), result))
+}
+
+fn var<C: Config>(x: &impl DslVar) -> Operand<C> {
+    Operand::Var(x.var_id())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asm::AsmBuilder;
+    use crate::passes::Pass;
+
+    use super::*;
+
+    type F = p3_baby_bear::BabyBear;
+    type EF = p3_field::extension::BinomialExtensionField<F, 4>;
+
+    #[test]
+    fn test_cse() {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let a: Felt<_> = builder.uninit();
+        let b: Felt<_> = builder.uninit();
+        let x: Felt<_> = builder.eval(a * b);
+        let y: Felt<_> = builder.eval(b * a);
+        builder.assign(a, a + F::one());
+        let z: Felt<_> = builder.eval(a * b);
+        builder.assert_felt_eq(x, y);
+        builder.assert_felt_eq(x, z);
+
+        CommonSubexpressionElimination.run(&mut builder.operations);
+
+        // `y` is a copy of `x`, but `z` is not, since `a` changed.
+        let ops = &builder.operations.vec;
+        assert!(ops
+            .iter()
+            .any(|op| as_copy(op) == Some((y.var_id(), x.var_id()))));
+        assert!(ops
+            .iter()
+            .all(|op| as_copy(op).map(|(dst, _)| dst) != Some(z.var_id())));
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use super::analysis::{blocks_mut, defs, is_pure, visit_operands, DslVar, VarId};
+use super::Pass;
+use crate::ir::{Config, DslIr, TracedVec};
+
+/// Removes the pure instructions whose results are never read, and the loops and branches with
+/// empty bodies.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeadCodeElimination;
+
+impl<C: Config> Pass<C> for DeadCodeElimination {
+    fn name(&self) -> &'static str {
+        "dead code elimination"
+    }
+
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) {
+        let mut reads = HashMap::new();
+        count_reads(operations, &mut reads);
+        while remove_dead(operations, &mut reads) {}
+    }
+}
+
+/// The reads of an instruction, excluding the bodies of loops and branches.
+fn reads<C: Config>(op: &mut DslIr<C>) -> Vec<VarId> {
+    let mut reads = Vec::new();
+    visit_operands(op, &mut |access, id| {
+        if access.reads() {
+            reads.push(id);
+        }
+        None
+    });
+    reads
+}
+
+/// Counts the number of instructions reading each variable.
+fn count_reads<C: Config>(block: &mut TracedVec<DslIr<C>>, counts: &mut HashMap<VarId, usize>) {
+    for op in block.vec.iter_mut() {
+        for var in reads(op) {
+            *counts.entry(var).or_default() += 1;
+        }
+        for body in blocks_mut(op) {
+            count_reads(body, counts);
+        }
+    }
+}
+
+/// Removes the dead instructions of a block, from last to first so that the instructions only
+/// read by dead ones are removed as well. Returns whether any instruction was removed.
+fn remove_dead<C: Config>(
+    block: &mut TracedVec<DslIr<C>>,
+    counts: &mut HashMap<VarId, usize>,
+) -> bool {
+    let mut removed = false;
+    let mut live = Vec::new();
+    for (mut op, trace) in std::mem::take(block).into_iter().rev() {
+        for body in blocks_mut(&mut op) {
+            removed |= remove_dead(body, counts);
+        }
+        if is_dead(&mut op, counts) {
+            for var in reads(&mut op) {
+                *counts.get_mut(&var).unwrap() -= 1;
+            }
+            removed = true;
+        } else {
+            live.push((op, trace));
+        }
+    }
+    block.extend(live.into_iter().rev());
+    removed
+}
+
+fn is_dead<C: Config>(op: &mut DslIr<C>, counts: &HashMap<VarId, usize>) -> bool {
+    let count = |var: &VarId| counts.get(var).copied().unwrap_or_default();
+    match op {
+        // The loop variable is read by the loop itself.
+        DslIr::For(_, _, _, loop_var, body) => body.is_empty() && count(&loop_var.var_id()) == 1,
+        DslIr::IfEq(_, _, then_block, else_block)
+        | DslIr::IfNe(_, _, then_block, else_block)
+        | DslIr::IfEqI(_, _, then_block, else_block)
+        | DslIr::IfNeI(_, _, then_block, else_block) => {
+            then_block.is_empty() && else_block.is_empty()
+        }
+        _ if is_pure(op) => {
+            // An instruction like `x = x + 1` is dead if no other instruction reads `x`.
+            let reads = reads(op);
+            defs(op)
+                .iter()
+                .all(|var| count(var) == reads.iter().filter(|read| *read == var).count())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+
+    use super::*;
+    use crate::asm::AsmBuilder;
+    use crate::ir::{Felt, Var};
+
+    type F = p3_baby_bear::BabyBear;
+    type EF = p3_field::extension::BinomialExtensionField<F, 4>;
+
+    #[test]
+    fn test_dead_code_elimination() {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let a: Felt<_> = builder.uninit();
+        let b: Felt<_> = builder.eval(a * a);
+        let c: Felt<_> = builder.eval(b + a);
+        let d: Felt<_> = builder.eval(a + F::one());
+        let counter: Var<_> = builder.eval(F::zero());
+        let n: Var<_> = builder.eval(F::from_canonical_u32(4));
+        builder.range(0, n).for_each(|_, builder| {
+            builder.assign(counter, counter + F::one());
+            let _unused: Felt<_> = builder.eval(c * c);
+        });
+        builder.print_f(d);
+
+        DeadCodeElimination.run(&mut builder.operations);
+
+        // Only `d` and the print are left, since `c` and `counter` are never read.
+        let ops = &builder.operations.vec;
+        assert_eq!(ops.len(), 2);
+        assert!(matches!(ops[0], DslIr::AddFI(..)));
+        assert!(matches!(ops[1], DslIr::PrintF(..)));
+    }
+}
@@ -0,0 +1,222 @@
+//! Optimization passes over the [DslIr] of a [Builder].
+//!
+//! The DSL is not in SSA form: a variable can be written many times, e.g. by
+//! [Builder::assign] or in a loop. The passes only rely on facts that hold on every path to an
+//! instruction, and forget the facts about the variables a loop or a branch writes.
+
+mod analysis;
+mod constant_folding;
+mod copy_propagation;
+mod cse;
+mod dead_code;
+
+use core::fmt::{self, Debug, Display};
+
+pub use analysis::{count_instructions, Access, VarId};
+pub use constant_folding::ConstantFolding;
+pub use copy_propagation::CopyPropagation;
+pub use cse::CommonSubexpressionElimination;
+pub use dead_code::DeadCodeElimination;
+
+use crate::constraints::ConstraintCompiler;
+use crate::ir::{Builder, Config, DslIr, TracedVec};
+
+/// The maximum number of times the pipeline of a [PassManager] is run.
+const MAX_ITERATIONS: usize = 8;
+
+/// A transformation of a program that preserves its behavior.
+pub trait Pass<C: Config> {
+    fn name(&self) -> &'static str;
+
+    fn run(&mut self, operations: &mut TracedVec<DslIr<C>>);
+}
+
+/// Runs a pipeline of passes until it stops removing instructions.
+pub struct PassManager<C: Config> {
+    passes: Vec<Box<dyn Pass<C>>>,
+    max_iterations: usize,
+}
+
+impl<C: Config> PassManager<C> {
+    /// A pass manager without passes.
+    pub fn empty() -> Self {
+        Self {
+            passes: Vec::new(),
+            max_iterations: MAX_ITERATIONS,
+        }
+    }
+
+    pub fn with_pass(mut self, pass: impl Pass<C> + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn run(&mut self, operations: &mut TracedVec<DslIr<C>>) -> OptimizationReport {
+        let mut report = OptimizationReport::new(count_instructions(operations));
+        for _ in 0..self.max_iterations {
+            let start = count_instructions(operations);
+            for pass in self.passes.iter_mut() {
+                let before = count_instructions(operations);
+                pass.run(operations);
+                let after = count_instructions(operations);
+                report.record(pass.name(), before, after);
+            }
+            report.iterations += 1;
+            if count_instructions(operations) >= start {
+                break;
+            }
+        }
+        report.instructions.1 = count_instructions(operations);
+        report
+    }
+}
+
+impl<C: Config> Default for PassManager<C> {
+    /// Constant folding, common subexpression elimination, copy propagation and dead code
+    /// elimination, in this order.
+    fn default() -> Self {
+        Self::empty()
+            .with_pass(ConstantFolding)
+            .with_pass(CommonSubexpressionElimination)
+            .with_pass(CopyPropagation)
+            .with_pass(DeadCodeElimination)
+    }
+}
+
+/// The instruction counts before and after running the passes of a [PassManager].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizationReport {
+    /// The number of instructions before and after optimizing, see [count_instructions].
+    pub instructions: (usize, usize),
+    /// The number of constraints emitted for the program before and after optimizing, if it
+    /// was compiled to a circuit with debug logging enabled.
+    pub constraints: Option<(usize, usize)>,
+    /// The number of instructions each pass removed over all iterations, in pipeline order.
+    pub passes: Vec<(&'static str, usize)>,
+    /// The number of times the pipeline was run.
+    pub iterations: usize,
+}
+
+impl OptimizationReport {
+    fn new(instructions: usize) -> Self {
+        Self {
+            instructions: (instructions, instructions),
+            constraints: None,
+            passes: Vec::new(),
+            iterations: 0,
+        }
+    }
+
+    fn record(&mut self, name: &'static str, before: usize, after: usize) {
+        // A pass can add instructions, e.g. by splitting one.
+        let removed = before.saturating_sub(after);
+        match self.passes.iter_mut().find(|(pass, _)| *pass == name) {
+            Some((_, total)) => *total += removed,
+            None => self.passes.push((name, removed)),
+        }
+    }
+}
+
+impl Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (before, after) = self.instructions;
+        write!(f, "instructions: {} -> {}", before, after)?;
+        if let Some((before, after)) = self.constraints {
+            write!(f, ", constraints: {} -> {}", before, after)?;
+        }
+        write!(f, " ({} iterations", self.iterations)?;
+        for (name, removed) in self.passes.iter() {
+            write!(f, ", {}: -{}", name, removed)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<C: Config> Builder<C> {
+    /// Optimizes the operations of the builder with the default passes of [PassManager].
+    pub fn optimize(&mut self) -> OptimizationReport {
+        let report = PassManager::default().run(&mut self.operations);
+        tracing::info!("optimized program: {}", report);
+        report
+    }
+}
+
+impl<C: Config + Debug> Builder<C> {
+    /// Optimizes the operations of the builder like [Builder::optimize].
+    ///
+    /// If debug logging is enabled, it also reports the number of constraints the circuit backend
+    /// emits for the operations, which compiles them twice.
+    pub fn optimize_circuit(&mut self) -> OptimizationReport {
+        let count_constraints = tracing::enabled!(tracing::Level::DEBUG);
+        let constraints = |operations: &TracedVec<DslIr<C>>| {
+            ConstraintCompiler::<C>::default()
+                .emit(operations.clone())
+                .len()
+        };
+        let before = count_constraints.then(|| constraints(&self.operations));
+        let mut report = PassManager::default().run(&mut self.operations);
+        if let Some(before) = before {
+            report.constraints = Some((before, constraints(&self.operations)));
+        }
+        tracing::info!("optimized circuit: {}", report);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+
+    use super::*;
+    use crate::asm::{AsmBuilder, AsmConfig};
+    use crate::ir::{Felt, Var};
+
+    type F = p3_baby_bear::BabyBear;
+    type EF = p3_field::extension::BinomialExtensionField<F, 4>;
+
+    #[test]
+    fn test_optimize() {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let a: Felt<_> = builder.uninit();
+        let b: Felt<_> = builder.uninit();
+        let two: Felt<_> = builder.eval(F::one() + F::one());
+        let x: Felt<_> = builder.eval(a * b);
+        let y: Felt<_> = builder.eval(b * a);
+        let _unused: Felt<_> = builder.eval(x + two);
+        builder.assert_felt_eq(x, y);
+        builder.assert_felt_eq(two, F::two());
+
+        let n: Var<_> = builder.eval(F::from_canonical_u32(10));
+        builder.range(0, n).for_each(|_, builder| {
+            let _unused: Felt<_> = builder.eval(a + b);
+        });
+
+        let report = builder.optimize();
+        assert!(report.instructions.1 < report.instructions.0);
+        assert_eq!(
+            report.instructions.1,
+            count_instructions(&builder.operations)
+        );
+
+        // `y` is replaced by `x`, and the unused values, the constant assertion and the empty
+        // loop are removed.
+        let ops = &builder.operations.vec;
+        let count =
+            |f: fn(&DslIr<AsmConfig<F, EF>>) -> bool| ops.iter().filter(|&op| f(op)).count();
+        assert_eq!(count(|op| matches!(op, DslIr::MulF(..))), 1);
+        assert_eq!(
+            count(|op| matches!(op, DslIr::AddF(..) | DslIr::AddFI(..))),
+            0
+        );
+        assert_eq!(
+            count(|op| matches!(op, DslIr::AssertEqFI(..) | DslIr::For(..))),
+            0
+        );
+        assert!(report.to_string().starts_with("instructions:"));
+    }
+}
@@ -0,0 +1,35 @@
+use p3_field::AbstractField;
+use sp1_core::stark::StarkGenericConfig;
+use sp1_core::utils::BabyBearPoseidon2;
+use sp1_recursion_compiler::asm::AsmBuilder;
+use sp1_recursion_compiler::prelude::*;
+use sp1_recursion_core::air::Block;
+use sp1_recursion_core::runtime::{FrameSlot, Opcode, Runtime};
+
+#[test]
+fn test_compiler_trap_report() {
+    type SC = BabyBearPoseidon2;
+    type F = <SC as StarkGenericConfig>::Val;
+    type EF = <SC as StarkGenericConfig>::Challenge;
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    let a: Felt<_> = builder.eval(F::from_canonical_u32(3));
+    let b: Felt<_> = builder.eval(F::from_canonical_u32(4));
+    builder.assert_felt_eq(a, b);
+    builder.halt();
+
+    let program = builder.compile_program();
+
+    let config = SC::default();
+    let mut runtime = Runtime::<F, EF, _>::new(&program, config.perm.clone());
+    runtime.run();
+
+    // The report decodes the frame into the DSL variables.
+    let report = runtime.trap.expect("the failed assertion should trap");
+    assert_eq!(report.instruction.opcode, Opcode::TRAP);
+    for (felt, value) in [(a, 3), (b, 4)] {
+        let value = Block::from(F::from_canonical_u32(value));
+        assert!(report.frame.contains(&(FrameSlot::Felt(felt.0), value)));
+    }
+    assert!(report.to_string().starts_with("TRAP encountered"));
+}
@@ -0,0 +1,45 @@
+use p3_field::AbstractField;
+use sp1_core::stark::StarkGenericConfig;
+use sp1_core::utils::BabyBearPoseidon2;
+use sp1_recursion_compiler::asm::AsmBuilder;
+use sp1_recursion_compiler::prelude::*;
+use sp1_recursion_core::runtime::{Opcode, Runtime, PERMUTATION_WIDTH};
+
+#[test]
+fn test_compiler_profile() {
+    type SC = BabyBearPoseidon2;
+    type F = <SC as StarkGenericConfig>::Val;
+    type EF = <SC as StarkGenericConfig>::Challenge;
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    builder.cycle_tracker("verify");
+    let width: Var<_> = builder.eval(F::from_canonical_usize(PERMUTATION_WIDTH));
+    let mut state = builder.array(width);
+    for i in 0..PERMUTATION_WIDTH {
+        builder.set(&mut state, i, F::from_canonical_usize(i));
+    }
+    for _ in 0..2 {
+        builder.cycle_tracker("permute");
+        builder.poseidon2_permute(&state);
+        builder.cycle_tracker("permute");
+    }
+    builder.cycle_tracker("verify");
+    builder.halt();
+
+    let program = builder.compile_program();
+
+    let config = SC::default();
+    let mut runtime = Runtime::<F, EF, _>::new(&program, config.perm.clone());
+    runtime.run();
+
+    let profile = runtime.profile();
+    assert_eq!(profile.cycles, runtime.timestamp);
+    assert_eq!(profile.opcodes[&Opcode::CycleTracker], 6);
+    assert_eq!(profile.opcodes[&Opcode::Poseidon2Compress], 2);
+    assert_eq!(profile.chips.poseidon2, 2);
+
+    let verify = profile.span(&["verify"]).unwrap();
+    let permute = profile.span(&["verify", "permute"]).unwrap();
+    assert_eq!((verify.entries, permute.entries), (1, 2));
+    assert!(permute.cycles < verify.cycles);
+}
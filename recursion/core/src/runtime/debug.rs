@@ -0,0 +1,137 @@
+use core::fmt::{self, Display};
+
+use backtrace::Backtrace;
+use p3_field::PrimeField32;
+
+use super::{Instruction, STACK_START_OFFSET};
+use crate::air::Block;
+
+/// A slot of a stack frame, as laid out by the assembly compiler.
+///
+/// The `i`-th var, felt and ext of a program are stored at the frame pointer offsets
+/// `-(3i + 1)`, `-(3i + 2)` and `-3i`, below [STACK_START_OFFSET]. The slots above it are
+/// reserved, e.g. for the heap pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FrameSlot {
+    Var(u32),
+    Felt(u32),
+    Ext(u32),
+    Reserved(i32),
+}
+
+impl FrameSlot {
+    /// The slot at an offset from the frame pointer.
+    pub const fn from_offset(offset: i32) -> Self {
+        let index = -offset - STACK_START_OFFSET;
+        if index < 0 {
+            return FrameSlot::Reserved(offset);
+        }
+        let i = (index / 3) as u32;
+        match index % 3 {
+            0 => FrameSlot::Ext(i),
+            1 => FrameSlot::Var(i),
+            _ => FrameSlot::Felt(i),
+        }
+    }
+}
+
+impl Display for FrameSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameSlot::Var(i) => write!(f, "var{}", i),
+            FrameSlot::Felt(i) => write!(f, "felt{}", i),
+            FrameSlot::Ext(i) => write!(f, "ext{}", i),
+            FrameSlot::Reserved(offset) => write!(f, "fp{}", offset),
+        }
+    }
+}
+
+/// The state of the runtime at a failing instruction, mapped back to the DSL program it was
+/// compiled from.
+#[derive(Debug, Clone)]
+pub struct DebugReport<F> {
+    /// Why the instruction failed.
+    pub reason: String,
+    pub pc: usize,
+    pub clk: F,
+    pub fp: F,
+    pub instruction: Instruction<F>,
+    /// The resolved backtrace of the DSL operation the instruction was compiled from, with the
+    /// pc it was recorded at. This is the pc of the closest preceding instruction with a
+    /// backtrace, since only the instructions compiled while `SP1_DEBUG` is set have one.
+    pub backtrace: Option<(usize, Backtrace)>,
+    /// The memory operands of the instruction, with their values if they were ever written.
+    pub operands: Vec<(FrameSlot, Option<Block<F>>)>,
+    /// The values of the variables of the frame, i.e. the memory written below the frame
+    /// pointer, ordered by slot.
+    pub frame: Vec<(FrameSlot, Block<F>)>,
+}
+
+impl<F: PrimeField32> DebugReport<F> {
+    fn write_value(f: &mut fmt::Formatter<'_>, slot: FrameSlot, value: &Block<F>) -> fmt::Result {
+        match slot {
+            FrameSlot::Ext(_) => write!(f, "{:?}", value.0),
+            _ => write!(f, "{}", value.0[0]),
+        }
+    }
+}
+
+/// Prints the failing instruction, its operands and the DSL backtrace. The alternate form
+/// `{:#}` also prints the whole frame.
+impl<F: PrimeField32> Display for DebugReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} at pc={}, clk={}, fp={}: {:?} {}",
+            self.reason,
+            self.pc,
+            self.clk,
+            self.fp,
+            self.instruction.opcode,
+            self.instruction.debug
+        )?;
+        for (slot, value) in self.operands.iter() {
+            write!(f, "    {} = ", slot)?;
+            match value {
+                Some(value) => Self::write_value(f, *slot, value)?,
+                None => write!(f, "<uninitialized>")?,
+            }
+            writeln!(f)?;
+        }
+        match &self.backtrace {
+            Some((pc, backtrace)) if *pc == self.pc => writeln!(f, "Backtrace:\n{:?}", backtrace)?,
+            Some((pc, backtrace)) => writeln!(
+                f,
+                "Backtrace of the closest instruction, at pc={}:\n{:?}",
+                pc, backtrace
+            )?,
+            None => writeln!(f, "No backtrace available, compile with SP1_DEBUG=true")?,
+        }
+        if f.alternate() {
+            writeln!(f, "Frame:")?;
+            for (slot, value) in self.frame.iter() {
+                write!(f, "    {} = ", slot)?;
+                Self::write_value(f, *slot, value)?;
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_slot_from_offset() {
+        // The offsets of the assembly compiler's `Var::fp`, `Felt::fp` and `Ext::fp`.
+        for i in 0..10u32 {
+            let offset = -(i as i32 * 3 + STACK_START_OFFSET);
+            assert_eq!(FrameSlot::from_offset(offset - 1), FrameSlot::Var(i));
+            assert_eq!(FrameSlot::from_offset(offset - 2), FrameSlot::Felt(i));
+            assert_eq!(FrameSlot::from_offset(offset), FrameSlot::Ext(i));
+        }
+        assert_eq!(FrameSlot::from_offset(-4), FrameSlot::Reserved(-4));
+    }
+}
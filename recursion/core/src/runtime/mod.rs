@@ -1,13 +1,16 @@
+mod debug;
 mod instruction;
 mod opcode;
+mod profile;
 mod program;
 mod record;
 mod utils;
 
 use std::collections::VecDeque;
-use std::process::exit;
+use std::fmt::Display;
 use std::{marker::PhantomData, sync::Arc};
 
+pub use debug::*;
 use hashbrown::HashMap;
 pub use instruction::*;
 use itertools::Itertools;
@@ -16,6 +19,7 @@ use p3_poseidon2::Poseidon2;
 use p3_poseidon2::Poseidon2ExternalMatrixGeneral;
 use p3_symmetric::CryptographicPermutation;
 use p3_symmetric::Permutation;
+pub use profile::*;
 pub use program::*;
 pub use record::*;
 pub use utils::*;
@@ -35,6 +39,9 @@ pub const HEAP_PTR: i32 = -4;
 pub const HEAP_START_ADDRESS: usize = STACK_SIZE + 4;
 
 pub const STACK_SIZE: usize = 1 << 24;
+
+/// The frame pointer offset below which the variables of a program are stored, see [FrameSlot].
+pub const STACK_START_OFFSET: i32 = 16;
 pub const MEMORY_SIZE: usize = 1 << 28;
 
 /// The width of the Poseidon2 permutation.
@@ -119,6 +126,15 @@ pub struct Runtime<F: PrimeField32, EF: ExtensionField<F>, Diffusion> {
 
     pub cycle_tracker: HashMap<String, CycleTrackerEntry>,
 
+    /// The nesting of the cycle tracker spans, see [Runtime::profile].
+    pub spans: SpanTracker,
+
+    /// The number of times each opcode was executed, indexed by opcode.
+    pub opcode_counts: [usize; OPCODE_BOUND],
+
+    /// The report of the TRAP the program stopped at, if any.
+    pub trap: Option<DebugReport<F>>,
+
     // pub witness_stream: Vec<Witness<F, EF>>,
     perm: Option<
         Poseidon2<
@@ -178,6 +194,9 @@ where
             access: CpuRecord::default(),
             witness_stream: VecDeque::new(),
             cycle_tracker: HashMap::new(),
+            spans: SpanTracker::default(),
+            opcode_counts: [0; OPCODE_BOUND],
+            trap: None,
             _marker: PhantomData,
         }
     }
@@ -208,6 +227,9 @@ where
             access: CpuRecord::default(),
             witness_stream: VecDeque::new(),
             cycle_tracker: HashMap::new(),
+            spans: SpanTracker::default(),
+            opcode_counts: [0; OPCODE_BOUND],
+            trap: None,
             _marker: PhantomData,
         }
     }
@@ -224,32 +246,113 @@ where
         }
     }
 
+    /// Reports the state of the runtime at the current instruction, mapped back to the DSL
+    /// program through the backtraces recorded by the compiler.
+    pub fn debug_report(&self, reason: impl Display) -> DebugReport<F> {
+        let pc = self.pc.as_canonical_u32() as usize;
+        let instruction = self.program.instructions[pc].clone();
+
+        let backtrace = self
+            .program
+            .traces
+            .iter()
+            .take(pc + 1)
+            .enumerate()
+            .rev()
+            .find_map(|(pc, trace)| trace.clone().map(|trace| (pc, trace)))
+            .map(|(pc, mut trace)| {
+                trace.resolve();
+                (pc, trace)
+            });
+
+        let fp = self.fp.as_canonical_u32() as usize;
+        let frame_slot = |addr: usize| FrameSlot::from_offset(addr as i32 - fp as i32);
+        let mut offsets = vec![instruction.op_a];
+        if !instruction.imm_b {
+            offsets.push(instruction.op_b[0]);
+        }
+        if !instruction.imm_c {
+            offsets.push(instruction.op_c[0]);
+        }
+        let operands = offsets
+            .into_iter()
+            .map(|offset| {
+                let slot = FrameSlot::from_offset(field_to_canonical_i32(offset));
+                let addr = (self.fp + offset).as_canonical_u32() as usize;
+                (slot, self.memory.get(&addr).map(|entry| entry.value))
+            })
+            .collect();
+        let frame = self
+            .memory
+            .iter()
+            .filter(|(addr, _)| **addr < fp)
+            .map(|(addr, entry)| (frame_slot(*addr), entry.value))
+            .filter(|(slot, _)| !matches!(slot, FrameSlot::Reserved(_)))
+            .sorted_by_key(|(slot, _)| *slot)
+            .collect();
+
+        DebugReport {
+            reason: reason.to_string(),
+            pc,
+            clk: self.clk,
+            fp: self.fp,
+            instruction,
+            backtrace,
+            operands,
+            frame,
+        }
+    }
+
+    /// Panics with a [DebugReport] of the current instruction.
+    fn fail(&self, reason: impl Display) -> ! {
+        panic!("{}", self.debug_report(reason))
+    }
+
+    /// A profile of the execution so far.
+    ///
+    /// The chip rows are counted from the execution record, so the profile must be taken
+    /// before the record is moved out of the runtime.
+    pub fn profile(&self) -> RecursionProfile {
+        RecursionProfile {
+            cycles: self.timestamp,
+            opcodes: Opcode::ALL
+                .into_iter()
+                .map(|opcode| (opcode, self.opcode_counts[opcode as usize]))
+                .filter(|(_, count)| *count > 0)
+                .collect(),
+            chips: ChipRows {
+                poseidon2: self.record.poseidon2_events.len(),
+                fri_fold: self.record.fri_fold_events.len(),
+                range_check: self.record.range_check_events.values().sum(),
+                memory: self.memory.len(),
+            },
+            spans: self.spans.spans(self.timestamp),
+        }
+    }
+
     // Peek at the memory without touching the record.
     fn peek(&mut self, addr: F) -> (F, Block<F>) {
-        (
-            addr,
-            self.memory
-                .get(&(addr.as_canonical_u32() as usize))
-                .unwrap()
-                .value,
-        )
+        match self.memory.get(&(addr.as_canonical_u32() as usize)) {
+            Some(entry) => (addr, entry.value),
+            None => self.fail(format!("read of uninitialized address {}", addr)),
+        }
     }
 
     // Write to uninitialized memory.
     fn mw_uninitialized(&mut self, addr: usize, value: Block<F>) {
+        if self.uninitialized_memory.contains_key(&addr) || self.memory.contains_key(&addr) {
+            self.fail(format!("address {} already initialized", addr));
+        }
         // Write it to uninitialized memory for creating MemoryInit table later.
-        self.uninitialized_memory
-            .entry(addr)
-            .and_modify(|_| panic!("address already initialized"))
-            .or_insert(value);
+        self.uninitialized_memory.insert(addr, value);
         // Also write it to the memory map so that it can be read later.
-        self.memory
-            .entry(addr)
-            .and_modify(|_| panic!("address already initialized"))
-            .or_insert(MemoryEntry {
+        self.memory.insert(
+            addr,
+            MemoryEntry {
                 value,
                 timestamp: F::zero(),
-            });
+            },
+        );
     }
 
     /// Given a MemoryRecord event, track the range checks for the memory access.
@@ -429,6 +532,7 @@ where
                 Opcode::CycleTracker => {
                     let (a_val, b_val, c_val) = self.all_rr(&instruction);
                     let name = instruction.debug.clone();
+                    self.spans.toggle(&name, self.timestamp);
                     let entry = self.cycle_tracker.entry(name).or_default();
                     if !entry.span_entered {
                         entry.span_entered = true;
@@ -589,25 +693,13 @@ where
                     self.record.public_values[RECURSION_PUBLIC_VALUES_COL_MAP.exit_code] = F::one();
 
                     let (a_val, b_val, c_val) = self.all_rr(&instruction);
-                    let trap_pc = self.pc.as_canonical_u32() as usize;
-                    let trace = self.program.traces[trap_pc].clone();
-                    if let Some(mut trace) = trace {
-                        trace.resolve();
-                        eprintln!("TRAP encountered. Backtrace:\n{:?}", trace);
+                    let report = self.debug_report("TRAP encountered");
+                    if self.program.traces.iter().any(Option::is_some) {
+                        eprintln!("{:#}", report);
                     } else {
-                        for nearby_pc in (0..trap_pc).rev() {
-                            let trace = self.program.traces[nearby_pc].clone();
-                            if let Some(mut trace) = trace {
-                                trace.resolve();
-                                eprintln!(
-                                    "TRAP encountered at pc={}. Nearest trace at pc={}: {:?}",
-                                    trap_pc, nearby_pc, trace
-                                );
-                                exit(1);
-                            }
-                        }
-                        eprintln!("TRAP encountered. No backtrace available");
+                        eprintln!("{}", report);
                     }
+                    self.trap = Some(report);
                     (a, b, c) = (a_val, b_val, c_val);
                 }
                 Opcode::HALT => {
@@ -707,15 +799,21 @@ where
                 }
                 Opcode::HintLen => {
                     let (a_ptr, b_val, c_val) = self.alu_rr(&instruction);
-                    let a_val: Block<F> =
-                        F::from_canonical_usize(self.witness_stream[0].len()).into();
+                    let len = match self.witness_stream.front() {
+                        Some(blocks) => blocks.len(),
+                        None => self.fail("the witness stream is empty"),
+                    };
+                    let a_val: Block<F> = F::from_canonical_usize(len).into();
                     self.mw_cpu(a_ptr, a_val, MemoryAccessPosition::A);
                     (a, b, c) = (a_val, b_val, c_val);
                 }
                 Opcode::Hint => {
                     let (a_val, b_val, c_val) = self.all_rr(&instruction);
                     let dst = a_val[0].as_canonical_u32() as usize;
-                    let blocks = self.witness_stream.pop_front().unwrap();
+                    let blocks = match self.witness_stream.pop_front() {
+                        Some(blocks) => blocks,
+                        None => self.fail("the witness stream is empty"),
+                    };
                     for (i, block) in blocks.into_iter().enumerate() {
                         self.mw_uninitialized(dst + i, block);
                     }
@@ -846,6 +944,7 @@ where
             };
             self.pc = next_pc;
             self.record.cpu_events.push(event);
+            self.opcode_counts[instruction.opcode as usize] += 1;
             self.clk = next_clk;
             self.timestamp += 1;
             self.access = CpuRecord::default();
@@ -2,7 +2,7 @@ use p3_field::AbstractField;
 use serde::{Deserialize, Serialize};
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Opcode {
     // Arithmetic field instructions.
     ADD = 0,
@@ -52,7 +52,54 @@ pub enum Opcode {
     CycleTracker = 44,
 }
 
+/// One more than the largest opcode value, so that arrays indexed by opcode can hold every opcode.
+pub const OPCODE_BOUND: usize = {
+    let mut bound = 0;
+    let mut i = 0;
+    while i < Opcode::ALL.len() {
+        let value = Opcode::ALL[i] as usize + 1;
+        if value > bound {
+            bound = value;
+        }
+        i += 1;
+    }
+    bound
+};
+
 impl Opcode {
+    /// All the opcodes, in the order they are declared in.
+    pub const ALL: [Opcode; 29] = [
+        Opcode::ADD,
+        Opcode::SUB,
+        Opcode::MUL,
+        Opcode::DIV,
+        Opcode::EADD,
+        Opcode::ESUB,
+        Opcode::EMUL,
+        Opcode::EDIV,
+        Opcode::LOAD,
+        Opcode::STORE,
+        Opcode::BEQ,
+        Opcode::BNE,
+        Opcode::JAL,
+        Opcode::JALR,
+        Opcode::TRAP,
+        Opcode::HALT,
+        Opcode::Poseidon2Compress,
+        Opcode::HintBits,
+        Opcode::PrintF,
+        Opcode::PrintE,
+        Opcode::HintExt2Felt,
+        Opcode::FRIFold,
+        Opcode::HintLen,
+        Opcode::Hint,
+        Opcode::BNEINC,
+        Opcode::Commit,
+        Opcode::RegisterPublicValue,
+        Opcode::LessThanF,
+        Opcode::CycleTracker,
+    ];
+
     pub fn as_field<F: AbstractField>(&self) -> F {
         F::from_canonical_u32(*self as u32)
     }
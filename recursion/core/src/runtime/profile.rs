@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::Opcode;
+
+/// A profile of the execution of a recursion program.
+///
+/// Profiles of several executions, e.g. of all the compress programs of a proof, can be
+/// combined with [RecursionProfile::merge].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecursionProfile {
+    /// The number of cycles, i.e. of rows of the CPU chip.
+    pub cycles: usize,
+    /// The number of times each opcode was executed.
+    pub opcodes: BTreeMap<Opcode, usize>,
+    /// The number of rows of the other chips.
+    pub chips: ChipRows,
+    /// The cycle tracker spans entered at the top level, with the spans entered in them.
+    pub spans: Vec<SpanProfile>,
+}
+
+/// The number of rows the events of an execution add to each chip, before padding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChipRows {
+    /// The number of Poseidon2 permutations, which is the number of rows of the wide Poseidon2
+    /// chip.
+    pub poseidon2: usize,
+    pub fri_fold: usize,
+    /// The number of range check lookups. The range check chip has a fixed number of rows, and
+    /// the lookups set their multiplicities.
+    pub range_check: usize,
+    /// The number of memory addresses accessed, each of which is initialized and finalized by
+    /// the memory chip.
+    pub memory: usize,
+}
+
+/// The cycles spent in a cycle tracker span.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanProfile {
+    pub name: String,
+    /// The number of cycles spent in the span, including its children, over all its entries.
+    pub cycles: usize,
+    /// The number of times the span was entered.
+    pub entries: usize,
+    /// The spans entered in this span, in the order they were first entered.
+    pub children: Vec<SpanProfile>,
+}
+
+impl RecursionProfile {
+    /// Adds the counts of another profile to this one.
+    pub fn merge(&mut self, other: &RecursionProfile) {
+        self.cycles += other.cycles;
+        for (opcode, count) in other.opcodes.iter() {
+            *self.opcodes.entry(*opcode).or_default() += count;
+        }
+        self.chips.poseidon2 += other.chips.poseidon2;
+        self.chips.fri_fold += other.chips.fri_fold;
+        self.chips.range_check += other.chips.range_check;
+        self.chips.memory += other.chips.memory;
+        SpanProfile::merge_all(&mut self.spans, &other.spans);
+    }
+
+    /// The span at a path of span names, e.g. `["stage-d-verify-pcs", "stage-d-2-fri-fold"]`.
+    pub fn span(&self, path: &[&str]) -> Option<&SpanProfile> {
+        let (name, path) = path.split_first()?;
+        let span = self.spans.iter().find(|span| span.name == *name)?;
+        path.iter().try_fold(span, |span, name| {
+            span.children.iter().find(|child| child.name == *name)
+        })
+    }
+}
+
+impl SpanProfile {
+    fn merge_all(spans: &mut Vec<SpanProfile>, others: &[SpanProfile]) {
+        for other in others {
+            match spans.iter_mut().find(|span| span.name == other.name) {
+                Some(span) => {
+                    span.cycles += other.cycles;
+                    span.entries += other.entries;
+                    Self::merge_all(&mut span.children, &other.children);
+                }
+                None => spans.push(other.clone()),
+            }
+        }
+    }
+}
+
+/// Tracks the nesting of the cycle tracker spans of an execution.
+///
+/// A `CycleTracker` instruction enters the span with its name, or exits it if it was already
+/// entered, along with the spans entered since.
+#[derive(Debug, Clone, Default)]
+pub struct SpanTracker {
+    /// The spans entered so far, where the children of a span are indices into this vector.
+    spans: Vec<(SpanProfile, Vec<usize>)>,
+    /// The spans entered at the top level.
+    roots: Vec<usize>,
+    /// The spans currently entered, with the cycle they were entered at.
+    stack: Vec<(usize, usize)>,
+}
+
+impl SpanTracker {
+    pub fn toggle(&mut self, name: &str, cycle: usize) {
+        let position = self
+            .stack
+            .iter()
+            .rposition(|(index, _)| self.spans[*index].0.name == name);
+        match position {
+            Some(position) => {
+                for (index, entered) in self.stack.drain(position..) {
+                    self.spans[index].0.cycles += cycle - entered;
+                }
+            }
+            None => {
+                let siblings = match self.stack.last() {
+                    Some((parent, _)) => &self.spans[*parent].1,
+                    None => &self.roots,
+                };
+                let sibling = siblings
+                    .iter()
+                    .copied()
+                    .find(|index| self.spans[*index].0.name == name);
+                let index = match sibling {
+                    Some(index) => index,
+                    None => {
+                        let index = self.spans.len();
+                        let span = SpanProfile {
+                            name: name.to_string(),
+                            ..Default::default()
+                        };
+                        self.spans.push((span, Vec::new()));
+                        match self.stack.last() {
+                            Some((parent, _)) => self.spans[*parent].1.push(index),
+                            None => self.roots.push(index),
+                        }
+                        index
+                    }
+                };
+                self.spans[index].0.entries += 1;
+                self.stack.push((index, cycle));
+            }
+        }
+    }
+
+    /// The spans entered at the top level, where the spans that are still entered are counted
+    /// up to the given cycle.
+    pub fn spans(&self, cycle: usize) -> Vec<SpanProfile> {
+        let mut spans = self.spans.clone();
+        for (index, entered) in self.stack.iter() {
+            spans[*index].0.cycles += cycle - entered;
+        }
+        self.roots
+            .iter()
+            .map(|index| Self::build(&spans, *index))
+            .collect()
+    }
+
+    fn build(spans: &[(SpanProfile, Vec<usize>)], index: usize) -> SpanProfile {
+        let (span, children) = &spans[index];
+        SpanProfile {
+            children: children
+                .iter()
+                .map(|child| Self::build(spans, *child))
+                .collect(),
+            ..span.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_tracker() {
+        let mut tracker = SpanTracker::default();
+        tracker.toggle("verify", 0);
+        for i in 0..3 {
+            tracker.toggle("query", 10 + 10 * i);
+            tracker.toggle("query", 15 + 10 * i);
+        }
+        // Exiting a span exits the spans entered in it.
+        tracker.toggle("batch", 50);
+        tracker.toggle("verify", 60);
+        tracker.toggle("commit", 70);
+
+        let spans = tracker.spans(100);
+        assert_eq!(spans.len(), 2);
+        let verify = &spans[0];
+        assert_eq!((verify.cycles, verify.entries), (60, 1));
+        let children = verify
+            .children
+            .iter()
+            .map(|span| (span.name.as_str(), span.cycles, span.entries))
+            .collect::<Vec<_>>();
+        assert_eq!(children, [("query", 15, 3), ("batch", 10, 1)]);
+        assert_eq!((spans[1].cycles, spans[1].entries), (30, 1));
+
+        let mut profile = RecursionProfile {
+            spans,
+            ..Default::default()
+        };
+        let other = profile.clone();
+        profile.merge(&other);
+        assert_eq!(profile.span(&["verify", "query"]).unwrap().entries, 6);
+        assert!(profile.span(&["query"]).is_none());
+    }
+}
@@ -14,6 +14,16 @@ pub fn canonical_i32_to_field<F: PrimeField32>(x: i32) -> F {
     }
 }
 
+/// The inverse of [canonical_i32_to_field], for field elements encoding an `i32`.
+pub fn field_to_canonical_i32<F: PrimeField32>(x: F) -> i32 {
+    let x = x.as_canonical_u32();
+    if x > F::ORDER_U32 / 2 {
+        -((F::ORDER_U32 - x) as i32)
+    } else {
+        x as i32
+    }
+}
+
 pub fn get_heap_size_range_check_events<F: PrimeField32>(
     end_heap_address: F,
 ) -> (RangeCheckEvent, RangeCheckEvent) {
@@ -16,6 +16,7 @@ use p3_symmetric::{MultiField32PaddingFreeSponge, TruncatedPermutation};
 use serde::Deserialize;
 use serde::Serialize;
 use sp1_core::stark::StarkGenericConfig;
+use sp1_core::utils::{FriParameters, SP1SecurityConfig};
 
 use super::poseidon2::bn254_poseidon2_rc3;
 use super::utils;
@@ -63,26 +64,26 @@ pub fn outer_perm() -> OuterPerm {
     )
 }
 
-/// The FRI config for outer recursion.
-pub fn outer_fri_config() -> FriConfig<OuterChallengeMmcs> {
+/// The MMCS used to commit to the FRI layers in outer recursion.
+pub fn outer_challenge_mmcs() -> OuterChallengeMmcs {
     let perm = outer_perm();
     let hash = OuterHash::new(perm.clone()).unwrap();
     let compress = OuterCompress::new(perm.clone());
-    let challenge_mmcs = OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress));
-    let num_queries = if utils::sp1_dev_mode() {
-        1
-    } else {
-        match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 25,
-        }
-    };
-    FriConfig {
-        log_blowup: 4,
-        num_queries,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
+    OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress))
+}
+
+/// The FRI parameters for outer recursion, with a single query in development mode.
+pub fn outer_fri_parameters() -> FriParameters {
+    let mut fri_parameters = SP1SecurityConfig::from_env().wrap;
+    if utils::sp1_dev_mode() {
+        fri_parameters.num_queries = 1;
     }
+    fri_parameters
+}
+
+/// The FRI config for outer recursion.
+pub fn outer_fri_config() -> FriConfig<OuterChallengeMmcs> {
+    outer_fri_parameters().fri_config(outer_challenge_mmcs())
 }
 
 #[derive(Deserialize)]
@@ -94,7 +95,7 @@ pub struct BabyBearPoseidon2Outer {
 
 impl Clone for BabyBearPoseidon2Outer {
     fn clone(&self) -> Self {
-        Self::new()
+        Self::with_fri_parameters(self.fri_parameters())
     }
 }
 
@@ -115,12 +116,16 @@ impl From<std::marker::PhantomData<BabyBearPoseidon2Outer>> for BabyBearPoseidon
 
 impl BabyBearPoseidon2Outer {
     pub fn new() -> Self {
+        Self::with_fri_parameters(outer_fri_parameters())
+    }
+
+    pub fn with_fri_parameters(fri_parameters: FriParameters) -> Self {
         let perm = outer_perm();
         let hash = OuterHash::new(perm.clone()).unwrap();
         let compress = OuterCompress::new(perm.clone());
         let val_mmcs = OuterValMmcs::new(hash, compress);
         let dft = OuterDft {};
-        let fri_config = outer_fri_config();
+        let fri_config = fri_parameters.fri_config(outer_challenge_mmcs());
         let pcs = OuterPcs::new(27, dft, val_mmcs, fri_config);
         Self { pcs, perm }
     }
@@ -143,6 +148,10 @@ impl StarkGenericConfig for BabyBearPoseidon2Outer {
         &self.pcs
     }
 
+    fn fri_parameters(&self) -> FriParameters {
+        FriParameters::from(self.pcs.fri_config())
+    }
+
     fn challenger(&self) -> Self::Challenger {
         OuterChallenger::new(self.perm.clone()).unwrap()
     }
@@ -150,14 +159,5 @@ impl StarkGenericConfig for BabyBearPoseidon2Outer {
 
 /// The FRI config for testing recursion.
 pub fn test_fri_config() -> FriConfig<OuterChallengeMmcs> {
-    let perm = outer_perm();
-    let hash = OuterHash::new(perm.clone()).unwrap();
-    let compress = OuterCompress::new(perm.clone());
-    let challenge_mmcs = OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress));
-    FriConfig {
-        log_blowup: 1,
-        num_queries: 1,
-        proof_of_work_bits: 1,
-        mmcs: challenge_mmcs,
-    }
+    FriParameters::new(1, 1, 1).fri_config(outer_challenge_mmcs())
 }
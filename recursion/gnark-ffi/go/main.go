@@ -47,11 +47,11 @@ func ProvePlonkBn254(dataDir *C.char, witnessPath *C.char) *C.C_PlonkBn254Proof
 }
 
 //export BuildPlonkBn254
-func BuildPlonkBn254(dataDir *C.char) {
+func BuildPlonkBn254(dataDir *C.char, devMode C.int) {
 	// Sanity check the required arguments have been provided.
 	dataDirString := C.GoString(dataDir)
 
-	sp1.Build(dataDirString)
+	sp1.Build(dataDirString, devMode != 0)
 }
 
 //export VerifyPlonkBn254
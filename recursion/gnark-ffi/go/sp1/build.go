@@ -5,7 +5,6 @@ import (
 	"fmt"
 	"log"
 	"os"
-	"strings"
 
 	"github.com/consensys/gnark-crypto/ecc"
 	"github.com/consensys/gnark-crypto/kzg"
@@ -16,7 +15,11 @@ import (
 	"github.com/succinctlabs/sp1-recursion-gnark/sp1/trusted_setup"
 )
 
-func Build(dataDir string) {
+// Build compiles the circuit and generates the proving and verifying keys in dataDir.
+//
+// In dev mode, an unsafe SRS is generated locally. Otherwise, the trusted setup at
+// dataDir/srs.bin is used, and downloaded from the Aztec Ignition ceremony if it doesn't exist.
+func Build(dataDir string, devMode bool) {
 	// Set the enviroment variable for the constraints file.
 	//
 	// TODO: There might be some non-determinism if a single process is running this command
@@ -59,7 +62,7 @@ func Build(dataDir string) {
 	}
 	defer srsLagrangeFile.Close()
 
-	if !strings.Contains(dataDir, "dev") {
+	if !devMode {
 		if _, err := os.Stat(srsFileName); os.IsNotExist(err) {
 			fmt.Println("downloading aztec ignition srs")
 			trusted_setup.DownloadAndSaveAztecIgnitionSrs(174, srsFileName)
@@ -82,6 +85,9 @@ func Build(dataDir string) {
 				panic(err)
 			}
 		} else {
+			// Use the trusted setup that was provided in the data directory and derive the
+			// lagrange form from it, so that the build doesn't need network access.
+			fmt.Println("using existing srs at " + srsFileName)
 			srsFile, err := os.Open(srsFileName)
 			if err != nil {
 				panic(err)
@@ -94,14 +100,8 @@ func Build(dataDir string) {
 				panic(err)
 			}
 
-			srsLagrangeFile, err := os.Open(srsLagrangeFileName)
-			if err != nil {
-				panic(err)
-			}
-			defer srsLagrangeFile.Close()
-
-			_, err = srsLagrange.ReadFrom(srsLagrangeFile)
-			srsLagrangeFile.Close()
+			srsLagrange = trusted_setup.ToLagrange(scs, srs)
+			_, err = srsLagrange.WriteTo(srsLagrangeFile)
 			if err != nil {
 				panic(err)
 			}
@@ -38,13 +38,13 @@ pub fn prove_plonk_bn254(data_dir: &str, witness_path: &str) -> PlonkBn254Proof
     }
 }
 
-pub fn build_plonk_bn254(data_dir: &str) {
+pub fn build_plonk_bn254(data_dir: &str, dev_mode: bool) {
     cfg_if! {
         if #[cfg(feature = "plonk")] {
             let data_dir = CString::new(data_dir).expect("CString::new failed");
 
             unsafe {
-                bind::BuildPlonkBn254(data_dir.as_ptr() as *mut c_char);
+                bind::BuildPlonkBn254(data_dir.as_ptr() as *mut c_char, dev_mode as i32);
             }
         } else {
             panic!("plonk feature not enabled");
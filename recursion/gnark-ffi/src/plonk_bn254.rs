@@ -55,7 +55,15 @@ impl PlonkBn254Prover {
     }
 
     /// Builds the PLONK circuit locally.
-    pub fn build<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>, build_dir: PathBuf) {
+    ///
+    /// In dev mode, an unsafe SRS is generated locally instead of using the trusted setup, so the
+    /// artifacts must not be used in production.
+    pub fn build<C: Config>(
+        constraints: Vec<Constraint>,
+        witness: Witness<C>,
+        build_dir: PathBuf,
+        dev_mode: bool,
+    ) {
         let serialized = serde_json::to_string(&constraints).unwrap();
 
         // Write constraints.
@@ -70,7 +78,7 @@ impl PlonkBn254Prover {
         let serialized = serde_json::to_string(&gnark_witness).unwrap();
         file.write_all(serialized.as_bytes()).unwrap();
 
-        build_plonk_bn254(build_dir.to_str().unwrap());
+        build_plonk_bn254(build_dir.to_str().unwrap(), dev_mode);
 
         // Write the corresponding asset files to the build dir.
         let sp1_mock_verifier_path = build_dir.join("SP1MockVerifier.sol");
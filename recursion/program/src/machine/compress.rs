@@ -42,7 +42,7 @@ pub struct SP1CompressVerifier<C: Config, SC: StarkGenericConfig, A> {
 }
 
 /// The different types of programs that can be verified by the `SP1ReduceVerifier`.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ReduceProgramType {
     /// A batch of proofs that are all SP1 Core proofs.
     Core = 0,
@@ -98,6 +98,8 @@ where
 
         builder.halt();
 
+        builder.optimize();
+
         builder.compile_program()
     }
 }
@@ -22,7 +22,7 @@ use sp1_recursion_compiler::prelude::*;
 use crate::challenger::{CanObserveVariable, DuplexChallengerVariable};
 use crate::fri::TwoAdicFriPcsVariable;
 use crate::hints::Hintable;
-use crate::stark::StarkVerifier;
+use crate::stark::{StarkVerifier, EMPTY};
 use crate::types::ShardProofVariable;
 use crate::types::VerifyingKeyVariable;
 use crate::utils::{const_fri_config, felt2var, get_challenger_public_values, hash_vkey, var2felt};
@@ -73,6 +73,8 @@ impl SP1RecursiveVerifier<InnerConfig, BabyBearPoseidon2> {
 
         builder.halt();
 
+        builder.optimize();
+
         builder.compile_program()
     }
 }
@@ -160,6 +162,12 @@ where
         let cumulative_sum: Ext<_, _> = builder.eval(C::EF::zero().cons());
         let current_pc: Felt<_> = builder.uninit();
         let exit_code: Felt<_> = builder.uninit();
+        let cpu_chip_id = machine
+            .chips()
+            .iter()
+            .position(|chip| chip.name() == "CPU")
+            .unwrap();
+
         // Verify proofs, validate transitions, and update accumulation variables.
         builder.range(0, shard_proofs.len()).for_each(|i, builder| {
             // Load the proof.
@@ -253,9 +261,20 @@ where
 
             // Assert that the start_pc of the proof is equal to the current pc.
             builder.assert_felt_eq(current_pc, public_values.start_pc);
-            // Assert that the start_pc is not zero (this means program has halted in a non-last
-            // shard).
-            builder.assert_felt_ne(public_values.start_pc, C::F::zero());
+            // If the shard has a CPU chip, assert that the start_pc is not zero (this means program
+            // has halted in a previous shard). Precompile shards have no CPU chip and don't
+            // execute any instructions, so assert that their pc doesn't change.
+            let cpu_index = builder.get(&proof.sorted_idxs, cpu_chip_id);
+            builder
+                .if_eq(cpu_index, C::N::from_canonical_usize(EMPTY))
+                .then_or_else(
+                    |builder| {
+                        builder.assert_felt_eq(public_values.start_pc, public_values.next_pc);
+                    },
+                    |builder| {
+                        builder.assert_felt_ne(public_values.start_pc, C::F::zero());
+                    },
+                );
 
             // Assert that the shard of the proof is equal to the current shard.
             builder.assert_felt_eq(current_shard, public_values.shard);
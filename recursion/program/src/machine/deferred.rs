@@ -93,6 +93,8 @@ where
 
         builder.halt();
 
+        builder.optimize();
+
         builder.compile_program()
     }
 }
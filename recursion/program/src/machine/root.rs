@@ -64,6 +64,8 @@ where
 
         SP1RootVerifier::verify(&mut builder, &pcs, machine, vk, &proof, is_compress);
 
+        builder.optimize();
+
         builder.compile_program()
     }
 }
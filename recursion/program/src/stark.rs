@@ -352,7 +352,7 @@ where
                 builder.assert_var_ne(index, C::N::from_canonical_usize(EMPTY));
             }
 
-            if chip.name() == "CPU" {
+            if chip.name() == "CPU" && !machine.allows_precompile_shards() {
                 builder.assert_var_ne(index, C::N::from_canonical_usize(EMPTY));
             }
 
@@ -0,0 +1,15 @@
+//! A simple program that aggregates the proofs of multiple programs proven with the zkVM.
+//!
+//! This is the program used by `ProverClient::aggregate`.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    // Read the verification keys and public values of the proofs, verify the proofs, and commit
+    // to them with the commitment scheme chosen by the host.
+    //
+    // To do something more interesting with the proofs, read the inputs and call
+    // `sp1_zkvm::aggregation::verify_proofs` directly.
+    sp1_zkvm::aggregation::aggregate();
+}
@@ -0,0 +1,118 @@
+//! Aggregation of compressed proofs into a single proof, with [crate::ProverClient::aggregate].
+//!
+//! The aggregated proof is a proof of [AGGREGATION_ELF], which verifies the proofs as deferred
+//! proofs and commits to their verifying keys and public values with an [AggregationCommitment].
+//! With [AggregationCommitment::MerkleRoot], an [AggregationInclusionProof] shows that a single
+//! proof is part of the aggregation, without revealing the others.
+
+use anyhow::{ensure, Result};
+pub use sp1_primitives::aggregation::AggregationCommitment;
+use sp1_primitives::aggregation::{
+    aggregation_leaf, aggregation_leaves, commit_aggregation, AGGREGATION_MERKLE_HASH,
+};
+use sp1_primitives::merkle::MerkleProof;
+
+use crate::{
+    HashableKey, MerkleHash, SP1CompressedProof, SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+};
+
+/// The program that aggregates proofs, built from `sdk/programs/aggregation`.
+///
+/// It must be rebuilt with `cargo prove build` whenever `sp1_zkvm::aggregation` changes, since
+/// the public values it commits are checked against [aggregation_public_values].
+pub const AGGREGATION_ELF: &[u8] = include_bytes!("../programs/elf/riscv32im-succinct-zkvm-elf");
+
+/// A proof that a program with the given verifying key and public values is part of an aggregation
+/// committed with [AggregationCommitment::MerkleRoot].
+pub type AggregationInclusionProof = MerkleProof;
+
+/// Writes the input of [AGGREGATION_ELF] aggregating the given proofs.
+///
+/// `merkle_hashes` gives, for each proof, the hash of the Merkle tree its program commits the
+/// public values with using `sp1_zkvm::merkle`, or `None` if it commits their SHA-256 hash.
+pub fn aggregation_stdin(
+    proofs: Vec<SP1CompressedProof>,
+    vks: &[SP1VerifyingKey],
+    merkle_hashes: &[Option<MerkleHash>],
+    commitment: AggregationCommitment,
+) -> Result<SP1Stdin> {
+    ensure!(
+        proofs.len() == vks.len(),
+        "expected a verifying key for each of the {} proofs, got {}",
+        proofs.len(),
+        vks.len()
+    );
+    ensure!(
+        proofs.len() == merkle_hashes.len(),
+        "expected a Merkle hash for each of the {} proofs, got {}",
+        proofs.len(),
+        merkle_hashes.len()
+    );
+
+    let mut stdin = SP1Stdin::new();
+    let vkeys = vks.iter().map(|vk| vk.hash_u32()).collect::<Vec<_>>();
+    stdin.write::<Vec<[u32; 8]>>(&vkeys);
+    let public_values = proofs
+        .iter()
+        .map(|proof| proof.public_values.to_vec())
+        .collect::<Vec<_>>();
+    stdin.write::<Vec<Vec<u8>>>(&public_values);
+    stdin.write::<Vec<Option<MerkleHash>>>(&merkle_hashes.to_vec());
+    stdin.write(&commitment);
+
+    // The proofs are not read by the program, but verified by the prover as deferred proofs.
+    for (proof, vk) in proofs.into_iter().zip(vks.iter()) {
+        stdin.write_proof(proof.proof, vk.vk.clone());
+    }
+    Ok(stdin)
+}
+
+/// The public values of the aggregation of proofs with the given verifying keys and public values.
+pub fn aggregation_public_values(
+    vks: &[SP1VerifyingKey],
+    public_values: &[SP1PublicValues],
+    commitment: AggregationCommitment,
+) -> Vec<u8> {
+    let vkeys = vks.iter().map(|vk| vk.hash_u32()).collect::<Vec<_>>();
+    let public_values = public_values
+        .iter()
+        .map(|values| values.to_vec())
+        .collect::<Vec<_>>();
+    commit_aggregation(commitment, &vkeys, &public_values)
+}
+
+/// Proves that the proof at `index` is part of the aggregation of proofs with the given verifying
+/// keys and public values, committed with [AggregationCommitment::MerkleRoot].
+pub fn aggregation_inclusion_proof(
+    vks: &[SP1VerifyingKey],
+    public_values: &[SP1PublicValues],
+    index: usize,
+) -> AggregationInclusionProof {
+    let vkeys = vks.iter().map(|vk| vk.hash_u32()).collect::<Vec<_>>();
+    let public_values = public_values
+        .iter()
+        .map(|values| values.to_vec())
+        .collect::<Vec<_>>();
+    let leaves = aggregation_leaves(&vkeys, &public_values);
+    AGGREGATION_MERKLE_HASH.prove(&leaves, index)
+}
+
+/// Checks that a proof with the given verifying key and public values is part of the aggregation
+/// whose public values are the Merkle `root`.
+pub fn verify_aggregation_inclusion(
+    root: &[u8],
+    vk: &SP1VerifyingKey,
+    public_values: &SP1PublicValues,
+    proof: &AggregationInclusionProof,
+) -> Result<()> {
+    let root: [u8; 32] = root
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("the Merkle root must be 32 bytes"))?;
+    let leaf = aggregation_leaf(&vk.hash_u32(), public_values.as_slice());
+    ensure!(
+        AGGREGATION_MERKLE_HASH.verify(&root, &leaf, proof),
+        "the proof at index {} is not part of the aggregation",
+        proof.index
+    );
+    Ok(())
+}
@@ -0,0 +1,44 @@
+//! A self-hosted prover network server.
+//!
+//! The server is configured through the following environment variables:
+//!
+//! - `SP1_NETWORK_SERVER_ADDR`: the address to listen on, `0.0.0.0:3000` by default.
+//! - `SP1_NETWORK_SERVER_DIR`: the directory of the job store, `~/.sp1/network-server` by default.
+//! - `SP1_NETWORK_SERVER_URL`: the url clients reach the server at, used for artifact urls.
+//! - `SP1_NETWORK_SERVER_CLAIM_TIMEOUT`: the seconds a prover has to fulfill a claimed request, one
+//!   hour by default.
+
+use std::{env, net::SocketAddr, time::Duration};
+
+use anyhow::Result;
+use sp1_sdk::network::{
+    server::{NetworkServer, DEFAULT_CLAIM_TIMEOUT},
+    store::JobStore,
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    sp1_sdk::utils::setup_logger();
+
+    let addr: SocketAddr = env::var("SP1_NETWORK_SERVER_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:3000".to_string())
+        .parse()?;
+    let dir = match env::var("SP1_NETWORK_SERVER_DIR") {
+        Ok(dir) => dir.into(),
+        Err(_) => dirs::home_dir()
+            .unwrap()
+            .join(".sp1")
+            .join("network-server"),
+    };
+    let public_url =
+        env::var("SP1_NETWORK_SERVER_URL").unwrap_or_else(|_| format!("http://{}", addr));
+
+    let claim_timeout = match env::var("SP1_NETWORK_SERVER_CLAIM_TIMEOUT") {
+        Ok(secs) => Duration::from_secs(secs.parse()?),
+        Err(_) => DEFAULT_CLAIM_TIMEOUT,
+    };
+
+    let server =
+        NetworkServer::new(JobStore::open(dir)?, public_url).with_claim_timeout(claim_timeout);
+    server.serve(addr).await
+}
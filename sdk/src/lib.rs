@@ -9,6 +9,7 @@
 pub mod proto {
     pub mod network;
 }
+pub mod aggregation;
 pub mod artifacts;
 #[cfg(feature = "network")]
 pub mod network;
@@ -23,8 +24,9 @@ pub mod utils {
 use cfg_if::cfg_if;
 use std::{env, fmt::Debug, fs::File, path::Path};
 
-use anyhow::{Ok, Result};
+use anyhow::{ensure, Ok, Result};
 
+pub use aggregation::{AggregationCommitment, AggregationInclusionProof};
 pub use provers::{LocalProver, MockProver, Prover};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -32,9 +34,12 @@ use sp1_core::{
     runtime::ExecutionReport,
     stark::{MachineVerificationError, ShardProof},
 };
+pub use sp1_primitives::merkle::{MerkleHash, MerkleProof};
+use sp1_prover::SP1CoreProofData;
 pub use sp1_prover::{
-    CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, SP1Prover, SP1ProvingKey,
-    SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+    BabyBearBlake3, BabyBearKeccak, CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof,
+    SP1CoreConfig, SP1CoreProver, SP1Prover, SP1ProvingKey, SP1PublicValues, SP1Stdin,
+    SP1VerifyingKey,
 };
 
 /// A client for interacting with SP1.
@@ -53,9 +58,10 @@ pub struct SP1ProofWithPublicValues<P> {
     pub public_values: SP1PublicValues,
 }
 
-/// A [SP1ProofWithPublicValues] generated with [ProverClient::prove].
-pub type SP1Proof = SP1ProofWithPublicValues<Vec<ShardProof<CoreSC>>>;
-pub type SP1ProofVerificationError = MachineVerificationError<CoreSC>;
+/// A [SP1ProofWithPublicValues] generated with [ProverClient::prove], or with
+/// [ProverClient::prove_with_config] for configs other than [CoreSC].
+pub type SP1Proof<SC = CoreSC> = SP1ProofWithPublicValues<Vec<ShardProof<SC>>>;
+pub type SP1ProofVerificationError<SC = CoreSC> = MachineVerificationError<SC>;
 
 /// A [SP1ProofWithPublicValues] generated with [ProverClient::prove_compressed].
 pub type SP1CompressedProof = SP1ProofWithPublicValues<ShardProof<InnerSC>>;
@@ -196,7 +202,7 @@ impl ProverClient {
         elf: &[u8],
         stdin: SP1Stdin,
     ) -> Result<(SP1PublicValues, ExecutionReport)> {
-        Ok(SP1Prover::execute(elf, &stdin)?)
+        Ok(self.prover.sp1_prover().execute_verified(elf, &stdin)?)
     }
 
     /// Setup a program to be proven and verified by the SP1 RISC-V zkVM by computing the proving
@@ -312,6 +318,65 @@ impl ProverClient {
         self.prover.prove_plonk(pk, stdin)
     }
 
+    /// Setup a program to be proven under the STARK config `SC` with [Self::prove_with_config].
+    ///
+    /// The config selects the hash used to commit to the traces of the proof, e.g.
+    /// [BabyBearKeccak] for proofs whose commitments are checked on the EVM. Proofs under configs
+    /// other than [CoreSC] can't be compressed, and are always generated locally.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{BabyBearKeccak, ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup_with_config::<BabyBearKeccak>(elf);
+    /// ```
+    pub fn setup_with_config<SC: SP1CoreConfig>(
+        &self,
+        elf: &[u8],
+    ) -> (SP1ProvingKey<SC>, SP1VerifyingKey<SC>) {
+        SP1CoreProver::<SC>::new().setup(elf)
+    }
+
+    /// Proves the execution of the given program with the given input under the STARK config
+    /// `SC`. See [Self::setup_with_config].
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{BabyBearKeccak, ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup_with_config::<BabyBearKeccak>(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let proof = client.prove_with_config(&pk, stdin).unwrap();
+    /// client.verify_with_config(&proof, &vk).unwrap();
+    /// ```
+    pub fn prove_with_config<SC: SP1CoreConfig>(
+        &self,
+        pk: &SP1ProvingKey<SC>,
+        stdin: SP1Stdin,
+    ) -> Result<SP1Proof<SC>> {
+        let proof = SP1CoreProver::<SC>::new().prove(pk, &stdin)?;
+        Ok(SP1ProofWithPublicValues {
+            proof: proof.proof.0,
+            stdin: proof.stdin,
+            public_values: proof.public_values,
+        })
+    }
+
+    /// Verifies that the given proof under the STARK config `SC` is valid and matches the given
+    /// verification key produced by [Self::setup_with_config].
+    pub fn verify_with_config<SC: SP1CoreConfig>(
+        &self,
+        proof: &SP1Proof<SC>,
+        vkey: &SP1VerifyingKey<SC>,
+    ) -> Result<(), SP1ProofVerificationError<SC>> {
+        SP1CoreProver::<SC>::new().verify(&SP1CoreProofData(proof.proof.clone()), vkey)
+    }
+
     /// Verifies that the given proof is valid and matches the given verification key produced by
     /// [Self::setup].
     ///
@@ -394,7 +459,124 @@ impl ProverClient {
     /// client.verify_plonk(&proof, &vk).unwrap();
     /// ```
     pub fn verify_plonk(&self, proof: &SP1PlonkBn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
-        self.prover.verify_plonk(proof, vkey)
+        self.prover.verify_plonk(proof, vkey, None)
+    }
+
+    /// Verifies that the given PLONK proof is valid and matches the given verification key, for a
+    /// program that commits to its public values with a Merkle tree of the given hash using
+    /// `sp1_zkvm::merkle`.
+    ///
+    /// The proof commits to the root of the tree instead of the SHA-256 hash of the public values.
+    /// The stock Solidity verifier hashes `publicValues` with SHA-256, so such proofs need a
+    /// contract that takes the root as the committed digest instead.
+    pub fn verify_plonk_merkle(
+        &self,
+        proof: &SP1PlonkBn254Proof,
+        vkey: &SP1VerifyingKey,
+        merkle_hash: MerkleHash,
+    ) -> Result<()> {
+        self.prover.verify_plonk(proof, vkey, Some(merkle_hash))
+    }
+
+    /// Setup the program that aggregates proofs with [Self::aggregate], computing its proving and
+    /// verifying keys.
+    pub fn aggregation_setup(&self) -> (SP1ProvingKey, SP1VerifyingKey) {
+        self.setup(aggregation::AGGREGATION_ELF)
+    }
+
+    /// Aggregates compressed proofs of programs with the given verifying keys into a single
+    /// compressed proof.
+    ///
+    /// The proofs are verified as deferred proofs of a proof of [aggregation::AGGREGATION_ELF],
+    /// whose public values commit to the verifying keys and public values of the proofs with the
+    /// given scheme. The aggregated proof can be verified with [Self::verify_aggregated], and
+    /// compressed further like any proof of the program with the proving key from
+    /// [Self::aggregation_setup] and [aggregation::aggregation_stdin].
+    ///
+    /// `merkle_hashes` gives, for each proof, the hash of the Merkle tree its program commits the
+    /// public values with using `sp1_zkvm::merkle`, or `None` if it commits their SHA-256 hash as
+    /// done by default.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{AggregationCommitment, ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proofs = [10usize, 20]
+    ///     .iter()
+    ///     .map(|n| {
+    ///         let mut stdin = SP1Stdin::new();
+    ///         stdin.write(n);
+    ///         client.prove_compressed(&pk, stdin).unwrap()
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// let public_values = proofs.iter().map(|p| p.public_values.clone()).collect::<Vec<_>>();
+    /// let vks = vec![vk.clone(), vk];
+    ///
+    /// let commitment = AggregationCommitment::MerkleRoot;
+    /// let proof = client
+    ///     .aggregate(proofs, &vks, &[None, None], commitment)
+    ///     .unwrap();
+    /// client
+    ///     .verify_aggregated(&proof, &vks, &public_values, commitment)
+    ///     .unwrap();
+    /// ```
+    pub fn aggregate(
+        &self,
+        proofs: Vec<SP1CompressedProof>,
+        vks: &[SP1VerifyingKey],
+        merkle_hashes: &[Option<MerkleHash>],
+        commitment: AggregationCommitment,
+    ) -> Result<SP1CompressedProof> {
+        let public_values = proofs
+            .iter()
+            .map(|proof| proof.public_values.clone())
+            .collect::<Vec<_>>();
+        let stdin = aggregation::aggregation_stdin(proofs, vks, merkle_hashes, commitment)?;
+        let (pk, _) = self.aggregation_setup();
+        let proof = self.prove_compressed(&pk, stdin)?;
+
+        // An aggregation program built from an older version of the zkVM may commit to the proofs
+        // with a different scheme, which would only be noticed when verifying.
+        ensure!(
+            proof.public_values.as_slice()
+                == aggregation::aggregation_public_values(vks, &public_values, commitment),
+            "the aggregation program committed to the proofs with a different scheme than {:?}, \
+             it may need to be rebuilt from sdk/programs/aggregation",
+            commitment
+        );
+        Ok(proof)
+    }
+
+    /// Verifies that the given proof produced by [Self::aggregate] is valid, and that it
+    /// aggregates proofs with the given verifying keys and public values, committed with the given
+    /// scheme.
+    ///
+    /// To check that a single proof is part of an aggregation committed with
+    /// [AggregationCommitment::MerkleRoot], see [aggregation::verify_aggregation_inclusion].
+    pub fn verify_aggregated(
+        &self,
+        proof: &SP1CompressedProof,
+        vks: &[SP1VerifyingKey],
+        public_values: &[SP1PublicValues],
+        commitment: AggregationCommitment,
+    ) -> Result<()> {
+        ensure!(
+            vks.len() == public_values.len(),
+            "expected public values for each of the {} verifying keys, got {}",
+            vks.len(),
+            public_values.len()
+        );
+        let (_, vk) = self.aggregation_setup();
+        self.verify_compressed(proof, &vk)?;
+        ensure!(
+            proof.public_values.as_slice()
+                == aggregation::aggregation_public_values(vks, public_values, commitment),
+            "the public values of the proof don't match the aggregated proofs"
+        );
+        Ok(())
     }
 }
 
@@ -427,7 +609,7 @@ impl SP1PlonkBn254Proof {
 #[cfg(test)]
 mod tests {
 
-    use crate::{utils, ProverClient, SP1Stdin};
+    use crate::{aggregation, utils, AggregationCommitment, MerkleHash, ProverClient, SP1Stdin};
 
     #[test]
     fn test_execute() {
@@ -476,4 +658,109 @@ mod tests {
         let proof = client.prove_plonk(&pk, stdin).unwrap();
         client.verify_plonk(&proof, &vk).unwrap();
     }
+
+    #[test]
+    fn test_e2e_prove_mock_invalid_deferred_proof() {
+        utils::setup_logger();
+        let keccak_elf = include_bytes!("../../tests/keccak256/elf/riscv32im-succinct-zkvm-elf");
+        let (keccak_pk, keccak_vk) = ProverClient::local().setup(keccak_elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&1usize);
+        stdin.write(&vec![0u8, 0, 0]);
+        let deferred_proof = ProverClient::local()
+            .prove_compressed(&keccak_pk, stdin)
+            .unwrap();
+
+        // The vkey digest the program verifies against doesn't match the deferred proof.
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&[0u32; 8]);
+        stdin.write(&vec![deferred_proof.public_values.as_slice().to_vec()]);
+        stdin.write_proof(deferred_proof.proof, keccak_vk.vk);
+
+        let client = ProverClient::mock();
+        let elf = include_bytes!("../../tests/verify-proof/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, _) = client.setup(elf);
+        assert!(client.prove(&pk, stdin.clone()).is_err());
+        assert!(client.prove_plonk(&pk, stdin).is_err());
+    }
+
+    #[test]
+    fn test_e2e_aggregate() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let proofs = [10usize, 20]
+            .iter()
+            .map(|n| {
+                let mut stdin = SP1Stdin::new();
+                stdin.write(n);
+                client.prove_compressed(&pk, stdin).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let public_values = proofs
+            .iter()
+            .map(|proof| proof.public_values.clone())
+            .collect::<Vec<_>>();
+        let vks = vec![vk.clone(), vk.clone()];
+
+        for commitment in [
+            AggregationCommitment::List,
+            AggregationCommitment::MerkleRoot,
+        ] {
+            let proof = client
+                .aggregate(proofs.clone(), &vks, &[None, None], commitment)
+                .unwrap();
+            client
+                .verify_aggregated(&proof, &vks, &public_values, commitment)
+                .unwrap();
+
+            if commitment == AggregationCommitment::MerkleRoot {
+                for (index, values) in public_values.iter().enumerate() {
+                    let inclusion =
+                        aggregation::aggregation_inclusion_proof(&vks, &public_values, index);
+                    aggregation::verify_aggregation_inclusion(
+                        proof.public_values.as_slice(),
+                        &vk,
+                        values,
+                        &inclusion,
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_e2e_aggregate_merkle_public_values() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../tests/merkle-public-values/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10u32);
+        let proof = client.prove_compressed(&pk, stdin).unwrap();
+        let public_values = vec![proof.public_values.clone()];
+        let vks = vec![vk];
+
+        // The proof commits to the Merkle root of its public values, not their hash.
+        let commitment = AggregationCommitment::List;
+        let stdin =
+            aggregation::aggregation_stdin(vec![proof.clone()], &vks, &[None], commitment).unwrap();
+        assert!(client
+            .prover
+            .sp1_prover()
+            .execute_verified(aggregation::AGGREGATION_ELF, &stdin)
+            .is_err());
+
+        let merkle_hashes = [Some(MerkleHash::Keccak256)];
+        let proof = client
+            .aggregate(vec![proof], &vks, &merkle_hashes, commitment)
+            .unwrap();
+        client
+            .verify_aggregated(&proof, &vks, &public_values, commitment)
+            .unwrap();
+    }
 }
@@ -5,8 +5,9 @@ use alloy_sol_types::{sol, Eip712Domain, SolStruct};
 use anyhow::Result;
 use ethers::{
     signers::{LocalWallet, Signer},
-    types::H256,
+    types::{Signature, H256},
 };
+use sha2::{Digest, Sha256};
 
 use crate::proto::network::UnclaimReason;
 
@@ -48,8 +49,20 @@ sol! {
         address callback;
         bytes callback_data;
     }
+
+    struct AccessArtifact {
+        string artifact_id;
+        bytes32 data_hash;
+        uint64 expires;
+    }
 }
 
+/// The header holding the hex encoded signature of an artifact download or upload.
+pub const ARTIFACT_SIGNATURE_HEADER: &str = "x-sp1-signature";
+
+/// The header holding the time, in seconds since the epoch, an artifact signature expires at.
+pub const ARTIFACT_EXPIRES_HEADER: &str = "x-sp1-expires";
+
 /// Handles authentication for the Succinct prover network. All interactions that could potentially
 /// use computational resources must be authenticated by signing a message with a secp256k1 key.
 ///
@@ -89,6 +102,15 @@ impl NetworkAuth {
         Ok(signature.to_vec())
     }
 
+    // Generic function to recover the address that signed a message based on the SolStruct.
+    fn recover_signer<T: SolStruct>(type_struct: T, signature: &[u8]) -> Result<[u8; 20]> {
+        let domain_separator = Self::get_domain_separator();
+        let message_hash = type_struct.eip712_signing_hash(&domain_separator);
+        let signature = Signature::try_from(signature)?;
+        let address = signature.recover(H256(message_hash.0))?;
+        Ok(address.0)
+    }
+
     /// Signs a message to to request ot create a proof.
     pub async fn sign_create_proof_message(
         &self,
@@ -172,4 +194,130 @@ impl NetworkAuth {
         };
         self.sign_message(type_struct).await
     }
+
+    /// Signs a message to download or upload an artifact, where `data` is the uploaded data, or
+    /// empty for a download. The signature is valid until `expires`.
+    pub async fn sign_access_artifact_message(
+        &self,
+        artifact_id: &str,
+        data: &[u8],
+        expires: u64,
+    ) -> Result<Vec<u8>> {
+        let type_struct = AccessArtifact {
+            artifact_id: artifact_id.to_string(),
+            data_hash: <[u8; 32]>::from(Sha256::digest(data)).into(),
+            expires,
+        };
+        self.sign_message(type_struct).await
+    }
+
+    /// Recovers the signer of a message to request to create a proof.
+    pub fn recover_create_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        deadline: u64,
+        mode: i32,
+        version: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = CreateProof {
+            nonce,
+            deadline,
+            mode: mode as u32,
+            version: version.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the signer of a message to mark a proof as ready for proof generation.
+    pub fn recover_submit_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = SubmitProof {
+            nonce,
+            proof_id: proof_id.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the signer of a message to claim a proof.
+    pub fn recover_claim_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = ClaimProof {
+            nonce,
+            proof_id: proof_id.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the signer of a message to unclaim a proof.
+    pub fn recover_unclaim_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: String,
+        reason: UnclaimReason,
+        description: String,
+    ) -> Result<[u8; 20]> {
+        let type_struct = UnclaimProof {
+            nonce,
+            proof_id,
+            reason: reason as u8,
+            description,
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the signer of a message to fulfill a proof.
+    pub fn recover_fulfill_proof_signer(
+        signature: &[u8],
+        nonce: u64,
+        proof_id: &str,
+    ) -> Result<[u8; 20]> {
+        let type_struct = FulfillProof {
+            nonce,
+            proof_id: proof_id.to_string(),
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+
+    /// Recovers the signer of a message to download or upload an artifact.
+    pub fn recover_access_artifact_signer(
+        signature: &[u8],
+        artifact_id: &str,
+        data: &[u8],
+        expires: u64,
+    ) -> Result<[u8; 20]> {
+        let type_struct = AccessArtifact {
+            artifact_id: artifact_id.to_string(),
+            data_hash: <[u8; 32]>::from(Sha256::digest(data)).into(),
+            expires,
+        };
+        Self::recover_signer(type_struct, signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recover_signer() {
+        let auth =
+            NetworkAuth::new("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318");
+        let signature = auth
+            .sign_claim_proof_message(7, "proofrequest_1")
+            .await
+            .unwrap();
+
+        let signer = NetworkAuth::recover_claim_proof_signer(&signature, 7, "proofrequest_1");
+        assert_eq!(signer.unwrap(), auth.get_address());
+
+        // A signature over a different nonce recovers a different address.
+        let signer = NetworkAuth::recover_claim_proof_signer(&signature, 8, "proofrequest_1");
+        assert_ne!(signer.unwrap(), auth.get_address());
+    }
 }
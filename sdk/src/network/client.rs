@@ -1,7 +1,7 @@
 use std::{env, time::Duration};
 
 use crate::{
-    network::auth::NetworkAuth,
+    network::auth::{NetworkAuth, ARTIFACT_EXPIRES_HEADER, ARTIFACT_SIGNATURE_HEADER},
     proto::network::{UnclaimProofRequest, UnclaimReason},
 };
 use anyhow::{Context, Ok, Result};
@@ -30,10 +30,14 @@ const DEFAULT_SP1_VERIFIER_ADDRESS: &str = "0xed2107448519345059eab9cddab42ddc78
 /// The timeout for a proof request to be fulfilled.
 const TIMEOUT: Duration = Duration::from_secs(60 * 60);
 
+/// How long the signature of an artifact download or upload is valid for.
+const ARTIFACT_SIGNATURE_TTL: Duration = Duration::from_secs(5 * 60);
+
 pub struct NetworkClient {
     pub rpc: TwirpClient,
     pub http: HttpClientWithMiddleware,
     pub auth: NetworkAuth,
+    rpc_url: String,
 }
 
 impl NetworkClient {
@@ -43,6 +47,12 @@ impl NetworkClient {
 
     // Create a new NetworkClient with the given private key for authentication.
     pub fn new(private_key: &str) -> Self {
+        Self::with_rpc_url(private_key, Self::rpc_url())
+    }
+
+    // Create a new NetworkClient with the given private key for authentication, connected to the
+    // given RPC endpoint.
+    pub fn with_rpc_url(private_key: &str, rpc_url: String) -> Self {
         let auth = NetworkAuth::new(private_key);
 
         let twirp_http_client = HttpClient::builder()
@@ -51,7 +61,6 @@ impl NetworkClient {
             .build()
             .unwrap();
 
-        let rpc_url = Self::rpc_url();
         let rpc =
             TwirpClient::new(Url::parse(&rpc_url).unwrap(), twirp_http_client, vec![]).unwrap();
 
@@ -65,6 +74,7 @@ impl NetworkClient {
             auth,
             rpc,
             http: http_client.into(),
+            rpc_url,
         }
     }
 
@@ -93,7 +103,73 @@ impl NetworkClient {
 
     // Upload a file to the specified url.
     async fn upload_file(&self, url: &str, data: Vec<u8>) -> Result<()> {
-        self.http.put(url).body(data).send().await?;
+        self.http
+            .put(url)
+            .body(data)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    // Get the URL of an artifact. Artifacts are located at `PROVER_NETWORK_ARTIFACTS_URL` if it is
+    // set, and under the `artifacts` path of the RPC endpoint otherwise.
+    pub fn artifact_url(&self, artifact_id: &str) -> String {
+        let base = env::var("PROVER_NETWORK_ARTIFACTS_URL")
+            .unwrap_or_else(|_| format!("{}/artifacts", self.rpc_url.trim_end_matches('/')));
+        format!("{}/{}", base.trim_end_matches('/'), artifact_id)
+    }
+
+    // Get the headers authenticating a download or upload of an artifact.
+    async fn artifact_headers(
+        &self,
+        artifact_id: &str,
+        data: &[u8],
+    ) -> Result<[(&'static str, String); 2]> {
+        let expires = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Invalid system time")
+            .as_secs()
+            + ARTIFACT_SIGNATURE_TTL.as_secs();
+        let signature = self
+            .auth
+            .sign_access_artifact_message(artifact_id, data, expires)
+            .await?;
+        Ok([
+            (ARTIFACT_SIGNATURE_HEADER, hex::encode(signature)),
+            (ARTIFACT_EXPIRES_HEADER, expires.to_string()),
+        ])
+    }
+
+    // Download the artifact with the given identifier.
+    pub async fn download_artifact(&self, artifact_id: &str) -> Result<Vec<u8>> {
+        let [signature, expires] = self.artifact_headers(artifact_id, &[]).await?;
+        let bytes = self
+            .http
+            .get(self.artifact_url(artifact_id))
+            .header(signature.0, signature.1)
+            .header(expires.0, expires.1)
+            .send()
+            .await
+            .with_context(|| format!("Failed to download artifact {}", artifact_id))?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(bytes.to_vec())
+    }
+
+    // Upload the artifact with the given identifier.
+    pub async fn upload_artifact(&self, artifact_id: &str, data: Vec<u8>) -> Result<()> {
+        let [signature, expires] = self.artifact_headers(artifact_id, &data).await?;
+        self.http
+            .put(self.artifact_url(artifact_id))
+            .header(signature.0, signature.1)
+            .header(expires.0, expires.1)
+            .body(data)
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload artifact {}", artifact_id))?
+            .error_for_status()?;
         Ok(())
     }
 
@@ -119,6 +195,7 @@ impl NetworkClient {
                     .send()
                     .await
                     .context("Failed to send HTTP request for proof")?
+                    .error_for_status()?
                     .bytes()
                     .await
                     .context("Failed to load proof bytes")?;
@@ -135,11 +212,13 @@ impl NetworkClient {
     pub async fn get_proof_requests(
         &self,
         status: ProofStatus,
+        modes: &[ProofMode],
     ) -> Result<GetProofRequestsResponse> {
         let res = self
             .rpc
             .get_proof_requests(GetProofRequestsRequest {
                 status: status.into(),
+                modes: modes.iter().map(|&mode| mode.into()).collect(),
             })
             .await?;
 
@@ -1,3 +1,6 @@
 pub mod auth;
 pub mod client;
 pub mod prover;
+pub mod server;
+pub mod store;
+pub mod worker;
@@ -47,7 +47,10 @@ impl NetworkProver {
             .unwrap_or(false);
 
         if !skip_simulation {
-            let (_, report) = SP1Prover::execute(elf, &stdin)?;
+            let (_, report) = self
+                .local_prover
+                .sp1_prover()
+                .execute_verified(elf, &stdin)?;
             log::info!(
                 "Simulation complete, cycles: {}",
                 report.total_instruction_count()
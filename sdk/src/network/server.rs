@@ -0,0 +1,836 @@
+//! A self-hostable implementation of the prover network [NetworkService].
+//!
+//! The server keeps its state in a [JobStore] and serves the program, stdin and proof artifacts
+//! of each request over HTTP at `<public url>/artifacts/<artifact id>`, so that a
+//! [crate::NetworkProver] can be pointed at it by setting `PROVER_NETWORK_RPC` to its public url.
+//! Provers download the program and stdin, and upload the proof, using the artifact identifiers
+//! returned by `ClaimProof`.
+//!
+//! Every request that changes state must be signed as described in [NetworkAuth] with the current
+//! nonce of the signer's account. Artifact downloads and uploads must either be signed by the
+//! requester or the claimer of the proof, or use the urls returned to the requester, which contain
+//! a token derived from a secret of the server.
+//!
+//! Claimed requests that are not fulfilled within the claim timeout are put back in the
+//! `PROOF_REQUESTED` state so that another prover can claim them, and requests that are still not
+//! fulfilled at their deadline are unclaimed. The times at which requests may expire are indexed in
+//! memory, so only the requests that are due are read from the store.
+
+use std::{
+    collections::BTreeSet,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+use twirp::TwirpErrorResponse;
+
+use crate::{
+    network::{
+        auth::{NetworkAuth, ARTIFACT_EXPIRES_HEADER, ARTIFACT_SIGNATURE_HEADER},
+        store::{ArtifactKind, JobStore, ProofJob},
+    },
+    proto::network::{
+        router, ClaimProofRequest, ClaimProofResponse, CreateProofRequest, CreateProofResponse,
+        FulfillProofRequest, FulfillProofResponse, GetNonceRequest, GetNonceResponse,
+        GetProofRequestsRequest, GetProofRequestsResponse, GetProofStatusRequest,
+        GetProofStatusResponse, GetRelayStatusRequest, GetRelayStatusResponse, NetworkService,
+        ProofMode, ProofStatus, RelayProofRequest, RelayProofResponse, RequestedProof,
+        SubmitProofRequest, SubmitProofResponse, UnclaimProofRequest, UnclaimProofResponse,
+        UnclaimReason, SERVICE_FQN,
+    },
+};
+
+/// The maximum number of proof requests returned by `GetProofRequests`.
+pub(crate) const MAX_PROOF_REQUESTS: usize = 10;
+
+/// The default time a prover has to fulfill a request after claiming it.
+pub const DEFAULT_CLAIM_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The maximum time an artifact signature can be valid for.
+const MAX_ARTIFACT_SIGNATURE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// A self-hosted prover network. See the [module documentation](self) for details.
+pub struct NetworkServer {
+    store: Mutex<JobStore>,
+    public_url: String,
+    counter: AtomicU64,
+    claim_timeout: Duration,
+    /// The secret the artifact tokens are derived from. It is generated when the server starts, so
+    /// the artifact urls of a previous run are no longer valid.
+    artifact_secret: [u8; 32],
+    /// The `(time, proof id)` pairs at which jobs may expire, built from the store the first time
+    /// jobs are expired. It is only accessed while the store is locked.
+    expiries: std::sync::Mutex<Option<BTreeSet<(u64, String)>>>,
+}
+
+impl NetworkServer {
+    /// Creates a server backed by the given store, reachable by clients at `public_url`.
+    pub fn new(store: JobStore, public_url: impl Into<String>) -> Self {
+        Self {
+            store: Mutex::new(store),
+            public_url: public_url.into().trim_end_matches('/').to_string(),
+            counter: AtomicU64::new(0),
+            claim_timeout: DEFAULT_CLAIM_TIMEOUT,
+            artifact_secret: ethers::core::rand::random(),
+            expiries: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Sets the time a prover has to fulfill a request after claiming it, [DEFAULT_CLAIM_TIMEOUT]
+    /// by default.
+    pub fn with_claim_timeout(mut self, claim_timeout: Duration) -> Self {
+        self.claim_timeout = claim_timeout;
+        self
+    }
+
+    /// The URL the requester uses to upload or download an artifact, which doesn't need to be
+    /// signed.
+    pub fn artifact_url(&self, artifact_id: &str) -> String {
+        format!(
+            "{}/artifacts/{}?token={}",
+            self.public_url,
+            artifact_id,
+            self.artifact_token(artifact_id)
+        )
+    }
+
+    /// The token authorizing access to an artifact without a signature.
+    fn artifact_token(&self, artifact_id: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.artifact_secret);
+        hasher.update(artifact_id.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Checks that an artifact of `job` can be downloaded, or uploaded with `data`.
+    ///
+    /// The program and stdin can be accessed with a token by the requester, and downloaded by the
+    /// claimer. The proof can only be uploaded by the claimer, and downloaded with a token or by the
+    /// requester or the claimer.
+    fn check_artifact_access(
+        &self,
+        job: &ProofJob,
+        kind: ArtifactKind,
+        artifact_id: &str,
+        upload: Option<&[u8]>,
+        token: Option<&str>,
+        headers: &HeaderMap,
+    ) -> Result<(), StatusCode> {
+        let is_proof_upload = kind == ArtifactKind::Proof && upload.is_some();
+        if !is_proof_upload && token == Some(self.artifact_token(artifact_id).as_str()) {
+            return Ok(());
+        }
+
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or(StatusCode::UNAUTHORIZED)
+        };
+        let signature = hex::decode(header(ARTIFACT_SIGNATURE_HEADER)?)
+            .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let expires: u64 = header(ARTIFACT_EXPIRES_HEADER)?
+            .parse()
+            .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let now = now_secs();
+        if expires <= now || expires > now + MAX_ARTIFACT_SIGNATURE_TTL.as_secs() {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        let signer = NetworkAuth::recover_access_artifact_signer(
+            &signature,
+            artifact_id,
+            upload.unwrap_or_default(),
+            expires,
+        )
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+        let is_requester = signer == job.requester;
+        let is_claimer = job.claimer == Some(signer);
+        let allowed = match (kind, upload.is_some()) {
+            (ArtifactKind::Program | ArtifactKind::Stdin, true) => is_requester,
+            (ArtifactKind::Proof, true) => is_claimer,
+            (_, false) => is_requester || is_claimer,
+        };
+        if !allowed {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        Ok(())
+    }
+
+    /// Puts the claimed requests whose claim timed out back in the requested state, and unclaims
+    /// the requests whose deadline has passed.
+    fn expire_jobs(&self, store: &JobStore) -> Result<(), TwirpErrorResponse> {
+        let now = now_secs();
+        let mut expiries = self.expiries.lock().unwrap();
+        let expiries = match &mut *expiries {
+            Some(expiries) => expiries,
+            None => expiries.insert(self.scan_expiries(store)?),
+        };
+        while let Some((time, _)) = expiries.first() {
+            if *time > now {
+                break;
+            }
+            let (_, proof_id) = expiries.pop_first().unwrap();
+            if let Some(job) = store.job(&proof_id).map_err(internal)? {
+                self.expire_job(store, job, now)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the times at which the jobs in the store may expire.
+    fn scan_expiries(
+        &self,
+        store: &JobStore,
+    ) -> Result<BTreeSet<(u64, String)>, TwirpErrorResponse> {
+        let mut expiries = BTreeSet::new();
+        for job in store.jobs().map_err(internal)? {
+            match job.status {
+                ProofStatus::ProofPreparing | ProofStatus::ProofRequested => {
+                    expiries.insert((job.deadline, job.proof_id));
+                }
+                ProofStatus::ProofClaimed => {
+                    let claim_expiry = job.claimed_at.unwrap_or(0) + self.claim_timeout.as_secs();
+                    expiries.insert((claim_expiry, job.proof_id.clone()));
+                    expiries.insert((job.deadline, job.proof_id));
+                }
+                _ => {}
+            }
+        }
+        Ok(expiries)
+    }
+
+    /// Records that the job may expire at `time`. Must be called while the store is locked.
+    fn record_expiry(&self, time: u64, proof_id: &str) {
+        if let Some(expiries) = self.expiries.lock().unwrap().as_mut() {
+            expiries.insert((time, proof_id.to_string()));
+        }
+    }
+
+    /// Expires a job if its claim timed out or its deadline passed.
+    fn expire_job(
+        &self,
+        store: &JobStore,
+        mut job: ProofJob,
+        now: u64,
+    ) -> Result<(), TwirpErrorResponse> {
+        match job.status {
+            ProofStatus::ProofPreparing
+            | ProofStatus::ProofRequested
+            | ProofStatus::ProofClaimed
+                if job.deadline <= now =>
+            {
+                job.status = ProofStatus::ProofUnclaimed;
+                job.unclaim_reason = Some(UnclaimReason::Abandoned);
+                job.unclaim_description =
+                    Some("the deadline passed before the proof was fulfilled".to_string());
+                log::info!("Deadline of {} passed", job.proof_id);
+            }
+            ProofStatus::ProofClaimed
+                if job.claimed_at.unwrap_or(now) + self.claim_timeout.as_secs() <= now =>
+            {
+                job.status = ProofStatus::ProofRequested;
+                job.claimer = None;
+                log::info!("Claim of {} timed out", job.proof_id);
+            }
+            _ => return Ok(()),
+        }
+        store.put_job(&job).map_err(internal)?;
+        Ok(())
+    }
+
+    /// Builds the HTTP application serving both the twirp API and the artifacts.
+    pub fn app(self: Arc<Self>) -> axum::Router {
+        axum::Router::new()
+            .route(
+                "/artifacts/:artifact_id",
+                get(download_artifact).put(upload_artifact),
+            )
+            .layer(DefaultBodyLimit::disable())
+            .with_state(self.clone())
+            .nest(SERVICE_FQN, router(self))
+    }
+
+    /// Serves the network on the given address until the process is stopped.
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        log::info!("Serving prover network on {}", addr);
+        axum::serve(listener, Arc::new(self).app()).await?;
+        Ok(())
+    }
+
+    /// Generates a new, unique proof identifier.
+    fn new_proof_id(&self, requester: &[u8; 20], nonce: u64) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(requester);
+        hasher.update(nonce.to_le_bytes());
+        hasher.update(now_nanos().to_le_bytes());
+        hasher.update(self.counter.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+        format!("proofrequest_{}", hex::encode(&hasher.finalize()[..16]))
+    }
+}
+
+/// Checks that `nonce` is the current nonce of the signer's account and increments it, so that a
+/// signed message can't be replayed.
+fn use_nonce(store: &JobStore, signer: &[u8; 20], nonce: u64) -> Result<(), TwirpErrorResponse> {
+    let expected = store.nonce(signer).map_err(internal)?;
+    if nonce != expected {
+        return Err(twirp::unauthenticated(format!(
+            "invalid nonce {}, expected {}",
+            nonce, expected
+        )));
+    }
+    store.set_nonce(signer, nonce + 1).map_err(internal)
+}
+
+/// Gets the job with the given identifier, failing if it does not exist.
+fn get_job(store: &JobStore, proof_id: &str) -> Result<ProofJob, TwirpErrorResponse> {
+    store
+        .job(proof_id)
+        .map_err(|e| twirp::invalid_argument(e.to_string()))?
+        .ok_or_else(|| twirp::not_found(format!("proof {} not found", proof_id)))
+}
+
+/// Checks that a job is in the expected status.
+fn check_status(job: &ProofJob, expected: ProofStatus) -> Result<(), TwirpErrorResponse> {
+    if job.status != expected {
+        return Err(twirp::failed_precondition(format!(
+            "proof {} is in status {}, expected {}",
+            job.proof_id,
+            job.status.as_str_name(),
+            expected.as_str_name()
+        )));
+    }
+    Ok(())
+}
+
+fn unauthenticated(e: anyhow::Error) -> TwirpErrorResponse {
+    twirp::unauthenticated(format!("invalid signature: {}", e))
+}
+
+fn internal(e: anyhow::Error) -> TwirpErrorResponse {
+    twirp::internal(e.to_string())
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Invalid system time")
+        .as_nanos()
+}
+
+fn now_secs() -> u64 {
+    (now_nanos() / 1_000_000_000) as u64
+}
+
+#[twirp::async_trait::async_trait]
+impl NetworkService for NetworkServer {
+    async fn create_proof(
+        &self,
+        _: twirp::Context,
+        req: CreateProofRequest,
+    ) -> Result<CreateProofResponse, TwirpErrorResponse> {
+        let signer = NetworkAuth::recover_create_proof_signer(
+            &req.signature,
+            req.nonce,
+            req.deadline,
+            req.mode,
+            &req.version,
+        )
+        .map_err(unauthenticated)?;
+        let mode = match ProofMode::try_from(req.mode) {
+            Ok(ProofMode::Unspecified) | Err(_) => {
+                return Err(twirp::invalid_argument("invalid proof mode"));
+            }
+            Ok(mode) => mode,
+        };
+        let now = now_secs();
+        if req.deadline <= now {
+            return Err(twirp::invalid_argument("deadline has already passed"));
+        }
+
+        let store = self.store.lock().await;
+        use_nonce(&store, &signer, req.nonce)?;
+
+        let proof_id = self.new_proof_id(&signer, req.nonce);
+        let job = ProofJob {
+            proof_id: proof_id.clone(),
+            mode,
+            status: ProofStatus::ProofPreparing,
+            requester: signer,
+            deadline: req.deadline,
+            version: req.version,
+            created_at: now,
+            claimer: None,
+            claimed_at: None,
+            unclaim_reason: None,
+            unclaim_description: None,
+        };
+        store.put_job(&job).map_err(internal)?;
+        self.record_expiry(job.deadline, &proof_id);
+        log::info!("Created {}", proof_id);
+
+        Ok(CreateProofResponse {
+            program_url: self.artifact_url(&ArtifactKind::Program.artifact_id(&proof_id)),
+            stdin_url: self.artifact_url(&ArtifactKind::Stdin.artifact_id(&proof_id)),
+            proof_id,
+        })
+    }
+
+    async fn submit_proof(
+        &self,
+        _: twirp::Context,
+        req: SubmitProofRequest,
+    ) -> Result<SubmitProofResponse, TwirpErrorResponse> {
+        let signer =
+            NetworkAuth::recover_submit_proof_signer(&req.signature, req.nonce, &req.proof_id)
+                .map_err(unauthenticated)?;
+
+        let store = self.store.lock().await;
+        let mut job = get_job(&store, &req.proof_id)?;
+        if job.requester != signer {
+            return Err(twirp::permission_denied(
+                "only the requester can submit a proof",
+            ));
+        }
+        check_status(&job, ProofStatus::ProofPreparing)?;
+        for kind in [ArtifactKind::Program, ArtifactKind::Stdin] {
+            let artifact_id = kind.artifact_id(&job.proof_id);
+            if !store.has_artifact(&artifact_id).map_err(internal)? {
+                return Err(twirp::failed_precondition(format!(
+                    "artifact {} has not been uploaded",
+                    artifact_id
+                )));
+            }
+        }
+        use_nonce(&store, &signer, req.nonce)?;
+
+        job.status = ProofStatus::ProofRequested;
+        store.put_job(&job).map_err(internal)?;
+        log::info!("Submitted {}", job.proof_id);
+
+        Ok(SubmitProofResponse {})
+    }
+
+    async fn claim_proof(
+        &self,
+        _: twirp::Context,
+        req: ClaimProofRequest,
+    ) -> Result<ClaimProofResponse, TwirpErrorResponse> {
+        let signer =
+            NetworkAuth::recover_claim_proof_signer(&req.signature, req.nonce, &req.proof_id)
+                .map_err(unauthenticated)?;
+
+        let store = self.store.lock().await;
+        self.expire_jobs(&store)?;
+        let mut job = get_job(&store, &req.proof_id)?;
+        check_status(&job, ProofStatus::ProofRequested)?;
+        if job.deadline <= now_secs() {
+            return Err(twirp::failed_precondition("deadline has already passed"));
+        }
+        use_nonce(&store, &signer, req.nonce)?;
+
+        job.status = ProofStatus::ProofClaimed;
+        job.claimer = Some(signer);
+        let now = now_secs();
+        job.claimed_at = Some(now);
+        store.put_job(&job).map_err(internal)?;
+        self.record_expiry(now + self.claim_timeout.as_secs(), &job.proof_id);
+        log::info!("Claimed {} by 0x{}", job.proof_id, hex::encode(signer));
+
+        Ok(ClaimProofResponse {
+            program_artifact_id: ArtifactKind::Program.artifact_id(&job.proof_id),
+            stdin_artifact_id: ArtifactKind::Stdin.artifact_id(&job.proof_id),
+            proof_artifact_id: ArtifactKind::Proof.artifact_id(&job.proof_id),
+        })
+    }
+
+    async fn unclaim_proof(
+        &self,
+        _: twirp::Context,
+        req: UnclaimProofRequest,
+    ) -> Result<UnclaimProofResponse, TwirpErrorResponse> {
+        let reason = UnclaimReason::try_from(req.reason)
+            .map_err(|_| twirp::invalid_argument("invalid unclaim reason"))?;
+        let signer = NetworkAuth::recover_unclaim_proof_signer(
+            &req.signature,
+            req.nonce,
+            req.proof_id.clone(),
+            reason,
+            req.description.clone(),
+        )
+        .map_err(unauthenticated)?;
+
+        let store = self.store.lock().await;
+        let mut job = get_job(&store, &req.proof_id)?;
+        check_status(&job, ProofStatus::ProofClaimed)?;
+        if job.claimer != Some(signer) {
+            return Err(twirp::permission_denied(
+                "only the claimer can unclaim a proof",
+            ));
+        }
+        use_nonce(&store, &signer, req.nonce)?;
+
+        job.status = ProofStatus::ProofUnclaimed;
+        job.unclaim_reason = Some(reason);
+        job.unclaim_description = Some(req.description);
+        store.put_job(&job).map_err(internal)?;
+        log::info!("Unclaimed {}", job.proof_id);
+
+        Ok(UnclaimProofResponse {})
+    }
+
+    async fn fulfill_proof(
+        &self,
+        _: twirp::Context,
+        req: FulfillProofRequest,
+    ) -> Result<FulfillProofResponse, TwirpErrorResponse> {
+        let signer =
+            NetworkAuth::recover_fulfill_proof_signer(&req.signature, req.nonce, &req.proof_id)
+                .map_err(unauthenticated)?;
+
+        let store = self.store.lock().await;
+        self.expire_jobs(&store)?;
+        let mut job = get_job(&store, &req.proof_id)?;
+        check_status(&job, ProofStatus::ProofClaimed)?;
+        if job.claimer != Some(signer) {
+            return Err(twirp::permission_denied(
+                "only the claimer can fulfill a proof",
+            ));
+        }
+        let proof_artifact_id = ArtifactKind::Proof.artifact_id(&job.proof_id);
+        if !store.has_artifact(&proof_artifact_id).map_err(internal)? {
+            return Err(twirp::failed_precondition(
+                "the proof has not been uploaded",
+            ));
+        }
+        use_nonce(&store, &signer, req.nonce)?;
+
+        let now = now_secs();
+        job.status = ProofStatus::ProofFulfilled;
+        store.put_job(&job).map_err(internal)?;
+        log::info!("Fulfilled {}", job.proof_id);
+
+        Ok(FulfillProofResponse {
+            proving_seconds: now.saturating_sub(job.claimed_at.unwrap_or(now)),
+        })
+    }
+
+    async fn relay_proof(
+        &self,
+        _: twirp::Context,
+        _: RelayProofRequest,
+    ) -> Result<RelayProofResponse, TwirpErrorResponse> {
+        Err(twirp::unimplemented(
+            "relaying proofs is not supported by a self-hosted network",
+        ))
+    }
+
+    async fn get_nonce(
+        &self,
+        _: twirp::Context,
+        req: GetNonceRequest,
+    ) -> Result<GetNonceResponse, TwirpErrorResponse> {
+        let address: [u8; 20] = req
+            .address
+            .try_into()
+            .map_err(|_| twirp::invalid_argument("address must be 20 bytes"))?;
+        let store = self.store.lock().await;
+        let nonce = store.nonce(&address).map_err(internal)?;
+        Ok(GetNonceResponse { nonce })
+    }
+
+    async fn get_proof_status(
+        &self,
+        _: twirp::Context,
+        req: GetProofStatusRequest,
+    ) -> Result<GetProofStatusResponse, TwirpErrorResponse> {
+        let store = self.store.lock().await;
+        self.expire_jobs(&store)?;
+        let job = get_job(&store, &req.proof_id)?;
+        let proof_url = match job.status {
+            ProofStatus::ProofFulfilled => {
+                Some(self.artifact_url(&ArtifactKind::Proof.artifact_id(&job.proof_id)))
+            }
+            _ => None,
+        };
+        Ok(GetProofStatusResponse {
+            status: job.status.into(),
+            proof_url,
+            unclaim_reason: job.unclaim_reason.map(Into::into),
+            unclaim_description: job.unclaim_description,
+        })
+    }
+
+    async fn get_proof_requests(
+        &self,
+        _: twirp::Context,
+        req: GetProofRequestsRequest,
+    ) -> Result<GetProofRequestsResponse, TwirpErrorResponse> {
+        let store = self.store.lock().await;
+        self.expire_jobs(&store)?;
+        let mut jobs = store
+            .jobs()
+            .map_err(internal)?
+            .into_iter()
+            .filter(|job| i32::from(job.status) == req.status)
+            .filter(|job| req.modes.is_empty() || req.modes.contains(&job.mode.into()))
+            .collect::<Vec<_>>();
+        // Oldest requests first, so that they are claimed first.
+        jobs.sort_by(|a, b| (a.created_at, &a.proof_id).cmp(&(b.created_at, &b.proof_id)));
+        let proofs = jobs
+            .into_iter()
+            .take(MAX_PROOF_REQUESTS)
+            .map(|job| RequestedProof {
+                proof_id: job.proof_id,
+                mode: job.mode.into(),
+                requester: job.requester.to_vec(),
+            })
+            .collect();
+        Ok(GetProofRequestsResponse { proofs })
+    }
+
+    async fn get_relay_status(
+        &self,
+        _: twirp::Context,
+        _: GetRelayStatusRequest,
+    ) -> Result<GetRelayStatusResponse, TwirpErrorResponse> {
+        Err(twirp::unimplemented(
+            "relaying proofs is not supported by a self-hosted network",
+        ))
+    }
+}
+
+/// The query parameters of the artifact routes.
+#[derive(Deserialize)]
+struct ArtifactQuery {
+    token: Option<String>,
+}
+
+async fn download_artifact(
+    State(server): State<Arc<NetworkServer>>,
+    Path(artifact_id): Path<String>,
+    Query(query): Query<ArtifactQuery>,
+    headers: HeaderMap,
+) -> Result<Vec<u8>, StatusCode> {
+    let Some((proof_id, kind)) = ArtifactKind::parse_artifact_id(&artifact_id) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    let store = server.store.lock().await;
+    let job = match store.job(proof_id) {
+        Ok(Some(job)) => job,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+    server.check_artifact_access(
+        &job,
+        kind,
+        &artifact_id,
+        None,
+        query.token.as_deref(),
+        &headers,
+    )?;
+
+    match store.read_artifact(&artifact_id) {
+        Ok(Some(data)) => Ok(data),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+async fn upload_artifact(
+    State(server): State<Arc<NetworkServer>>,
+    Path(artifact_id): Path<String>,
+    Query(query): Query<ArtifactQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let Some((proof_id, kind)) = ArtifactKind::parse_artifact_id(&artifact_id) else {
+        return StatusCode::BAD_REQUEST;
+    };
+    let store = server.store.lock().await;
+    let job = match store.job(proof_id) {
+        Ok(Some(job)) => job,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::BAD_REQUEST,
+    };
+    if let Err(status) = server.check_artifact_access(
+        &job,
+        kind,
+        &artifact_id,
+        Some(&body),
+        query.token.as_deref(),
+        &headers,
+    ) {
+        return status;
+    }
+
+    // The program and stdin can only be uploaded before the request is submitted, and the proof
+    // only while the request is claimed.
+    let expected = match kind {
+        ArtifactKind::Program | ArtifactKind::Stdin => ProofStatus::ProofPreparing,
+        ArtifactKind::Proof => ProofStatus::ProofClaimed,
+    };
+    if job.status != expected {
+        return StatusCode::CONFLICT;
+    }
+
+    match store.write_artifact(&artifact_id, &body) {
+        Ok(()) => StatusCode::OK,
+        Err(e) => {
+            log::error!("Failed to store artifact {}: {}", artifact_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use sp1_prover::SP1Stdin;
+
+    use super::*;
+    use crate::network::client::NetworkClient;
+
+    pub(crate) const REQUESTER_KEY: &str =
+        "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    pub(crate) const PROVER_KEY: &str =
+        "0x0101010101010101010101010101010101010101010101010101010101010101";
+    const OTHER_KEY: &str = "0x0202020202020202020202020202020202020202020202020202020202020202";
+
+    /// Serves a network backed by a temporary store on a local port, and returns the store
+    /// directory and the RPC url of the network.
+    pub(crate) async fn spawn_server(claim_timeout: Duration) -> (tempfile::TempDir, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = NetworkServer::new(JobStore::open(dir.path()).unwrap(), &url)
+            .with_claim_timeout(claim_timeout);
+        tokio::spawn(async move { axum::serve(listener, Arc::new(server).app()).await });
+        (dir, format!("{}/", url))
+    }
+
+    #[tokio::test]
+    async fn test_network_round_trip() {
+        let (_dir, rpc_url) = spawn_server(DEFAULT_CLAIM_TIMEOUT).await;
+        let requester = NetworkClient::with_rpc_url(REQUESTER_KEY, rpc_url.clone());
+        let prover = NetworkClient::with_rpc_url(PROVER_KEY, rpc_url.clone());
+        let other = NetworkClient::with_rpc_url(OTHER_KEY, rpc_url);
+
+        let elf = b"elf".to_vec();
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&1u32);
+        let proof_id = requester
+            .create_proof(&elf, &stdin, ProofMode::Core, "v")
+            .await
+            .unwrap();
+
+        let requests = prover
+            .get_proof_requests(ProofStatus::ProofRequested, &[])
+            .await
+            .unwrap();
+        assert_eq!(requests.proofs.len(), 1);
+        assert_eq!(requests.proofs[0].proof_id, proof_id);
+
+        // Requests are filtered by proof mode on the server.
+        let requests = prover
+            .get_proof_requests(ProofStatus::ProofRequested, &[ProofMode::Plonk])
+            .await
+            .unwrap();
+        assert!(requests.proofs.is_empty());
+        let requests = prover
+            .get_proof_requests(
+                ProofStatus::ProofRequested,
+                &[ProofMode::Plonk, ProofMode::Core],
+            )
+            .await
+            .unwrap();
+        assert_eq!(requests.proofs.len(), 1);
+
+        // The program can only be downloaded by the prover once it has claimed the request.
+        let program_id = ArtifactKind::Program.artifact_id(&proof_id);
+        assert!(prover.download_artifact(&program_id).await.is_err());
+        let claim = prover.claim_proof(&proof_id).await.unwrap();
+        let program = prover
+            .download_artifact(&claim.program_artifact_id)
+            .await
+            .unwrap();
+        assert_eq!(program, bincode::serialize(&elf).unwrap());
+
+        // Artifacts can't be accessed without a signature of the requester or the claimer.
+        assert!(other
+            .download_artifact(&claim.stdin_artifact_id)
+            .await
+            .is_err());
+        let unsigned = reqwest::get(prover.artifact_url(&claim.stdin_artifact_id))
+            .await
+            .unwrap();
+        assert_eq!(unsigned.status().as_u16(), 401);
+
+        // Only the claimer can upload the proof.
+        let proof = bincode::serialize(&vec![1u8, 2, 3]).unwrap();
+        assert!(other
+            .upload_artifact(&claim.proof_artifact_id, proof.clone())
+            .await
+            .is_err());
+        prover
+            .upload_artifact(&claim.proof_artifact_id, proof)
+            .await
+            .unwrap();
+        prover.fulfill_proof(&proof_id).await.unwrap();
+
+        let (status, proof) = requester
+            .get_proof_status::<Vec<u8>>(&proof_id)
+            .await
+            .unwrap();
+        assert_eq!(status.status(), ProofStatus::ProofFulfilled);
+        assert_eq!(proof, Some(vec![1, 2, 3]));
+    }
+
+    #[tokio::test]
+    async fn test_network_claim_timeout() {
+        let (_dir, rpc_url) = spawn_server(Duration::ZERO).await;
+        let requester = NetworkClient::with_rpc_url(REQUESTER_KEY, rpc_url.clone());
+        let prover = NetworkClient::with_rpc_url(PROVER_KEY, rpc_url);
+
+        // Requests are returned oldest first. Creation times have a precision of a second.
+        let mut proof_ids = Vec::new();
+        for _ in 0..2 {
+            let proof_id = requester
+                .create_proof(b"elf", &SP1Stdin::new(), ProofMode::Core, "v")
+                .await
+                .unwrap();
+            proof_ids.push(proof_id);
+            tokio::time::sleep(Duration::from_millis(1100)).await;
+        }
+        let requested = || async {
+            prover
+                .get_proof_requests(ProofStatus::ProofRequested, &[])
+                .await
+                .unwrap()
+                .proofs
+                .into_iter()
+                .map(|proof| proof.proof_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(requested().await, proof_ids);
+
+        // The claim times out immediately, so the request can be claimed again.
+        prover.claim_proof(&proof_ids[0]).await.unwrap();
+        assert_eq!(requested().await, proof_ids);
+        assert!(prover.fulfill_proof(&proof_ids[0]).await.is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! A persistent, file-backed store for the jobs of a self-hosted prover network.
+//!
+//! The store is laid out as follows:
+//!
+//! ```text
+//! <root>/nonces/<hex address>
+//! <root>/proofs/<proof_id>.json
+//! <root>/artifacts/<artifact_id>
+//! ```
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::proto::network::{ProofMode, ProofStatus, UnclaimReason};
+
+/// The kind of an artifact attached to a proof request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    Program,
+    Stdin,
+    Proof,
+}
+
+impl ArtifactKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactKind::Program => "program",
+            ArtifactKind::Stdin => "stdin",
+            ArtifactKind::Proof => "proof",
+        }
+    }
+
+    /// The identifier of the artifact of this kind for the given proof.
+    pub fn artifact_id(&self, proof_id: &str) -> String {
+        format!("{}_{}", proof_id, self.as_str())
+    }
+
+    /// Splits an artifact identifier into its proof identifier and kind.
+    pub fn parse_artifact_id(artifact_id: &str) -> Option<(&str, Self)> {
+        let (proof_id, kind) = artifact_id.rsplit_once('_')?;
+        let kind = match kind {
+            "program" => ArtifactKind::Program,
+            "stdin" => ArtifactKind::Stdin,
+            "proof" => ArtifactKind::Proof,
+            _ => return None,
+        };
+        Some((proof_id, kind))
+    }
+}
+
+/// A proof request and its progress through the network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofJob {
+    pub proof_id: String,
+    pub mode: ProofMode,
+    pub status: ProofStatus,
+    /// The address of the account that created the request.
+    pub requester: [u8; 20],
+    /// The latest time, in seconds since the epoch, a fulfillment is valid.
+    pub deadline: u64,
+    /// The client version used to create the request.
+    pub version: String,
+    /// The time, in seconds since the epoch, the request was created.
+    pub created_at: u64,
+    /// The address of the prover that claimed the request, if it is claimed.
+    pub claimer: Option<[u8; 20]>,
+    /// The time, in seconds since the epoch, the request was last claimed.
+    pub claimed_at: Option<u64>,
+    pub unclaim_reason: Option<UnclaimReason>,
+    pub unclaim_description: Option<String>,
+}
+
+/// A persistent store of proof jobs, account nonces and artifacts. See the
+/// [module documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct JobStore {
+    root: PathBuf,
+}
+
+impl JobStore {
+    /// Opens the store rooted at the given directory, creating it if it does not exist.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        for dir in ["nonces", "proofs", "artifacts"] {
+            fs::create_dir_all(root.join(dir))
+                .with_context(|| format!("failed to create {:?}", root.join(dir)))?;
+        }
+        Ok(Self { root })
+    }
+
+    /// The root directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Gets the current nonce of an account.
+    pub fn nonce(&self, address: &[u8; 20]) -> Result<u64> {
+        match fs::read_to_string(self.nonce_path(address)) {
+            Ok(nonce) => Ok(nonce.trim().parse()?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Sets the nonce of an account.
+    pub fn set_nonce(&self, address: &[u8; 20], nonce: u64) -> Result<()> {
+        write_atomic(&self.nonce_path(address), nonce.to_string().as_bytes())
+    }
+
+    /// Gets the job with the given proof identifier, if it exists.
+    pub fn job(&self, proof_id: &str) -> Result<Option<ProofJob>> {
+        check_id(proof_id)?;
+        match fs::read(self.job_path(proof_id)) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Inserts or updates a job.
+    pub fn put_job(&self, job: &ProofJob) -> Result<()> {
+        check_id(&job.proof_id)?;
+        write_atomic(&self.job_path(&job.proof_id), &serde_json::to_vec(job)?)
+    }
+
+    /// Gets all the jobs in the store.
+    pub fn jobs(&self) -> Result<Vec<ProofJob>> {
+        let mut jobs = Vec::new();
+        for entry in fs::read_dir(self.root.join("proofs"))? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                jobs.push(serde_json::from_slice(&fs::read(&path)?)?);
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// Whether the artifact with the given identifier has been uploaded.
+    pub fn has_artifact(&self, artifact_id: &str) -> Result<bool> {
+        check_id(artifact_id)?;
+        Ok(self.artifact_path(artifact_id).exists())
+    }
+
+    /// Gets the contents of an artifact, if it has been uploaded.
+    pub fn read_artifact(&self, artifact_id: &str) -> Result<Option<Vec<u8>>> {
+        check_id(artifact_id)?;
+        match fs::read(self.artifact_path(artifact_id)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stores the contents of an artifact, replacing any previous upload.
+    pub fn write_artifact(&self, artifact_id: &str, data: &[u8]) -> Result<()> {
+        check_id(artifact_id)?;
+        write_atomic(&self.artifact_path(artifact_id), data)
+    }
+
+    fn nonce_path(&self, address: &[u8; 20]) -> PathBuf {
+        self.root.join("nonces").join(hex::encode(address))
+    }
+
+    fn job_path(&self, proof_id: &str) -> PathBuf {
+        self.root.join("proofs").join(format!("{}.json", proof_id))
+    }
+
+    fn artifact_path(&self, artifact_id: &str) -> PathBuf {
+        self.root.join("artifacts").join(artifact_id)
+    }
+}
+
+/// Checks that an identifier is safe to use as a file name.
+fn check_id(id: &str) -> Result<()> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(anyhow!("invalid identifier {:?}", id));
+    }
+    Ok(())
+}
+
+/// Writes a file by writing to a temporary file first, so that readers never see a partial write.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, data).with_context(|| format!("failed to write {:?}", tmp_path))?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_store() {
+        let root = tempfile::tempdir().unwrap();
+        let store = JobStore::open(root.path()).unwrap();
+
+        let address = [1u8; 20];
+        assert_eq!(store.nonce(&address).unwrap(), 0);
+        store.set_nonce(&address, 3).unwrap();
+        assert_eq!(store.nonce(&address).unwrap(), 3);
+
+        let job = ProofJob {
+            proof_id: "proofrequest_abc".to_string(),
+            mode: ProofMode::Core,
+            status: ProofStatus::ProofPreparing,
+            requester: address,
+            deadline: 100,
+            version: "v".to_string(),
+            created_at: 0,
+            claimer: None,
+            claimed_at: None,
+            unclaim_reason: None,
+            unclaim_description: None,
+        };
+        assert!(store.job(&job.proof_id).unwrap().is_none());
+        store.put_job(&job).unwrap();
+        assert_eq!(store.job(&job.proof_id).unwrap(), Some(job.clone()));
+        assert_eq!(store.jobs().unwrap(), vec![job.clone()]);
+
+        let artifact_id = ArtifactKind::Stdin.artifact_id(&job.proof_id);
+        assert_eq!(
+            ArtifactKind::parse_artifact_id(&artifact_id),
+            Some(("proofrequest_abc", ArtifactKind::Stdin))
+        );
+        assert!(!store.has_artifact(&artifact_id).unwrap());
+        store.write_artifact(&artifact_id, b"stdin").unwrap();
+        assert_eq!(
+            store.read_artifact(&artifact_id).unwrap().unwrap(),
+            b"stdin"
+        );
+
+        // Identifiers that could escape the store are rejected.
+        assert!(store.read_artifact("../nonces").is_err());
+    }
+}
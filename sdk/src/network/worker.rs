@@ -0,0 +1,332 @@
+//! A worker that acts as a prover on the network.
+//!
+//! The worker polls the network for proof requests in the `PROOF_REQUESTED` state, claims them,
+//! proves them with a [LocalProver] and fulfills them. If a request can't be proven, it is
+//! unclaimed with an [UnclaimReason] so that the requester is notified. Requests for proof modes
+//! the worker doesn't support are never claimed, so that another prover can claim them.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Result};
+use sp1_prover::SP1Stdin;
+use tokio::{
+    sync::{Mutex, Semaphore},
+    task::JoinSet,
+    time::interval,
+};
+
+use crate::{
+    network::client::NetworkClient,
+    proto::network::{ClaimProofResponse, ProofMode, ProofStatus, UnclaimReason},
+    LocalProver, Prover,
+};
+
+/// Options for a [NetworkWorker].
+#[derive(Debug, Clone)]
+pub struct WorkerOpts {
+    /// The maximum number of requests proven at the same time.
+    pub max_concurrency: usize,
+    /// How often to poll the network for new requests.
+    pub poll_interval: Duration,
+    /// The proof modes of the requests the worker claims. PLONK proofs are only supported with the
+    /// `plonk` feature.
+    pub supported_modes: Vec<ProofMode>,
+}
+
+impl Default for WorkerOpts {
+    fn default() -> Self {
+        let mut supported_modes = vec![ProofMode::Core, ProofMode::Compressed];
+        if cfg!(feature = "plonk") {
+            supported_modes.push(ProofMode::Plonk);
+        }
+        Self {
+            max_concurrency: 1,
+            poll_interval: Duration::from_secs(5),
+            supported_modes,
+        }
+    }
+}
+
+/// A prover that claims and fulfills requests from the network. See the
+/// [module documentation](self) for details.
+pub struct NetworkWorker {
+    client: NetworkClient,
+    prover: LocalProver,
+    opts: WorkerOpts,
+    /// Authenticated requests are signed with the account's current nonce, so they are sent one at
+    /// a time.
+    auth_lock: Mutex<()>,
+}
+
+impl NetworkWorker {
+    /// Creates a new worker that authenticates with the given private key.
+    pub fn new(private_key: &str, opts: WorkerOpts) -> Self {
+        Self::with_client(NetworkClient::new(private_key), opts)
+    }
+
+    /// Creates a new worker that uses the given client to talk to the network.
+    pub fn with_client(client: NetworkClient, opts: WorkerOpts) -> Self {
+        Self {
+            client,
+            prover: LocalProver::new(),
+            opts,
+            auth_lock: Mutex::new(()),
+        }
+    }
+
+    /// Whether the worker can prove requests of the given mode.
+    fn supports(&self, mode: ProofMode) -> bool {
+        mode != ProofMode::Unspecified && self.opts.supported_modes.contains(&mode)
+    }
+
+    /// Runs the worker until `shutdown` completes.
+    ///
+    /// Once `shutdown` completes no new requests are claimed, and the worker returns after the
+    /// requests it has already claimed are fulfilled or unclaimed.
+    pub async fn run(self, shutdown: impl Future<Output = ()>) -> Result<()> {
+        let worker = Arc::new(self);
+        let semaphore = Arc::new(Semaphore::new(worker.opts.max_concurrency));
+        let mut tasks = JoinSet::new();
+        let mut poll_interval = interval(worker.opts.poll_interval);
+        tokio::pin!(shutdown);
+
+        log::info!(
+            "Worker 0x{} polling for {:?} proofs",
+            hex::encode(worker.client.auth.get_address()),
+            worker
+                .opts
+                .supported_modes
+                .iter()
+                .map(|mode| mode.as_str_name())
+                .collect::<Vec<_>>()
+        );
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                Some(result) = tasks.join_next(), if !tasks.is_empty() => {
+                    log_task_result(result);
+                }
+                _ = poll_interval.tick() => {
+                    if let Err(e) = worker.poll(&semaphore, &mut tasks).await {
+                        log::warn!("Failed to poll for proof requests: {:?}", e);
+                    }
+                }
+            }
+        }
+
+        log::info!(
+            "Shutting down, waiting for {} claimed proofs to finish",
+            tasks.len()
+        );
+        while let Some(result) = tasks.join_next().await {
+            log_task_result(result);
+        }
+        Ok(())
+    }
+
+    /// Claims as many requested proofs as there are free slots.
+    async fn poll(
+        self: &Arc<Self>,
+        semaphore: &Arc<Semaphore>,
+        tasks: &mut JoinSet<Result<()>>,
+    ) -> Result<()> {
+        if semaphore.available_permits() == 0 {
+            return Ok(());
+        }
+
+        let requests = self
+            .client
+            .get_proof_requests(ProofStatus::ProofRequested, &self.opts.supported_modes)
+            .await?;
+        for request in requests.proofs {
+            // The server only returns requests of the supported modes.
+            let mode = match ProofMode::try_from(request.mode) {
+                Ok(mode) if self.supports(mode) => mode,
+                _ => {
+                    log::warn!(
+                        "Skipping {}, proof mode {} is not supported",
+                        request.proof_id,
+                        request.mode
+                    );
+                    continue;
+                }
+            };
+            let Ok(permit) = semaphore.clone().try_acquire_owned() else {
+                break;
+            };
+
+            let claim = {
+                let _guard = self.auth_lock.lock().await;
+                self.client.claim_proof(&request.proof_id).await
+            };
+            let claim = match claim {
+                Ok(claim) => claim,
+                Err(e) => {
+                    // Another prover may have claimed the request first.
+                    log::warn!("Failed to claim {}: {:?}", request.proof_id, e);
+                    continue;
+                }
+            };
+            log::info!("Claimed {}, proving...", request.proof_id);
+
+            let worker = self.clone();
+            tasks.spawn(async move {
+                let _permit = permit;
+                worker.process(request.proof_id, mode, claim).await
+            });
+        }
+        Ok(())
+    }
+
+    /// Proves a claimed request, and fulfills or unclaims it.
+    async fn process(
+        self: Arc<Self>,
+        proof_id: String,
+        mode: ProofMode,
+        claim: ClaimProofResponse,
+    ) -> Result<()> {
+        let result = match self.clone().prove(mode, &claim).await {
+            Ok(proof) => self
+                .client
+                .upload_artifact(&claim.proof_artifact_id, proof)
+                .await
+                .map_err(|e| (UnclaimReason::Abandoned, e)),
+            Err(e) => Err(e),
+        };
+
+        let _guard = self.auth_lock.lock().await;
+        match result {
+            Ok(()) => {
+                let res = self.client.fulfill_proof(&proof_id).await?;
+                log::info!("Fulfilled {} in {} seconds", proof_id, res.proving_seconds);
+            }
+            Err((reason, e)) => {
+                log::error!("Failed to prove {}: {:?}", proof_id, e);
+                self.client
+                    .unclaim_proof(proof_id, reason, e.to_string())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Downloads the program and stdin of a claimed request and proves it in the requested mode,
+    /// returning the serialized proof.
+    ///
+    /// Requests that can't be executed are reported as [UnclaimReason::Invalid], and any other
+    /// failure as [UnclaimReason::Abandoned].
+    async fn prove(
+        self: Arc<Self>,
+        mode: ProofMode,
+        claim: &ClaimProofResponse,
+    ) -> Result<Vec<u8>, (UnclaimReason, anyhow::Error)> {
+        let abandoned = |e: anyhow::Error| (UnclaimReason::Abandoned, e);
+        let invalid = |e: anyhow::Error| (UnclaimReason::Invalid, e);
+
+        let program = self
+            .client
+            .download_artifact(&claim.program_artifact_id)
+            .await
+            .map_err(abandoned)?;
+        let stdin = self
+            .client
+            .download_artifact(&claim.stdin_artifact_id)
+            .await
+            .map_err(abandoned)?;
+        let elf: Vec<u8> = bincode::deserialize(&program).map_err(|e| invalid(e.into()))?;
+        let stdin: SP1Stdin = bincode::deserialize(&stdin).map_err(|e| invalid(e.into()))?;
+
+        // Execute the program first, so that invalid requests are told apart from proving failures.
+        let worker = self.clone();
+        let (elf, stdin) = tokio::task::spawn_blocking(move || {
+            worker.prover.sp1_prover().execute_verified(&elf, &stdin)?;
+            Ok::<_, anyhow::Error>((elf, stdin))
+        })
+        .await
+        .map_err(|e| invalid(anyhow!("execution panicked: {}", e)))?
+        .map_err(invalid)?;
+
+        tokio::task::spawn_blocking(move || {
+            let prover = &self.prover;
+            let (pk, _) = prover.setup(&elf);
+            let proof = match mode {
+                ProofMode::Core => bincode::serialize(&prover.prove(&pk, stdin)?)?,
+                ProofMode::Compressed => bincode::serialize(&prover.prove_compressed(&pk, stdin)?)?,
+                ProofMode::Plonk => bincode::serialize(&prover.prove_plonk(&pk, stdin)?)?,
+                ProofMode::Unspecified => unreachable!("unsupported proof modes are never claimed"),
+            };
+            Ok::<_, anyhow::Error>(proof)
+        })
+        .await
+        .map_err(|e| abandoned(anyhow!("proving panicked: {}", e)))?
+        .map_err(abandoned)
+    }
+}
+
+fn log_task_result(result: Result<Result<()>, tokio::task::JoinError>) {
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => log::error!("Failed to report proof result: {:?}", e),
+        Err(e) => log::error!("Proof task panicked: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::server::{
+        tests::{spawn_server, PROVER_KEY, REQUESTER_KEY},
+        DEFAULT_CLAIM_TIMEOUT, MAX_PROOF_REQUESTS,
+    };
+
+    #[tokio::test]
+    async fn test_worker_unclaims_invalid_and_skips_unsupported() {
+        let (_dir, rpc_url) = spawn_server(DEFAULT_CLAIM_TIMEOUT).await;
+        let requester = NetworkClient::with_rpc_url(REQUESTER_KEY, rpc_url.clone());
+
+        // Older requests for an unsupported mode fill a whole page of requests, but don't hide the
+        // newer supported request. Creation times have a precision of a second.
+        let mut unsupported_ids = Vec::new();
+        for _ in 0..MAX_PROOF_REQUESTS {
+            let unsupported_id = requester
+                .create_proof(b"not an elf", &SP1Stdin::new(), ProofMode::Plonk, "v")
+                .await
+                .unwrap();
+            unsupported_ids.push(unsupported_id);
+        }
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let invalid_id = requester
+            .create_proof(b"not an elf", &SP1Stdin::new(), ProofMode::Core, "v")
+            .await
+            .unwrap();
+
+        let opts = WorkerOpts {
+            poll_interval: Duration::from_millis(100),
+            supported_modes: vec![ProofMode::Core],
+            ..Default::default()
+        };
+        let worker =
+            NetworkWorker::with_client(NetworkClient::with_rpc_url(PROVER_KEY, rpc_url), opts);
+        worker
+            .run(tokio::time::sleep(Duration::from_secs(2)))
+            .await
+            .unwrap();
+
+        // The request that can't be executed is unclaimed as invalid.
+        let (status, _) = requester
+            .get_proof_status::<Vec<u8>>(&invalid_id)
+            .await
+            .unwrap();
+        assert_eq!(status.status(), ProofStatus::ProofUnclaimed);
+        assert_eq!(status.unclaim_reason(), UnclaimReason::Invalid);
+
+        // The requests for an unsupported mode are left for another prover.
+        for unsupported_id in unsupported_ids {
+            let (status, _) = requester
+                .get_proof_status::<Vec<u8>>(&unsupported_id)
+                .await
+                .unwrap();
+            assert_eq!(status.status(), ProofStatus::ProofRequested);
+        }
+    }
+}
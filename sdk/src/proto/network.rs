@@ -232,6 +232,9 @@ pub struct GetProofRequestsRequest {
     /// The status of the proof requests to get.
     #[prost(enumeration = "ProofStatus", tag = "1")]
     pub status: i32,
+    /// The proof modes of the requests to get. Requests of any mode are returned if empty.
+    #[prost(enumeration = "ProofMode", repeated, tag = "2")]
+    pub modes: ::prost::alloc::vec::Vec<i32>,
 }
 /// A proof request.
 #[derive(serde::Serialize, serde::Deserialize)]
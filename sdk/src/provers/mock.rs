@@ -1,7 +1,7 @@
 #![allow(unused_variables)]
 use crate::{
-    Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof, SP1ProofVerificationError,
-    SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
+    MerkleHash, Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof,
+    SP1ProofVerificationError, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
 };
 use anyhow::Result;
 use p3_field::PrimeField;
@@ -34,11 +34,11 @@ impl Prover for MockProver {
     }
 
     fn sp1_prover(&self) -> &SP1Prover {
-        unimplemented!("MockProver does not support SP1Prover")
+        &self.prover
     }
 
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
-        let (public_values, _) = SP1Prover::execute(&pk.elf, &stdin)?;
+        let (public_values, _) = self.prover.execute_verified(&pk.elf, &stdin)?;
         Ok(SP1ProofWithPublicValues {
             proof: vec![],
             stdin,
@@ -55,7 +55,7 @@ impl Prover for MockProver {
     }
 
     fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
-        let (public_values, _) = SP1Prover::execute(&pk.elf, &stdin)?;
+        let (public_values, _) = self.prover.execute_verified(&pk.elf, &stdin)?;
         Ok(SP1PlonkBn254Proof {
             proof: PlonkBn254Proof {
                 public_inputs: [
@@ -86,8 +86,18 @@ impl Prover for MockProver {
         Ok(())
     }
 
-    fn verify_plonk(&self, proof: &SP1PlonkBn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
-        verify_plonk_bn254_public_inputs(vkey, &proof.public_values, &proof.proof.public_inputs)?;
+    fn verify_plonk(
+        &self,
+        proof: &SP1PlonkBn254Proof,
+        vkey: &SP1VerifyingKey,
+        merkle_hash: Option<MerkleHash>,
+    ) -> Result<()> {
+        verify_plonk_bn254_public_inputs(
+            vkey,
+            &proof.public_values,
+            merkle_hash,
+            &proof.proof.public_inputs,
+        )?;
         Ok(())
     }
 }
@@ -1,7 +1,7 @@
 mod local;
 mod mock;
 
-use crate::{SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof};
+use crate::{MerkleHash, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof};
 use anyhow::Result;
 pub use local::LocalProver;
 pub use mock::MockProver;
@@ -61,8 +61,14 @@ pub trait Prover: Send + Sync {
     }
 
     /// Verify that a SP1 PLONK proof is valid. Verify that the public inputs of the PlonkBn254 proof match
-    /// the hash of the VK and the committed public values of the SP1ProofWithPublicValues.
-    fn verify_plonk(&self, proof: &SP1PlonkBn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
+    /// the hash of the VK and the committed public values of the SP1ProofWithPublicValues, hashed
+    /// with SHA-256 or, with a `merkle_hash`, as the root of their Merkle tree.
+    fn verify_plonk(
+        &self,
+        proof: &SP1PlonkBn254Proof,
+        vkey: &SP1VerifyingKey,
+        merkle_hash: Option<MerkleHash>,
+    ) -> Result<()> {
         let sp1_prover = self.sp1_prover();
 
         let plonk_bn254_aritfacts = if sp1_prover::build::sp1_dev_mode() {
@@ -74,6 +80,7 @@ pub trait Prover: Send + Sync {
             &proof.proof,
             vkey,
             &proof.public_values,
+            merkle_hash,
             &plonk_bn254_aritfacts,
         )?;
 
@@ -0,0 +1,43 @@
+//! A test program that copies and fills buffers with `memcpy` and `memset`, with the offsets and
+//! lengths read from the input so that the host can exercise each of their paths.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+/// A word aligned buffer, so that `memcpy` and `memset` of at least 128 bytes at word aligned
+/// offsets use the syscalls.
+#[repr(align(4))]
+struct Buffer([u8; 1024]);
+
+fn byte(i: usize) -> u8 {
+    (i * 7 + 3) as u8
+}
+
+pub fn main() {
+    // Copies as `(dst_offset, src_offset, len)`, and fills as `(offset, len, value)`.
+    let copies = sp1_zkvm::io::read::<Vec<(usize, usize, usize)>>();
+    let fills = sp1_zkvm::io::read::<Vec<(usize, usize, u8)>>();
+
+    let mut src = Buffer([0; 1024]);
+    for (i, b) in src.0.iter_mut().enumerate() {
+        *b = byte(i);
+    }
+
+    let mut dst = Buffer([0; 1024]);
+    for (dst_offset, src_offset, len) in copies {
+        dst.0[dst_offset..dst_offset + len].copy_from_slice(&src.0[src_offset..src_offset + len]);
+        assert_eq!(
+            dst.0[dst_offset..dst_offset + len],
+            src.0[src_offset..src_offset + len]
+        );
+    }
+
+    for (offset, len, value) in fills {
+        let before = (offset > 0).then(|| dst.0[offset - 1]);
+        dst.0[offset..offset + len].fill(value);
+        assert!(dst.0[offset..offset + len].iter().all(|&b| b == value));
+        assert_eq!(before, (offset > 0).then(|| dst.0[offset - 1]));
+    }
+
+    sp1_zkvm::io::commit(&dst.0.iter().map(|&b| b as u32).sum::<u32>());
+}
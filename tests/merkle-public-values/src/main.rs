@@ -0,0 +1,23 @@
+//! A test program that commits its public values as the leaves of a Keccak-256 Merkle tree.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::merkle::{self, MerkleHash};
+
+pub fn main() {
+    merkle::enable(MerkleHash::Keccak256);
+
+    let n = sp1_zkvm::io::read::<u32>();
+    merkle::commit(&n);
+
+    let mut a = 0u32;
+    let mut b = 1u32;
+    for _ in 0..n {
+        let c = a.wrapping_add(b);
+        a = b;
+        b = c;
+    }
+    merkle::commit(&b);
+    merkle::commit_leaf(b"fibonacci");
+}
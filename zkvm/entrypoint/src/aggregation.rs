@@ -0,0 +1,42 @@
+//! Aggregation of proofs of other programs, as done by the program behind
+//! `ProverClient::aggregate`.
+//!
+//! The host writes the verifying key digests and the public values of the proofs, how each proof
+//! commits to its public values, the [AggregationCommitment] to commit them with, and the proofs
+//! themselves with `SP1Stdin::write_proof`. The proofs are not read by the program, but verified by
+//! the prover through the deferred proofs of the recursion.
+
+pub use sp1_primitives::aggregation::*;
+use sp1_primitives::merkle::{public_values_digest, MerkleHash};
+
+/// Reads the proofs to aggregate from the input, verifies them, and commits to them with the
+/// commitment scheme read last. Returns the verifying key digests and public values of the proofs.
+pub fn aggregate() -> (Vec<[u32; 8]>, Vec<Vec<u8>>) {
+    let vkeys = crate::io::read::<Vec<[u32; 8]>>();
+    let public_values = crate::io::read::<Vec<Vec<u8>>>();
+    let merkle_hashes = crate::io::read::<Vec<Option<MerkleHash>>>();
+    let commitment = crate::io::read::<AggregationCommitment>();
+
+    verify_proofs(&vkeys, &public_values, &merkle_hashes);
+    crate::io::commit_slice(&commit_aggregation(commitment, &vkeys, &public_values));
+    (vkeys, public_values)
+}
+
+/// Verifies the next proofs in the proof input stream, given their verifying key digests and
+/// public values.
+///
+/// Each proof commits to the SHA-256 hash of its public values, or if its program commits them
+/// with `sp1_zkvm::merkle`, to the root of their Merkle tree with the given hash.
+pub fn verify_proofs(
+    vkeys: &[[u32; 8]],
+    public_values: &[Vec<u8>],
+    merkle_hashes: &[Option<MerkleHash>],
+) {
+    assert_eq!(vkeys.len(), public_values.len());
+    assert_eq!(vkeys.len(), merkle_hashes.len());
+    for ((vkey, values), merkle_hash) in vkeys.iter().zip(public_values).zip(merkle_hashes) {
+        let public_values_digest = public_values_digest(values, *merkle_hash)
+            .expect("the public values are not a list of Merkle leaves");
+        crate::precompiles::verify::verify_sp1_proof(vkey, &public_values_digest);
+    }
+}
@@ -0,0 +1,124 @@
+//! Seeding of the randomness the program gets from the `getrandom` crate, and so from
+//! `rand::thread_rng`, hash map seeds and any other crate built on it.
+//!
+//! Without a seed, `getrandom` returns the same bytes in every execution, and prints a warning.
+//! A seed makes the randomness as unpredictable as the seed is. It can be supplied by the host
+//! through `SP1Stdin::write_entropy`, or derived from data the program has already committed to,
+//! so that the randomness can't be chosen by the prover after the fact.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+
+/// A seed of the randomness of the program.
+pub type Seed = [u8; 32];
+
+/// The prefix of the data hashed into the seeds derived by the program.
+const DOMAIN_SEPARATOR: &[u8] = b"SP1_ENTROPY";
+
+/// The generator `getrandom` draws from, once a seed is set.
+static mut RNG: Option<StdRng> = None;
+
+/// Seeds the randomness of the program, replacing any previous seed.
+pub fn set_seed(seed: Seed) {
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    unsafe { RNG = Some(StdRng::from_seed(seed)) };
+}
+
+/// Seeds the randomness of the program with the next input, written by the host with
+/// `SP1Stdin::write_entropy`.
+///
+/// The seed is not bound to the proof, so the prover can choose it. Use [read_committed_seed]
+/// if the verifier needs to check which seed was used.
+pub fn read_seed() -> Seed {
+    let seed: Seed = crate::io::read_vec()
+        .try_into()
+        .expect("the entropy seed must be 32 bytes");
+    set_seed(seed);
+    seed
+}
+
+/// Seeds the randomness of the program with the next input, like [read_seed], and commits the
+/// seed to the public values.
+pub fn read_committed_seed() -> Seed {
+    let seed = read_seed();
+    crate::io::commit_slice(&seed);
+    seed
+}
+
+/// Seeds the randomness of the program with a hash of the given data.
+pub fn seed_from_data(data: &[u8]) -> Seed {
+    let seed: Seed = Sha256::new()
+        .chain_update(DOMAIN_SEPARATOR)
+        .chain_update(data)
+        .finalize()
+        .into();
+    set_seed(seed);
+    seed
+}
+
+/// Seeds the randomness of the program with a hash of the public values committed so far.
+///
+/// Like the challenges of a Fiat-Shamir transform, the randomness is then fixed by what the
+/// program has committed to, and a prover can only change it by changing the public values.
+///
+/// Only available in the zkVM, as there are no public values on the host.
+#[cfg(target_os = "zkvm")]
+pub fn seed_from_public_values() -> Seed {
+    // SAFETY: Single threaded, and the hasher is set before `main` is called.
+    let digest = unsafe { crate::zkvm::PUBLIC_VALUES_HASHER.clone() }
+        .unwrap()
+        .finalize();
+    seed_from_data(&digest)
+}
+
+/// Fills the buffer from the seeded generator. Returns false if no seed is set.
+#[cfg(any(target_os = "zkvm", test))]
+pub(crate) fn fill_bytes(buf: &mut [u8]) -> bool {
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    match unsafe { RNG.as_mut() } {
+        Some(rng) => {
+            rand::RngCore::fill_bytes(rng, buf);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::*;
+
+    fn fill() -> Option<[u8; 64]> {
+        let mut buf = [0; 64];
+        fill_bytes(&mut buf).then_some(buf)
+    }
+
+    // The generator is global, so the seeding paths are tested in order in a single test.
+    #[test]
+    fn test_seeding() {
+        assert_eq!(fill(), None);
+
+        // A seed set directly is used as is.
+        let seed = [7; 32];
+        set_seed(seed);
+        let mut expected = [0; 64];
+        StdRng::from_seed(seed).fill_bytes(&mut expected);
+        assert_eq!(fill(), Some(expected));
+        assert_ne!(fill(), Some(expected));
+
+        // A derived seed is a domain separated hash of the data, and replaces the previous seed.
+        let derived = seed_from_data(b"committed");
+        let mut hash_input = DOMAIN_SEPARATOR.to_vec();
+        hash_input.extend_from_slice(b"committed");
+        assert_eq!(derived, <Seed>::from(Sha256::digest(&hash_input)));
+        assert_ne!(derived, seed_from_data(b"other"));
+
+        seed_from_data(b"committed");
+        let mut expected = [0; 64];
+        StdRng::from_seed(derived).fill_bytes(&mut expected);
+        assert_eq!(fill(), Some(expected));
+    }
+}
@@ -0,0 +1,33 @@
+//! A read-only filesystem of the files added by the host with `SP1Stdin::write_file` and
+//! `SP1Stdin::write_dir`.
+//!
+//! The files are read from the host on every call, so a program that reads a file more than once
+//! should keep its contents. The host can't be trusted to serve the files it claims to, so a
+//! program whose output depends on a file should commit its [hash].
+
+use std::io::{Error, ErrorKind, Result};
+
+use sha2::{Digest, Sha256};
+
+/// Reads the contents of a file.
+pub fn read(path: &str) -> Result<Vec<u8>> {
+    crate::io::read_file(path)
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("file not found: {}", path)))
+}
+
+/// Reads the contents of a file as a string.
+pub fn read_to_string(path: &str) -> Result<String> {
+    String::from_utf8(read(path)?).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Reads a file, and returns its contents with their SHA-256 hash.
+pub fn read_with_hash(path: &str) -> Result<(Vec<u8>, [u8; 32])> {
+    let contents = read(path)?;
+    let hash = Sha256::digest(&contents).into();
+    Ok((contents, hash))
+}
+
+/// The SHA-256 hash of a file, which the host computes with `SP1Stdin::file_hash`.
+pub fn hash(path: &str) -> Result<[u8; 32]> {
+    read_with_hash(path).map(|(_, hash)| hash)
+}
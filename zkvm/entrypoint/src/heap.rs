@@ -2,11 +2,42 @@ use core::alloc::{GlobalAlloc, Layout};
 
 use crate::syscalls::sys_alloc_aligned;
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "free-list-alloc")] {
+        /// The allocator installed by the `entrypoint!` macro.
+        pub type GuestAlloc = FreeListAlloc;
+    } else {
+        /// The allocator installed by the `entrypoint!` macro.
+        pub type GuestAlloc = SimpleAlloc;
+    }
+}
+
+/// Allocates memory that has never been written, as the hint stream can only be read into such
+/// memory. The allocation can be freed like any allocation of the same size by the global
+/// allocator.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn sys_alloc_fresh(bytes: usize, align: usize) -> *mut u8 {
+    GuestAlloc::alloc_fresh(Layout::from_size_align_unchecked(bytes, align))
+}
+
 /// A simple heap allocator.
 ///
 /// Allocates memory from left to right, without any deallocation.
+#[derive(Default)]
 pub struct SimpleAlloc;
 
+impl SimpleAlloc {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Allocates memory that has never been written, which is any memory of this allocator.
+    pub unsafe fn alloc_fresh(layout: Layout) -> *mut u8 {
+        sys_alloc_aligned(layout.size(), layout.align())
+    }
+}
+
 unsafe impl GlobalAlloc for SimpleAlloc {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         sys_alloc_aligned(layout.size(), layout.align())
@@ -14,3 +45,280 @@ unsafe impl GlobalAlloc for SimpleAlloc {
 
     unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
 }
+
+/// The size of the smallest block, which is also the alignment of all blocks.
+const MIN_BLOCK_SIZE: usize = 16;
+
+/// The number of size classes, whose blocks are 16 bytes to 32 KiB.
+const NUM_SIZE_CLASSES: usize = 12;
+
+/// A heap allocator that reuses freed memory.
+///
+/// Allocations of up to 32 KiB are rounded up to a power of two, and their blocks are kept in a
+/// free list per size class when freed. Larger allocations, and the ones aligned to more than 16
+/// bytes, are served first-fit from a list of free blocks ordered by address, in which adjacent
+/// blocks are merged. Memory that is not on any free list is allocated from the heap like
+/// [SimpleAlloc] does, and blocks never move from a size class to the large blocks or back.
+pub struct FreeListAlloc {
+    lists: core::cell::UnsafeCell<FreeLists>,
+}
+
+// SAFETY: The zkVM is single threaded.
+unsafe impl Sync for FreeListAlloc {}
+
+struct FreeLists {
+    /// The first free block of each size class.
+    classes: [*mut FreeBlock; NUM_SIZE_CLASSES],
+    /// The first free large block.
+    large: *mut FreeBlock,
+}
+
+/// The header written into a free block.
+struct FreeBlock {
+    /// The size of the block, only set for large blocks.
+    size: usize,
+    next: *mut FreeBlock,
+}
+
+/// The block an allocation is served from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockSize {
+    /// A block of `MIN_BLOCK_SIZE << class` bytes.
+    Class(usize),
+    /// A block of the given number of bytes, a multiple of `MIN_BLOCK_SIZE`.
+    Large(usize),
+}
+
+impl BlockSize {
+    fn of(layout: &Layout) -> Self {
+        let size = layout.size().max(MIN_BLOCK_SIZE);
+        if layout.align() <= MIN_BLOCK_SIZE {
+            let class = (size.next_power_of_two() / MIN_BLOCK_SIZE).trailing_zeros() as usize;
+            if class < NUM_SIZE_CLASSES {
+                return BlockSize::Class(class);
+            }
+        }
+        BlockSize::Large((size + MIN_BLOCK_SIZE - 1) & !(MIN_BLOCK_SIZE - 1))
+    }
+}
+
+impl FreeListAlloc {
+    pub const fn new() -> Self {
+        Self {
+            lists: core::cell::UnsafeCell::new(FreeLists {
+                classes: [core::ptr::null_mut(); NUM_SIZE_CLASSES],
+                large: core::ptr::null_mut(),
+            }),
+        }
+    }
+
+    /// Allocates a block that has never been written, from the heap instead of the free lists.
+    pub unsafe fn alloc_fresh(layout: Layout) -> *mut u8 {
+        match BlockSize::of(&layout) {
+            BlockSize::Class(class) => sys_alloc_aligned(MIN_BLOCK_SIZE << class, MIN_BLOCK_SIZE),
+            BlockSize::Large(size) => sys_alloc_aligned(size, layout.align().max(MIN_BLOCK_SIZE)),
+        }
+    }
+}
+
+impl Default for FreeListAlloc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FreeLists {
+    /// Removes a large block of at least `size` bytes aligned to `align` from the free list,
+    /// keeping its remainder on the list.
+    unsafe fn take_large(&mut self, size: usize, align: usize) -> *mut u8 {
+        let mut prev: *mut FreeBlock = core::ptr::null_mut();
+        let mut block = self.large;
+        while !block.is_null() {
+            let block_size = (*block).size;
+            if block as usize % align == 0 && block_size >= size {
+                let mut next = (*block).next;
+                if block_size > size {
+                    let rest = (block as *mut u8).add(size) as *mut FreeBlock;
+                    rest.write(FreeBlock {
+                        size: block_size - size,
+                        next,
+                    });
+                    next = rest;
+                }
+                if prev.is_null() {
+                    self.large = next;
+                } else {
+                    (*prev).next = next;
+                }
+                return block as *mut u8;
+            }
+            prev = block;
+            block = (*block).next;
+        }
+        core::ptr::null_mut()
+    }
+
+    /// Inserts a large block into the free list, merging it with its free neighbours.
+    unsafe fn free_large(&mut self, ptr: *mut u8, mut size: usize) {
+        let block = ptr as *mut FreeBlock;
+        let mut prev: *mut FreeBlock = core::ptr::null_mut();
+        let mut next = self.large;
+        while !next.is_null() && (next as usize) < (block as usize) {
+            prev = next;
+            next = (*next).next;
+        }
+        if !next.is_null() && block as usize + size == next as usize {
+            size += (*next).size;
+            next = (*next).next;
+        }
+        if !prev.is_null() && prev as usize + (*prev).size == block as usize {
+            (*prev).size += size;
+            (*prev).next = next;
+            return;
+        }
+        block.write(FreeBlock { size, next });
+        if prev.is_null() {
+            self.large = block;
+        } else {
+            (*prev).next = block;
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for FreeListAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let lists = &mut *self.lists.get();
+        let ptr = match BlockSize::of(&layout) {
+            BlockSize::Class(class) => {
+                let block = lists.classes[class];
+                if !block.is_null() {
+                    lists.classes[class] = (*block).next;
+                }
+                block as *mut u8
+            }
+            BlockSize::Large(size) => lists.take_large(size, layout.align().max(MIN_BLOCK_SIZE)),
+        };
+        if ptr.is_null() {
+            return Self::alloc_fresh(layout);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let lists = &mut *self.lists.get();
+        match BlockSize::of(&layout) {
+            BlockSize::Class(class) => {
+                let block = ptr as *mut FreeBlock;
+                block.write(FreeBlock {
+                    size: 0,
+                    next: lists.classes[class],
+                });
+                lists.classes[class] = block;
+            }
+            BlockSize::Large(size) => lists.free_large(ptr, size),
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        // The block already fits the new size.
+        if BlockSize::of(&layout) == BlockSize::of(&new_layout) {
+            return ptr;
+        }
+        let new_ptr = self.alloc(new_layout);
+        core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        self.dealloc(ptr, layout);
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The memory the free blocks of a test live in.
+    #[repr(align(256))]
+    struct Arena([u8; 1024]);
+
+    fn free_lists() -> FreeLists {
+        FreeLists {
+            classes: [core::ptr::null_mut(); NUM_SIZE_CLASSES],
+            large: core::ptr::null_mut(),
+        }
+    }
+
+    /// The free large blocks, as offsets and sizes in the arena.
+    unsafe fn large_blocks(lists: &FreeLists, arena: &Arena) -> Vec<(usize, usize)> {
+        let base = arena.0.as_ptr() as usize;
+        let mut blocks = Vec::new();
+        let mut block = lists.large;
+        while !block.is_null() {
+            blocks.push((block as usize - base, (*block).size));
+            block = (*block).next;
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_take_large_reuses_and_splits_blocks() {
+        let mut arena = Arena([0; 1024]);
+        let base = arena.0.as_mut_ptr();
+        let mut lists = free_lists();
+        unsafe {
+            lists.free_large(base, 512);
+            assert_eq!(lists.take_large(128, 16), base);
+            assert_eq!(large_blocks(&lists, &arena), [(128, 384)]);
+
+            // A freed block is reused for a request of the same size.
+            lists.free_large(base, 128);
+            assert_eq!(lists.take_large(128, 16), base);
+
+            // Blocks that are too small or misaligned are not taken.
+            assert!(lists.take_large(512, 16).is_null());
+            assert!(lists.take_large(256, 256).is_null());
+            assert_eq!(large_blocks(&lists, &arena), [(128, 384)]);
+        }
+    }
+
+    #[test]
+    fn test_free_large_coalesces_neighbours() {
+        let mut arena = Arena([0; 1024]);
+        let base = arena.0.as_mut_ptr();
+        let mut lists = free_lists();
+        unsafe {
+            // Blocks freed out of order are kept sorted, and merged once they are adjacent.
+            lists.free_large(base.add(512), 128);
+            lists.free_large(base, 128);
+            lists.free_large(base.add(256), 128);
+            assert_eq!(
+                large_blocks(&lists, &arena),
+                [(0, 128), (256, 128), (512, 128)]
+            );
+            lists.free_large(base.add(128), 128);
+            assert_eq!(large_blocks(&lists, &arena), [(0, 384), (512, 128)]);
+            lists.free_large(base.add(384), 128);
+            assert_eq!(large_blocks(&lists, &arena), [(0, 640)]);
+
+            assert_eq!(lists.take_large(640, 16), base);
+            assert!(lists.large.is_null());
+        }
+    }
+
+    #[test]
+    fn test_take_large_exhausted() {
+        let mut arena = Arena([0; 1024]);
+        let base = arena.0.as_mut_ptr();
+        let mut lists = free_lists();
+        unsafe {
+            assert!(lists.take_large(16, 16).is_null());
+            lists.free_large(base, 256);
+            lists.free_large(base.add(512), 256);
+
+            // No single block is large enough, so the request falls back to the heap.
+            assert!(lists.take_large(512, 16).is_null());
+            assert_eq!(lists.take_large(256, 16), base);
+            assert_eq!(lists.take_large(256, 16), base.add(512));
+            assert!(lists.take_large(16, 16).is_null());
+        }
+    }
+}
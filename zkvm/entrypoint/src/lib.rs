@@ -1,4 +1,10 @@
+#[cfg(feature = "verify")]
+pub mod aggregation;
+pub mod entropy;
+pub mod fs;
 pub mod heap;
+#[cfg(feature = "merkle")]
+pub mod merkle;
 pub mod syscalls;
 pub mod io {
     pub use sp1_precompiles::io::*;
@@ -14,10 +20,8 @@ macro_rules! entrypoint {
     ($path:path) => {
         const ZKVM_ENTRY: fn() = $path;
 
-        use $crate::heap::SimpleAlloc;
-
         #[global_allocator]
-        static HEAP: SimpleAlloc = SimpleAlloc;
+        static HEAP: $crate::heap::GuestAlloc = $crate::heap::GuestAlloc::new();
 
         mod zkvm_generated_main {
 
@@ -101,8 +105,12 @@ mod zkvm {
         use rand::Rng;
         use rand::SeedableRng;
 
+        if crate::entropy::fill_bytes(s) {
+            return Ok(());
+        }
+
         GETRANDOM_WARNING_ONCE.call_once(|| {
-            println!("WARNING: Using insecure random number generator");
+            println!("WARNING: Using insecure random number generator, see `sp1_zkvm::entropy`");
         });
         let mut rng = rand::rngs::StdRng::seed_from_u64(123);
         for i in 0..s.len() {
@@ -205,10 +205,40 @@
 	.attribute	4, 16
 	.attribute	5, "rv32im"
 	.file	"musl_memcpy.c"
+
+// Copies of at least one block between word aligned buffers are done a block of 32 words at a
+// time with the MEMCPY syscall (0x00_01_01_26), and the rest is left to the musl memcpy below,
+// which has been renamed to `memcpy_musl`.
 	.globl	memcpy
 	.p2align	2
 	.type	memcpy,@function
 memcpy:
+	li	a3, 128
+	bltu	a2, a3, memcpy_musl
+	or	a4, a0, a1
+	andi	a4, a4, 3
+	bnez	a4, memcpy_musl
+	addi	sp, sp, -16
+	sw	ra, 12(sp)
+	sw	a0, 8(sp)
+.LBBmemcpy_block:
+	li	t0, 0x00010126
+	ecall
+	addi	a0, a0, 128
+	addi	a1, a1, 128
+	addi	a2, a2, -128
+	bgeu	a2, a3, .LBBmemcpy_block
+	call	memcpy_musl
+	lw	a0, 8(sp)
+	lw	ra, 12(sp)
+	addi	sp, sp, 16
+	ret
+.Lfuncmemcpy_block_end:
+	.size	memcpy, .Lfuncmemcpy_block_end-memcpy
+
+	.p2align	2
+	.type	memcpy_musl,@function
+memcpy_musl:
 	andi	a3, a1, 3
 	seqz	a3, a3
 	seqz	a4, a2
@@ -491,7 +521,7 @@ memcpy:
 	bnez	a1, .LBBmemcpy0_29
 	j	.LBBmemcpy0_30
 .Lfuncmemcpy_end0:
-	.size	memcpy, .Lfuncmemcpy_end0-memcpy
+	.size	memcpy_musl, .Lfuncmemcpy_end0-memcpy_musl
 
 	.ident	"Ubuntu clang version 14.0.6-++20220622053131+f28c006a5895-1~exp1~20220622173215.157"
 	.section	".note.GNU-stack","",@progbits
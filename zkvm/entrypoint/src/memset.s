@@ -205,10 +205,39 @@
 	.attribute	4, 16
 	.attribute	5, "rv32im"
 	.file	"musl_memset.c"
+
+// Fills of at least one block of a word aligned buffer are done a block of 32 words at a time
+// with the MEMSET syscall (0x00_00_01_27), and the rest is left to the musl memset below, which
+// has been renamed to `memset_musl`.
 	.globl	memset
 	.p2align	2
 	.type	memset,@function
 memset:
+	li	a3, 128
+	bltu	a2, a3, memset_musl
+	andi	a4, a0, 3
+	bnez	a4, memset_musl
+	addi	sp, sp, -16
+	sw	ra, 12(sp)
+	sw	a0, 8(sp)
+	andi	a1, a1, 255
+.LBB0_block_memset:
+	li	t0, 0x00000127
+	ecall
+	addi	a0, a0, 128
+	addi	a2, a2, -128
+	bgeu	a2, a3, .LBB0_block_memset
+	call	memset_musl
+	lw	a0, 8(sp)
+	lw	ra, 12(sp)
+	addi	sp, sp, 16
+	ret
+.Lfunc_block_end0memset:
+	.size	memset, .Lfunc_block_end0memset-memset
+
+	.p2align	2
+	.type	memset_musl,@function
+memset_musl:
 	beqz	a2, .LBB0_9memset
 	sb	a1, 0(a0)
 	add	a3, a2, a0
@@ -274,7 +303,7 @@ memset:
 .LBB0_9memset:
 	ret
 .Lfunc_end0memset:
-	.size	memset, .Lfunc_end0memset-memset
+	.size	memset_musl, .Lfunc_end0memset-memset_musl
 
 	.ident	"Ubuntu clang version 14.0.6-++20220622053131+f28c006a5895-1~exp1~20220622173215.157"
 	.section	".note.GNU-stack","",@progbits
@@ -0,0 +1,96 @@
+//! Committing the public values as the leaves of a Merkle tree.
+//!
+//! By default, the digest the proof commits to is the SHA-256 hash of all the public values, so a
+//! verifier needs all of them to check any. Once [enable] is called, each value committed with
+//! [commit_leaf] or [commit] is instead a leaf of a Merkle tree, and the digest is its root. A
+//! verifier can then check a single value against the root with an inclusion proof from
+//! `SP1PublicValues::merkle_proof`, e.g. in a contract that only needs one of the values.
+//!
+//! The tree is described in [sp1_primitives::merkle]. Note that the digest checked by the PLONK
+//! verifier is the root with its top 3 bits cleared. Keccak-256 trees are cheaper to check on
+//! chain, and are accelerated in the program by patching `tiny-keccak` with the SP1 patch.
+
+use serde::Serialize;
+use sp1_primitives::merkle::MerkleTreeBuilder;
+pub use sp1_primitives::merkle::{MerkleHash, MerkleProof};
+
+/// The tree of the public values, once enabled.
+static mut TREE: Option<PublicValuesTree> = None;
+
+struct PublicValuesTree {
+    builder: MerkleTreeBuilder,
+    /// Whether a leaf is being written to the public values, which is the only way they can be
+    /// written once the tree is enabled.
+    writing_leaf: bool,
+}
+
+/// Commits to the public values with a Merkle tree of the given hash, instead of hashing them.
+///
+/// Must be called before any public values are committed.
+pub fn enable(hash: MerkleHash) {
+    #[cfg(target_os = "zkvm")]
+    {
+        use sha2::{Digest, Sha256};
+
+        // SAFETY: Single threaded, and the hasher is set before `main` is called.
+        let digest = unsafe { crate::zkvm::PUBLIC_VALUES_HASHER.clone() }
+            .unwrap()
+            .finalize();
+        assert!(
+            digest == Sha256::digest(b""),
+            "the Merkle tree must be enabled before any public values are committed"
+        );
+    }
+
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    unsafe {
+        assert!(TREE.is_none(), "the Merkle tree is already enabled");
+        TREE = Some(PublicValuesTree {
+            builder: MerkleTreeBuilder::new(hash),
+            writing_leaf: false,
+        });
+    }
+}
+
+/// Commits a value as the next leaf of the tree.
+///
+/// The value is written to the public values prefixed by its length as a little endian u32, so
+/// that the host can split them back into leaves.
+pub fn commit_leaf(value: &[u8]) {
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    let tree = unsafe { TREE.as_mut() }.expect("the Merkle tree is not enabled");
+    tree.builder.push(value);
+
+    tree.writing_leaf = true;
+    let len = u32::try_from(value.len()).expect("the leaf is too large");
+    crate::io::commit_slice(&len.to_le_bytes());
+    crate::io::commit_slice(value);
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    unsafe { TREE.as_mut() }.unwrap().writing_leaf = false;
+}
+
+/// Commits a value serialized with bincode as the next leaf of the tree.
+pub fn commit<T: Serialize>(value: &T) {
+    let bytes = bincode::serialize(value).expect("serialization failed");
+    commit_leaf(&bytes);
+}
+
+/// Panics if the public values are written outside of [commit_leaf] while the tree is enabled,
+/// as those values would not be committed to.
+#[allow(dead_code)]
+pub(crate) fn check_public_values_write() {
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    if let Some(tree) = unsafe { TREE.as_ref() } {
+        assert!(
+            tree.writing_leaf,
+            "public values must be committed with `merkle::commit_leaf` once the tree is enabled"
+        );
+    }
+}
+
+/// The root of the tree, if it's enabled.
+#[allow(dead_code)]
+pub(crate) fn root() -> Option<[u8; 32]> {
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    unsafe { TREE.as_ref() }.map(|tree| tree.builder.root())
+}
@@ -0,0 +1,82 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Uint512 modular multiplication operation.
+///
+/// The result is written over the first input, and the modulus is read after the second input.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint512_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT512_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Uint1024 modular multiplication operation.
+///
+/// The result is written over the first input, and the modulus is read after the second input.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint1024_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT1024_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Uint2048 modular multiplication operation.
+///
+/// The result is written over the first input, and the modulus is read after the second input.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint2048_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT2048_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Uint4096 modular multiplication operation.
+///
+/// The result is written over the first input, and the modulus is read after the second input.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint4096_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT4096_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
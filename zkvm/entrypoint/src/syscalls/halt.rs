@@ -16,15 +16,23 @@ cfg_if! {
 }
 
 /// Halts the program.
-#[allow(unused_variables)]
 pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
+    halt(exit_code as u32)
+}
+
+/// Halts the program with any exit code, including the ones reserved for the runtime such as
+/// [sp1_primitives::OUT_OF_MEMORY_EXIT_CODE].
+#[allow(unused_variables)]
+pub(crate) fn halt(exit_code: u32) -> ! {
     #[cfg(target_os = "zkvm")]
     unsafe {
         // When we halt, we retrieve the public values finalized digest.  This is the hash of all
-        // the bytes written to the public values fd.
+        // the bytes written to the public values fd, or the root of their Merkle tree if enabled.
         let pv_digest_bytes = core::mem::take(&mut zkvm::PUBLIC_VALUES_HASHER)
             .unwrap()
             .finalize();
+        #[cfg(feature = "merkle")]
+        let pv_digest_bytes = crate::merkle::root().map_or(pv_digest_bytes, Into::into);
 
         // Convert the digest bytes into words, since we will be calling COMMIT ecall with
         // the words as a parameter.
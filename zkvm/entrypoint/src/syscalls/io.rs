@@ -27,6 +27,9 @@ pub extern "C" fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize) {
             // being written. At the end of the program, we call the COMMIT ecall with the finalized
             // version of this hash.
             if fd == FD_PUBLIC_VALUES {
+                #[cfg(feature = "merkle")]
+                crate::merkle::check_public_values_write();
+
                 let pi_slice: &[u8] = unsafe { core::slice::from_raw_parts(write_buf, nbytes) };
                 unsafe { zkvm::PUBLIC_VALUES_HASHER.as_mut().unwrap().update(pi_slice) };
             }
@@ -0,0 +1,42 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Copies a block of 32 words from `src` to `dst`.
+///
+/// Both pointers must be word aligned. The blocks may overlap.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_memcpy(dst: *mut u32, src: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::MEMCPY,
+            in("a0") dst,
+            in("a1") src,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Fills a block of 32 words at `dst` with the byte `value`.
+///
+/// The pointer must be word aligned, and the value must be less than 256.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_memset(dst: *mut u32, value: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::MEMSET,
+            in("a0") dst,
+            in("a1") value,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
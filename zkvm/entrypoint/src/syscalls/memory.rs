@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use sp1_primitives::OUT_OF_MEMORY_EXIT_CODE;
+
+use crate::syscalls::{halt, sys_write};
+
 const SYSTEM_START: usize = 0x0C00_0000;
 
 #[allow(clippy::missing_safety_doc)]
@@ -39,11 +43,14 @@ pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u
     }
 
     let ptr = heap_pos as *mut u8;
-    heap_pos += bytes;
+    heap_pos = heap_pos.saturating_add(bytes);
 
-    // Check to make sure heap doesn't collide with SYSTEM memory.
+    // Check to make sure heap doesn't collide with SYSTEM memory. Panicking here would allocate,
+    // so halt with a dedicated exit code that the runtime reports as out of memory.
     if SYSTEM_START < heap_pos {
-        panic!();
+        const MESSAGE: &[u8] = b"out of memory: the heap collided with the system memory\n";
+        sys_write(2, MESSAGE.as_ptr(), MESSAGE.len());
+        halt(OUT_OF_MEMORY_EXIT_CODE);
     }
 
     unsafe { HEAP_POS = heap_pos };
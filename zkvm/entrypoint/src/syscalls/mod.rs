@@ -1,3 +1,4 @@
+mod bigint_mulmod;
 mod blake3_compress;
 mod bls12381;
 mod bn254;
@@ -5,27 +6,32 @@ mod ed25519;
 mod halt;
 mod io;
 mod keccak_permute;
+mod memcopy;
 mod memory;
 mod secp256k1;
 mod sha_compress;
 mod sha_extend;
 mod sys;
+mod uint256_add_sub;
 mod uint256_mul;
 mod unconstrained;
 #[cfg(feature = "verify")]
 mod verify;
 
+pub use bigint_mulmod::*;
 pub use bls12381::*;
 pub use bn254::*;
 pub use ed25519::*;
 pub use halt::*;
 pub use io::*;
 pub use keccak_permute::*;
+pub use memcopy::*;
 pub use memory::*;
 pub use secp256k1::*;
 pub use sha_compress::*;
 pub use sha_extend::*;
 pub use sys::*;
+pub use uint256_add_sub::*;
 pub use uint256_mul::*;
 pub use unconstrained::*;
 #[cfg(feature = "verify")]
@@ -105,3 +111,27 @@ pub const BLS12381_ADD: u32 = 0x00_01_01_1E;
 
 /// Executes the `BLS12381_DOUBLE` precompile.
 pub const BLS12381_DOUBLE: u32 = 0x00_00_01_1F;
+
+/// Executes the `UINT256_ADDMOD` precompile.
+pub const UINT256_ADDMOD: u32 = 0x00_00_01_20;
+
+/// Executes the `UINT256_SUBMOD` precompile.
+pub const UINT256_SUBMOD: u32 = 0x00_00_01_21;
+
+/// Executes the `UINT512_MULMOD` precompile.
+pub const UINT512_MULMOD: u32 = 0x00_00_01_22;
+
+/// Executes the `UINT1024_MULMOD` precompile.
+pub const UINT1024_MULMOD: u32 = 0x00_00_01_23;
+
+/// Executes the `UINT2048_MULMOD` precompile.
+pub const UINT2048_MULMOD: u32 = 0x00_00_01_24;
+
+/// Executes the `UINT4096_MULMOD` precompile.
+pub const UINT4096_MULMOD: u32 = 0x00_00_01_25;
+
+/// Executes the `MEMCPY` precompile.
+pub const MEMCPY: u32 = 0x00_01_01_26;
+
+/// Executes the `MEMSET` precompile.
+pub const MEMSET: u32 = 0x00_00_01_27;
@@ -1,4 +1,4 @@
-use crate::syscalls::{syscall_halt, syscall_write};
+use crate::syscalls::{sys_alloc_aligned, syscall_halt, syscall_write};
 
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
@@ -7,21 +7,33 @@ pub unsafe extern "C" fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
     syscall_halt(1);
 }
 
-#[allow(unused_variables)]
+/// Reads the environment variable `varname` set by the host into `recv_buf`, truncated to `words`
+/// words. Returns the length of the value in bytes, or `usize::MAX` if the variable is not set.
+#[allow(clippy::missing_safety_doc)]
 #[no_mangle]
-pub const fn sys_getenv(
+pub unsafe extern "C" fn sys_getenv(
     recv_buf: *mut u32,
     words: usize,
     varname: *const u8,
     varname_len: usize,
 ) -> usize {
-    0
+    let varname = core::slice::from_raw_parts(varname, varname_len);
+    match crate::io::read_env(varname) {
+        Some(value) => {
+            let len = value.len().min(words * 4);
+            if len > 0 {
+                core::ptr::copy_nonoverlapping(value.as_ptr(), recv_buf as *mut u8, len);
+            }
+            value.len()
+        }
+        None => usize::MAX,
+    }
 }
 
-#[allow(unused_variables)]
+#[allow(clippy::missing_safety_doc)]
 #[no_mangle]
-pub const fn sys_alloc_words(nwords: usize) -> *mut u32 {
-    core::ptr::null_mut()
+pub unsafe extern "C" fn sys_alloc_words(nwords: usize) -> *mut u32 {
+    sys_alloc_aligned(nwords * 4, 4) as *mut u32
 }
 
 #[allow(unused_unsafe)]
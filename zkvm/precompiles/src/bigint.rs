@@ -0,0 +1,137 @@
+//! Modular multiplication of unsigned integers of up to 4096 bits, represented as little endian
+//! slices of u32 words, with the uint256 and bigint mulmod precompiles.
+//!
+//! The operations take a modulus of zero to mean 2^bits, where bits is 32 times the number of
+//! words. Unlike the precompiles themselves, they accept inputs that are not reduced modulo the
+//! modulus.
+
+#![allow(unused_imports)]
+use crate::{
+    syscall_uint1024_mulmod, syscall_uint2048_mulmod, syscall_uint256_mulmod,
+    syscall_uint4096_mulmod, syscall_uint512_mulmod,
+};
+use num::{BigUint, One, Zero};
+
+/// The largest number of words of an integer supported by [bigint_mulmod].
+pub const BIGINT_MAX_NUM_WORDS: usize = 128;
+
+/// Returns whether `x < y`, for integers with the same number of words.
+fn bigint_lt(x: &[u32], y: &[u32]) -> bool {
+    x.iter().rev().lt(y.iter().rev())
+}
+
+fn bigint_is_zero(x: &[u32]) -> bool {
+    x.iter().all(|&word| word == 0)
+}
+
+/// Calls the mulmod precompile with the smallest width that fits the inputs, which sets `x` to
+/// `(x * y) % modulus`. One of the inputs must be less than a nonzero modulus.
+#[allow(dead_code)]
+fn bigint_mulmod_syscall(x: &mut [u32], y: &[u32], modulus: &[u32]) {
+    let len = x.len();
+    let (num_words, syscall): (usize, unsafe extern "C" fn(*mut u32, *const u32)) = match len {
+        0..=8 => (8, syscall_uint256_mulmod),
+        9..=16 => (16, syscall_uint512_mulmod),
+        17..=32 => (32, syscall_uint1024_mulmod),
+        33..=64 => (64, syscall_uint2048_mulmod),
+        _ => (128, syscall_uint4096_mulmod),
+    };
+
+    // The inputs are zero-padded to the width of the precompile, which reads the modulus after y.
+    // A zero modulus stands for 2^num_bits of the precompile, so it's only passed as is if there is
+    // no padding.
+    let mut x_padded = [0u32; BIGINT_MAX_NUM_WORDS];
+    x_padded[..len].copy_from_slice(x);
+    let mut y_modulus = [0u32; BIGINT_MAX_NUM_WORDS * 2];
+    y_modulus[..len].copy_from_slice(y);
+    if bigint_is_zero(modulus) && len < num_words {
+        y_modulus[num_words + len] = 1;
+    } else {
+        y_modulus[num_words..num_words + len].copy_from_slice(modulus);
+    }
+    unsafe {
+        syscall(x_padded.as_mut_ptr(), y_modulus.as_ptr());
+    }
+    x.copy_from_slice(&x_padded[..len]);
+}
+
+/// Computes `(x * y) % modulus` in software, outside of the zkVM.
+#[allow(dead_code)]
+fn bigint_mulmod_host(x: &mut [u32], y: &[u32], modulus: &[u32]) {
+    let modulus = match BigUint::from_slice(modulus) {
+        modulus if modulus.is_zero() => BigUint::one() << (32 * x.len()),
+        modulus => modulus,
+    };
+    let mut words = ((BigUint::from_slice(x) * BigUint::from_slice(y)) % modulus).to_u32_digits();
+    words.resize(x.len(), 0);
+    x.copy_from_slice(&words);
+}
+
+/// Reduces `x` modulo `modulus` if it isn't already, by multiplying it by one.
+fn bigint_reduce(x: &mut [u32], modulus: &[u32]) {
+    if bigint_is_zero(modulus) || bigint_lt(x, modulus) {
+        return;
+    }
+    let mut one = [0u32; BIGINT_MAX_NUM_WORDS];
+    one[0] = 1;
+    if !bigint_lt(&one[..x.len()], modulus) {
+        // Everything is zero modulo one.
+        x.fill(0);
+    } else {
+        bigint_mulmod(x, &one[..x.len()], modulus);
+    }
+}
+
+/// Sets `x` to `(x * y) % modulus`.
+///
+/// The inputs and the modulus must have the same number of words, which is at most
+/// [BIGINT_MAX_NUM_WORDS]. In the zkVM, this calls the precompile of the smallest width that fits
+/// them.
+pub fn bigint_mulmod(x: &mut [u32], y: &[u32], modulus: &[u32]) {
+    assert!(
+        x.len() == y.len() && x.len() == modulus.len(),
+        "the inputs and the modulus must have the same number of words"
+    );
+    assert!(
+        x.len() <= BIGINT_MAX_NUM_WORDS,
+        "at most {} words are supported",
+        BIGINT_MAX_NUM_WORDS
+    );
+    // The precompiles need one of the inputs to be reduced.
+    if !bigint_is_zero(modulus) && !bigint_lt(x, modulus) && !bigint_lt(y, modulus) {
+        bigint_reduce(x, modulus);
+    }
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            bigint_mulmod_syscall(x, y, modulus);
+        } else {
+            bigint_mulmod_host(x, y, modulus);
+        }
+    }
+}
+
+/// Returns `base^exponent % modulus`, by square-and-multiply with [bigint_mulmod].
+///
+/// The base and the modulus must have the same number of words, and the exponent can have any.
+pub fn bigint_modpow(base: &[u32], exponent: &[u32], modulus: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; base.len()];
+    if let Some(low) = result.first_mut() {
+        *low = 1;
+    }
+    bigint_reduce(&mut result, modulus);
+    let mut base = base.to_vec();
+    let num_bits = exponent
+        .iter()
+        .rposition(|&word| word != 0)
+        .map_or(0, |i| i * 32 + 32 - exponent[i].leading_zeros() as usize);
+    for i in 0..num_bits {
+        if (exponent[i / 32] >> (i % 32)) & 1 == 1 {
+            bigint_mulmod(&mut result, &base, modulus);
+        }
+        if i + 1 < num_bits {
+            let square = base.clone();
+            bigint_mulmod(&mut base, &square, modulus);
+        }
+    }
+    result
+}
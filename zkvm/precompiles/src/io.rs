@@ -1,13 +1,18 @@
 #![allow(unused_unsafe)]
 use crate::syscall_write;
-use crate::{syscall_hint_len, syscall_hint_read};
+use crate::{sys_alloc_fresh, syscall_hint_len, syscall_hint_read};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::alloc::Layout;
 use std::io::Write;
+#[cfg(feature = "rkyv")]
+use std::{marker::PhantomData, ops::Deref};
 
 const FD_HINT: u32 = 4;
 pub const FD_PUBLIC_VALUES: u32 = 3;
+/// These codes MUST match the codes in `core/src/syscall/write.rs`.
+const FD_ENV: u32 = 5;
+const FD_FILE: u32 = 6;
 
 pub struct SyscallWriter {
     fd: u32,
@@ -29,22 +34,28 @@ impl std::io::Write for SyscallWriter {
 }
 
 pub fn read_vec() -> Vec<u8> {
+    read_vec_aligned(4)
+}
+
+/// Reads the next input into a buffer aligned to `align` bytes, which must be a multiple of 4 and
+/// at most 16.
+fn read_vec_aligned(align: usize) -> Vec<u8> {
     let len = unsafe { syscall_hint_len() };
     // Round up to the nearest multiple of 4 so that the memory allocated is in whole words
     let capacity = (len + 3) / 4 * 4;
 
-    // Allocate a buffer of the required length that is 4 byte aligned
-    let layout = Layout::from_size_align(capacity, 4).expect("vec is too large");
-    let ptr = unsafe { std::alloc::alloc(layout) };
+    // Allocate a buffer of the required length that is aligned to at least 4 bytes
+    let layout = Layout::from_size_align(capacity, align).expect("vec is too large");
+    let ptr = unsafe { sys_alloc_fresh(layout.size(), layout.align()) };
     // SAFETY:
-    // 1. `ptr` was allocated using alloc
-    // 2. We assuume that the VM global allocator doesn't dealloc
+    // 1. `ptr` was allocated like the global allocator would allocate it
+    // 2. The global allocator frees blocks aligned to at most 16 bytes the same way
     // 3/6. Size is correct from above
     // 4/5. Length is 0
     // 7. Layout::from_size_align already checks this
     let mut vec = unsafe { Vec::from_raw_parts(ptr, 0, capacity) };
     // Read the vec into uninitialized memory. The syscall assumes the memory is uninitialized,
-    // which should be true because the allocator does not dealloc, so a new alloc should be fresh.
+    // which is true because `sys_alloc_fresh` never reuses freed memory.
     unsafe {
         syscall_hint_read(ptr, len);
         vec.set_len(len);
@@ -57,6 +68,97 @@ pub fn read<T: DeserializeOwned>() -> T {
     bincode::deserialize(&vec).expect("deserialization failed")
 }
 
+/// The alignment of the buffers archived inputs are read into, which is the alignment of the
+/// buffers `rkyv::to_bytes` serializes into.
+#[cfg(feature = "rkyv")]
+const ARCHIVE_ALIGN: usize = 16;
+
+/// An input written by the host with `SP1Stdin::write_archived`, which dereferences to its
+/// archived form in the buffer it was read into.
+#[cfg(feature = "rkyv")]
+pub struct ArchivedInput<T: rkyv::Archive> {
+    bytes: Vec<u8>,
+    _marker: PhantomData<T>,
+}
+
+#[cfg(feature = "rkyv")]
+impl<T: rkyv::Archive> Deref for ArchivedInput<T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &T::Archived {
+        // SAFETY: The bytes were checked to be a valid archive of `T`, or the caller of
+        // `read_archived_unchecked` guaranteed they are, and the buffer is aligned.
+        unsafe { rkyv::archived_root::<T>(&self.bytes) }
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<T: rkyv::Archive> ArchivedInput<T>
+where
+    T::Archived: rkyv::Deserialize<T, rkyv::Infallible>,
+{
+    /// Deserializes the input, for the parts of a program that need it owned.
+    pub fn deserialize(&self) -> T {
+        rkyv::Deserialize::deserialize(&**self, &mut rkyv::Infallible).unwrap()
+    }
+}
+
+/// Reads an input written by the host with `SP1Stdin::write_archived`, without deserializing it.
+///
+/// The input is accessed in place in the buffer the hint stream is read into, after checking
+/// that it is a valid archive. This costs far fewer cycles than deserializing it with [read].
+/// The archived type must derive `CheckBytes`, e.g. with `#[archive(check_bytes)]`.
+#[cfg(feature = "rkyv")]
+pub fn read_archived<T>() -> ArchivedInput<T>
+where
+    T: rkyv::Archive,
+    T::Archived: for<'a> rkyv::CheckBytes<rkyv::validation::validators::DefaultValidator<'a>>,
+{
+    let bytes = read_vec_aligned(ARCHIVE_ALIGN);
+    rkyv::check_archived_root::<T>(&bytes).expect("invalid archived input");
+    ArchivedInput {
+        bytes,
+        _marker: PhantomData,
+    }
+}
+
+/// Reads an input written by the host with `SP1Stdin::write_archived`, like [read_archived]
+/// but without checking that it is a valid archive.
+///
+/// # Safety
+///
+/// The input must be a valid archive of `T`. Since the prover chooses the inputs, this is only
+/// safe when the program's guarantees don't depend on the input being well formed.
+#[cfg(feature = "rkyv")]
+pub unsafe fn read_archived_unchecked<T: rkyv::Archive>() -> ArchivedInput<T> {
+    ArchivedInput {
+        bytes: read_vec_aligned(ARCHIVE_ALIGN),
+        _marker: PhantomData,
+    }
+}
+
+/// Requests a value by name from the host, which inserts it as the next input.
+fn read_named(fd: u32, name: &[u8]) -> Option<Vec<u8>> {
+    unsafe {
+        syscall_write(fd, name.as_ptr(), name.len());
+    }
+    let mut value = read_vec();
+    match value.pop() {
+        Some(1) => Some(value),
+        _ => None,
+    }
+}
+
+/// Reads an environment variable set by the host with `SP1Stdin::set_env`.
+pub fn read_env(name: &[u8]) -> Option<Vec<u8>> {
+    read_named(FD_ENV, name)
+}
+
+/// Reads a file added by the host with `SP1Stdin::write_file` or `SP1Stdin::write_dir`.
+pub fn read_file(path: &str) -> Option<Vec<u8>> {
+    read_named(FD_FILE, path.as_bytes())
+}
+
 pub fn commit<T: Serialize>(value: &T) {
     let writer = SyscallWriter {
         fd: FD_PUBLIC_VALUES,
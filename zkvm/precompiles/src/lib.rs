@@ -1,8 +1,10 @@
+pub mod bigint;
 pub mod bigint_mulmod;
 pub mod bls12381;
 pub mod bn254;
 pub mod io;
 pub mod secp256k1;
+pub mod uint256;
 pub mod uint256_div;
 pub mod unconstrained;
 pub mod utils;
@@ -26,6 +28,14 @@ extern "C" {
     pub fn syscall_bls12381_double(p: *mut u32);
     pub fn syscall_keccak_permute(state: *mut u64);
     pub fn syscall_uint256_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint256_addmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint256_submod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint512_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint1024_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint2048_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint4096_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_memcpy(dst: *mut u32, src: *const u32);
+    pub fn syscall_memset(dst: *mut u32, value: u32);
     pub fn syscall_blake3_compress_inner(p: *mut u32, q: *const u32);
     pub fn syscall_enter_unconstrained() -> bool;
     pub fn syscall_exit_unconstrained();
@@ -33,5 +43,6 @@ extern "C" {
     pub fn syscall_hint_len() -> usize;
     pub fn syscall_hint_read(ptr: *mut u8, len: usize);
     pub fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8;
+    pub fn sys_alloc_fresh(bytes: usize, align: usize) -> *mut u8;
     pub fn syscall_bls12381_decompress(point: &mut [u8; 96], is_odd: bool);
 }
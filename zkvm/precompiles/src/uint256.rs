@@ -0,0 +1,365 @@
+//! Arithmetic on 256-bit unsigned integers, represented as little endian arrays of u32 words, with
+//! the uint256 precompiles.
+//!
+//! The modular operations take a modulus of zero to mean 2^256. Unlike the precompiles themselves,
+//! they accept inputs that are not reduced modulo the modulus.
+
+#![allow(unused_imports)]
+use crate::{syscall_uint256_addmod, syscall_uint256_mulmod, syscall_uint256_submod};
+use num::{BigUint, One, Zero};
+
+/// The number of words of a uint256.
+pub const UINT256_NUM_WORDS: usize = 8;
+
+/// A uint256, as little endian u32 words.
+pub type Uint256 = [u32; UINT256_NUM_WORDS];
+
+/// The uint256 of zero.
+pub const ZERO: Uint256 = [0; UINT256_NUM_WORDS];
+
+/// The uint256 of one.
+pub const ONE: Uint256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// Returns whether `x < y`.
+pub fn uint256_lt(x: &Uint256, y: &Uint256) -> bool {
+    x.iter().rev().lt(y.iter().rev())
+}
+
+/// Calls a uint256 precompile, which sets `x` to `(x op y) % modulus`.
+#[allow(dead_code)]
+fn uint256_op(
+    syscall: unsafe extern "C" fn(*mut u32, *const u32),
+    x: &mut Uint256,
+    y: &Uint256,
+    modulus: &Uint256,
+) {
+    // The precompiles read the modulus after y.
+    let mut y_modulus = [0u32; UINT256_NUM_WORDS * 2];
+    y_modulus[..UINT256_NUM_WORDS].copy_from_slice(y);
+    y_modulus[UINT256_NUM_WORDS..].copy_from_slice(modulus);
+    unsafe {
+        syscall(x.as_mut_ptr(), y_modulus.as_ptr());
+    }
+}
+
+/// Computes `(x op y) % modulus` in software, outside of the zkVM.
+#[allow(dead_code)]
+fn uint256_op_host(
+    x: &Uint256,
+    y: &Uint256,
+    modulus: &Uint256,
+    op: impl Fn(BigUint, BigUint, &BigUint) -> BigUint,
+) -> Uint256 {
+    let to_biguint = |words: &Uint256| BigUint::from_slice(words);
+    let modulus = match to_biguint(modulus) {
+        modulus if modulus.is_zero() => BigUint::one() << 256,
+        modulus => modulus,
+    };
+    let mut words = op(to_biguint(x), to_biguint(y), &modulus).to_u32_digits();
+    words.resize(UINT256_NUM_WORDS, 0);
+    words.try_into().unwrap()
+}
+
+/// Reduces `x` modulo `modulus` if it isn't already, by multiplying it by one.
+fn uint256_reduce(x: &mut Uint256, modulus: &Uint256) {
+    if *modulus != ZERO && !uint256_lt(x, modulus) {
+        uint256_mulmod(x, &ONE, modulus);
+    }
+}
+
+/// Sets `x` to `(x * y) % modulus`.
+pub fn uint256_mulmod(x: &mut Uint256, y: &Uint256, modulus: &Uint256) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            uint256_op(syscall_uint256_mulmod, x, y, modulus);
+        } else {
+            *x = uint256_op_host(x, y, modulus, |x, y, modulus| (x * y) % modulus);
+        }
+    }
+}
+
+/// Sets `x` to `(x + y) % modulus`.
+pub fn uint256_addmod(x: &mut Uint256, y: &Uint256, modulus: &Uint256) {
+    let mut y = *y;
+    uint256_reduce(x, modulus);
+    uint256_reduce(&mut y, modulus);
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            uint256_op(syscall_uint256_addmod, x, &y, modulus);
+        } else {
+            *x = uint256_op_host(x, &y, modulus, |x, y, modulus| (x + y) % modulus);
+        }
+    }
+}
+
+/// Sets `x` to `(x - y) % modulus`.
+pub fn uint256_submod(x: &mut Uint256, y: &Uint256, modulus: &Uint256) {
+    let mut y = *y;
+    uint256_reduce(x, modulus);
+    uint256_reduce(&mut y, modulus);
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            uint256_op(syscall_uint256_submod, x, &y, modulus);
+        } else {
+            *x = uint256_op_host(x, &y, modulus, |x, y, modulus| (modulus + x - y) % modulus);
+        }
+    }
+}
+
+/// Sets `x` to `x + y` modulo 2^256, and returns the carry.
+pub fn uint256_add(x: &mut Uint256, y: &Uint256) -> bool {
+    let x_in = *x;
+    uint256_addmod(x, y, &ZERO);
+    // The sum wrapped around if and only if it is less than either operand.
+    uint256_lt(x, &x_in)
+}
+
+/// Sets `x` to `x - y` modulo 2^256, and returns the borrow.
+pub fn uint256_sub(x: &mut Uint256, y: &Uint256) -> bool {
+    let borrow = uint256_lt(x, y);
+    uint256_submod(x, y, &ZERO);
+    borrow
+}
+
+/// Returns `base^exponent % modulus`, by square-and-multiply with [uint256_mulmod].
+pub fn uint256_modpow(base: &Uint256, exponent: &Uint256, modulus: &Uint256) -> Uint256 {
+    let mut result = ONE;
+    uint256_reduce(&mut result, modulus);
+    let mut base = *base;
+    let num_bits = exponent
+        .iter()
+        .rposition(|&word| word != 0)
+        .map_or(0, |i| i * 32 + 32 - exponent[i].leading_zeros() as usize);
+    for i in 0..num_bits {
+        if (exponent[i / 32] >> (i % 32)) & 1 == 1 {
+            uint256_mulmod(&mut result, &base, modulus);
+        }
+        if i + 1 < num_bits {
+            let square = base;
+            uint256_mulmod(&mut base, &square, modulus);
+        }
+    }
+    result
+}
+
+/// The uint256 operations on [crypto_bigint::U256], named after its own methods.
+#[cfg(feature = "crypto-bigint")]
+pub mod u256 {
+    use crypto_bigint::{Encoding, U256};
+
+    use super::Uint256;
+
+    /// The words of a [U256].
+    pub fn to_words(x: &U256) -> Uint256 {
+        let bytes = x.to_le_bytes();
+        core::array::from_fn(|i| u32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap()))
+    }
+
+    /// The [U256] of the given words.
+    pub fn from_words(words: &Uint256) -> U256 {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        U256::from_le_bytes(bytes)
+    }
+
+    /// Computes `(x op y) % modulus` with one of the operations above.
+    fn op(f: fn(&mut Uint256, &Uint256, &Uint256), x: &U256, y: &U256, modulus: &U256) -> U256 {
+        let mut result = to_words(x);
+        f(&mut result, &to_words(y), &to_words(modulus));
+        from_words(&result)
+    }
+
+    /// Computes `(x + y) % modulus`, where a zero modulus means 2^256.
+    pub fn add_mod(x: &U256, y: &U256, modulus: &U256) -> U256 {
+        op(super::uint256_addmod, x, y, modulus)
+    }
+
+    /// Computes `(x - y) % modulus`, where a zero modulus means 2^256.
+    pub fn sub_mod(x: &U256, y: &U256, modulus: &U256) -> U256 {
+        op(super::uint256_submod, x, y, modulus)
+    }
+
+    /// Computes `(x * y) % modulus`, where a zero modulus means 2^256.
+    pub fn mul_mod(x: &U256, y: &U256, modulus: &U256) -> U256 {
+        op(super::uint256_mulmod, x, y, modulus)
+    }
+
+    /// Computes `base^exponent % modulus`, where a zero modulus means 2^256.
+    pub fn pow_mod(base: &U256, exponent: &U256, modulus: &U256) -> U256 {
+        from_words(&super::uint256_modpow(
+            &to_words(base),
+            &to_words(exponent),
+            &to_words(modulus),
+        ))
+    }
+
+    /// Computes `x + y` modulo 2^256, and whether it overflowed.
+    pub fn overflowing_add(x: &U256, y: &U256) -> (U256, bool) {
+        let mut result = to_words(x);
+        let carry = super::uint256_add(&mut result, &to_words(y));
+        (from_words(&result), carry)
+    }
+
+    /// Computes `x - y` modulo 2^256, and whether it underflowed.
+    pub fn overflowing_sub(x: &U256, y: &U256) -> (U256, bool) {
+        let mut result = to_words(x);
+        let borrow = super::uint256_sub(&mut result, &to_words(y));
+        (from_words(&result), borrow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    const MAX: Uint256 = [u32::MAX; UINT256_NUM_WORDS];
+
+    fn to_biguint(words: &Uint256) -> BigUint {
+        BigUint::from_slice(words)
+    }
+
+    fn from_biguint(x: &BigUint) -> Uint256 {
+        let mut words = x.to_u32_digits();
+        words.resize(UINT256_NUM_WORDS, 0);
+        words.try_into().unwrap()
+    }
+
+    /// Random uint256s, with the edge cases of the carries and borrows first.
+    fn samples() -> Vec<Uint256> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut samples = vec![ZERO, ONE, MAX, [0, 0, 0, 0, 0, 0, 0, 1 << 31]];
+        samples.extend((0..16).map(|_| rng.gen::<Uint256>()));
+        // Values with some words zero, so that the modulus or exponent is shorter.
+        samples.extend((0..8).map(|i| {
+            let mut x = rng.gen::<Uint256>();
+            x[i..].fill(0);
+            x
+        }));
+        samples
+    }
+
+    #[test]
+    fn test_uint256_add_sub() {
+        let modulus = BigUint::one() << 256;
+        for x in samples() {
+            for y in samples() {
+                let (x_big, y_big) = (to_biguint(&x), to_biguint(&y));
+
+                let mut sum = x;
+                let carry = uint256_add(&mut sum, &y);
+                assert_eq!(sum, from_biguint(&((&x_big + &y_big) % &modulus)));
+                assert_eq!(carry, &x_big + &y_big >= modulus);
+
+                let mut difference = x;
+                let borrow = uint256_sub(&mut difference, &y);
+                assert_eq!(
+                    difference,
+                    from_biguint(&((&modulus + &x_big - &y_big) % &modulus))
+                );
+                assert_eq!(borrow, x_big < y_big);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uint256_modular_ops() {
+        for x in samples() {
+            for y in samples() {
+                for modulus in [ZERO, ONE, [7, 0, 0, 0, 0, 0, 0, 0], y] {
+                    let expected = |op: fn(&BigUint, &BigUint, &BigUint) -> BigUint| {
+                        let modulus = match to_biguint(&modulus) {
+                            m if m.is_zero() => BigUint::one() << 256,
+                            m => m,
+                        };
+                        from_biguint(&op(&to_biguint(&x), &to_biguint(&y), &modulus))
+                    };
+
+                    let mut result = x;
+                    uint256_addmod(&mut result, &y, &modulus);
+                    assert_eq!(result, expected(|x, y, m| (x + y) % m));
+
+                    let mut result = x;
+                    uint256_submod(&mut result, &y, &modulus);
+                    assert_eq!(result, expected(|x, y, m| (m + x % m - y % m) % m));
+
+                    let mut result = x;
+                    uint256_mulmod(&mut result, &y, &modulus);
+                    assert_eq!(result, expected(|x, y, m| (x * y) % m));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_uint256_modpow() {
+        let samples = samples();
+        for base in samples.iter().step_by(3) {
+            for exponent in samples.iter().step_by(2) {
+                for modulus in [ZERO, ONE, [7, 0, 0, 0, 0, 0, 0, 0], samples[12]] {
+                    let modulus_big = match to_biguint(&modulus) {
+                        m if m.is_zero() => BigUint::one() << 256,
+                        m => m,
+                    };
+                    let expected = to_biguint(base).modpow(&to_biguint(exponent), &modulus_big);
+                    assert_eq!(
+                        uint256_modpow(base, exponent, &modulus),
+                        from_biguint(&expected)
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "crypto-bigint")]
+    #[test]
+    fn test_u256() {
+        use crypto_bigint::{NonZero, U256};
+
+        let big_modulus = BigUint::one() << 256;
+        let modulus = U256::from_u64(1_000_000_007);
+        let nonzero = NonZero::new(modulus).unwrap();
+        for x in samples() {
+            for y in samples() {
+                let (x_u256, y_u256) = (u256::from_words(&x), u256::from_words(&y));
+                assert_eq!(u256::to_words(&x_u256), x);
+                let mut bytes = to_biguint(&x).to_bytes_le();
+                bytes.resize(32, 0);
+                assert_eq!(x_u256, U256::from_le_slice(&bytes));
+
+                let (sum, carry) = u256::overflowing_add(&x_u256, &y_u256);
+                assert_eq!(sum, x_u256.wrapping_add(&y_u256));
+                assert_eq!(carry, to_biguint(&x) + to_biguint(&y) >= big_modulus);
+                let (difference, borrow) = u256::overflowing_sub(&x_u256, &y_u256);
+                assert_eq!(difference, x_u256.wrapping_sub(&y_u256));
+                assert_eq!(borrow, x_u256 < y_u256);
+
+                let (x_reduced, y_reduced) = (x_u256.rem(&nonzero), y_u256.rem(&nonzero));
+                assert_eq!(
+                    u256::add_mod(&x_u256, &y_u256, &modulus),
+                    x_reduced.add_mod(&y_reduced, &modulus)
+                );
+                assert_eq!(
+                    u256::sub_mod(&x_u256, &y_u256, &modulus),
+                    x_reduced.sub_mod(&y_reduced, &modulus)
+                );
+                assert_eq!(
+                    u256::mul_mod(&x_u256, &y_u256, &modulus),
+                    u256::from_words(&from_biguint(
+                        &(to_biguint(&x) * to_biguint(&y) % to_biguint(&u256::to_words(&modulus)))
+                    ))
+                );
+            }
+        }
+        assert_eq!(
+            u256::pow_mod(&U256::from_u64(3), &U256::from_u64(200), &U256::ZERO),
+            u256::from_words(&from_biguint(
+                &BigUint::from(3u32).modpow(&BigUint::from(200u32), &big_modulus)
+            ))
+        );
+    }
+}